@@ -3,7 +3,7 @@ use bevy_kira_audio::prelude::*;
 
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, AudioPlugin))
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
         .add_systems(Startup, play_loop)
         .run()
 }
@@ -9,7 +9,7 @@ use std::time::Duration;
 // Right-click to resume the audio
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, AudioPlugin))
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
         .add_systems(Startup, play_loop)
         .add_systems(Update, channel_control)
         .run()
@@ -5,7 +5,7 @@ use bevy_kira_audio::prelude::*;
 /// that is not known at compile time, you can create and use dynamic channels based on string keys.
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, AudioPlugin))
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
         .add_systems(Startup, start_background_audio)
         .add_systems(Update, plop)
         .run()
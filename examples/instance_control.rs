@@ -6,7 +6,7 @@ use bevy_kira_audio::prelude::*;
 // sent to the audio thread immediately.
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, AudioPlugin))
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
         .add_systems(Startup, play_loop)
         .add_systems(Update, instance_control)
         .run()
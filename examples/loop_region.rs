@@ -0,0 +1,17 @@
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+/// This example plays the first seconds of a sound once as an intro and then loops a part of it.
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
+        .add_systems(Startup, play_music)
+        .run();
+}
+
+fn play_music(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    audio
+        .play(asset_server.load("sounds/loop.ogg"))
+        // Play from the start, then jump back to 4.8 seconds every time the sound reaches 19.2 seconds
+        .loop_region(4.8, 19.2);
+}
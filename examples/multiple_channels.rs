@@ -8,7 +8,7 @@ use std::marker::PhantomData;
 // This is a bigger example with a GUI for full control over three audio channels
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, AudioPlugin))
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
         .init_resource::<LastAction>()
         .add_systems(Startup, prepare_audio_and_ui)
         .add_systems(Update, create_row_systems::<FirstChannel>())
@@ -5,7 +5,7 @@ use std::time::Duration;
 /// This example shows the different settings that can be applied when playing a sound.
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, AudioPlugin))
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
         .add_systems(Startup, play_audio)
         .run();
 }
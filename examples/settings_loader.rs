@@ -5,7 +5,7 @@ use bevy_kira_audio::prelude::*;
 /// You can also easily apply settings when playing a sound (see the `settings` example).
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, AudioPlugin))
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
         .add_systems(Startup, play_audio)
         .run();
 }
@@ -6,8 +6,11 @@ use bevy_kira_audio::prelude::*;
 
 fn main() {
     App::new()
-        .insert_resource(SpacialAudio { max_distance: 25. })
-        .add_plugins((DefaultPlugins, AudioPlugin, CameraPlugin))
+        .insert_resource(SpacialAudio {
+            max_distance: 25.,
+            ..default()
+        })
+        .add_plugins((DefaultPlugins, AudioPlugin::default(), CameraPlugin))
         .add_systems(Startup, setup)
         .run()
 }
@@ -20,40 +23,42 @@ fn setup(
     mut materials: ResMut<Assets<StandardMaterial>>,
 ) {
     // Emitter Nr. 1
-    let cooking = audio
-        .play(asset_server.load("sounds/cooking.ogg"))
-        .looped()
-        .handle();
+    let mut emitter = AudioEmitter {
+        range: 1.,
+        ..default()
+    };
+    emitter
+        .play(audio.as_ref(), asset_server.load("sounds/cooking.ogg"))
+        .looped();
     commands
         .spawn(SceneBundle {
             scene: asset_server.load("models/panStew.glb#Scene0"),
             transform: Transform::from_xyz(-5.0, 0., 0.),
             ..default()
         })
-        .insert(AudioEmitter {
-            instances: vec![cooking],
-        });
+        .insert(emitter);
     // Emitter Nr. 2
-    let elevator_music = audio
-        .play(asset_server.load("sounds/loop.ogg"))
-        .looped()
-        .handle();
+    let mut emitter = AudioEmitter {
+        range: 1.,
+        ..default()
+    };
+    emitter
+        .play(audio.as_ref(), asset_server.load("sounds/loop.ogg"))
+        .looped();
     commands
         .spawn(SceneBundle {
             scene: asset_server.load("models/boxOpen.glb#Scene0"),
             transform: Transform::from_xyz(10., 0., 0.),
             ..default()
         })
-        .insert(AudioEmitter {
-            instances: vec![elevator_music],
-        });
+        .insert(emitter);
     // Our camera will be the receiver
     commands
         .spawn(Camera3dBundle {
             transform: Transform::from_xyz(0.0, 0.5, 10.0),
             ..default()
         })
-        .insert(AudioReceiver)
+        .insert(AudioReceiver::default())
         .insert(FlyCam);
 
     // Other scene setup...
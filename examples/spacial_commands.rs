@@ -0,0 +1,106 @@
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use bevy_kira_audio::SpacialAudioDebug;
+
+/// Spacial audio only sends changes above `SpacialAudio::update_epsilon` to the audio thread.
+/// This example shows the number of commands sent per frame for 100 looping emitters.
+///
+/// While the receiver stands still, no commands are sent. Press SPACE to move the receiver
+/// and E to toggle between the default epsilon and sending every update.
+/// `cargo run --example spacial_commands --features debug`
+fn main() {
+    App::new()
+        .insert_resource(SpacialAudio {
+            max_distance: 15.,
+            ..default()
+        })
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (controls, move_receiver, show_commands).chain())
+        .run()
+}
+
+#[derive(Component)]
+struct Receiver {
+    moving: bool,
+}
+
+#[derive(Component)]
+struct CommandText;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    // Stop our ears from exploding...
+    audio.set_volume(0.01);
+    for index in 0..100 {
+        let mut emitter = AudioEmitter::default();
+        emitter
+            .play(audio.as_ref(), asset_server.load("sounds/loop.ogg"))
+            .looped();
+        let position = Vec2::new((index % 10) as f32 - 4.5, (index / 10) as f32 - 4.5) * 3.;
+        commands.spawn((
+            TransformBundle::from_transform(Transform::from_translation(position.extend(0.))),
+            emitter,
+        ));
+    }
+    commands.spawn((
+        Camera2dBundle::default(),
+        AudioReceiver::default(),
+        Receiver { moving: false },
+    ));
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("fonts/monogram.ttf"),
+                font_size: 40.0,
+                color: Color::rgb(0.9, 0.9, 0.9),
+            },
+        )
+        .with_style(Style {
+            margin: UiRect::all(Val::Px(15.)),
+            ..default()
+        }),
+        CommandText,
+    ));
+}
+
+fn controls(
+    keys: Res<Input<KeyCode>>,
+    mut spacial_audio: ResMut<SpacialAudio>,
+    mut receiver: Query<&mut Receiver>,
+) {
+    if keys.just_pressed(KeyCode::Space) {
+        let mut receiver = receiver.single_mut();
+        receiver.moving = !receiver.moving;
+    }
+    if keys.just_pressed(KeyCode::E) {
+        spacial_audio.update_epsilon = if spacial_audio.update_epsilon > 0. {
+            0.
+        } else {
+            SpacialAudio::default().update_epsilon
+        };
+    }
+}
+
+fn move_receiver(time: Res<Time>, mut receiver: Query<(&mut Transform, &Receiver)>) {
+    let (mut transform, receiver) = receiver.single_mut();
+    if receiver.moving {
+        let angle = time.elapsed_seconds() * 0.5;
+        transform.translation = Vec3::new(angle.cos(), angle.sin(), 0.) * 8.;
+    }
+}
+
+fn show_commands(
+    debug: Res<SpacialAudioDebug>,
+    spacial_audio: Res<SpacialAudio>,
+    receiver: Query<&Receiver>,
+    mut text: Query<&mut Text, With<CommandText>>,
+) {
+    let moving = receiver.single().moving;
+    text.single_mut().sections[0].value = format!(
+        "Commands in the last update: {}\nReceiver: {} (SPACE)\nUpdate epsilon: {} (E)",
+        debug.instance_commands,
+        if moving { "moving" } else { "standing still" },
+        spacial_audio.update_epsilon,
+    );
+}
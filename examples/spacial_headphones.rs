@@ -0,0 +1,56 @@
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+/// This example plays a sound on an emitter circling the receiver.
+/// Press space to toggle the interaural time delay of the receiver.
+///
+/// With headphones, the delay between the ears makes the direction of the sound clearer than
+/// panning alone.
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (circle_emitter, toggle_ear_distance))
+        .run()
+}
+
+const EAR_DISTANCE: f32 = 0.2;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    let mut emitter = AudioEmitter::default();
+    emitter
+        .play(audio.as_ref(), asset_server.load("sounds/loop.ogg"))
+        .with_interaural_delay()
+        .looped();
+    commands.spawn((TransformBundle::default(), emitter));
+    commands.spawn((
+        TransformBundle::default(),
+        AudioReceiver {
+            ear_distance: EAR_DISTANCE,
+            ..default()
+        },
+    ));
+    info!("Interaural delay is on. Press space to toggle it.");
+}
+
+fn circle_emitter(time: Res<Time>, mut emitter: Query<&mut Transform, With<AudioEmitter>>) {
+    let angle = time.elapsed_seconds() * 0.5;
+    emitter.single_mut().translation = Vec3::new(angle.cos(), 0., angle.sin()) * 3.;
+}
+
+fn toggle_ear_distance(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut receiver: Query<&mut AudioReceiver>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    let mut receiver = receiver.single_mut();
+    if receiver.ear_distance > 0. {
+        receiver.ear_distance = 0.;
+        info!("Interaural delay is off");
+    } else {
+        receiver.ear_distance = EAR_DISTANCE;
+        info!("Interaural delay is on");
+    }
+}
@@ -0,0 +1,61 @@
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+
+/// This example plays a stereo sound on an emitter circling the receiver.
+/// Press space to switch between the original stereo sound and a version that is mixed
+/// down to mono by the settings loader (see `assets/sounds/sound_mono.ogg.ron`).
+///
+/// The stereo image of the original fights the spacial panning, while the mono version
+/// is only placed by the position of the emitter.
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
+        .add_systems(Startup, setup)
+        .add_systems(Update, (circle_emitter, switch_source))
+        .run()
+}
+
+#[derive(Component)]
+struct Mono(bool);
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    let mut emitter = AudioEmitter::default();
+    emitter
+        .play(audio.as_ref(), asset_server.load("sounds/sound.ogg"))
+        .looped();
+    commands.spawn((TransformBundle::default(), emitter, Mono(false)));
+    commands.spawn((TransformBundle::default(), AudioReceiver::default()));
+    info!("Playing the stereo sound. Press space to switch.");
+}
+
+fn circle_emitter(time: Res<Time>, mut emitter: Query<&mut Transform, With<AudioEmitter>>) {
+    let angle = time.elapsed_seconds() * 0.5;
+    emitter.single_mut().translation = Vec3::new(angle.cos(), 0., angle.sin()) * 3.;
+}
+
+fn switch_source(
+    keyboard_input: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    mut emitter: Query<(&mut AudioEmitter, &mut Mono)>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    let (mut emitter, mut mono) = emitter.single_mut();
+    for instance in emitter.instances.drain(..) {
+        if let Some(instance) = audio_instances.get_mut(&instance) {
+            instance.stop(AudioTween::default());
+        }
+    }
+    mono.0 = !mono.0;
+    let source = if mono.0 {
+        info!("Playing the sound mixed down to mono");
+        asset_server.load("sounds/sound_mono.ogg.ron")
+    } else {
+        info!("Playing the stereo sound");
+        asset_server.load("sounds/sound.ogg")
+    };
+    emitter.play(audio.as_ref(), source).looped();
+}
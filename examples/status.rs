@@ -7,7 +7,7 @@ struct LoopAudioInstanceHandle(Handle<AudioInstance>);
 
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, AudioPlugin))
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
         .add_systems(Startup, (start_audio, display_help_text))
         .add_systems(Update, (print_status, process_keyboard_input))
         .run();
@@ -17,8 +17,9 @@ fn main() {
         .insert_resource(AudioSettings {
             sound_capacity: 8192,
             command_capacity: 4096,
+            ..default()
         })
-        .add_plugins((DefaultPlugins, AudioPlugin))
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
         .add_systems(Startup, prepare)
         .add_systems(Update, (check, play))
         .run()
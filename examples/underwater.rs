@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+use bevy_kira_audio::prelude::*;
+use std::time::Duration;
+
+// This example demonstrates how LFO modulators wobble the parameters of an instance
+// The loop plays muffled with a slow vibrato and tremolo, like heard under water.
+// Press space to surface (unbind the modulators) and dive again (bind them).
+// Press the up or down arrow to make the wobble faster or slower.
+fn main() {
+    App::new()
+        .add_plugins((DefaultPlugins, AudioPlugin::default()))
+        .add_systems(Startup, dive)
+        .add_systems(Update, (surface_and_dive, change_wobble))
+        .run()
+}
+
+#[derive(Resource)]
+struct Underwater {
+    instance: Handle<AudioInstance>,
+    wobble: AudioModulator,
+    frequency: f64,
+    submerged: bool,
+}
+
+fn dive(
+    mut commands: Commands,
+    mut modulators: ResMut<AudioModulators>,
+    asset_server: Res<AssetServer>,
+    audio: Res<Audio>,
+) {
+    let wobble = modulators.add_lfo(LfoSettings {
+        frequency: 0.8,
+        ..default()
+    });
+    // The modulator can be bound in the frame it was added in
+    let instance = audio
+        .play(asset_server.load("sounds/loop.ogg"))
+        .looped()
+        .with_low_pass_filter(600.)
+        .with_modulator(ModulatedParameter::PlaybackRate, wobble, 0.6)
+        .with_modulator(ModulatedParameter::Volume, wobble, 0.2)
+        .handle();
+    commands.insert_resource(Underwater {
+        instance,
+        wobble,
+        frequency: 0.8,
+        submerged: true,
+    });
+}
+
+fn surface_and_dive(
+    mut underwater: ResMut<Underwater>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    modulators: Res<AudioModulators>,
+    input: Res<Input<KeyCode>>,
+) {
+    if !input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    let Some(instance) = audio_instances.get_mut(&underwater.instance) else {
+        return;
+    };
+    underwater.submerged = !underwater.submerged;
+    if underwater.submerged {
+        let wobble = underwater.wobble;
+        instance.bind_modulator(&modulators, wobble, ModulatedParameter::PlaybackRate, 0.6);
+        instance.bind_modulator(&modulators, wobble, ModulatedParameter::Volume, 0.2);
+        instance.set_filter_cutoff(600., AudioTween::linear(Duration::from_millis(300)));
+    } else {
+        instance.unbind_modulator(ModulatedParameter::PlaybackRate);
+        instance.unbind_modulator(ModulatedParameter::Volume);
+        instance.set_filter_cutoff(20_000., AudioTween::linear(Duration::from_millis(300)));
+    }
+}
+
+fn change_wobble(
+    mut underwater: ResMut<Underwater>,
+    mut modulators: ResMut<AudioModulators>,
+    input: Res<Input<KeyCode>>,
+) {
+    let frequency = if input.just_pressed(KeyCode::Up) {
+        (underwater.frequency * 2.).min(8.)
+    } else if input.just_pressed(KeyCode::Down) {
+        (underwater.frequency / 2.).max(0.1)
+    } else {
+        return;
+    };
+    underwater.frequency = frequency;
+    // The LFO speeds up or slows down over a second instead of jumping
+    modulators.set_frequency(
+        underwater.wobble,
+        frequency,
+        AudioTween::linear(Duration::from_secs(1)),
+    );
+}
@@ -1,37 +1,80 @@
 //! Common audio types
 
 use crate::audio_output::{play_audio_channel, update_instance_states};
+use crate::channel::ducking::DuckSettings;
+use crate::channel::effects::{ChannelEffects, ChannelReverb, EffectChain};
+use crate::channel::hierarchy::ChannelHierarchy;
+use crate::channel::queue::QueueRepeat;
+use crate::channel::snapshot::TypedAudioChannels;
 use crate::channel::typed::AudioChannel;
-use crate::channel::AudioCommandQue;
+use crate::channel::{AudioCommandQue, Channel, ChannelSettings, StealPolicy};
+use crate::clock::{AudioClockId, AudioClockTime, ScheduledStart};
 use crate::instance::AudioInstance;
-use crate::source::AudioSource;
+use crate::modulator::{AudioModulator, ModulatedParameter};
+use crate::source::collection::AudioCollection;
+use crate::source::streamed::StreamedAudioSource;
+use crate::source::{AudioSource, SoundLayout};
+use crate::volume::scale_volume;
 use crate::AudioSystemSet;
 use bevy::app::{App, PreUpdate};
 use bevy::asset::{AssetId, Handle};
 use bevy::ecs::system::Resource;
 use bevy::prelude::{default, IntoSystemConfigs, PostUpdate};
+use bevy::reflect::Reflect;
 use bevy::utils::Uuid;
-use kira::sound::static_sound::{StaticSoundData, StaticSoundHandle};
-use kira::sound::EndPosition;
+use kira::sound::static_sound::StaticSoundHandle;
+use kira::sound::{EndPosition, PlaybackRate};
+use kira::track::effect::filter::{FilterBuilder, FilterMode};
+use kira::track::TrackBuilder;
 use kira::tween::Value;
 use kira::Volume;
+use parking_lot::Mutex;
 use std::marker::PhantomData;
 use std::time::Duration;
 
+// Most commands are play commands, so boxing their settings would cost an allocation per sound
+#[allow(clippy::large_enum_variant)]
 pub(crate) enum AudioCommand {
     Play(PlayAudioSettings),
     SetVolume(Volume, Option<AudioTween>),
     SetPanning(f64, Option<AudioTween>),
-    SetPlaybackRate(f64, Option<AudioTween>),
+    /// Tweens interpolate in the unit of the rate, so glides in semitones sound even
+    SetPlaybackRate(PlaybackRate, Option<AudioTween>),
     Stop(Option<AudioTween>),
     Pause(Option<AudioTween>),
     Resume(Option<AudioTween>),
+    SeekTo(f64),
+    SeekBy(f64),
+    /// Replace the state of a new channel before anything plays in it
+    ApplySettings(ChannelSettings),
+    SetInstanceLimit(Option<(usize, StealPolicy)>),
+    SetReverb(ChannelReverb),
+    /// Track with the effects of an [`EffectChain`](crate::EffectChain), taken when the channel track is created
+    SetEffects(Mutex<Option<TrackBuilder>>),
+    SetReverbMix(f64, Option<AudioTween>),
+    SetSend(Channel, f64, Option<AudioTween>),
+    SetMuted(bool, Option<AudioTween>),
+    SetSolo(bool, Option<AudioTween>),
+    /// Duck the channel while the given channel plays, or stop that with `None`
+    DuckBy(Channel, Option<DuckSettings>),
+    DuckFor(Duration, DuckSettings),
+    /// Pause the channel and resume it after the given time
+    PauseFor(Duration, AudioTween),
+    /// Tween of resumes without their own
+    SetResumeTween(AudioTween),
+    SetOutputDevice(Option<String>),
+    Enqueue(Handle<AudioSource>),
+    SkipQueued,
+    ClearQueue,
+    SetQueueRepeat(QueueRepeat),
 }
 
 #[derive(Clone, Default)]
 pub(crate) struct PartialSoundSettings {
     pub(crate) loop_start: Option<f64>,
     pub(crate) loop_end: Option<f64>,
+    /// Loop the region of the source instead, if it has one
+    pub(crate) source_loop: bool,
     pub(crate) volume: Option<Volume>,
     pub(crate) playback_rate: Option<f64>,
     pub(crate) start_position: Option<f64>,
@@ -40,15 +83,33 @@ pub(crate) struct PartialSoundSettings {
     pub(crate) reverse: Option<bool>,
     pub(crate) paused: bool,
     pub(crate) fade_in: Option<AudioTween>,
+    pub(crate) filter: Option<FilterSettings>,
+    pub(crate) start_silent: bool,
+    pub(crate) ambient_send: bool,
+    pub(crate) interaural_delay: bool,
+    pub(crate) start_time: Option<ScheduledStart>,
+    pub(crate) pitch_variation: Option<f64>,
+    pub(crate) volume_variation: Option<f64>,
+    /// Fade out the other instances of the channel while this one fades in
+    pub(crate) crossfade: Option<(Duration, CrossfadeCurve)>,
+    /// Remove the instance asset once it stopped
+    pub(crate) one_shot: bool,
+    /// Modulators with their depth, see [`PlayAudioCommand::with_modulator`]
+    pub(crate) modulators: Vec<(ModulatedParameter, AudioModulator, f64)>,
 }
 
 /// Different kinds of easing for fade-in and fade-out
 pub type AudioEasing = kira::tween::Easing;
 
-#[derive(Clone)]
 /// A tween for audio transitions
 ///
 /// Use the default for almost instantaneous transitions without audio artifacts
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct AudioTween {
     duration: Duration,
     easing: AudioEasing,
@@ -74,6 +135,38 @@ impl AudioTween {
 
         self
     }
+
+    pub(crate) fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+/// The shape of the volume fades of a crossfade
+///
+/// See [`AudioControl::crossfade_to`](crate::AudioControl::crossfade_to).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CrossfadeCurve {
+    /// Fade the amplitudes linearly, so the combined loudness dips in the middle
+    Linear,
+    /// Keep the combined power of both sounds about constant, which suits music
+    #[default]
+    EqualPower,
+}
+
+impl CrossfadeCurve {
+    /// The tweens of the incoming and the outgoing sound
+    ///
+    /// Equal power fades follow a sine and a cosine, which quadratic easings approximate.
+    pub(crate) fn tweens(&self, duration: Duration) -> (AudioTween, AudioTween) {
+        match self {
+            CrossfadeCurve::Linear => (AudioTween::linear(duration), AudioTween::linear(duration)),
+            CrossfadeCurve::EqualPower => (
+                AudioTween::new(duration, AudioEasing::OutPowi(2)),
+                AudioTween::new(duration, AudioEasing::InPowi(2)),
+            ),
+        }
+    }
 }
 
 impl Default for AudioTween {
@@ -82,6 +175,84 @@ impl Default for AudioTween {
     }
 }
 
+/// The filter of a single instance
+///
+/// See [`PlayAudioCommand::with_filter`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct FilterSettings {
+    /// The frequencies that the filter removes
+    pub mode: FilterMode,
+    /// The cutoff frequency in Hz
+    pub cutoff: f64,
+    /// Feedback that makes the filter ring around the cutoff frequency
+    pub resonance: f64,
+    /// From `0.0` for only the unfiltered sound to `1.0` for only the filtered sound
+    pub mix: f64,
+}
+
+impl Default for FilterSettings {
+    fn default() -> Self {
+        FilterSettings::low_pass(1_000.)
+    }
+}
+
+impl FilterSettings {
+    /// Remove frequencies above the cutoff frequency in Hz
+    pub fn low_pass(cutoff: f64) -> Self {
+        FilterSettings {
+            mode: FilterMode::LowPass,
+            cutoff,
+            resonance: 0.,
+            mix: 1.,
+        }
+    }
+
+    /// Remove frequencies below the cutoff frequency in Hz
+    pub fn high_pass(cutoff: f64) -> Self {
+        FilterSettings {
+            mode: FilterMode::HighPass,
+            ..FilterSettings::low_pass(cutoff)
+        }
+    }
+
+    /// Remove frequencies away from the cutoff frequency in Hz
+    pub fn band_pass(cutoff: f64) -> Self {
+        FilterSettings {
+            mode: FilterMode::BandPass,
+            ..FilterSettings::low_pass(cutoff)
+        }
+    }
+
+    /// Set the resonance of the filter
+    pub fn with_resonance(mut self, resonance: f64) -> Self {
+        self.resonance = resonance;
+
+        self
+    }
+
+    /// Set how much of the filtered sound is heard
+    pub fn with_mix(mut self, mix: f64) -> Self {
+        self.mix = mix;
+
+        self
+    }
+}
+
+impl From<FilterSettings> for FilterBuilder {
+    fn from(settings: FilterSettings) -> Self {
+        FilterBuilder::new()
+            .mode(settings.mode)
+            .cutoff(settings.cutoff)
+            .resonance(settings.resonance)
+            .mix(settings.mix)
+    }
+}
+
 pub fn map_tween(tween: &Option<AudioTween>) -> kira::tween::Tween {
     match tween {
         Some(tween) => tween.into(),
@@ -106,46 +277,63 @@ impl From<&AudioTween> for kira::tween::Tween {
 }
 
 impl PartialSoundSettings {
-    pub(crate) fn apply(&self, sound: &mut StaticSoundData) {
-        if let Some(loop_start) = self.loop_start {
-            sound
-                .settings
-                .loop_region
-                .get_or_insert_with(Default::default)
-                .start = loop_start.into();
+    pub(crate) fn apply(&self, sound: &mut impl SoundLayout) {
+        let duration = sound.length().as_secs_f64();
+        let source_loop = sound.source_loop_region().filter(|_| self.source_loop);
+        let settings = sound.settings_mut();
+        // Positions of reversed sounds count from the end, as if the audio itself was reversed
+        let reverse = self.reverse.unwrap_or(settings.reverse);
+        let mirror = |position: f64| duration - position;
+        if let Some(loop_region) = source_loop {
+            settings.loop_region = Some(loop_region);
+        } else if let Some(loop_start) = self.loop_start {
+            let loop_region = settings.loop_region.get_or_insert_with(Default::default);
+            if reverse {
+                loop_region.end = EndPosition::Custom(mirror(loop_start).into());
+            } else {
+                loop_region.start = loop_start.into();
+            }
         }
         if let Some(loop_end) = self.loop_end {
-            sound
-                .settings
-                .loop_region
-                .get_or_insert_with(Default::default)
-                .end = EndPosition::Custom(loop_end.into());
+            let loop_region = settings.loop_region.get_or_insert_with(Default::default);
+            if reverse {
+                loop_region.start = mirror(loop_end).into();
+            } else {
+                loop_region.end = EndPosition::Custom(loop_end.into());
+            }
         }
         if let Some(volume) = self.volume {
-            if let Value::Fixed(channel_volume) = sound.settings.volume {
-                sound.settings.volume =
-                    Value::Fixed((volume.as_amplitude() * channel_volume.as_amplitude()).into());
+            if let Value::Fixed(channel_volume) = settings.volume {
+                settings.volume = Value::Fixed(scale_volume(volume, channel_volume.as_amplitude()));
             } else {
-                sound.settings.volume = Value::Fixed(volume);
+                settings.volume = Value::Fixed(volume);
             }
         }
         if let Some(playback_rate) = self.playback_rate {
-            sound.settings.playback_rate = playback_rate.into();
+            settings.playback_rate = playback_rate.into();
         }
         if let Some(start) = self.start_position {
-            sound.settings.playback_region.start = start.into();
+            if reverse {
+                settings.playback_region.end = EndPosition::Custom(mirror(start).into());
+            } else {
+                settings.playback_region.start = start.into();
+            }
         }
         if let Some(end) = self.end_position {
-            sound.settings.playback_region.end = EndPosition::Custom(end.into());
+            if reverse {
+                settings.playback_region.start = mirror(end).into();
+            } else {
+                settings.playback_region.end = EndPosition::Custom(end.into());
+            }
         }
         if let Some(panning) = self.panning {
-            sound.settings.panning = Value::Fixed(panning);
+            settings.panning = Value::Fixed(panning);
         }
         if let Some(reverse) = self.reverse {
-            sound.settings.reverse = reverse;
+            settings.reverse = reverse;
         }
         if let Some(AudioTween { duration, easing }) = self.fade_in {
-            sound.settings.fade_in_tween = Some(kira::tween::Tween {
+            settings.fade_in_tween = Some(kira::tween::Tween {
                 duration,
                 easing,
                 ..default()
@@ -157,6 +345,12 @@ impl PartialSoundSettings {
 pub struct PlayAudioSettings {
     pub(crate) instance_handle: Handle<AudioInstance>,
     pub(crate) source: Handle<AudioSource>,
+    /// Sources to play back to back instead of `source`
+    pub(crate) sequence: Option<Vec<Handle<AudioSource>>>,
+    /// A source to decode while playing instead of `source`
+    pub(crate) streamed: Option<Handle<StreamedAudioSource>>,
+    /// A collection to pick `source` from once it loaded
+    pub(crate) collection: Option<Handle<AudioCollection>>,
     pub(crate) settings: PartialSoundSettings,
 }
 
@@ -165,6 +359,9 @@ impl<'a> From<&mut PlayAudioCommand<'a>> for PlayAudioSettings {
         PlayAudioSettings {
             instance_handle: command.instance_handle.clone(),
             source: command.source.clone(),
+            sequence: command.sequence.clone(),
+            streamed: command.streamed.clone(),
+            collection: command.collection.clone(),
             settings: command.settings.clone(),
         }
     }
@@ -174,6 +371,9 @@ impl<'a> From<&mut PlayAudioCommand<'a>> for PlayAudioSettings {
 pub struct PlayAudioCommand<'a> {
     pub(crate) instance_handle: Handle<AudioInstance>,
     pub(crate) source: Handle<AudioSource>,
+    pub(crate) sequence: Option<Vec<Handle<AudioSource>>>,
+    pub(crate) streamed: Option<Handle<StreamedAudioSource>>,
+    pub(crate) collection: Option<Handle<AudioCollection>>,
     pub(crate) settings: PartialSoundSettings,
     pub(crate) que: &'a dyn AudioCommandQue,
 }
@@ -192,14 +392,48 @@ impl<'a> PlayAudioCommand<'a> {
         Self {
             instance_handle: Handle::<AudioInstance>::Weak(asset_id),
             source,
+            sequence: None,
+            streamed: None,
+            collection: None,
             settings: PartialSoundSettings::default(),
             que,
         }
     }
 
+    pub(crate) fn sequence(parts: &[Handle<AudioSource>], que: &'a dyn AudioCommandQue) -> Self {
+        let mut command = Self::new(parts.first().cloned().unwrap_or_default(), que);
+        command.sequence = Some(parts.to_vec());
+
+        command
+    }
+
+    pub(crate) fn streamed(
+        source: Handle<StreamedAudioSource>,
+        que: &'a dyn AudioCommandQue,
+    ) -> Self {
+        let mut command = Self::new(Handle::default(), que);
+        command.streamed = Some(source);
+
+        command
+    }
+
+    pub(crate) fn collection(
+        collection: Handle<AudioCollection>,
+        que: &'a dyn AudioCommandQue,
+    ) -> Self {
+        let mut command = Self::new(Handle::default(), que);
+        command.collection = Some(collection);
+
+        command
+    }
+
     /// Loop the playing sound.
+    ///
+    /// Sounds with a loop region in their [`AudioSource`], like ogg files with `LOOPSTART`
+    /// comments, loop that region. Otherwise, the whole sound loops.
     pub fn looped(&mut self) -> &mut Self {
         self.settings.loop_start = Some(0.0);
+        self.settings.source_loop = true;
 
         self
     }
@@ -214,6 +448,7 @@ impl<'a> PlayAudioCommand<'a> {
     /// Loop the playing sound, starting from the given position in seconds.
     pub fn loop_from(&mut self, loop_start_position: f64) -> &mut Self {
         self.settings.loop_start = Some(loop_start_position);
+        self.settings.source_loop = false;
 
         self
     }
@@ -221,6 +456,29 @@ impl<'a> PlayAudioCommand<'a> {
     /// Loop the playing sound, ending at the given position in seconds.
     pub fn loop_until(&mut self, loop_end_position: f64) -> &mut Self {
         self.settings.loop_end = Some(loop_end_position);
+        self.settings.source_loop = false;
+
+        self
+    }
+
+    /// Play the sound from its start, then loop it between the given positions in seconds.
+    ///
+    /// This allows music with an intro that plays once. The region has to be inside the sound,
+    /// with `start < end`. Otherwise, a warning is logged and the whole sound loops instead.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// fn my_system(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    ///     audio
+    ///         .play(asset_server.load("music.ogg"))
+    ///         .loop_region(12.4, 96.8);
+    /// }
+    /// ```
+    pub fn loop_region(&mut self, start: f64, end: f64) -> &mut Self {
+        self.settings.loop_start = Some(start);
+        self.settings.loop_end = Some(end);
+        self.settings.source_loop = false;
 
         self
     }
@@ -239,13 +497,90 @@ impl<'a> PlayAudioCommand<'a> {
         self
     }
 
+    /// Set the pitch of the sound in semitones.
+    ///
+    /// This sets the playback rate to `2^(semitones / 12)`, so `12.` plays the sound an octave
+    /// higher and twice as fast.
+    pub fn pitch_semitones(&mut self, semitones: f64) -> &mut Self {
+        self.settings.playback_rate = Some(PlaybackRate::Semitones(semitones).as_factor());
+
+        self
+    }
+
+    /// Vary the pitch randomly by up to the given semitones up or down.
+    ///
+    /// The playback rate of the sound is multiplied with a random factor each time it plays,
+    /// so this composes with [`with_playback_rate`](Self::with_playback_rate) and the playback
+    /// rate of the channel. Seed the [`AudioRng`](crate::AudioRng) for repeatable variations.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// fn footstep(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    ///     audio
+    ///         .play(asset_server.load("sounds/footstep.ogg"))
+    ///         .pitch_variation(1.5)
+    ///         .volume_variation(3.);
+    /// }
+    /// ```
+    pub fn pitch_variation(&mut self, semitones: f64) -> &mut Self {
+        self.settings.pitch_variation = Some(semitones);
+
+        self
+    }
+
+    /// Vary the volume randomly by up to the given decibels up or down.
+    ///
+    /// Like [`pitch_variation`](Self::pitch_variation), this composes with
+    /// [`with_volume`](Self::with_volume) and the volume of the channel.
+    pub fn volume_variation(&mut self, decibels: f64) -> &mut Self {
+        self.settings.volume_variation = Some(decibels);
+
+        self
+    }
+
     /// Start the sound from the given position in seconds.
+    ///
+    /// Looped sounds wrap a position past the end of the loop back into it. Other sounds that
+    /// would start at or after their end are not played, and a warning is logged.
     pub fn start_from(&mut self, start_position: f64) -> &mut Self {
         self.settings.start_position = Some(start_position);
 
         self
     }
 
+    /// Start the sound when the [`AudioClock`](crate::AudioClock) reaches the given time.
+    ///
+    /// The sound starts on the exact sample of that clock tick, so sounds scheduled for the same
+    /// time play in sync. A time that already passed starts the sound right away.
+    pub fn start_at(&mut self, time: AudioClockTime) -> &mut Self {
+        self.settings.start_time = Some(ScheduledStart::At(time));
+
+        self
+    }
+
+    /// Start the sound when the given clock of the [`AudioClocks`](crate::AudioClocks) reaches
+    /// the given tick.
+    ///
+    /// The sound waits until a clock added in this update runs on the audio thread.
+    /// A tick that already passed starts the sound right away.
+    pub fn start_at_clock(&mut self, clock: AudioClockId, ticks: u64) -> &mut Self {
+        self.settings.start_time = Some(ScheduledStart::Clock(clock, ticks));
+
+        self
+    }
+
+    /// Start the sound the given time after the play command is processed.
+    ///
+    /// The delay is measured on the [`AudioClock`](crate::AudioClock). Play commands of
+    /// different channels are processed at slightly different times, so use
+    /// [`start_at`](Self::start_at) to start several sounds in sync.
+    pub fn delayed_by(&mut self, delay: Duration) -> &mut Self {
+        self.settings.start_time = Some(ScheduledStart::Delayed(delay));
+
+        self
+    }
+
     /// End the sound at the given position in seconds.
     pub fn end_at(&mut self, end_position: f64) -> &mut Self {
         self.settings.end_position = Some(end_position);
@@ -265,6 +600,12 @@ impl<'a> PlayAudioCommand<'a> {
     }
 
     /// Reverse the playing sound.
+    ///
+    /// The sound then starts at its end. Positions, like the ones given to
+    /// [`start_from`](Self::start_from) or [`loop_region`](Self::loop_region), count from the
+    /// end of the sound, as if the audio itself was reversed.
+    /// To reverse an instance that is already playing, give it a negative playback rate with
+    /// [`AudioInstance::set_playback_rate`].
     pub fn reverse(&mut self) -> &mut Self {
         let current = self.settings.reverse.unwrap_or(false);
         self.settings.reverse = Some(!current);
@@ -287,6 +628,89 @@ impl<'a> PlayAudioCommand<'a> {
         self
     }
 
+    /// Set the curve of a crossfade started with [`AudioControl::crossfade_to`](crate::AudioControl::crossfade_to).
+    ///
+    /// This does nothing for sounds that are not crossfaded to.
+    pub fn crossfade_curve(&mut self, curve: CrossfadeCurve) -> &mut Self {
+        if let Some((_, crossfade_curve)) = self.settings.crossfade.as_mut() {
+            *crossfade_curve = curve;
+        }
+
+        self
+    }
+
+    /// Route the sound through its own low-pass filter with the given cutoff frequency in Hz.
+    ///
+    /// The cutoff can be changed later with [`AudioInstance::set_filter_cutoff`].
+    /// Spacial audio uses the filter to muffle distant sounds (see
+    /// [`SpacialAudio::cutoff_at_max_distance`](crate::SpacialAudio::cutoff_at_max_distance)).
+    pub fn with_low_pass_filter(&mut self, cutoff: f64) -> &mut Self {
+        self.with_filter(FilterSettings::low_pass(cutoff))
+    }
+
+    /// Route the sound through its own filter
+    ///
+    /// The sound plays on a sub track with the filter, which is dropped once the sound stopped.
+    /// Change the filter while the sound plays with [`AudioInstance::set_filter_cutoff`] and
+    /// [`AudioInstance::set_filter_resonance`]. Sounds without a filter play without the track.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// fn talk_behind_glass(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    ///     audio
+    ///         .play(asset_server.load("sounds/voice.ogg"))
+    ///         .with_filter(FilterSettings::low_pass(800.).with_resonance(0.2));
+    /// }
+    /// ```
+    pub fn with_filter(&mut self, filter: FilterSettings) -> &mut Self {
+        self.settings.filter = Some(filter);
+
+        self
+    }
+
+    /// Let a modulator of [`AudioModulators`](crate::AudioModulators) oscillate a parameter of the sound
+    ///
+    /// The parameter moves around the value it is played with by the depth, see
+    /// [`ModulatedParameter`]. The modulator can be added in the same frame. Bind or unbind
+    /// modulators later with [`AudioInstance::bind_modulator`] and
+    /// [`AudioInstance::unbind_modulator`]. Sounds on other output devices than the default one
+    /// play without modulation.
+    pub fn with_modulator(
+        &mut self,
+        parameter: ModulatedParameter,
+        modulator: AudioModulator,
+        depth: f64,
+    ) -> &mut Self {
+        self.settings
+            .modulators
+            .retain(|(bound, _, _)| *bound != parameter);
+        self.settings.modulators.push((parameter, modulator, depth));
+
+        self
+    }
+
+    /// Send part of the sound to the spacial reverb track.
+    ///
+    /// The send level is controlled by spacial audio (see
+    /// [`AudioEmitter::ambient_send`](crate::prelude::AudioEmitter::ambient_send)) or
+    /// [`AudioInstance::set_ambient_send`]. This has no effect unless
+    /// [`SpacialAudio::reverb`](crate::SpacialAudio::reverb) is set when the sound starts playing.
+    pub fn with_ambient_send(&mut self) -> &mut Self {
+        self.settings.ambient_send = true;
+
+        self
+    }
+
+    /// Route the sound through its own interaural delay.
+    ///
+    /// Spacial audio delays the sound for the ear further away from an emitter, if the receiver
+    /// has an [`ear_distance`](crate::prelude::AudioReceiver::ear_distance).
+    pub fn with_interaural_delay(&mut self) -> &mut Self {
+        self.settings.interaural_delay = true;
+
+        self
+    }
+
     /// Get the handle of the audio instance.
     pub fn handle(&mut self) -> Handle<AudioInstance> {
         self.instance_handle.clone()
@@ -296,10 +720,14 @@ impl<'a> PlayAudioCommand<'a> {
 pub(crate) enum TweenCommandKind {
     SetVolume(Volume),
     SetPanning(f64),
-    SetPlaybackRate(f64),
+    SetPlaybackRate(PlaybackRate),
     Stop,
     Pause,
     Resume,
+    SetMuted(bool),
+    SetSolo(bool),
+    SetReverbMix(f64),
+    SetSend(Channel, f64),
 }
 
 impl TweenCommandKind {
@@ -313,6 +741,12 @@ impl TweenCommandKind {
             TweenCommandKind::Stop => AudioCommand::Stop(tween),
             TweenCommandKind::Pause => AudioCommand::Pause(tween),
             TweenCommandKind::Resume => AudioCommand::Resume(tween),
+            TweenCommandKind::SetMuted(muted) => AudioCommand::SetMuted(*muted, tween),
+            TweenCommandKind::SetSolo(solo) => AudioCommand::SetSolo(*solo, tween),
+            TweenCommandKind::SetReverbMix(mix) => AudioCommand::SetReverbMix(*mix, tween),
+            TweenCommandKind::SetSend(target, level) => {
+                AudioCommand::SetSend(target.clone(), *level, tween)
+            }
         }
     }
 }
@@ -387,7 +821,7 @@ pub enum AudioCommandResult {
 }
 
 /// Playback status of a currently playing sound.
-#[derive(Clone, Copy, Debug, PartialOrd, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialOrd, PartialEq, Reflect)]
 pub enum PlaybackState {
     /// The instance is paused.
     Paused {
@@ -428,6 +862,16 @@ impl PlaybackState {
             | PlaybackState::Stopping { position } => Some(*position),
         }
     }
+
+    pub(crate) fn new(state: kira::sound::PlaybackState, position: f64) -> Self {
+        match state {
+            kira::sound::PlaybackState::Playing => PlaybackState::Playing { position },
+            kira::sound::PlaybackState::Paused => PlaybackState::Paused { position },
+            kira::sound::PlaybackState::Stopped => PlaybackState::Stopped,
+            kira::sound::PlaybackState::Pausing => PlaybackState::Pausing { position },
+            kira::sound::PlaybackState::Stopping => PlaybackState::Stopping { position },
+        }
+    }
 }
 
 impl From<StaticSoundHandle> for PlaybackState {
@@ -438,14 +882,7 @@ impl From<StaticSoundHandle> for PlaybackState {
 
 impl From<&StaticSoundHandle> for PlaybackState {
     fn from(sound_handle: &StaticSoundHandle) -> Self {
-        let position = sound_handle.position();
-        match sound_handle.state() {
-            kira::sound::PlaybackState::Playing => PlaybackState::Playing { position },
-            kira::sound::PlaybackState::Paused => PlaybackState::Paused { position },
-            kira::sound::PlaybackState::Stopped => PlaybackState::Stopped,
-            kira::sound::PlaybackState::Pausing => PlaybackState::Pausing { position },
-            kira::sound::PlaybackState::Stopping => PlaybackState::Stopping { position },
-        }
+        PlaybackState::new(sound_handle.state(), sound_handle.position())
     }
 }
 
@@ -460,7 +897,7 @@ pub trait AudioApp {
     /// fn main() {
     ///     App::new()
     ///         .add_plugins(DefaultPlugins)
-    ///         .add_plugins(AudioPlugin)
+    ///         .add_plugins(AudioPlugin::default())
     ///         .add_audio_channel::<Background>()
     ///         .add_systems(Startup, play)
     ///         .run();
@@ -474,6 +911,114 @@ pub trait AudioApp {
     /// struct Background;
     /// ```
     fn add_audio_channel<T: Resource>(&mut self) -> &mut Self;
+
+    /// Add a new audio channel to the application that starts with the given settings
+    ///
+    /// The settings are applied before anything plays in the channel, so sounds played in the
+    /// first frames already use them.
+    ///
+    /// ```no_run
+    /// use bevy::prelude::*;
+    /// use bevy_kira_audio::prelude::*;
+    ///
+    /// fn main() {
+    ///     App::new()
+    ///         .add_plugins(DefaultPlugins)
+    ///         .add_plugins(AudioPlugin::default())
+    ///         .add_audio_channel_with_settings::<Music>(ChannelSettings {
+    ///             volume: Volume::Amplitude(0.4),
+    ///             ..default()
+    ///         })
+    ///         .run();
+    /// }
+    ///
+    /// #[derive(Resource)]
+    /// struct Music;
+    /// ```
+    fn add_audio_channel_with_settings<T: Resource>(
+        &mut self,
+        settings: ChannelSettings,
+    ) -> &mut Self;
+
+    /// Make the audio channel `P` the parent of the audio channel `T`
+    ///
+    /// The volume of `P` then also applies to all sounds played on `T`.
+    /// See [`ChannelHierarchy`] for changing parents at runtime.
+    ///
+    /// ```no_run
+    /// use bevy::prelude::*;
+    /// use bevy_kira_audio::prelude::*;
+    ///
+    /// fn main() {
+    ///     App::new()
+    ///         .add_plugins(DefaultPlugins)
+    ///         .add_plugins(AudioPlugin::default())
+    ///         .add_audio_channel::<Sfx>()
+    ///         .add_audio_channel::<Footsteps>()
+    ///         .set_audio_channel_parent::<Footsteps, Sfx>()
+    ///         .run();
+    /// }
+    ///
+    /// #[derive(Resource)]
+    /// struct Sfx;
+    /// #[derive(Resource)]
+    /// struct Footsteps;
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `P` is `T` or one of its descendants.
+    fn set_audio_channel_parent<T: Resource, P: Resource>(&mut self) -> &mut Self;
+
+    /// Play all sounds of the audio channel `T` through a reverb
+    ///
+    /// See [`AudioControl::set_reverb`](crate::AudioControl::set_reverb).
+    ///
+    /// ```no_run
+    /// use bevy::prelude::*;
+    /// use bevy_kira_audio::prelude::*;
+    ///
+    /// fn main() {
+    ///     App::new()
+    ///         .add_plugins(DefaultPlugins)
+    ///         .add_plugins(AudioPlugin::default())
+    ///         .add_audio_channel::<CaveSfx>()
+    ///         .set_audio_channel_reverb::<CaveSfx>(ChannelReverb::default())
+    ///         .run();
+    /// }
+    ///
+    /// #[derive(Resource)]
+    /// struct CaveSfx;
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel `T` was not added with [`add_audio_channel`](Self::add_audio_channel).
+    fn set_audio_channel_reverb<T: Resource>(&mut self, reverb: ChannelReverb) -> &mut Self;
+
+    /// Add a new audio channel that plays all sounds through a chain of effects
+    ///
+    /// The handles of the effects are available in the [`ChannelEffects<T>`] resource.
+    ///
+    /// ```no_run
+    /// use bevy::prelude::*;
+    /// use bevy_kira_audio::prelude::*;
+    /// use kira::track::effect::filter::FilterBuilder;
+    ///
+    /// fn main() {
+    ///     App::new()
+    ///         .add_plugins(DefaultPlugins)
+    ///         .add_plugins(AudioPlugin::default())
+    ///         .add_audio_channel_with_effects::<Sfx>(
+    ///             EffectChain::new().with(FilterBuilder::new().cutoff(20_000.)),
+    ///         )
+    ///         .run();
+    /// }
+    ///
+    /// #[derive(Resource)]
+    /// struct Sfx;
+    /// ```
+    fn add_audio_channel_with_effects<T: Resource>(&mut self, effects: EffectChain) -> &mut Self;
 }
 
 impl AudioApp for App {
@@ -486,6 +1031,76 @@ impl AudioApp for App {
             PreUpdate,
             update_instance_states::<T>.after(AudioSystemSet::InstanceCleanup),
         )
-        .insert_resource(AudioChannel::<T>::default())
+        .insert_resource(AudioChannel::<T>::default());
+        self.world
+            .get_resource_or_insert_with(TypedAudioChannels::default)
+            .register::<T>();
+
+        self
+    }
+
+    fn add_audio_channel_with_settings<T: Resource>(
+        &mut self,
+        settings: ChannelSettings,
+    ) -> &mut Self {
+        self.add_audio_channel::<T>();
+        self.world
+            .resource::<AudioChannel<T>>()
+            .que(AudioCommand::ApplySettings(settings));
+
+        self
+    }
+
+    fn set_audio_channel_parent<T: Resource, P: Resource>(&mut self) -> &mut Self {
+        let mut hierarchy = self
+            .world
+            .get_resource_or_insert_with(ChannelHierarchy::default);
+        if let Err(error) = hierarchy.set_parent(Channel::typed::<T>(), Channel::typed::<P>()) {
+            panic!("{}", error);
+        }
+
+        self
+    }
+
+    fn add_audio_channel_with_effects<T: Resource>(&mut self, effects: EffectChain) -> &mut Self {
+        self.add_audio_channel::<T>();
+        self.world
+            .resource::<AudioChannel<T>>()
+            .que(AudioCommand::SetEffects(Mutex::new(Some(effects.track))));
+
+        self.insert_resource(ChannelEffects::<T>::new(effects.handles))
+    }
+
+    fn set_audio_channel_reverb<T: Resource>(&mut self, reverb: ChannelReverb) -> &mut Self {
+        self.world
+            .resource::<AudioChannel<T>>()
+            .que(AudioCommand::SetReverb(reverb));
+
+        self
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn tweens_and_curves_round_trip() {
+        let tween = AudioTween::new(Duration::from_millis(250), AudioEasing::InOutPowi(3));
+        let saved = ron::to_string(&tween).unwrap();
+        assert_eq!(ron::from_str::<AudioTween>(&saved).unwrap(), tween);
+        assert_eq!(
+            ron::from_str::<AudioTween>("()").unwrap(),
+            AudioTween::default()
+        );
+
+        for curve in [CrossfadeCurve::Linear, CrossfadeCurve::EqualPower] {
+            let saved = ron::to_string(&curve).unwrap();
+            assert_eq!(ron::from_str::<CrossfadeCurve>(&saved).unwrap(), curve);
+        }
+
+        let filter = FilterSettings::high_pass(300.).with_mix(0.5);
+        let saved = ron::to_string(&filter).unwrap();
+        assert_eq!(ron::from_str::<FilterSettings>(&saved).unwrap(), filter);
     }
 }
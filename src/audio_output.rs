@@ -1,52 +1,323 @@
 //! The internal audio systems and resource
 
-use crate::audio::{map_tween, AudioCommand, AudioCommandResult, AudioTween, PartialSoundSettings};
+use crate::audio::{
+    map_tween, AudioCommand, AudioCommandResult, AudioTween, FilterSettings, PartialSoundSettings,
+    PlayAudioSettings,
+};
 use std::any::TypeId;
 
-use crate::backend_settings::AudioSettings;
+use crate::backend_settings::{AudioSettings, FocusLoss};
+use crate::channel::ducking::{ChannelDucking, DuckSettings};
 use crate::channel::dynamic::DynamicAudioChannels;
+use crate::channel::effects::{
+    ChannelReverb, EffectChain, EffectHandles, MainEffects, MainTrackEffects,
+};
+use crate::channel::hierarchy::ChannelHierarchy;
+use crate::channel::limit::make_room;
+use crate::channel::queue::{ChannelQueues, QueueAdvanced, QUEUE_START_DELAY};
 use crate::channel::typed::AudioChannel;
 use crate::channel::{Channel, ChannelState};
-use crate::instance::AudioInstance;
-use crate::source::AudioSource;
+use crate::clock::{AudioClock, AudioClockTime, AudioClocks};
+use crate::instance::{
+    has_stopped, AudioCommandError, AudioError, AudioErrorKind, AudioInstance, InstanceCompletions,
+    InstanceTrack, LoopCompleted, LoopTracker, PlaybackCompleted, SoundHandle, SoundTiming,
+};
+use crate::main_playback::{pause_manager, set_main_volume, MainPlayback, FOCUS_FADE};
+#[cfg(feature = "mock-backend")]
+use crate::mock::MockAudio;
+use crate::modulator::{AudioModulators, Lfos, Modulations};
+use crate::output_device::{
+    find_output_device, AudioBackendStatus, AudioContextState, AudioDevice, AudioDeviceChanged,
+    AudioDeviceFailed, AudioDeviceLost, MainBackend, OutputDeviceError, OutputDevices,
+};
+use crate::source::collection::{AudioCollection, CollectionPickers};
+use crate::source::streamed::StreamedAudioSource;
+use crate::source::{AudioSource, PlayedSound, SoundLayout};
+use crate::spacial::interaural_delay::InterauralDelayBuilder;
+use crate::spacial::zone::{zone_receiver, EnteredReverbZones, ReverbZone, ZoneMix, ZoneReceivers};
+use crate::spacial::{
+    AudioEmitter, AudioReceiver, PrimaryReceiver, SpacialAudio, SpacialBackend, SpacialMode,
+    SpacialReverb,
+};
+use crate::variation::{AudioRng, Variation};
+use crate::volume::{finite_volume, scale_volume};
 use crate::PlaybackState;
-use bevy::asset::{Assets, Handle};
-use bevy::ecs::change_detection::{NonSendMut, ResMut};
-use bevy::ecs::system::{NonSend, Res, Resource};
+use bevy::app::AppExit;
+use bevy::asset::{AssetId, Assets, Handle, UntypedHandle};
+use bevy::ecs::change_detection::{DetectChanges, DetectChangesMut, NonSendMut, ResMut};
+use bevy::ecs::entity::Entity;
+use bevy::ecs::event::{EventReader, EventWriter, Events};
+use bevy::ecs::query::{Has, With};
+use bevy::ecs::system::Query;
+use bevy::ecs::system::{Local, NonSend, Res, Resource};
+use bevy::ecs::world::Ref;
 use bevy::ecs::world::{FromWorld, World};
+use bevy::input::keyboard::KeyboardInput;
+use bevy::input::mouse::MouseButtonInput;
+use bevy::input::touch::TouchInput;
 use bevy::log::{error, warn};
-use kira::manager::backend::{Backend, DefaultBackend};
-use kira::manager::AudioManager;
-use kira::{sound::PlaybackRate, CommandError, Volume};
-use std::collections::HashMap;
+use bevy::math::{Quat, Vec3};
+use bevy::time::{Real, Time};
+use bevy::transform::components::GlobalTransform;
+use bevy::utils::{HashSet, Uuid};
+use kira::manager::backend::Backend;
+use kira::manager::{AudioManager, AudioManagerSettings, MainPlaybackState};
+use kira::sound::{EndPosition, PlaybackPosition, Region};
+use kira::spatial::emitter::{EmitterHandle, EmitterSettings};
+use kira::spatial::listener::{ListenerHandle, ListenerSettings};
+use kira::spatial::scene::{SpatialSceneHandle, SpatialSceneSettings};
+use kira::track::effect::filter::FilterBuilder;
+use kira::track::effect::reverb::{ReverbBuilder, ReverbHandle};
+use kira::track::{TrackBuilder, TrackHandle, TrackId, TrackRoutes};
+use kira::tween::{Tween, Value};
+use kira::{sound::PlaybackRate, CommandError, StartTime, Volume};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Non-send resource that acts as audio output
 ///
 /// This struct holds the [`AudioManager`] to play audio through. It also
 /// keeps track of all audio instance handles and which sounds are playing in which channel.
-pub(crate) struct AudioOutput<B: Backend = DefaultBackend> {
+pub(crate) struct AudioOutput<B: Backend = MainBackend> {
     manager: Option<AudioManager<B>>,
+    devices: OutputDevices,
     instances: HashMap<Channel, Vec<Handle<AudioInstance>>>,
+    /// Commands left in the queue of each channel after the last update
+    pending_commands: HashMap<Channel, usize>,
+    /// Instances of play commands left in the queue of each channel, waiting for their source
+    pending_plays: HashMap<Channel, HashSet<AssetId<AudioInstance>>>,
+    /// Play commands that failed, see [`AudioDiagnosticsPlugin`](crate::AudioDiagnosticsPlugin)
+    dropped_commands: u64,
+    /// Failures of commands to send as [`AudioError`] events
+    errors: Vec<AudioError>,
+    /// Played instances until they complete, see [`PlaybackCompleted`]
+    completions: InstanceCompletions,
     channels: HashMap<Channel, ChannelState>,
+    spacial_reverb: Option<SpacialReverbTrack>,
+    kira_spacial: Option<KiraSpacialScene>,
+    buses: HashMap<Channel, ChannelBus>,
+    /// Channels that are soloed, all other channels are muted while this is not empty
+    soloed: HashSet<Channel>,
+    channel_tracks: HashMap<Channel, ChannelTrack>,
+    send_tracks: HashMap<Channel, SendTrack>,
+    /// Replaced tracks and tracks of removed channels, kept until their instances stopped
+    retired_tracks: Vec<(TrackHandle, Vec<Handle<AudioInstance>>)>,
+    /// Ducking of channels, see [`AudioControl::duck_by`](crate::AudioControl::duck_by)
+    ducking: ChannelDucking,
+    settings: AudioSettings,
+    main_effects: Option<MainTrackEffects>,
+    /// Handles of the effects on the main track of a new manager, see [`MainEffects`]
+    new_main_effects: Option<EffectHandles>,
+    /// Master volume, window focus and speed of virtual time
+    main_playback: MainPlayback,
+    /// Whether the browser did not allow audio yet, see [`AudioContextState`]
+    suspended: bool,
+    clock: AudioClock,
+    clocks: AudioClocks,
+    rng: AudioRng,
+    /// Resumes of paused channels with the remaining time, see [`AudioControl::pause_for`](crate::AudioControl::pause_for)
+    scheduled_resumes: HashMap<Channel, (Duration, AudioTween)>,
+    /// Sounds to play one after the other, see [`AudioControl::enqueue`](crate::AudioControl::enqueue)
+    queues: ChannelQueues,
+    /// LFOs of the main manager, see [`AudioModulators`]
+    lfos: Lfos,
+    /// Loaded collections with the variants they played, see [`AudioCollection`]
+    collections: CollectionPickers,
+    /// Log and time of the mock backend, see [`MockAudio`]
+    #[cfg(feature = "mock-backend")]
+    mock: Option<MockAudio>,
+}
+
+/// The track that sounds of a channel with sends play on
+///
+/// It routes the dry signal on unchanged and sends to the tracks of effect channels.
+struct SendTrack {
+    track: TrackHandle,
+    /// Send levels by target channel
+    levels: HashMap<Channel, f64>,
+    /// Target channels that the track has a route to
+    routes: HashMap<Channel, TrackId>,
+}
+
+/// The track that all sounds of a channel with effects play on
+struct ChannelTrack {
+    track: TrackHandle,
+    reverb: Option<ReverbHandle>,
+}
+
+/// The track that sounds of child channels play on, see [`ChannelHierarchy`]
+///
+/// The volume of the track follows the volume of the channel.
+struct ChannelBus {
+    track: TrackHandle,
+    parent: Option<Channel>,
+    children: Vec<Channel>,
+    /// Created in the last update, so the buses of children have to be recreated as well
+    recreated: bool,
+}
+
+/// The reverb track that spacial instances send their ambient signal to
+struct SpacialReverbTrack {
+    track: TrackHandle,
+    reverb: ReverbHandle,
+    settings: SpacialReverb,
+}
+
+/// The kira spatial scene used by [`SpacialBackend::Kira`]
+struct KiraSpacialScene {
+    scene: SpatialSceneHandle,
+    listener: Option<ListenerHandle>,
+    emitters: HashMap<Entity, EmitterHandle>,
+    /// Emitter entities of instances that did not start playing yet
+    pending: HashMap<AssetId<AudioInstance>, Entity>,
+    /// Whether new instances on emitters are routed to the scene
+    active: bool,
+    channels: Option<HashSet<Channel>>,
+}
+
+/// Receiver and emitter transforms to sync to the kira spatial scene
+pub(crate) struct KiraSpacialSync<'a> {
+    pub(crate) listener: Option<(Vec3, Quat, bool)>,
+    pub(crate) emitters: Vec<(Entity, Vec3, bool, &'a AudioEmitter)>,
+    pub(crate) pending: HashMap<AssetId<AudioInstance>, Entity>,
 }
 
 impl FromWorld for AudioOutput {
     fn from_world(world: &mut World) -> Self {
         let settings = world.remove_resource::<AudioSettings>().unwrap_or_default();
-        let manager = AudioManager::new(settings.into());
-        if let Err(ref setup_error) = manager {
-            warn!("Failed to setup audio: {:?}", setup_error);
+        let main_effects = world.remove_resource::<MainTrackEffects>();
+        #[cfg(feature = "mock-backend")]
+        let mock = world.get_resource::<MockAudio>().cloned();
+        #[cfg(feature = "mock-backend")]
+        let mocked = mock.is_some();
+        #[cfg(not(feature = "mock-backend"))]
+        let mocked = false;
+        let mut device = world.get_resource_or_insert_with(AudioDevice::default);
+        let device_manager = device.name.clone().filter(|_| !mocked).and_then(|name| {
+            find_output_device(&name)
+                .and_then(|output| {
+                    open_main_manager(main_effects.as_ref(), |main_track| {
+                        settings.manager_settings(Some(output), main_track)
+                    })
+                })
+                .map_err(|error| {
+                    warn!(
+                        "Failed to open output device {:?}, playing on the default device: {}",
+                        name, error
+                    );
+                    device.bypass_change_detection().name = None;
+                })
+                .ok()
+        });
+        let device = device.name.clone();
+        let manager = match device_manager {
+            Some(manager) => Ok(manager),
+            None if mocked => open_main_manager(main_effects.as_ref(), |main_track| {
+                settings.headless_manager_settings(main_track)
+            }),
+            None => open_main_manager(main_effects.as_ref(), |main_track| {
+                settings.manager_settings(None, main_track)
+            }),
+        };
+        let (manager, status) = match manager {
+            Ok((mut manager, effects)) => {
+                let sample_rate = manager.backend_mut().sample_rate();
+                (
+                    Some((manager, effects)),
+                    AudioBackendStatus::Running { sample_rate },
+                )
+            }
+            Err(setup_error) if settings.headless_fallback => {
+                warn!(
+                    "Failed to setup audio, playing without output: {:?}",
+                    setup_error
+                );
+                let manager = open_main_manager(main_effects.as_ref(), |main_track| {
+                    settings.headless_manager_settings(main_track)
+                })
+                .map_err(|error| error!("Failed to setup headless audio: {:?}", error))
+                .ok();
+                (manager, AudioBackendStatus::Headless(Arc::new(setup_error)))
+            }
+            Err(setup_error) => {
+                warn!("Failed to setup audio: {:?}", setup_error);
+                (None, AudioBackendStatus::Unavailable(Arc::new(setup_error)))
+            }
+        };
+        let suspended = cfg!(target_arch = "wasm32") && status.is_running();
+        world.insert_resource(status);
+        let (mut manager, effects) = manager.unzip();
+        world.insert_resource(MainEffects::new(effects.unwrap_or_default()));
+        world.insert_resource(if suspended {
+            AudioContextState::Suspended
+        } else {
+            AudioContextState::Running
+        });
+        let clock = manager
+            .as_mut()
+            .map_or_else(AudioClock::default, AudioClock::start);
+        world.insert_resource(clock.clone());
+        let clocks = world
+            .get_resource_or_insert_with(AudioClocks::default)
+            .clone();
+        let rng = world.get_resource_or_insert_with(AudioRng::default).clone();
+
+        AudioOutput {
+            devices: OutputDevices::new(device),
+            main_effects,
+            suspended,
+            #[cfg(feature = "mock-backend")]
+            mock,
+            ..AudioOutput::new(manager, settings, clock, clocks, rng)
         }
+    }
+}
 
-        Self {
-            manager: manager.ok(),
+impl<B: Backend> AudioOutput<B> {
+    /// An audio output on the given manager, without any channels or instances yet
+    fn new(
+        manager: Option<AudioManager<B>>,
+        settings: AudioSettings,
+        clock: AudioClock,
+        clocks: AudioClocks,
+        rng: AudioRng,
+    ) -> Self {
+        AudioOutput {
+            manager,
+            devices: OutputDevices::default(),
             instances: HashMap::default(),
+            pending_commands: HashMap::default(),
+            pending_plays: HashMap::default(),
+            dropped_commands: 0,
+            errors: vec![],
+            completions: InstanceCompletions::default(),
             channels: HashMap::default(),
+            spacial_reverb: None,
+            kira_spacial: None,
+            buses: HashMap::default(),
+            soloed: HashSet::default(),
+            channel_tracks: HashMap::default(),
+            send_tracks: HashMap::default(),
+            retired_tracks: vec![],
+            ducking: ChannelDucking::default(),
+            settings,
+            main_effects: None,
+            new_main_effects: None,
+            main_playback: MainPlayback::default(),
+            suspended: false,
+            clock,
+            clocks,
+            rng,
+            scheduled_resumes: HashMap::default(),
+            queues: ChannelQueues::default(),
+            lfos: Lfos::default(),
+            collections: CollectionPickers::default(),
+            #[cfg(feature = "mock-backend")]
+            mock: None,
         }
     }
-}
 
-impl<B: Backend> AudioOutput<B> {
     fn stop(
         &mut self,
         channel: &Channel,
@@ -55,14 +326,18 @@ impl<B: Backend> AudioOutput<B> {
     ) -> AudioCommandResult {
         if let Some(instances) = self.instances.get_mut(channel) {
             let tween = map_tween(tween);
-            for instance in instances {
-                if let Some(instance) = audio_instances.get_mut(instance.id()) {
+            for handle in instances {
+                if let Some(instance) = audio_instances.get_mut(handle.id()) {
+                    instance.stop_requested = true;
+                    instance.start_fade(&tween);
                     match instance.handle.stop(tween) {
                         Err(CommandError::CommandQueueFull) => {
                             return AudioCommandResult::Retry;
                         }
                         Err(error) => {
                             error!("Failed to stop instance: {:?}", error);
+                            self.errors
+                                .push(AudioError::new(error, channel, Some(handle.clone())));
                         }
                         _ => (),
                     }
@@ -81,11 +356,14 @@ impl<B: Backend> AudioOutput<B> {
     ) {
         if let Some(instance_handles) = self.instances.get_mut(channel) {
             let tween = map_tween(tween);
-            for instance in instance_handles.iter_mut() {
-                if let Some(instance) = audio_instances.get_mut(instance.id()) {
+            for handle in instance_handles.iter() {
+                if let Some(instance) = audio_instances.get_mut(handle.id()) {
                     if kira::sound::PlaybackState::Playing == instance.handle.state() {
+                        instance.start_fade(&tween);
                         if let Err(error) = instance.handle.pause(tween) {
                             error!("Failed to pause instance: {:?}", error);
+                            self.errors
+                                .push(AudioError::new(error, channel, Some(handle.clone())));
                         }
                     }
                 }
@@ -108,16 +386,24 @@ impl<B: Backend> AudioOutput<B> {
         audio_instances: &mut Assets<AudioInstance>,
         tween: &Option<AudioTween>,
     ) {
+        let tween = tween.clone().or_else(|| {
+            self.channels
+                .get(channel)
+                .and_then(|channel_state| channel_state.resume_tween.clone())
+        });
         if let Some(instances) = self.instances.get_mut(channel) {
-            let tween = map_tween(tween);
-            for instance in instances.iter_mut() {
-                if let Some(instance) = audio_instances.get_mut(instance.id()) {
+            let tween = map_tween(&tween);
+            for handle in instances.iter() {
+                if let Some(instance) = audio_instances.get_mut(handle.id()) {
                     if instance.handle.state() == kira::sound::PlaybackState::Paused
                         || instance.handle.state() == kira::sound::PlaybackState::Pausing
                         || instance.handle.state() == kira::sound::PlaybackState::Stopping
                     {
+                        instance.start_fade(&tween);
                         if let Err(error) = instance.handle.resume(tween) {
                             error!("Failed to resume instance: {:?}", error);
+                            self.errors
+                                .push(AudioError::new(error, channel, Some(handle.clone())));
                         }
                     }
                 }
@@ -140,14 +426,31 @@ impl<B: Backend> AudioOutput<B> {
     ) {
         if let Some(instances) = self.instances.get_mut(channel) {
             let tween = map_tween(tween);
-            for instance in instances.iter_mut() {
-                if let Some(instance) = audio_instances.get_mut(instance.id()) {
-                    if let Err(error) = instance.handle.set_volume(volume, tween) {
+            for handle in instances.iter() {
+                if let Some(instance) = audio_instances.get_mut(handle.id()) {
+                    instance.volume = finite_volume(volume);
+                    if instance.muted {
+                        continue;
+                    }
+                    let volume = scale_volume(volume, instance.duck * instance.gain);
+                    instance.start_fade(&tween);
+                    if let Err(error) = instance.set_handle_volume(volume, tween) {
                         error!("Failed to set volume for instance: {:?}", error);
+                        self.errors
+                            .push(AudioError::new(error, channel, Some(handle.clone())));
                     }
                 }
             }
         }
+        if let Some(bus) = self.buses.get_mut(channel) {
+            if let Err(error) = bus
+                .track
+                .set_volume(finite_volume(volume), map_tween(tween))
+            {
+                error!("Failed to set volume for channel bus: {:?}", error);
+                self.errors.push(AudioError::new(error, channel, None));
+            }
+        }
         if let Some(channel_state) = self.channels.get_mut(channel) {
             channel_state.volume = volume;
         } else {
@@ -168,10 +471,12 @@ impl<B: Backend> AudioOutput<B> {
     ) {
         if let Some(instances) = self.instances.get_mut(channel) {
             let tween = map_tween(tween);
-            for instance in instances.iter_mut() {
-                if let Some(instance) = audio_instances.get_mut(instance.id()) {
-                    if let Err(error) = instance.handle.set_panning(panning, tween) {
+            for handle in instances.iter() {
+                if let Some(instance) = audio_instances.get_mut(handle.id()) {
+                    if let Err(error) = instance.set_handle_panning(panning, tween) {
                         error!("Failed to set panning for instance: {:?}", error);
+                        self.errors
+                            .push(AudioError::new(error, channel, Some(handle.clone())));
                     }
                 }
             }
@@ -187,19 +492,58 @@ impl<B: Backend> AudioOutput<B> {
         }
     }
 
+    /// Seek all instances of the channel with the given closure
+    ///
+    /// The next sound of the channel queue is scheduled again for the new end of the current one.
+    fn seek(
+        &mut self,
+        channel: &Channel,
+        audio_instances: &mut Assets<AudioInstance>,
+        seek: impl Fn(&mut AudioInstance) -> Option<AudioCommandError>,
+    ) {
+        for handle in self.instances.get(channel).into_iter().flatten() {
+            if let Some(instance) = audio_instances.get_mut(handle) {
+                if let Some(error) = seek(instance) {
+                    error!("Failed to seek instance: {:?}", error);
+                    self.errors
+                        .push(AudioError::new(error, channel, Some(handle.clone())));
+                }
+            }
+        }
+        self.queues
+            .reschedule(channel, self.clock.now(), audio_instances);
+    }
+
     fn set_playback_rate(
         &mut self,
         channel: &Channel,
         audio_instances: &mut Assets<AudioInstance>,
-        playback_rate: f64,
+        rate: PlaybackRate,
         tween: &Option<AudioTween>,
     ) {
+        let playback_rate = rate.as_factor();
         if let Some(instances) = self.instances.get_mut(channel) {
             let tween = map_tween(tween);
-            for instance in instances.iter_mut() {
-                if let Some(instance) = audio_instances.get_mut(instance.id()) {
-                    if let Err(error) = instance.handle.set_playback_rate(playback_rate, tween) {
+            for handle in instances.iter() {
+                if let Some(instance) = audio_instances.get_mut(handle.id()) {
+                    if playback_rate < 0. && instance.handle.is_streamed() {
+                        error!(
+                            "Failed to set playback rate for instance: {}",
+                            AudioCommandError::NotSupportedForStreamedSound
+                        );
+                        self.errors.push(AudioError::new(
+                            AudioCommandError::NotSupportedForStreamedSound,
+                            channel,
+                            Some(handle.clone()),
+                        ));
+                        continue;
+                    }
+                    instance.playback_rate = playback_rate;
+                    let rate = instance.scale_rate(rate);
+                    if let Err(error) = instance.set_handle_playback_rate(rate, tween) {
                         error!("Failed to set playback rate for instance: {:?}", error);
+                        self.errors
+                            .push(AudioError::new(error, channel, Some(handle.clone())));
                     }
                 }
             }
@@ -219,11 +563,10 @@ impl<B: Backend> AudioOutput<B> {
         &mut self,
         channel: &Channel,
         partial_sound_settings: &PartialSoundSettings,
-        audio_source: &AudioSource,
+        mut sound: PlayedSound,
         instance_handle: Handle<AudioInstance>,
         audio_instances: &mut Assets<AudioInstance>,
     ) -> AudioCommandResult {
-        let mut sound = audio_source.sound.clone();
         if let Some(channel_state) = self.channels.get(channel) {
             channel_state.apply(&mut sound);
             // This is reverted after pausing the sound handle.
@@ -236,12 +579,254 @@ impl<B: Backend> AudioOutput<B> {
             sound.settings.playback_rate = kira::tween::Value::Fixed(PlaybackRate::Factor(0.0));
         }
         partial_sound_settings.apply(&mut sound);
-        let sound_handle = self.manager.as_mut().unwrap().play(sound);
-        if let Err(error) = sound_handle {
-            warn!("Failed to play sound due to {:?}", error);
+        if !has_valid_loop_region(&sound) {
+            warn!(
+                "The loop region of a sound is not inside of it, looping the whole sound instead"
+            );
+            sound.settings.loop_region = Some(Region::default());
+        }
+        if starts_after_end(&sound) {
+            warn!("Not playing a sound, because its start position is after its end");
+            self.errors.push(AudioError::new(
+                AudioErrorKind::StartsAfterEnd,
+                channel,
+                Some(instance_handle),
+            ));
+            return AudioCommandResult::Ok;
+        }
+        let crossfade = partial_sound_settings
+            .crossfade
+            .map(|(duration, curve)| (duration, curve.tweens(duration)));
+        if let Some((duration, (_, fade_out))) = &crossfade {
+            self.fade_out_for_crossfade(channel, audio_instances, *duration, fade_out);
+        }
+        if !self.make_room_for_instance(channel, audio_instances) {
+            self.errors.push(AudioError::new(
+                AudioErrorKind::InstanceLimitReached,
+                channel,
+                Some(instance_handle),
+            ));
+            return AudioCommandResult::Ok;
+        }
+        let variation = Variation::sample(
+            partial_sound_settings.pitch_variation,
+            partial_sound_settings.volume_variation,
+            &self.rng,
+        );
+        let time_scale = if self.follows_virtual_time(channel) {
+            self.main_playback.time_speed()
+        } else {
+            1.
+        };
+        if let Value::Fixed(playback_rate) = sound.settings.playback_rate {
+            sound.settings.playback_rate = Value::Fixed(PlaybackRate::Factor(
+                playback_rate.as_factor() * variation.playback_rate * time_scale,
+            ));
+        }
+        if let Value::Fixed(volume) = sound.settings.volume {
+            sound.settings.volume = Value::Fixed(scale_volume(volume, variation.volume));
+        }
+        if let Some(start_time) = partial_sound_settings.start_time {
+            sound.settings.start_time = start_time.start_time(&self.clock, &self.clocks);
+        }
+        // The rate once a paused sound resumes
+        let playback_rate = partial_sound_settings.playback_rate.unwrap_or_else(|| {
+            self.channels
+                .get(channel)
+                .map_or(1., |channel_state| channel_state.playback_rate)
+        }) * variation.playback_rate;
+        if sound.is_streamed() && (sound.settings.reverse || playback_rate < 0.) {
+            self.dropped_commands += 1;
+            error!(
+                "Not playing a sound: {}",
+                AudioCommandError::NotSupportedForStreamedSound
+            );
+            self.errors.push(AudioError::new(
+                AudioCommandError::NotSupportedForStreamedSound,
+                channel,
+                Some(instance_handle),
+            ));
             return AudioCommandResult::Ok;
         }
-        let mut sound_handle = sound_handle.unwrap();
+        let region_end = match sound.settings.playback_region.end {
+            EndPosition::EndOfAudio => sound.duration().as_secs_f64(),
+            EndPosition::Custom(end) => seconds(&sound, end),
+        };
+        let timing = SoundTiming {
+            duration: sound.duration().as_secs_f64(),
+            region: (
+                seconds(&sound, sound.settings.playback_region.start),
+                region_end,
+            ),
+            reverse: sound.settings.reverse,
+            loop_region: loop_region_seconds(&sound),
+        };
+        let start_position = if timing.reverse {
+            timing.region.1
+        } else {
+            timing.region.0
+        };
+        let loops = LoopTracker::new(timing.loop_region, timing.reverse, start_position);
+        let fade_in_tween = sound.settings.fade_in_tween;
+        let sound_volume = match sound.settings.volume {
+            Value::Fixed(volume) => finite_volume(volume),
+            _ => Volume::Amplitude(1.),
+        };
+        let base_volume = sound_volume.as_amplitude();
+        let output_device = self
+            .channels
+            .get(channel)
+            .and_then(|channel_state| channel_state.output_device.as_ref());
+        let kira_emitter = self.kira_spacial.as_ref().and_then(|kira_spacial| {
+            if !kira_spacial.active
+                || output_device.is_some()
+                || !kira_spacial
+                    .channels
+                    .as_ref()
+                    .map_or(true, |channels| channels.contains(channel))
+            {
+                return None;
+            }
+            kira_spacial
+                .pending
+                .get(&instance_handle.id())
+                .and_then(|entity| kira_spacial.emitters.get(entity))
+                .map(|emitter| emitter.id())
+        });
+        // Kira sets the volume of instances on its emitters
+        let start_silent = partial_sound_settings.start_silent && kira_emitter.is_none();
+        let volume = if start_silent {
+            Volume::Amplitude(0.)
+        } else {
+            sound_volume
+        };
+        let muted = !self.is_audible(channel);
+        let zone = self
+            .channels
+            .get(channel)
+            .map_or_else(ZoneMix::default, |channel_state| channel_state.zone);
+        let duck = self.channels.get(channel).map_or(1., |channel_state| {
+            channel_state.duck * channel_state.zone.volume
+        });
+        let gain = sound.gain();
+        if start_silent || muted {
+            sound.settings.volume = Value::Fixed(Volume::Amplitude(0.));
+        } else if let Value::Fixed(_) = sound.settings.volume {
+            sound.settings.volume = Value::Fixed(scale_volume(sound_volume, duck * gain));
+        }
+        let mut fade_in = None;
+        if let (Some((_, (tween, _))), Value::Fixed(volume), false) =
+            (crossfade, sound.settings.volume, start_silent)
+        {
+            sound.settings.volume = Value::Fixed(scale_volume(volume, 0.));
+            fade_in = Some((volume, tween));
+        }
+        let ambient_route = if partial_sound_settings.ambient_send {
+            self.spacial_reverb.as_ref().map(|reverb| reverb.track.id())
+        } else {
+            None
+        };
+        let panning = match sound.settings.panning {
+            Value::Fixed(panning) => panning,
+            _ => 0.5,
+        };
+        // Modulators only exist in the manager of the default device
+        let mut modulations = Modulations::default();
+        if output_device.is_none() {
+            for (parameter, modulator, depth) in partial_sound_settings.modulators.iter() {
+                let id = self.lfos.id(modulator);
+                if id.is_none() {
+                    warn!(
+                        "Playing a sound without its modulator, since the modulator does not exist"
+                    );
+                }
+                modulations.bind(*parameter, id.map(|id| (id, *depth)));
+            }
+        }
+        sound.settings.volume = modulations.volume(sound.settings.volume);
+        sound.settings.playback_rate = modulations.playback_rate(sound.settings.playback_rate);
+        if let Value::Fixed(panning) = sound.settings.panning {
+            sound.settings.panning = modulations.panning(panning);
+        }
+        // Sounds play on the send or effect track of their channel, or on the bus of its parent channel
+        let bus = self
+            .send_tracks
+            .get(channel)
+            .map(|send_track| send_track.track.id())
+            .or_else(|| self.dry_route(channel));
+        let mut track = None;
+        // Tracks belong to the mixer of the default device, so other devices play without them
+        let device_manager = output_device.and_then(|device| self.devices.get_mut(device));
+        let on_output_device = device_manager.is_some();
+        let sound_handle = if let Some(device_manager) = device_manager {
+            // The audio clock only ticks in the mixer of the default device
+            if sound.settings.start_time != StartTime::Immediate {
+                warn!("Sounds on other output devices can't be scheduled, starting it right away");
+                sound.settings.start_time = StartTime::Immediate;
+            }
+            sound.play(device_manager)
+        } else {
+            let manager = self.manager.as_mut().unwrap();
+            if let Some(kira_emitter) = kira_emitter {
+                sound.settings.output_destination = kira_emitter.into();
+            } else if partial_sound_settings.filter.is_some()
+                || ambient_route.is_some()
+                || partial_sound_settings.interaural_delay
+            {
+                let direct_route = bus.unwrap_or(TrackId::Main);
+                let mut routes = TrackRoutes::parent(direct_route);
+                if let Some(ambient_route) = ambient_route {
+                    routes = routes.with_route(ambient_route, 0.);
+                }
+                let mut track_builder = TrackBuilder::new().routes(routes);
+                let cutoff = partial_sound_settings
+                    .filter
+                    .map_or(0., |filter| filter.cutoff);
+                let filter = partial_sound_settings.filter.map(|filter| {
+                    let cutoff = zone.filter_cutoff.unwrap_or(filter.cutoff);
+                    track_builder
+                        .add_effect(FilterBuilder::from(FilterSettings { cutoff, ..filter }))
+                });
+                let interaural_delay = partial_sound_settings
+                    .interaural_delay
+                    .then(|| track_builder.add_effect(InterauralDelayBuilder::new()));
+                match manager.add_sub_track(track_builder) {
+                    Ok(handle) => {
+                        sound.settings.output_destination = (&handle).into();
+                        track = Some(InstanceTrack {
+                            handle,
+                            filter,
+                            cutoff,
+                            direct_route,
+                            ambient_route,
+                            interaural_delay,
+                        });
+                    }
+                    Err(error) => warn!("Failed to create track for sound: {:?}", error),
+                }
+            }
+            if track.is_none() && kira_emitter.is_none() {
+                if let Some(bus) = bus {
+                    sound.settings.output_destination = bus.into();
+                }
+            }
+            sound.play(manager)
+        };
+        let mut sound_handle = match sound_handle {
+            Ok(sound_handle) => sound_handle,
+            Err(error) => {
+                self.dropped_commands += 1;
+                warn!("Failed to play sound due to {:?}", error);
+                self.errors
+                    .push(AudioError::new(error, channel, Some(instance_handle)));
+                return AudioCommandResult::Ok;
+            }
+        };
+        if let Some((volume, tween)) = &fade_in {
+            if let Err(error) = sound_handle.set_volume(modulations.volume(*volume), tween.into()) {
+                error!("Failed to fade in instance: {:?}", error);
+            }
+        }
         if let Some(channel_state) = self.channels.get(channel) {
             if channel_state.paused {
                 if let Err(error) = sound_handle.pause(kira::tween::Tween::default()) {
@@ -252,10 +837,13 @@ impl<B: Backend> AudioOutput<B> {
                 }
                 let playback_rate = partial_sound_settings
                     .playback_rate
-                    .unwrap_or(channel_state.playback_rate);
-                if let Err(error) =
-                    sound_handle.set_playback_rate(playback_rate, kira::tween::Tween::default())
-                {
+                    .unwrap_or(channel_state.playback_rate)
+                    * variation.playback_rate
+                    * time_scale;
+                if let Err(error) = sound_handle.set_playback_rate(
+                    modulations.playback_rate(PlaybackRate::Factor(playback_rate)),
+                    kira::tween::Tween::default(),
+                ) {
                     error!("Failed to set playback rate for instance: {:?}", error);
                 }
             }
@@ -264,10 +852,13 @@ impl<B: Backend> AudioOutput<B> {
             if let Err(error) = sound_handle.pause(kira::tween::Tween::default()) {
                 warn!("Failed to pause instance due to {:?}", error);
             }
-            let playback_rate = partial_sound_settings.playback_rate.unwrap_or(1.0);
-            if let Err(error) =
-                sound_handle.set_playback_rate(playback_rate, kira::tween::Tween::default())
-            {
+            let playback_rate = partial_sound_settings.playback_rate.unwrap_or(1.0)
+                * variation.playback_rate
+                * time_scale;
+            if let Err(error) = sound_handle.set_playback_rate(
+                modulations.playback_rate(PlaybackRate::Factor(playback_rate)),
+                kira::tween::Tween::default(),
+            ) {
                 error!("Failed to set playback rate for instance: {:?}", error);
             }
         }
@@ -275,8 +866,48 @@ impl<B: Backend> AudioOutput<B> {
             &instance_handle,
             AudioInstance {
                 handle: sound_handle,
+                track,
+                base_volume,
+                spacial_volume: if start_silent { 0. } else { 1. },
+                volume,
+                muted,
+                duck,
+                gain,
+                channel: channel.clone(),
+                started_silent: start_silent,
+                in_kira_scene: kira_emitter.is_some(),
+                on_output_device,
+                scheduled_resume: None,
+                stop_requested: false,
+                playback_rate,
+                panning,
+                modulations,
+                time_scale,
+                doppler: 1.,
+                culled: false,
+                timing,
+                fading_until: None,
+                resume_tween: self
+                    .channels
+                    .get(channel)
+                    .and_then(|channel_state| channel_state.resume_tween.clone()),
+                seek_target: None,
             },
         );
+        if let Some(instance) = audio_instances.get_mut(&instance_handle) {
+            if let Some((_, tween)) = fade_in.as_ref() {
+                instance.start_fade(&tween.into());
+            }
+            if let Some(tween) = fade_in_tween {
+                instance.start_fade(&tween);
+            }
+        }
+        self.completions.track(
+            &instance_handle,
+            channel,
+            partial_sound_settings.one_shot,
+            loops,
+        );
         if let Some(instance_states) = self.instances.get_mut(channel) {
             instance_states.push(instance_handle);
         } else {
@@ -287,9 +918,76 @@ impl<B: Backend> AudioOutput<B> {
         AudioCommandResult::Ok
     }
 
+    /// Fade out and then stop the playing instances of the channel for a crossfade
+    ///
+    /// Instances that already stop, like the outgoing ones of an earlier crossfade, are left alone.
+    fn fade_out_for_crossfade(
+        &mut self,
+        channel: &Channel,
+        audio_instances: &mut Assets<AudioInstance>,
+        duration: Duration,
+        tween: &AudioTween,
+    ) {
+        let Some(instances) = self.instances.get(channel) else {
+            return;
+        };
+        let stop = match self.clock.start_time(self.clock.now() + duration) {
+            // Without a clock to wait for, the stop fades out by itself
+            StartTime::Immediate => tween.into(),
+            start_time => Tween {
+                start_time,
+                ..Default::default()
+            },
+        };
+        for handle in instances {
+            let Some(instance) = audio_instances.get_mut(handle) else {
+                continue;
+            };
+            if matches!(
+                instance.handle.state(),
+                kira::sound::PlaybackState::Stopping | kira::sound::PlaybackState::Stopped
+            ) {
+                continue;
+            }
+            if let Some(error) =
+                instance.set_volume(scale_volume(instance.volume, 0.), tween.clone())
+            {
+                error!("Failed to fade out instance: {:?}", error);
+            }
+            instance.stop_requested = true;
+            instance.start_fade(&tween.into());
+            if let Err(error) = instance.handle.stop(stop) {
+                error!("Failed to stop instance: {:?}", error);
+            }
+        }
+    }
+
+    /// Apply the instance limit of the channel before playing a new sound
+    ///
+    /// Returns `false` if the new sound should not be played.
+    fn make_room_for_instance(
+        &mut self,
+        channel: &Channel,
+        audio_instances: &mut Assets<AudioInstance>,
+    ) -> bool {
+        let Some((limit, policy)) = self
+            .channels
+            .get(channel)
+            .and_then(|channel_state| channel_state.instance_limit)
+        else {
+            return true;
+        };
+        let Some(instances) = self.instances.get_mut(channel) else {
+            return limit > 0;
+        };
+
+        make_room(instances, limit, policy, audio_instances)
+    }
+
     pub(crate) fn play_channel<T: Resource>(
         &mut self,
         audio_sources: &Assets<AudioSource>,
+        streamed_sources: &Assets<StreamedAudioSource>,
         channel: &AudioChannel<T>,
         audio_instances: &mut Assets<AudioInstance>,
     ) {
@@ -303,25 +1001,36 @@ impl<B: Backend> AudioOutput<B> {
         let mut commands_to_retry = vec![];
         let mut i = 0;
         while i < len {
-            let audio_command = commands.pop_back().unwrap();
-            let result =
-                self.run_audio_command(&audio_command, audio_sources, audio_instances, &channel);
+            let mut audio_command = commands.pop_back().unwrap();
+            let result = self.pick_variant(&mut audio_command).unwrap_or_else(|| {
+                self.run_audio_command(
+                    &audio_command,
+                    audio_sources,
+                    streamed_sources,
+                    audio_instances,
+                    &channel,
+                )
+            });
             if let AudioCommand::Stop(_) = audio_command {
                 commands_to_retry.clear();
             }
             if let AudioCommandResult::Retry = result {
                 commands_to_retry.push(audio_command);
+            } else {
+                self.log_command(&channel, &audio_command);
             }
             i += 1;
         }
         commands_to_retry
             .drain(..)
             .for_each(|command| commands.push_front(command));
+        self.track_pending_commands(channel, &commands);
     }
 
     pub(crate) fn play_dynamic_channels(
         &mut self,
         audio_sources: &Assets<AudioSource>,
+        streamed_sources: &Assets<StreamedAudioSource>,
         channels: &DynamicAudioChannels,
         audio_instances: &mut Assets<AudioInstance>,
     ) {
@@ -334,18 +1043,111 @@ impl<B: Backend> AudioOutput<B> {
             let channel = Channel::Dynamic(key.clone());
             let mut i = 0;
             while i < len {
-                let audio_command = commands.pop_back().unwrap();
-                let result = self.run_audio_command(
-                    &audio_command,
-                    audio_sources,
-                    audio_instances,
-                    &channel,
-                );
+                let mut audio_command = commands.pop_back().unwrap();
+                let result = self.pick_variant(&mut audio_command).unwrap_or_else(|| {
+                    self.run_audio_command(
+                        &audio_command,
+                        audio_sources,
+                        streamed_sources,
+                        audio_instances,
+                        &channel,
+                    )
+                });
                 if let AudioCommandResult::Retry = result {
                     commands.push_front(audio_command);
+                } else {
+                    self.log_command(&channel, &audio_command);
                 }
                 i += 1;
             }
+            self.track_pending_commands(channel, &commands);
+        }
+    }
+
+    /// Remember the commands left in the queue of a channel after its update
+    fn track_pending_commands(&mut self, channel: Channel, commands: &VecDeque<AudioCommand>) {
+        let plays = commands
+            .iter()
+            .filter_map(|command| match command {
+                AudioCommand::Play(play) => Some(play.instance_handle.id()),
+                _ => None,
+            })
+            .collect();
+        self.pending_plays.insert(channel.clone(), plays);
+        self.pending_commands.insert(channel, commands.len());
+    }
+
+    /// Stop all instances of a removed channel and forget its state
+    pub(crate) fn remove_channel(
+        &mut self,
+        channel: &Channel,
+        audio_instances: &mut Assets<AudioInstance>,
+        tween: &Option<AudioTween>,
+    ) {
+        if self.manager.is_some() {
+            self.stop(channel, audio_instances, tween);
+        }
+        let instances = self.instances.remove(channel).unwrap_or_default();
+        if let Some(channel_track) = self.channel_tracks.remove(channel) {
+            self.retired_tracks
+                .push((channel_track.track, instances.clone()));
+        }
+        if let Some(send_track) = self.send_tracks.remove(channel) {
+            self.retired_tracks.push((send_track.track, instances));
+        }
+        self.channels.remove(channel);
+        self.pending_commands.remove(channel);
+        self.pending_plays.remove(channel);
+        self.ducking.remove(channel);
+        self.scheduled_resumes.remove(channel);
+        self.queues.remove(channel);
+        if self.soloed.remove(channel) {
+            self.update_muted_instances(audio_instances, &None);
+        }
+    }
+
+    /// Whether the audio plays on the backend of a [`MockAudio`]
+    fn mocked(&self) -> bool {
+        #[cfg(feature = "mock-backend")]
+        return self.mock.is_some();
+        #[cfg(not(feature = "mock-backend"))]
+        false
+    }
+
+    /// Mirror the loaded collections, keeping what unchanged ones played so far
+    pub(crate) fn update_collections(&mut self, collections: &Assets<AudioCollection>) {
+        self.collections.update(collections);
+    }
+
+    /// Replace the collection of a play command with the variant to play
+    ///
+    /// Returns the result of the command if it can't run yet or has nothing to play. Picking
+    /// before running keeps retried commands at the variant they got.
+    fn pick_variant(&mut self, audio_command: &mut AudioCommand) -> Option<AudioCommandResult> {
+        let AudioCommand::Play(play) = audio_command else {
+            return None;
+        };
+        let collection = play.collection.as_ref()?;
+        let Some(picker) = self.collections.get_mut(collection.id()) else {
+            // The collection hasn't loaded yet
+            return Some(AudioCommandResult::Retry);
+        };
+        let Some(source) = picker.pick(&self.rng) else {
+            warn!("Not playing an audio collection without variants");
+            return Some(AudioCommandResult::Ok);
+        };
+        play.source = source;
+        play.collection = None;
+
+        None
+    }
+
+    /// Record a command that ran in the log of the [`MockAudio`]
+    #[cfg_attr(not(feature = "mock-backend"), allow(unused_variables))]
+    fn log_command(&self, channel: &Channel, audio_command: &AudioCommand) {
+        #[cfg(feature = "mock-backend")]
+        if let Some(mock) = self.mock.as_ref() {
+            mock.log(channel, audio_command);
         }
     }
 
@@ -353,16 +1155,72 @@ impl<B: Backend> AudioOutput<B> {
         &mut self,
         audio_command: &AudioCommand,
         audio_sources: &Assets<AudioSource>,
+        streamed_sources: &Assets<StreamedAudioSource>,
         audio_instances: &mut Assets<AudioInstance>,
         channel: &Channel,
     ) -> AudioCommandResult {
-        match audio_command {
+        if let AudioCommand::Play(PlayAudioSettings { settings, .. }) = audio_command {
+            // Sounds would all start at once when the browser resumes the audio
+            if self.suspended
+                || !settings
+                    .start_time
+                    .map_or(true, |start_time| start_time.is_ready(&self.clocks))
+            {
+                return AudioCommandResult::Retry;
+            }
+        }
+        let errors = self.errors.len();
+        let result = match audio_command {
+            AudioCommand::Play(PlayAudioSettings {
+                sequence: Some(parts),
+                settings,
+                instance_handle,
+                ..
+            }) => {
+                let Some(parts) = parts
+                    .iter()
+                    .map(|part| audio_sources.get(part))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    // Not all parts loaded yet
+                    return AudioCommandResult::Retry;
+                };
+                if parts.is_empty() {
+                    return AudioCommandResult::Ok;
+                }
+                self.play(
+                    channel,
+                    settings,
+                    (&AudioSource::sequence(&parts)).into(),
+                    instance_handle.clone(),
+                    audio_instances,
+                )
+            }
+            AudioCommand::Play(PlayAudioSettings {
+                streamed: Some(source),
+                settings,
+                instance_handle,
+                ..
+            }) => {
+                if let Some(source) = streamed_sources.get(source) {
+                    self.play(
+                        channel,
+                        settings,
+                        source.into(),
+                        instance_handle.clone(),
+                        audio_instances,
+                    )
+                } else {
+                    // audio source hasn't loaded yet. Add it back to the queue
+                    AudioCommandResult::Retry
+                }
+            }
             AudioCommand::Play(play_args) => {
                 if let Some(audio_source) = audio_sources.get(&play_args.source) {
                     self.play(
                         channel,
                         &play_args.settings,
-                        audio_source,
+                        audio_source.into(),
                         play_args.instance_handle.clone(),
                         audio_instances,
                     )
@@ -371,15 +1229,69 @@ impl<B: Backend> AudioOutput<B> {
                     AudioCommandResult::Retry
                 }
             }
-            AudioCommand::Stop(tween) => self.stop(channel, audio_instances, tween),
+            AudioCommand::Stop(tween) => {
+                self.queues.remove(channel);
+                self.stop(channel, audio_instances, tween)
+            }
             AudioCommand::Pause(tween) => {
+                self.scheduled_resumes.remove(channel);
+                self.queues.pause(channel, audio_instances);
                 self.pause(channel, audio_instances, tween);
                 AudioCommandResult::Ok
             }
             AudioCommand::Resume(tween) => {
+                self.scheduled_resumes.remove(channel);
                 self.resume(channel, audio_instances, tween);
                 AudioCommandResult::Ok
             }
+            AudioCommand::SeekTo(position) => {
+                self.seek(channel, audio_instances, |instance| {
+                    instance.seek_to(*position)
+                });
+                AudioCommandResult::Ok
+            }
+            AudioCommand::SeekBy(amount) => {
+                self.seek(channel, audio_instances, |instance| {
+                    instance.seek_by(*amount)
+                });
+                AudioCommandResult::Ok
+            }
+            AudioCommand::SetResumeTween(tween) => {
+                let tween = Some(tween.clone()).filter(|tween| !tween.duration().is_zero());
+                for handle in self.instances.get(channel).into_iter().flatten() {
+                    if let Some(instance) = audio_instances.get_mut(handle) {
+                        instance.resume_tween = tween.clone();
+                    }
+                }
+                self.channels
+                    .entry(channel.clone())
+                    .or_default()
+                    .resume_tween = tween;
+                AudioCommandResult::Ok
+            }
+            AudioCommand::PauseFor(duration, tween) => {
+                self.queues.pause(channel, audio_instances);
+                self.pause(channel, audio_instances, &Some(tween.clone()));
+                self.scheduled_resumes
+                    .insert(channel.clone(), (*duration, tween.clone()));
+                AudioCommandResult::Ok
+            }
+            AudioCommand::Enqueue(source) => {
+                self.queues.enqueue(channel, source.clone());
+                AudioCommandResult::Ok
+            }
+            AudioCommand::SkipQueued => {
+                self.queues.skip(channel, audio_instances);
+                AudioCommandResult::Ok
+            }
+            AudioCommand::ClearQueue => {
+                self.queues.clear(channel, audio_instances);
+                AudioCommandResult::Ok
+            }
+            AudioCommand::SetQueueRepeat(repeat) => {
+                self.queues.set_repeat(channel, *repeat, audio_instances);
+                AudioCommandResult::Ok
+            }
             AudioCommand::SetVolume(volume, tween) => {
                 self.set_volume(channel, audio_instances, *volume, tween);
                 AudioCommandResult::Ok
@@ -392,49 +1304,1393 @@ impl<B: Backend> AudioOutput<B> {
                 self.set_playback_rate(channel, audio_instances, *playback_rate, tween);
                 AudioCommandResult::Ok
             }
+            AudioCommand::SetInstanceLimit(instance_limit) => {
+                self.channels
+                    .entry(channel.clone())
+                    .or_default()
+                    .instance_limit = *instance_limit;
+                AudioCommandResult::Ok
+            }
+            AudioCommand::SetMuted(muted, tween) => {
+                self.channels.entry(channel.clone()).or_default().muted = *muted;
+                self.update_muted_instances(audio_instances, tween);
+                AudioCommandResult::Ok
+            }
+            AudioCommand::SetSolo(solo, tween) => {
+                if *solo {
+                    self.soloed.insert(channel.clone());
+                } else {
+                    self.soloed.remove(channel);
+                }
+                self.update_muted_instances(audio_instances, tween);
+                AudioCommandResult::Ok
+            }
+            AudioCommand::DuckBy(trigger, settings) => {
+                self.ducking.duck_by(channel, trigger, settings.as_ref());
+                AudioCommandResult::Ok
+            }
+            AudioCommand::DuckFor(duration, settings) => {
+                self.ducking.duck_for(channel, *duration, settings.clone());
+                AudioCommandResult::Ok
+            }
+            AudioCommand::SetOutputDevice(device) => {
+                self.set_output_device(channel, device.as_deref());
+                AudioCommandResult::Ok
+            }
+            AudioCommand::SetReverb(reverb) => {
+                self.set_reverb(channel, reverb);
+                AudioCommandResult::Ok
+            }
+            AudioCommand::SetEffects(track) => {
+                if let Some(track) = track.lock().take() {
+                    self.set_effects(channel, track);
+                }
+                AudioCommandResult::Ok
+            }
+            AudioCommand::SetSend(target, level, tween) => {
+                self.set_send(channel, target, *level, tween);
+                AudioCommandResult::Ok
+            }
+            AudioCommand::SetReverbMix(mix, tween) => {
+                let reverb = self
+                    .channel_tracks
+                    .get_mut(channel)
+                    .and_then(|channel_track| channel_track.reverb.as_mut());
+                if let Some(reverb) = reverb {
+                    if let Err(error) = reverb.set_mix(*mix, map_tween(tween)) {
+                        error!("Failed to set reverb mix for channel: {:?}", error);
+                    }
+                }
+                AudioCommandResult::Ok
+            }
+            AudioCommand::ApplySettings(settings) => {
+                if let Some(bus) = self.buses.get_mut(channel) {
+                    if let Err(error) = bus.track.set_volume(settings.volume, Tween::default()) {
+                        error!("Failed to set volume for channel bus: {:?}", error);
+                    }
+                }
+                let mut channel_state: ChannelState = (*settings).into();
+                if let Some(previous) = self.channels.get(channel) {
+                    channel_state.duck = previous.duck;
+                    channel_state.zone = previous.zone;
+                    channel_state.output_device = previous.output_device.clone();
+                    channel_state.resume_tween = previous.resume_tween.clone();
+                }
+                self.channels.insert(channel.clone(), channel_state);
+                AudioCommandResult::Ok
+            }
+        };
+        if let AudioCommand::Play(play_args) = audio_command {
+            let source = match play_args {
+                PlayAudioSettings {
+                    sequence: Some(_), ..
+                } => None,
+                PlayAudioSettings {
+                    streamed: Some(source),
+                    ..
+                } => Some(source.clone().untyped()),
+                _ => Some(play_args.source.clone().untyped()),
+            };
+            self.set_error_source(errors, source);
         }
+
+        result
     }
 
-    pub(crate) fn cleanup_stopped_instances(&mut self, instances: &mut Assets<AudioInstance>) {
-        for (_, handles) in self.instances.iter_mut() {
-            handles.retain(|handle| {
-                if let Some(instance) = instances.get(handle) {
-                    instance.handle.state() != kira::sound::PlaybackState::Stopped
-                } else {
+    /// Name the source of a play command in the errors reported since the given count
+    fn set_error_source(&mut self, errors: usize, source: Option<UntypedHandle>) {
+        for error in &mut self.errors[errors..] {
+            error.source = source.clone();
+        }
+    }
+
+    /// Play new sounds of the channel on the given output device, opening it if needed
+    ///
+    /// Falls back to the default device if the device can't be opened.
+    fn set_output_device(&mut self, channel: &Channel, device: Option<&str>) {
+        let device = device.filter(|device| {
+            match self.devices.open(device, &self.settings) {
+                Ok(opened) => {
+                    // A newly opened device follows the pause and volume of the main one
+                    if let Some(manager) = self.devices.get(device).filter(|_| opened) {
+                        self.follow_main_state(manager);
+                    }
+                    true
+                }
+                Err(error) => {
+                    error!(
+                        "Failed to open output device {:?}, the channel plays on the default device: {}",
+                        device, error
+                    );
                     false
                 }
-            });
-        }
+            }
+        });
+        self.channels
+            .entry(channel.clone())
+            .or_default()
+            .output_device = device.map(str::to_string);
     }
-}
 
-pub(crate) fn play_dynamic_channels(
-    mut audio_output: NonSendMut<AudioOutput>,
-    channels: Res<DynamicAudioChannels>,
-    audio_sources: Option<Res<Assets<AudioSource>>>,
-    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    /// Create the track of the channel with a reverb, or update the existing reverb
+    fn set_reverb(&mut self, channel: &Channel, settings: &ChannelReverb) {
+        if let Some(reverb) = self
+            .channel_tracks
+            .get_mut(channel)
+            .and_then(|channel_track| channel_track.reverb.as_mut())
+        {
+            let tween = Tween::default();
+            if let Err(error) = reverb
+                .set_feedback(settings.feedback, tween)
+                .and_then(|_| reverb.set_damping(settings.damping, tween))
+                .and_then(|_| reverb.set_stereo_width(settings.stereo_width, tween))
+                .and_then(|_| reverb.set_mix(settings.mix, tween))
+            {
+                warn!("Failed to update channel reverb: {:?}", error);
+            }
+            return;
+        }
+        if self.channel_tracks.contains_key(channel) {
+            warn!("The audio channel already has an effect chain, add the reverb to the chain instead");
+            return;
+        }
+        let parent = self.bus_of_parent(channel).unwrap_or(TrackId::Main);
+        let Some(manager) = self.manager.as_mut() else {
+            return;
+        };
+        let mut track_builder = TrackBuilder::new().routes(TrackRoutes::parent(parent));
+        let reverb = track_builder.add_effect(ReverbBuilder::from(*settings));
+        match manager.add_sub_track(track_builder) {
+            Ok(track) => {
+                self.channel_tracks.insert(
+                    channel.clone(),
+                    ChannelTrack {
+                        track,
+                        reverb: Some(reverb),
+                    },
+                );
+                self.rebuild_send_track(channel);
+            }
+            Err(error) => warn!("Failed to create track for audio channel: {:?}", error),
+        }
+    }
+
+    /// Create the track of the channel from an effect chain
+    ///
+    /// A previous track of the channel is kept until the sounds playing on it stopped.
+    fn set_effects(&mut self, channel: &Channel, track: TrackBuilder) {
+        let parent = self.bus_of_parent(channel).unwrap_or(TrackId::Main);
+        let Some(manager) = self.manager.as_mut() else {
+            return;
+        };
+        match manager.add_sub_track(track.routes(TrackRoutes::parent(parent))) {
+            Ok(track) => {
+                let previous = self.channel_tracks.insert(
+                    channel.clone(),
+                    ChannelTrack {
+                        track,
+                        reverb: None,
+                    },
+                );
+                if let Some(previous) = previous {
+                    let instances = self.instances.get(channel).cloned().unwrap_or_default();
+                    self.retired_tracks.push((previous.track, instances));
+                }
+                self.rebuild_send_track(channel);
+            }
+            Err(error) => warn!("Failed to create track for audio channel: {:?}", error),
+        }
+    }
+
+    /// The track that the unchanged signal of the channel goes to
+    fn dry_route(&self, channel: &Channel) -> Option<TrackId> {
+        self.channel_tracks
+            .get(channel)
+            .map(|channel_track| channel_track.track.id())
+            .or_else(|| self.bus_of_parent(channel))
+    }
+
+    /// Set the level that the channel sends to the effect track of `target`
+    fn set_send(
+        &mut self,
+        channel: &Channel,
+        target: &Channel,
+        level: f64,
+        tween: &Option<AudioTween>,
+    ) {
+        if channel == target || !self.channel_tracks.contains_key(target) {
+            warn!(
+                "Audio channels can only send to other channels with effects, ignoring send to {:?}",
+                target
+            );
+            return;
+        }
+        if let Some(send_track) = self.send_tracks.get_mut(channel) {
+            send_track.levels.insert(target.clone(), level);
+            if let Some(route) = send_track.routes.get(target) {
+                if let Err(error) = send_track.track.set_route(*route, level, map_tween(tween)) {
+                    error!("Failed to set send level for channel: {:?}", error);
+                }
+                return;
+            }
+        } else if level == 0. {
+            return;
+        }
+        // Routes of kira tracks are fixed, so adding a send needs a new track
+        let levels = self
+            .send_tracks
+            .remove(channel)
+            .map(|send_track| {
+                let instances = self.instances.get(channel).cloned().unwrap_or_default();
+                self.retired_tracks.push((send_track.track, instances));
+                send_track.levels
+            })
+            .unwrap_or_else(|| HashMap::from([(target.clone(), level)]));
+        self.create_send_track(channel, levels);
+    }
+
+    /// Replace the send track of the channel, for example after its dry route changed
+    fn rebuild_send_track(&mut self, channel: &Channel) {
+        if let Some(send_track) = self.send_tracks.remove(channel) {
+            let instances = self.instances.get(channel).cloned().unwrap_or_default();
+            self.retired_tracks.push((send_track.track, instances));
+            self.create_send_track(channel, send_track.levels);
+        }
+    }
+
+    fn create_send_track(&mut self, channel: &Channel, levels: HashMap<Channel, f64>) {
+        let mut routes = TrackRoutes::parent(self.dry_route(channel).unwrap_or(TrackId::Main));
+        let mut send_routes = HashMap::default();
+        for (target, level) in levels.iter() {
+            if let Some(target_track) = self.channel_tracks.get(target) {
+                routes = routes.with_route(target_track.track.id(), *level);
+                send_routes.insert(target.clone(), target_track.track.id());
+            }
+        }
+        let Some(manager) = self.manager.as_mut() else {
+            return;
+        };
+        match manager.add_sub_track(TrackBuilder::new().routes(routes)) {
+            Ok(track) => {
+                self.send_tracks.insert(
+                    channel.clone(),
+                    SendTrack {
+                        track,
+                        levels,
+                        routes: send_routes,
+                    },
+                );
+            }
+            Err(error) => warn!("Failed to create send track for audio channel: {:?}", error),
+        }
+    }
+
+    /// Whether the channel is neither muted nor silenced by the solo of another channel
+    fn is_audible(&self, channel: &Channel) -> bool {
+        let muted = self
+            .channels
+            .get(channel)
+            .map_or(false, |channel_state| channel_state.muted);
+
+        !muted && (self.soloed.is_empty() || self.soloed.contains(channel))
+    }
+
+    /// Resume channels and instances once the time of their [`pause_for`](crate::AudioControl::pause_for) is up
+    pub(crate) fn resume_paused_audio(
+        &mut self,
+        audio_instances: &mut Assets<AudioInstance>,
+        delta: Duration,
+    ) {
+        let mut resumed = vec![];
+        for (channel, (remaining, tween)) in self.scheduled_resumes.iter_mut() {
+            *remaining = remaining.saturating_sub(delta);
+            if remaining.is_zero() {
+                resumed.push((channel.clone(), tween.clone()));
+            }
+        }
+        for (channel, tween) in resumed {
+            self.scheduled_resumes.remove(&channel);
+            self.resume(&channel, audio_instances, &Some(tween));
+        }
+
+        for handle in self.instances.values().flatten() {
+            let scheduled = audio_instances
+                .get(handle)
+                .map_or(false, |instance| instance.scheduled_resume.is_some());
+            if !scheduled {
+                continue;
+            }
+            let Some(instance) = audio_instances.get_mut(handle) else {
+                continue;
+            };
+            let Some((remaining, tween)) = instance.scheduled_resume.as_mut() else {
+                continue;
+            };
+            *remaining = remaining.saturating_sub(delta);
+            if remaining.is_zero() {
+                let tween = tween.clone();
+                if let Some(error) = instance.resume(tween) {
+                    error!("Failed to resume instance: {:?}", error);
+                }
+            }
+        }
+    }
+
+    /// Apply the changes of ducking to the instances of the ducked channels
+    pub(crate) fn update_ducking(
+        &mut self,
+        audio_instances: &mut Assets<AudioInstance>,
+        delta: Duration,
+    ) {
+        let instances = &self.instances;
+        let is_playing = |trigger: &Channel, settings: &DuckSettings| {
+            instances.get(trigger).map_or(false, |handles| {
+                handles.iter().any(|handle| {
+                    audio_instances.get(handle).map_or(false, |instance| {
+                        !instance.muted
+                            && instance.volume.as_amplitude() > settings.threshold
+                            && matches!(instance.state(), PlaybackState::Playing { .. })
+                    })
+                })
+            })
+        };
+        let changes = self.ducking.update(delta, is_playing);
+        for (channel, duck, tween) in changes {
+            let channel_state = self.channels.entry(channel.clone()).or_default();
+            channel_state.duck = duck;
+            let duck = duck * channel_state.zone.volume;
+            for handle in self.instances.get(&channel).into_iter().flatten() {
+                let Some(instance) = audio_instances.get_mut(handle) else {
+                    continue;
+                };
+                if let Some(error) = instance.set_duck(duck, tween.clone()) {
+                    error!("Failed to duck instance: {:?}", error);
+                }
+            }
+        }
+    }
+
+    /// Apply the combined adjustments of the audio zones the receiver is in to a channel
+    pub(crate) fn set_zone_mix(
+        &mut self,
+        channel: &Channel,
+        mix: ZoneMix,
+        tween: AudioTween,
+        audio_instances: &mut Assets<AudioInstance>,
+    ) {
+        let channel_state = self.channels.entry(channel.clone()).or_default();
+        let previous = std::mem::replace(&mut channel_state.zone, mix);
+        let duck = channel_state.duck * mix.volume;
+        for handle in self.instances.get(channel).into_iter().flatten() {
+            let Some(instance) = audio_instances.get_mut(handle) else {
+                continue;
+            };
+            if previous.volume != mix.volume {
+                if let Some(error) = instance.set_duck(duck, tween.clone()) {
+                    error!("Failed to apply audio zone to instance: {:?}", error);
+                }
+            }
+            if previous.filter_cutoff != mix.filter_cutoff {
+                if let Some(error) = instance.set_zone_cutoff(mix.filter_cutoff, tween.clone()) {
+                    error!("Failed to apply audio zone to instance filter: {:?}", error);
+                }
+            }
+        }
+    }
+
+    /// Mute or unmute all instances after the mute or solo state of a channel changed
+    fn update_muted_instances(
+        &self,
+        audio_instances: &mut Assets<AudioInstance>,
+        tween: &Option<AudioTween>,
+    ) {
+        for (channel, instances) in self.instances.iter() {
+            let muted = !self.is_audible(channel);
+            for instance in instances {
+                let Some(instance) = audio_instances.get_mut(instance) else {
+                    continue;
+                };
+                if let Some(error) = instance.set_muted(muted, tween.clone().unwrap_or_default()) {
+                    error!("Failed to mute instance: {:?}", error);
+                }
+            }
+        }
+    }
+
+    /// The bus track that sounds of the given channel play on
+    fn bus_of_parent(&self, channel: &Channel) -> Option<TrackId> {
+        self.buses
+            .values()
+            .find(|bus| bus.children.contains(channel))
+            .map(|bus| bus.track.id())
+    }
+
+    /// Create and remove bus tracks to match the channel hierarchy
+    ///
+    /// Buses are only recreated if their own parent changed.
+    pub(crate) fn update_channel_buses(&mut self, hierarchy: &ChannelHierarchy) {
+        let Some(manager) = self.manager.as_mut() else {
+            return;
+        };
+        let mut buses = HashMap::default();
+        for channel in hierarchy.parents_in_order() {
+            let parent = hierarchy.parent(channel).cloned();
+            let children = hierarchy.children(channel);
+            let parent_track = parent
+                .as_ref()
+                .and_then(|parent| buses.get(parent))
+                .map(|bus: &ChannelBus| (bus.track.id(), bus.recreated));
+            match self.buses.remove(channel) {
+                Some(mut bus)
+                    if bus.parent == parent
+                        && !parent_track.map_or(false, |(_, recreated)| recreated) =>
+                {
+                    bus.children = children;
+                    bus.recreated = false;
+                    buses.insert(channel.clone(), bus);
+                }
+                _ => {
+                    let volume = self
+                        .channels
+                        .get(channel)
+                        .map_or(Volume::Amplitude(1.), |state| finite_volume(state.volume));
+                    let routes =
+                        TrackRoutes::parent(parent_track.map_or(TrackId::Main, |(track, _)| track));
+                    match manager.add_sub_track(TrackBuilder::new().volume(volume).routes(routes)) {
+                        Ok(track) => {
+                            buses.insert(
+                                channel.clone(),
+                                ChannelBus {
+                                    track,
+                                    parent,
+                                    children,
+                                    recreated: true,
+                                },
+                            );
+                        }
+                        Err(error) => {
+                            warn!("Failed to create track for audio channel: {:?}", error)
+                        }
+                    }
+                }
+            }
+        }
+        self.buses = buses;
+    }
+
+    /// Create, update, or remove the spacial reverb track to match the settings
+    ///
+    /// Changed settings of an existing track move there with the tween.
+    pub(crate) fn update_spacial_reverb(
+        &mut self,
+        settings: Option<&SpacialReverb>,
+        tween: &AudioTween,
+    ) {
+        let Some(manager) = self.manager.as_mut() else {
+            return;
+        };
+        let Some(settings) = settings else {
+            self.spacial_reverb = None;
+            return;
+        };
+        match self.spacial_reverb.as_mut() {
+            Some(reverb_track) if reverb_track.settings != *settings => {
+                let tween = tween.into();
+                let reverb = &mut reverb_track.reverb;
+                if let Err(error) = reverb
+                    .set_feedback(settings.feedback, tween)
+                    .and_then(|_| reverb.set_damping(settings.damping, tween))
+                    .and_then(|_| reverb.set_stereo_width(settings.stereo_width, tween))
+                {
+                    warn!("Failed to update spacial reverb: {:?}", error);
+                    return;
+                }
+                reverb_track.settings = settings.clone();
+            }
+            Some(_) => (),
+            None => {
+                let mut track_builder = TrackBuilder::new();
+                let reverb = track_builder.add_effect(
+                    ReverbBuilder::new()
+                        .feedback(settings.feedback)
+                        .damping(settings.damping)
+                        .stereo_width(settings.stereo_width)
+                        .mix(1.),
+                );
+                match manager.add_sub_track(track_builder) {
+                    Ok(track) => {
+                        self.spacial_reverb = Some(SpacialReverbTrack {
+                            track,
+                            reverb,
+                            settings: settings.clone(),
+                        })
+                    }
+                    Err(error) => warn!("Failed to create spacial reverb track: {:?}", error),
+                }
+            }
+        }
+    }
+
+    /// Create, update or remove the kira spatial scene
+    pub(crate) fn sync_kira_spacial_scene(
+        &mut self,
+        spacial_audio: &SpacialAudio,
+        spacial_audio_changed: bool,
+        sync: KiraSpacialSync,
+    ) {
+        let Some(manager) = self.manager.as_mut() else {
+            return;
+        };
+        let active = spacial_audio.enabled && spacial_audio.backend == SpacialBackend::Kira;
+        if self.kira_spacial.is_none() {
+            if !active {
+                return;
+            }
+            match manager.add_spatial_scene(SpatialSceneSettings::default()) {
+                Ok(scene) => {
+                    self.kira_spacial = Some(KiraSpacialScene {
+                        scene,
+                        listener: None,
+                        emitters: HashMap::default(),
+                        pending: HashMap::default(),
+                        active,
+                        channels: spacial_audio.channels.clone(),
+                    })
+                }
+                Err(error) => {
+                    warn!("Failed to create kira spatial scene: {:?}", error);
+                    return;
+                }
+            }
+        }
+        let kira_spacial = self.kira_spacial.as_mut().unwrap();
+        if spacial_audio_changed {
+            kira_spacial.active = active;
+            kira_spacial.channels = spacial_audio.channels.clone();
+        }
+        let tween = (&spacial_audio.update_tween).into();
+
+        match (sync.listener, kira_spacial.listener.as_mut()) {
+            (Some((position, rotation, moved)), Some(listener)) => {
+                if moved {
+                    if let Err(error) = listener
+                        .set_position(mint::Vector3::from(position.to_array()), tween)
+                        .and_then(|_| {
+                            listener
+                                .set_orientation(mint::Quaternion::from(rotation.to_array()), tween)
+                        })
+                    {
+                        warn!("Failed to move kira listener: {:?}", error);
+                    }
+                }
+            }
+            (Some((position, rotation, _)), None) => {
+                match kira_spacial.scene.add_listener(
+                    mint::Vector3::from(position.to_array()),
+                    mint::Quaternion::from(rotation.to_array()),
+                    ListenerSettings::default(),
+                ) {
+                    Ok(listener) => kira_spacial.listener = Some(listener),
+                    Err(error) => warn!("Failed to create kira listener: {:?}", error),
+                }
+            }
+            (None, _) => kira_spacial.listener = None,
+        }
+
+        kira_spacial.pending = sync.pending;
+        let mut existing = HashSet::with_capacity(sync.emitters.len());
+        for (entity, position, moved, emitter) in sync.emitters {
+            existing.insert(entity);
+            if let Some(kira_emitter) = kira_spacial.emitters.get_mut(&entity) {
+                if moved {
+                    if let Err(error) =
+                        kira_emitter.set_position(mint::Vector3::from(position.to_array()), tween)
+                    {
+                        warn!("Failed to move kira emitter: {:?}", error);
+                    }
+                }
+                continue;
+            }
+            if !kira_spacial.active {
+                continue;
+            }
+            let max_distance = spacial_audio.max_distance_of(emitter);
+            let min_distance = emitter
+                .min_distance
+                .unwrap_or(spacial_audio.min_distance)
+                .min(max_distance);
+            match kira_spacial.scene.add_emitter(
+                mint::Vector3::from(position.to_array()),
+                EmitterSettings::new()
+                    .distances((min_distance, max_distance))
+                    .persist_until_sounds_finish(emitter.keep_playing_on_despawn),
+            ) {
+                Ok(kira_emitter) => {
+                    kira_spacial.emitters.insert(entity, kira_emitter);
+                }
+                Err(error) => warn!("Failed to create kira emitter: {:?}", error),
+            }
+        }
+        kira_spacial
+            .emitters
+            .retain(|entity, _| existing.contains(entity));
+    }
+
+    /// Start the next sounds of channel queues and return the queues that advanced
+    pub(crate) fn update_queues(
+        &mut self,
+        audio_sources: &Assets<AudioSource>,
+        audio_instances: &mut Assets<AudioInstance>,
+    ) -> Vec<QueueAdvanced> {
+        // Sounds of queues play through the output, which does not touch the queues meanwhile
+        let mut queues = std::mem::take(&mut self.queues);
+        let advanced = queues.update(self, audio_sources, audio_instances);
+        self.queues = queues;
+
+        advanced
+    }
+
+    /// Start fading out the output of all audio managers for the exit fade of the settings
+    ///
+    /// Returns how long the fade takes, see [`AudioSettings::exit_fade`].
+    pub(crate) fn fade_out_for_exit(&mut self) -> Duration {
+        let fade = self.settings.exit_fade;
+        if fade.is_zero() || self.manager.is_none() {
+            return Duration::ZERO;
+        }
+        let tween = Tween {
+            duration: fade,
+            ..Default::default()
+        };
+        if let Some(manager) = self.manager.as_mut() {
+            if let Err(error) = manager.pause(tween) {
+                error!("Failed to fade out audio for exit: {:?}", error);
+            }
+        }
+        for manager in self.devices.managers_mut() {
+            if let Err(error) = manager.pause(tween) {
+                error!("Failed to fade out output device for exit: {:?}", error);
+            }
+        }
+
+        fade
+    }
+
+    /// Continue on the given audio manager, after switching the output device
+    ///
+    /// Instances of the old manager are lost, see [`release_manager`](Self::release_manager).
+    /// The buses of the channel hierarchy are created again.
+    pub(crate) fn replace_manager(
+        &mut self,
+        mut manager: AudioManager<B>,
+        hierarchy: &ChannelHierarchy,
+        audio_instances: &mut Assets<AudioInstance>,
+    ) {
+        self.release_manager(audio_instances);
+        self.clock = AudioClock::start(&mut manager);
+        self.follow_main_state(&manager);
+        self.lfos.start(&mut manager);
+        self.manager = Some(manager);
+        self.update_channel_buses(hierarchy);
+    }
+
+    /// Run the commands of the modulators and hand them the ids of the created LFOs
+    pub(crate) fn update_modulators(&mut self, modulators: &mut AudioModulators) {
+        self.lfos.update(modulators, self.manager.as_mut());
+    }
+
+    /// Add new [`AudioClocks`] to the audio manager and hand changes to the audio thread
+    pub(crate) fn sync_clocks(&mut self) {
+        if let Some(manager) = self.manager.as_mut() {
+            self.clocks.sync(manager);
+        }
+    }
+
+    /// Whether there is an audio manager to play sounds on
+    pub(crate) fn has_manager(&self) -> bool {
+        self.manager.is_some()
+    }
+
+    /// A time of the audio clock to start sounds on in sync
+    ///
+    /// `None` while the browser did not allow audio yet, since scheduled sounds would all start
+    /// at once when it resumes the audio.
+    pub(crate) fn synced_start(&self) -> Option<AudioClockTime> {
+        (!self.suspended).then(|| self.clock.now() + QUEUE_START_DELAY)
+    }
+
+    /// The current time of the audio clock
+    pub(crate) fn now(&self) -> AudioClockTime {
+        self.clock.now()
+    }
+
+    /// Whether the channel is paused
+    pub(crate) fn is_paused(&self, channel: &Channel) -> bool {
+        self.channels
+            .get(channel)
+            .map_or(false, |channel_state| channel_state.paused)
+    }
+
+    /// Whether the instance loops, see [`LoopCompleted`]
+    pub(crate) fn is_looping(&self, instance: &Handle<AudioInstance>) -> bool {
+        self.completions.is_looping(instance)
+    }
+
+    /// Play the loaded source on the channel as a new instance
+    ///
+    /// Failures are reported with the source.
+    pub(crate) fn play_source(
+        &mut self,
+        channel: &Channel,
+        settings: &PartialSoundSettings,
+        source: &Handle<AudioSource>,
+        audio_sources: &Assets<AudioSource>,
+        audio_instances: &mut Assets<AudioInstance>,
+    ) -> Option<Handle<AudioInstance>> {
+        let audio_source = audio_sources.get(source)?;
+        let instance = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::new_v4(),
+        });
+        let errors = self.errors.len();
+        self.play(
+            channel,
+            settings,
+            audio_source.into(),
+            instance.clone(),
+            audio_instances,
+        );
+        self.set_error_source(errors, Some(source.clone().untyped()));
+
+        Some(instance)
+    }
+
+    /// The number of instances of each channel that did not stop yet
+    pub(crate) fn instance_counts(&self) -> impl Iterator<Item = (&Channel, usize)> {
+        self.instances
+            .iter()
+            .map(|(channel, instances)| (channel, instances.len()))
+    }
+
+    /// The state and instances of all channels that got a command or played a sound
+    #[cfg(feature = "inspection")]
+    pub(crate) fn inspected_channels(
+        &self,
+    ) -> impl Iterator<Item = (&Channel, Option<&ChannelState>, &[Handle<AudioInstance>])> {
+        let without_state = self
+            .instances
+            .keys()
+            .filter(|channel| !self.channels.contains_key(*channel))
+            .map(|channel| (channel, None));
+        self.channels
+            .iter()
+            .map(|(channel, channel_state)| (channel, Some(channel_state)))
+            .chain(without_state)
+            .map(|(channel, channel_state)| {
+                let instances = self.instances.get(channel).map_or(&[][..], Vec::as_slice);
+                (channel, channel_state, instances)
+            })
+    }
+
+    /// The number of commands left in the queues of all channels after the last update
+    pub(crate) fn pending_commands(&self) -> usize {
+        self.pending_commands.values().sum()
+    }
+
+    /// Whether the play command of the instance waits in a queue, for example for its source
+    pub(crate) fn is_play_pending(&self, instance: AssetId<AudioInstance>) -> bool {
+        self.pending_plays
+            .values()
+            .any(|plays| plays.contains(&instance))
+    }
+
+    /// The number of play commands that failed since the start
+    pub(crate) fn dropped_commands(&self) -> u64 {
+        self.dropped_commands
+    }
+
+    /// Whether sounds of the channel play at the speed of virtual time
+    fn follows_virtual_time(&self, channel: &Channel) -> bool {
+        self.settings.follow_virtual_time
+            || self
+                .channels
+                .get(channel)
+                .map_or(false, |channel_state| channel_state.follow_virtual_time)
+    }
+
+    /// Scale the playback rates of the instances that follow virtual time by its new speed
+    ///
+    /// See [`AudioSettings::follow_virtual_time`].
+    pub(crate) fn set_time_speed(
+        &mut self,
+        speed: f64,
+        audio_instances: &mut Assets<AudioInstance>,
+    ) {
+        if !self.main_playback.set_time_speed(speed) {
+            return;
+        }
+        for (channel, instances) in self.instances.iter() {
+            if !self.follows_virtual_time(channel) {
+                continue;
+            }
+            for instance in instances {
+                let Some(instance) = audio_instances.get_mut(instance) else {
+                    continue;
+                };
+                if let Err(error) = instance.set_time_scale(speed, Tween::default()) {
+                    error!("Failed to follow the speed of virtual time: {:?}", error);
+                }
+            }
+        }
+    }
+
+    /// Fade the main tracks of all audio managers to the given volume, see [`MasterVolume`](crate::MasterVolume)
+    pub(crate) fn set_master_volume(&mut self, volume: Volume, tween: Tween) {
+        self.main_playback.set_master_volume(volume);
+        self.update_main_volume(tween);
+    }
+
+    fn update_main_volume(&self, tween: Tween) {
+        let volume = self.main_playback.main_volume(self.settings.focus_loss);
+        if let Some(manager) = self.manager.as_ref() {
+            set_main_volume(manager, volume, tween);
+        }
+        for manager in self.devices.managers() {
+            set_main_volume(manager, volume, tween);
+        }
+    }
+
+    /// Give a new audio manager the volume and pause of the others
+    fn follow_main_state<M: Backend>(&self, manager: &AudioManager<M>) {
+        self.main_playback.start(manager, self.settings.focus_loss);
+    }
+
+    /// Pause or duck all audio while no window has the focus, see [`AudioSettings::focus_loss`]
+    ///
+    /// Pausing happens on the audio managers, so the states of channels and sounds stay as the
+    /// game left them.
+    pub(crate) fn set_focused(&mut self, focused: bool) {
+        if !self.main_playback.set_focused(focused) {
+            return;
+        }
+        let unfocused = self.main_playback.is_unfocused();
+        let tween = Tween {
+            duration: FOCUS_FADE,
+            ..Default::default()
+        };
+        match self.settings.focus_loss {
+            FocusLoss::Ignore => (),
+            FocusLoss::Pause => {
+                if let Some(manager) = self.manager.as_ref() {
+                    pause_manager(manager, unfocused, tween);
+                }
+                for manager in self.devices.managers() {
+                    pause_manager(manager, unfocused, tween);
+                }
+            }
+            FocusLoss::Duck { .. } => self.update_main_volume(tween),
+        }
+    }
+
+    /// Drop the audio manager and everything that lives in its mixer
+    ///
+    /// Its instances are lost and complete as stopped. Channel states and the upcoming sounds of
+    /// queues are kept. Effect tracks of channels are dropped.
+    fn release_manager(&mut self, audio_instances: &mut Assets<AudioInstance>) {
+        for handle in self.completions.uncompleted() {
+            let Some(instance) = audio_instances.get_mut(handle) else {
+                continue;
+            };
+            if instance.on_output_device {
+                continue;
+            }
+            instance.handle = SoundHandle::Lost {
+                position: instance.handle.position(),
+            };
+            instance.track = None;
+            instance.stop_requested = true;
+        }
+        self.queues.release(audio_instances);
+        if !self.channel_tracks.is_empty() || !self.send_tracks.is_empty() {
+            warn!("The effects and sends of audio channels were lost with the output device, set them again");
+        }
+        self.channel_tracks.clear();
+        self.send_tracks.clear();
+        self.retired_tracks.clear();
+        self.buses.clear();
+        self.spacial_reverb = None;
+        self.kira_spacial = None;
+        self.clocks.release();
+        self.lfos.release();
+        self.manager = None;
+    }
+
+    /// Drop the audio managers, which stops their audio threads
+    ///
+    /// Handles of sounds and tracks stay valid, but commands to them have no effect anymore.
+    pub(crate) fn close(&mut self) {
+        self.devices.close();
+        self.manager = None;
+    }
+
+    /// Count the completed iterations of looping instances since the last update
+    ///
+    /// `delta` is the real time since then.
+    pub(crate) fn track_loops(
+        &mut self,
+        instances: &mut Assets<AudioInstance>,
+        delta: Duration,
+    ) -> Vec<LoopCompleted> {
+        self.completions.track_loops(instances, delta)
+    }
+
+    /// Forget stopped instances and return the completion events of newly stopped instances
+    ///
+    /// The assets of stopped one-shots are removed.
+    pub(crate) fn cleanup_stopped_instances(
+        &mut self,
+        instances: &mut Assets<AudioInstance>,
+    ) -> Vec<PlaybackCompleted> {
+        let is_playing = |handle: &Handle<AudioInstance>| !has_stopped(instances, handle);
+        for (_, handles) in self.instances.iter_mut() {
+            handles.retain(is_playing);
+        }
+        self.retired_tracks
+            .retain(|(_, handles)| handles.iter().any(is_playing));
+
+        self.completions.complete_stopped(instances)
+    }
+}
+
+impl AudioOutput {
+    /// The status of the main audio manager, if there is one
+    fn running_status(&mut self) -> Option<AudioBackendStatus> {
+        self.manager
+            .as_mut()
+            .map(|manager| AudioBackendStatus::Running {
+                sample_rate: manager.backend_mut().sample_rate(),
+            })
+    }
+
+    /// Play on the output device with the given name, or the default one for `None`
+    ///
+    /// If the device fails to open, the current one keeps playing.
+    fn set_device(
+        &mut self,
+        name: Option<&str>,
+        hierarchy: &ChannelHierarchy,
+        audio_instances: &mut Assets<AudioInstance>,
+    ) -> Result<(), OutputDeviceError> {
+        let device = name.map(find_output_device).transpose()?;
+        let (manager, effects) = open_main_manager(self.main_effects.as_ref(), |main_track| {
+            self.settings.manager_settings(device, main_track)
+        })?;
+        self.replace_manager(manager, hierarchy, audio_instances);
+        self.new_main_effects = Some(effects);
+        self.devices.main = name.map(str::to_owned);
+
+        Ok(())
+    }
+}
+
+/// Open the main audio manager with the [`MainTrackEffects`] on its main track
+fn open_main_manager(
+    effects: Option<&MainTrackEffects>,
+    settings: impl FnOnce(TrackBuilder) -> AudioManagerSettings<MainBackend>,
+) -> Result<(AudioManager<MainBackend>, EffectHandles), OutputDeviceError> {
+    let chain = effects.map_or_else(EffectChain::default, MainTrackEffects::build);
+    AudioManager::new(settings(chain.track)).map(|manager| (manager, chain.handles))
+}
+
+/// Start and end of the loop region of a sound in seconds
+fn loop_region_seconds(sound: &impl SoundLayout) -> Option<(f64, f64)> {
+    let loop_region = sound.settings().loop_region?;
+    let end = match loop_region.end {
+        EndPosition::EndOfAudio => sound.length().as_secs_f64(),
+        EndPosition::Custom(end) => seconds(sound, end),
+    };
+
+    Some((seconds(sound, loop_region.start), end))
+}
+
+/// Whether a sound that does not loop would start outside of its audio
+///
+/// Reversed sounds start at the end of their playback region.
+fn starts_after_end(sound: &impl SoundLayout) -> bool {
+    let settings = sound.settings();
+    if settings.loop_region.is_some() {
+        return false;
+    }
+    if settings.reverse {
+        return match settings.playback_region.end {
+            EndPosition::EndOfAudio => false,
+            EndPosition::Custom(end) => seconds(sound, end) <= 0.,
+        };
+    }
+
+    seconds(sound, settings.playback_region.start) >= sound.length().as_secs_f64()
+}
+
+/// Whether the loop region of a sound, if any, is not empty and inside the sound
+fn has_valid_loop_region(sound: &impl SoundLayout) -> bool {
+    let Some((start, end)) = loop_region_seconds(sound) else {
+        return true;
+    };
+
+    start >= 0. && start < end && end <= sound.length().as_secs_f64()
+}
+
+fn seconds(sound: &impl SoundLayout, position: PlaybackPosition) -> f64 {
+    sound.seconds(position)
+}
+
+pub(crate) fn update_spacial_reverb(
+    mut audio_output: NonSendMut<AudioOutput>,
+    spacial_audio: Res<SpacialAudio>,
+    zones: Query<(Entity, &ReverbZone, &GlobalTransform)>,
+    receivers: ZoneReceivers,
+    mut entered: Local<EnteredReverbZones>,
 ) {
-    if let Some(audio_sources) = audio_sources {
-        audio_output.play_dynamic_channels(&audio_sources, &channels, &mut audio_instances);
+    if zones.is_empty() {
+        entered.clear();
+        audio_output.update_spacial_reverb(spacial_audio.reverb.as_ref(), &AudioTween::default());
+        return;
+    }
+    let inside: Vec<_> = zone_receiver(&receivers)
+        .map(|receiver| {
+            zones
+                .iter()
+                .filter(|(_, zone, transform)| zone.extent.contains(transform, receiver))
+                .map(|(entity, zone, _)| (entity, zone))
+                .collect()
+        })
+        .unwrap_or_default();
+    let tween = entered.update(&inside).unwrap_or_default();
+    let reverb = entered
+        .current()
+        .and_then(|entity| zones.get(entity).ok())
+        .map(|(_, zone, _)| zone.reverb.clone())
+        .or_else(|| spacial_audio.reverb.clone())
+        .unwrap_or_default();
+    audio_output.update_spacial_reverb(Some(&reverb), &tween);
+}
+
+pub(crate) fn sync_kira_spacial_scene(
+    mut audio_output: NonSendMut<AudioOutput>,
+    spacial_audio: Res<SpacialAudio>,
+    receivers: Query<(Ref<GlobalTransform>, Has<PrimaryReceiver>), With<AudioReceiver>>,
+    emitters: Query<(Entity, Ref<GlobalTransform>, &AudioEmitter)>,
+    audio_instances: Res<Assets<AudioInstance>>,
+) {
+    let mode = spacial_audio.mode;
+    let listener = receivers
+        .iter()
+        .find(|(_, primary)| *primary)
+        .or_else(|| receivers.iter().next())
+        .map(|(transform, _)| {
+            let rotation = match mode {
+                SpacialMode::ThreeD => transform.compute_transform().rotation,
+                SpacialMode::TwoD { .. } => Quat::IDENTITY,
+            };
+
+            (
+                mode.project(transform.translation()),
+                rotation,
+                transform.is_changed(),
+            )
+        });
+    let mut pending = HashMap::default();
+    let emitters = emitters
+        .iter()
+        .map(|(entity, transform, emitter)| {
+            for handle in emitter.instances.iter() {
+                if !audio_instances.contains(handle) {
+                    pending.insert(handle.id(), entity);
+                }
+            }
+
+            (
+                entity,
+                mode.project(transform.translation()),
+                transform.is_changed(),
+                emitter,
+            )
+        })
+        .collect();
+    let spacial_audio_changed = spacial_audio.is_changed();
+    audio_output.sync_kira_spacial_scene(
+        &spacial_audio,
+        spacial_audio_changed,
+        KiraSpacialSync {
+            listener,
+            emitters,
+            pending,
+        },
+    );
+}
+
+pub(crate) fn update_channel_buses(
+    mut audio_output: NonSendMut<AudioOutput>,
+    hierarchy: Res<ChannelHierarchy>,
+) {
+    audio_output.update_channel_buses(&hierarchy);
+}
+
+pub(crate) fn play_dynamic_channels(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut channels: ResMut<DynamicAudioChannels>,
+    mut hierarchy: ResMut<ChannelHierarchy>,
+    audio_sources: Option<Res<Assets<AudioSource>>>,
+    streamed_sources: Option<Res<Assets<StreamedAudioSource>>>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    for (key, tween) in channels.removed.drain(..) {
+        let channel = Channel::Dynamic(key);
+        audio_output.remove_channel(&channel, &mut audio_instances, &tween);
+        if hierarchy.bypass_change_detection().remove_channel(&channel) {
+            hierarchy.set_changed();
+        }
+    }
+    channels.detached.commands.write().clear();
+    if let (Some(audio_sources), Some(streamed_sources)) = (audio_sources, streamed_sources) {
+        audio_output.play_dynamic_channels(
+            &audio_sources,
+            &streamed_sources,
+            &channels,
+            &mut audio_instances,
+        );
     };
 }
 
-pub(crate) fn play_audio_channel<T: Resource>(
+pub(crate) fn resume_paused_audio(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    time: Res<Time>,
+) {
+    audio_output.resume_paused_audio(&mut audio_instances, time.delta());
+}
+
+/// Hand the effect handles of a new main track to [`MainEffects`]
+pub(crate) fn update_main_effects(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut main_effects: ResMut<MainEffects>,
+) {
+    if let Some(effects) = audio_output.new_main_effects.take() {
+        *main_effects = MainEffects::new(effects);
+    }
+}
+
+/// Render the audio of the headless backend, see [`AudioSettings::headless_fallback`]
+///
+/// With a [`MockAudio`], only the time passed to [`MockAudio::advance`] is rendered.
+pub(crate) fn advance_headless_audio(
+    mut audio_output: NonSendMut<AudioOutput>,
+    time: Res<Time<Real>>,
+) {
+    #[cfg(feature = "mock-backend")]
+    let delta = audio_output
+        .mock
+        .as_ref()
+        .map_or_else(|| time.delta(), MockAudio::take_unrendered);
+    #[cfg(not(feature = "mock-backend"))]
+    let delta = time.delta();
+    if let Some(MainBackend::Headless(backend)) = audio_output
+        .manager
+        .as_mut()
+        .map(|manager| manager.backend_mut())
+    {
+        backend.advance(delta);
+    }
+}
+
+/// Resume the audio on the first input in the browser, see [`AudioContextState`]
+///
+/// The browser only lets the stream play after an interaction, so it is started again on every
+/// input until the audio clock moves.
+pub(crate) fn unlock_audio_context(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut state: ResMut<AudioContextState>,
+    mouse: Option<Res<Events<MouseButtonInput>>>,
+    keyboard: Option<Res<Events<KeyboardInput>>>,
+    touch: Option<Res<Events<TouchInput>>>,
+) {
+    if audio_output.clock.now().ticks > 0 {
+        audio_output.suspended = false;
+        *state = AudioContextState::Running;
+        return;
+    }
+    let interacted = mouse.map_or(false, |events| !events.is_empty())
+        || keyboard.map_or(false, |events| !events.is_empty())
+        || touch.map_or(false, |events| !events.is_empty());
+    if interacted {
+        if let Some(manager) = audio_output.manager.as_mut() {
+            manager.backend_mut().resume();
+        }
+    }
+}
+
+/// Time for the audio thread to pick up the exit fade before waiting for it
+const EXIT_FLUSH: Duration = Duration::from_millis(20);
+
+/// Fade out and close the audio backend once the app exits, see [`AudioSettings::exit_fade`]
+pub(crate) fn close_on_exit(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut exit: EventReader<AppExit>,
+) {
+    if exit.is_empty() {
+        return;
+    }
+    exit.clear();
+    let fade = audio_output.fade_out_for_exit();
+    if !fade.is_zero() {
+        std::thread::sleep(fade + EXIT_FLUSH);
+    }
+    audio_output.close();
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn switch_audio_device(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut device: ResMut<AudioDevice>,
+    mut clock: ResMut<AudioClock>,
+    mut status: ResMut<AudioBackendStatus>,
+    hierarchy: Res<ChannelHierarchy>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    mut failed: EventWriter<AudioDeviceFailed>,
+    mut changed: EventWriter<AudioDeviceChanged>,
+) {
+    // The mock backend never plays on a device
+    if device.name == audio_output.devices.main || audio_output.mocked() {
+        return;
+    }
+    match audio_output.set_device(device.name.as_deref(), &hierarchy, &mut audio_instances) {
+        Ok(()) => {
+            *clock = audio_output.clock.clone();
+            if let Some(running) = audio_output.running_status() {
+                *status = running;
+            }
+            changed.send(AudioDeviceChanged {
+                device: device.name.clone(),
+            });
+        }
+        Err(error) => {
+            error!(
+                "Failed to switch to output device {:?}, the audio keeps playing on the previous device: {}",
+                device.name, error
+            );
+            failed.send(AudioDeviceFailed {
+                device: device.name.clone(),
+                error,
+            });
+            device.bypass_change_detection().name = audio_output.devices.main.clone();
+        }
+    }
+}
+
+/// Notice lost output devices and reopen the default device with increasing waits in between
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn recover_audio_device(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut device: ResMut<AudioDevice>,
+    mut clock: ResMut<AudioClock>,
+    mut status: ResMut<AudioBackendStatus>,
+    hierarchy: Res<ChannelHierarchy>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    time: Res<Time<Real>>,
+    mut lost: EventWriter<AudioDeviceLost>,
+    mut changed: EventWriter<AudioDeviceChanged>,
+) {
+    let audio_output = &mut *audio_output;
+    for name in audio_output.devices.remove_failed() {
+        error!(
+            "Lost the audio output device {:?}, its channels play on the main device",
+            name
+        );
+        lost.send(AudioDeviceLost { device: Some(name) });
+    }
+    let delta = time.delta();
+    // The clock of the mock backend only moves with the mock time
+    let mocked = audio_output.mocked();
+    if let Some(manager) = audio_output.manager.as_mut() {
+        // Browsers hold the audio back until the user interacts with the page,
+        // and the clock stands still while the audio is paused for the window focus
+        let stalled = !cfg!(target_arch = "wasm32")
+            && audio_output.clock.is_running()
+            && manager.state() == MainPlaybackState::Playing
+            && !mocked
+            && audio_output
+                .devices
+                .watchdog
+                .is_stalled(audio_output.clock.now().ticks, delta);
+        if !manager.backend_mut().has_failed() && !stalled {
+            return;
+        }
+        error!(
+            "Lost the audio output device {:?}, trying to open the default device",
+            audio_output.devices.main
+        );
+        lost.send(AudioDeviceLost {
+            device: audio_output.devices.main.take(),
+        });
+        audio_output.release_manager(&mut audio_instances);
+        audio_output.devices.watchdog.lost();
+        device.bypass_change_detection().name = None;
+    }
+    if !audio_output.devices.watchdog.retry_due(delta) {
+        return;
+    }
+    let settings = audio_output.settings;
+    match open_main_manager(audio_output.main_effects.as_ref(), |main_track| {
+        settings.manager_settings(None, main_track)
+    }) {
+        Ok((manager, effects)) => {
+            audio_output.replace_manager(manager, &hierarchy, &mut audio_instances);
+            audio_output.new_main_effects = Some(effects);
+            audio_output.devices.watchdog.recovered();
+            *clock = audio_output.clock.clone();
+            if let Some(running) = audio_output.running_status() {
+                *status = running;
+            }
+            changed.send(AudioDeviceChanged { device: None });
+        }
+        Err(error) => {
+            warn!(
+                "Failed to reopen the default audio output device: {}",
+                error
+            );
+            audio_output.devices.watchdog.retry_failed();
+            *status = AudioBackendStatus::Unavailable(Arc::new(error));
+        }
+    }
+}
+
+pub(crate) fn report_audio_errors(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut errors: EventWriter<AudioError>,
+) {
+    errors.send_batch(audio_output.errors.drain(..));
+}
+
+pub(crate) fn update_queues(
     mut audio_output: NonSendMut<AudioOutput>,
-    channel: Res<AudioChannel<T>>,
     audio_sources: Option<Res<Assets<AudioSource>>>,
-    mut instances: ResMut<Assets<AudioInstance>>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    mut advanced: EventWriter<QueueAdvanced>,
 ) {
     if let Some(audio_sources) = audio_sources {
-        audio_output.play_channel(&audio_sources, &channel, &mut instances);
-    };
+        advanced.send_batch(audio_output.update_queues(&audio_sources, &mut audio_instances));
+    }
 }
 
-pub(crate) fn cleanup_stopped_instances(
+pub(crate) fn play_audio_channel<T: Resource>(
     mut audio_output: NonSendMut<AudioOutput>,
+    channel: Res<AudioChannel<T>>,
+    audio_sources: Option<Res<Assets<AudioSource>>>,
+    streamed_sources: Option<Res<Assets<StreamedAudioSource>>>,
     mut instances: ResMut<Assets<AudioInstance>>,
 ) {
-    audio_output.cleanup_stopped_instances(&mut instances);
+    if let (Some(audio_sources), Some(streamed_sources)) = (audio_sources, streamed_sources) {
+        audio_output.play_channel(&audio_sources, &streamed_sources, &channel, &mut instances);
+    };
 }
 
 pub(crate) fn update_instance_states<T: Resource>(
@@ -454,104 +2710,30 @@ pub(crate) fn update_instance_states<T: Resource>(
                 .unwrap_or(PlaybackState::Stopped);
             channel.states.insert(instance_handle.id(), state);
         }
+        channel.instances = instances.clone();
     }
 }
 
-#[cfg(test)]
-mod test {
-    use super::{AudioInstance, AudioManager, AudioOutput, HashMap, AudioCommand, AudioChannel, AudioSource};
-    use crate::channel::AudioControl;
-    use crate::{Audio, AudioPlugin};
-    use bevy::app::App;
-    use bevy::asset::{AssetId, AssetPlugin};
-    // use bevy::prelude::*;
-    use bevy::utils::Uuid;
-    use bevy::prelude::{Assets, MinimalPlugins, Handle};
-    use kira::manager::backend::mock::MockBackend;
-    use kira::manager::AudioManagerSettings;
-
-    #[test]
-    fn keeps_order_of_commands_to_retry() {
-        // we only need this app to conveniently get a assets collection for `AudioSource`...
-        let mut app = App::new();
-        app.add_plugins((MinimalPlugins, AssetPlugin::default(), AudioPlugin));
-        let audio_source_assets = app.world.remove_resource::<Assets<AudioSource>>().unwrap();
-        let mut audio_instance_assets = app
-            .world
-            .remove_resource::<Assets<AudioInstance>>()
-            .unwrap();
-
-        let mut audio_output = AudioOutput {
-            manager: AudioManager::new(AudioManagerSettings::<MockBackend>::default()).ok(),
-            instances: HashMap::default(),
-            channels: HashMap::default(),
-        };
-        let audio_handle_one: Handle<AudioSource> = Handle::<AudioSource>::Weak(AssetId::Uuid {
-            uuid: Uuid::from_u128(1758302748397294),
-        });
-        let audio_handle_two: Handle<AudioSource> = Handle::<AudioSource>::Weak(AssetId::Uuid {
-            uuid: Uuid::from_u128(2537024739048739),
-        });
-
-        let channel = AudioChannel::<Audio>::default();
-        channel.play(audio_handle_one.clone());
-        channel.play(audio_handle_two.clone());
-
-        audio_output.play_channel(&audio_source_assets, &channel, &mut audio_instance_assets);
-
-        let command_one = channel.commands.write().pop_back().unwrap();
-        match command_one {
-            AudioCommand::Play(settings) => {
-                assert_eq!(settings.source.id(), audio_handle_one.id())
-            }
-            _ => panic!("Wrong audio command"),
-        }
-        let command_two = channel.commands.write().pop_back().unwrap();
-        match command_two {
-            AudioCommand::Play(settings) => {
-                assert_eq!(settings.source.id(), audio_handle_two.id())
-            }
-            _ => panic!("Wrong audio command"),
-        }
-    }
-
-    #[test]
-    fn stop_command_removes_previous_play_commands() {
-        // we only need this app to conveniently get a assets collection for `AudioSource`...
-        let mut app = App::new();
-        app.add_plugins((MinimalPlugins, AssetPlugin::default(), AudioPlugin));
-        let audio_source_assets = app.world.remove_resource::<Assets<AudioSource>>().unwrap();
-        let mut audio_instance_assets = app
-            .world
-            .remove_resource::<Assets<AudioInstance>>()
-            .unwrap();
-
-        let mut audio_output = AudioOutput {
-            manager: AudioManager::new(AudioManagerSettings::<MockBackend>::default()).ok(),
-            instances: HashMap::default(),
-            channels: HashMap::default(),
+pub(crate) fn update_dynamic_instance_states(
+    audio_output: NonSend<AudioOutput>,
+    audio_instances: Res<Assets<AudioInstance>>,
+    mut channels: ResMut<DynamicAudioChannels>,
+) {
+    for (key, channel) in channels.channels.iter_mut() {
+        let Some(instances) = audio_output.instances.get(&Channel::Dynamic(key.clone())) else {
+            continue;
         };
-        let audio_handle_one: Handle<AudioSource> = Handle::<AudioSource>::Weak(AssetId::Uuid {
-            uuid: Uuid::from_u128(13290473942075938),
-        });
-        let audio_handle_two: Handle<AudioSource> = Handle::<AudioSource>::Weak(AssetId::Uuid {
-            uuid: Uuid::from_u128(243290473942075938),
-        });
-
-        let channel = AudioChannel::<Audio>::default();
-        channel.play(audio_handle_one);
-        channel.stop();
-        channel.play(audio_handle_two.clone());
-
-        audio_output.play_channel(&audio_source_assets, &channel, &mut audio_instance_assets);
-
-        let command = channel.commands.write().pop_back().unwrap();
-        match command {
-            AudioCommand::Play(settings) => {
-                assert_eq!(settings.source.id(), audio_handle_two.id())
-            }
-            _ => panic!("Wrong audio command"),
+        channel.states.clear();
+        for instance_handle in instances.iter() {
+            let state = audio_instances
+                .get(instance_handle)
+                .map(|instance| instance.state())
+                .unwrap_or(PlaybackState::Stopped);
+            channel.states.insert(instance_handle.id(), state);
         }
-        assert!(channel.commands.write().pop_back().is_none());
+        channel.instances = instances.clone();
     }
 }
+
+#[cfg(test)]
+mod test;
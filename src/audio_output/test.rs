@@ -0,0 +1,2489 @@
+use super::{
+    has_valid_loop_region, AudioChannel, AudioClock, AudioClocks, AudioCommand, AudioCommandResult,
+    AudioEmitter, AudioErrorKind, AudioInstance, AudioManager, AudioOutput, AudioRng,
+    AudioSettings, AudioSource, AudioTween, Channel, ChannelHierarchy, ChannelReverb, EndPosition,
+    Entity, FilterBuilder, FocusLoss, HashMap, KiraSpacialSync, PartialSoundSettings,
+    PlayAudioSettings, Quat, SpacialAudio, SpacialBackend, SpacialReverb, Vec3,
+};
+use crate::audio::{AudioEasing, CrossfadeCurve, FilterSettings};
+use crate::channel::ducking::DuckSettings;
+use crate::channel::effects::{EffectChain, MainEffects, MainTrackEffects};
+use crate::channel::queue::QueueRepeat;
+use crate::channel::{AudioControl, ChannelState, StealPolicy};
+use crate::instance::AudioCommandError;
+use crate::layers::{MusicLayers, LAYER_SYNC_TOLERANCE};
+use crate::modulator::{AudioModulators, LfoSettings, ModulatedParameter};
+use crate::{
+    Audio, AudioBackendFailed, AudioBackendStatus, AudioCollection, AudioContextState, AudioPlugin,
+    MainTrack, PlaybackCompleted, SelectionMode, StreamedAudioSource,
+};
+use bevy::app::App;
+use bevy::asset::{AssetId, AssetPlugin};
+use bevy::ecs::event::Events;
+// use bevy::prelude::*;
+use crate::clock::ScheduledStart;
+use bevy::prelude::{Assets, Handle, MinimalPlugins};
+use bevy::utils::Uuid;
+use kira::dsp::Frame;
+use kira::manager::backend::mock::{MockBackend, MockBackendSettings};
+use kira::manager::{AudioManagerSettings, MainPlaybackState};
+use kira::modulator::lfo::Waveform;
+use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+use kira::sound::PlaybackRate;
+use kira::track::effect::compressor::{CompressorBuilder, CompressorHandle};
+use kira::track::effect::filter::FilterHandle;
+use kira::Volume;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// An audio manager on a mock backend that renders one frame per millisecond
+fn mock_manager() -> AudioManager<MockBackend> {
+    AudioManager::new(AudioManagerSettings::<MockBackend> {
+        backend_settings: MockBackendSettings { sample_rate: 1_000 },
+        ..Default::default()
+    })
+    .unwrap()
+}
+
+/// An audio output on a [`mock_manager`] with a running clock
+fn mock_audio_output() -> AudioOutput<MockBackend> {
+    let mut manager = mock_manager();
+    let clock = AudioClock::start(&mut manager);
+    AudioOutput::new(
+        Some(manager),
+        AudioSettings::default(),
+        clock,
+        AudioClocks::default(),
+        AudioRng::seeded(0),
+    )
+}
+
+/// A silent sound with the given number of frames at the sample rate of the [`mock_manager`]
+fn silent_source(frames: usize) -> AudioSource {
+    AudioSource {
+        sound: StaticSoundData {
+            sample_rate: 1_000,
+            frames: Arc::from(vec![Frame::ZERO; frames]),
+            settings: StaticSoundSettings::default(),
+        },
+        loop_region: None,
+        gain: 1.,
+    }
+}
+
+/// Render the given number of frames and share the new state of the sounds
+fn render(audio_output: &mut AudioOutput<MockBackend>, frames: usize) {
+    let backend = audio_output.manager.as_mut().unwrap().backend_mut();
+    backend.on_start_processing();
+    for _ in 0..frames {
+        backend.process();
+    }
+    backend.on_start_processing();
+}
+
+#[test]
+fn keeps_order_of_commands_to_retry() {
+    // we only need this app to conveniently get a assets collection for `AudioSource`...
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        AudioPlugin::default(),
+    ));
+    let audio_source_assets = app.world.remove_resource::<Assets<AudioSource>>().unwrap();
+    let mut audio_instance_assets = app
+        .world
+        .remove_resource::<Assets<AudioInstance>>()
+        .unwrap();
+
+    let mut audio_output = mock_audio_output();
+    let audio_handle_one: Handle<AudioSource> = Handle::<AudioSource>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1758302748397294),
+    });
+    let audio_handle_two: Handle<AudioSource> = Handle::<AudioSource>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(2537024739048739),
+    });
+
+    let channel = AudioChannel::<Audio>::default();
+    channel.play(audio_handle_one.clone());
+    channel.play(audio_handle_two.clone());
+
+    audio_output.play_channel(
+        &audio_source_assets,
+        &Assets::default(),
+        &channel,
+        &mut audio_instance_assets,
+    );
+
+    let command_one = channel.commands.write().pop_back().unwrap();
+    match command_one {
+        AudioCommand::Play(settings) => {
+            assert_eq!(settings.source.id(), audio_handle_one.id())
+        }
+        _ => panic!("Wrong audio command"),
+    }
+    let command_two = channel.commands.write().pop_back().unwrap();
+    match command_two {
+        AudioCommand::Play(settings) => {
+            assert_eq!(settings.source.id(), audio_handle_two.id())
+        }
+        _ => panic!("Wrong audio command"),
+    }
+}
+
+#[test]
+fn collections_pick_one_variant_per_play_once_loaded() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        AudioPlugin::default(),
+    ));
+    let audio_source_assets = app.world.remove_resource::<Assets<AudioSource>>().unwrap();
+    let mut audio_instance_assets = app
+        .world
+        .remove_resource::<Assets<AudioInstance>>()
+        .unwrap();
+    let mut collections = Assets::<AudioCollection>::default();
+    let variants: Vec<Handle<AudioSource>> = (1..=3)
+        .map(|uuid| {
+            Handle::Weak(AssetId::Uuid {
+                uuid: Uuid::from_u128(uuid),
+            })
+        })
+        .collect();
+    let collection = collections.add(AudioCollection::from_sources(
+        variants.clone(),
+        SelectionMode::RoundRobin,
+    ));
+
+    let mut audio_output = mock_audio_output();
+    let channel = AudioChannel::<Audio>::default();
+    channel.play_collection(collection.clone());
+    channel.play_collection(collection.clone());
+    let play_and_peek = |audio_output: &mut AudioOutput<MockBackend>,
+                         audio_instances: &mut Assets<AudioInstance>| {
+        audio_output.play_channel(
+            &audio_source_assets,
+            &Assets::default(),
+            &channel,
+            audio_instances,
+        );
+        channel
+            .commands
+            .read()
+            .iter()
+            .rev()
+            .map(|command| match command {
+                AudioCommand::Play(play) => (play.source.clone(), play.collection.is_some()),
+                _ => panic!("Wrong audio command"),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // The collection did not reach the audio output yet
+    let waiting = play_and_peek(&mut audio_output, &mut audio_instance_assets);
+    assert!(waiting.iter().all(|(_, unpicked)| *unpicked));
+
+    audio_output.update_collections(&collections);
+    let picked = play_and_peek(&mut audio_output, &mut audio_instance_assets);
+    assert_eq!(
+        picked,
+        vec![(variants[0].clone(), false), (variants[1].clone(), false)]
+    );
+    // Retrying until the variants loaded keeps the picked variants
+    let retried = play_and_peek(&mut audio_output, &mut audio_instance_assets);
+    assert_eq!(retried, picked);
+}
+
+#[test]
+fn stop_command_removes_previous_play_commands() {
+    // we only need this app to conveniently get a assets collection for `AudioSource`...
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        AudioPlugin::default(),
+    ));
+    let audio_source_assets = app.world.remove_resource::<Assets<AudioSource>>().unwrap();
+    let mut audio_instance_assets = app
+        .world
+        .remove_resource::<Assets<AudioInstance>>()
+        .unwrap();
+
+    let mut audio_output = mock_audio_output();
+    let audio_handle_one: Handle<AudioSource> = Handle::<AudioSource>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(13290473942075938),
+    });
+    let audio_handle_two: Handle<AudioSource> = Handle::<AudioSource>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(243290473942075938),
+    });
+
+    let channel = AudioChannel::<Audio>::default();
+    channel.play(audio_handle_one);
+    channel.stop();
+    channel.play(audio_handle_two.clone());
+
+    audio_output.play_channel(
+        &audio_source_assets,
+        &Assets::default(),
+        &channel,
+        &mut audio_instance_assets,
+    );
+
+    let command = channel.commands.write().pop_back().unwrap();
+    match command {
+        AudioCommand::Play(settings) => {
+            assert_eq!(settings.source.id(), audio_handle_two.id())
+        }
+        _ => panic!("Wrong audio command"),
+    }
+    assert!(channel.commands.write().pop_back().is_none());
+}
+
+#[test]
+fn play_commands_wait_until_the_audio_context_runs() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        AudioPlugin::default(),
+    ));
+    assert_eq!(
+        *app.world.resource::<AudioContextState>(),
+        AudioContextState::Running
+    );
+    let mut audio_sources = app.world.remove_resource::<Assets<AudioSource>>().unwrap();
+    let mut audio_instances = app
+        .world
+        .remove_resource::<Assets<AudioInstance>>()
+        .unwrap();
+    let source = audio_sources.add(silent_source(100));
+
+    let mut audio_output = mock_audio_output();
+    audio_output.suspended = true;
+    let channel = AudioChannel::<Audio>::default();
+    let instance = channel.play(source).handle();
+    channel.set_volume(0.5);
+    audio_output.play_channel(
+        &audio_sources,
+        &Assets::default(),
+        &channel,
+        &mut audio_instances,
+    );
+    assert_eq!(channel.commands.read().len(), 1);
+    assert!(audio_instances.get(&instance).is_none());
+
+    audio_output.suspended = false;
+    audio_output.play_channel(
+        &audio_sources,
+        &Assets::default(),
+        &channel,
+        &mut audio_instances,
+    );
+    assert!(channel.commands.read().is_empty());
+    assert!(audio_instances.get(&instance).is_some());
+}
+
+#[test]
+fn spacial_reverb_track_follows_settings() {
+    let mut audio_output = mock_audio_output();
+
+    audio_output.update_spacial_reverb(Some(&SpacialReverb::default()), &AudioTween::default());
+    let track_id = audio_output.spacial_reverb.as_ref().unwrap().track.id();
+
+    let settings = SpacialReverb {
+        feedback: 0.5,
+        ..Default::default()
+    };
+    audio_output.update_spacial_reverb(Some(&settings), &AudioTween::default());
+    let reverb_track = audio_output.spacial_reverb.as_ref().unwrap();
+    assert_eq!(reverb_track.track.id(), track_id);
+    assert_eq!(reverb_track.settings, settings);
+
+    audio_output.update_spacial_reverb(None, &AudioTween::default());
+    assert!(audio_output.spacial_reverb.is_none());
+}
+
+#[test]
+fn channel_buses_follow_the_hierarchy() {
+    struct Master;
+    struct Sfx;
+    struct Footsteps;
+    let mut audio_output = mock_audio_output();
+    let mut hierarchy = ChannelHierarchy::default();
+    hierarchy
+        .set_parent(Channel::typed::<Footsteps>(), Channel::typed::<Sfx>())
+        .unwrap();
+    hierarchy
+        .set_parent(Channel::typed::<Sfx>(), Channel::typed::<Master>())
+        .unwrap();
+
+    audio_output.update_channel_buses(&hierarchy);
+    assert_eq!(audio_output.buses.len(), 2);
+    let sfx_bus = audio_output.buses[&Channel::typed::<Sfx>()].track.id();
+    assert_eq!(
+        audio_output.bus_of_parent(&Channel::typed::<Footsteps>()),
+        Some(sfx_bus)
+    );
+    assert_eq!(
+        audio_output.bus_of_parent(&Channel::typed::<Master>()),
+        None
+    );
+
+    audio_output.update_channel_buses(&hierarchy);
+    assert_eq!(
+        audio_output.buses[&Channel::typed::<Sfx>()].track.id(),
+        sfx_bus
+    );
+
+    // Sfx now routes to the main track, so its bus has to be replaced
+    hierarchy.remove_parent(&Channel::typed::<Sfx>());
+    audio_output.update_channel_buses(&hierarchy);
+    assert_eq!(audio_output.buses.len(), 1);
+    assert_ne!(
+        audio_output.buses[&Channel::typed::<Sfx>()].track.id(),
+        sfx_bus
+    );
+}
+
+#[test]
+fn kira_backend_routes_emitter_instances_to_the_scene() {
+    let mut audio_output = mock_audio_output();
+    let spacial_audio = SpacialAudio {
+        backend: SpacialBackend::Kira,
+        ..Default::default()
+    };
+    let emitter = AudioEmitter::default();
+    let entity = Entity::from_raw(7);
+    let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(41),
+    });
+    audio_output.sync_kira_spacial_scene(
+        &spacial_audio,
+        true,
+        KiraSpacialSync {
+            listener: Some((Vec3::ZERO, Quat::IDENTITY, true)),
+            emitters: vec![(entity, Vec3::X, true, &emitter)],
+            pending: HashMap::from([(instance_handle.id(), entity)]),
+        },
+    );
+    let kira_spacial = audio_output.kira_spacial.as_ref().unwrap();
+    assert!(kira_spacial.listener.is_some());
+    assert_eq!(kira_spacial.scene.num_emitters(), 1);
+
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = silent_source(100);
+    let settings = PartialSoundSettings {
+        start_silent: true,
+        ..Default::default()
+    };
+    audio_output.play(
+        &Channel::typed::<MainTrack>(),
+        &settings,
+        (&source).into(),
+        instance_handle.clone(),
+        &mut audio_instances,
+    );
+    let instance = audio_instances.get(&instance_handle).unwrap();
+    assert!(instance.in_kira_scene);
+    assert!(!instance.started_silent);
+    assert!(!spacial_audio.controls(instance));
+
+    audio_output.sync_kira_spacial_scene(
+        &spacial_audio,
+        false,
+        KiraSpacialSync {
+            listener: None,
+            emitters: vec![],
+            pending: HashMap::default(),
+        },
+    );
+    let kira_spacial = audio_output.kira_spacial.as_ref().unwrap();
+    assert!(kira_spacial.listener.is_none());
+    assert!(kira_spacial.emitters.is_empty());
+}
+
+#[test]
+fn instance_limit_stops_the_quietest_instance() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    audio_output.channels.insert(
+        channel.clone(),
+        ChannelState {
+            instance_limit: Some((2, StealPolicy::StopQuietest)),
+            ..Default::default()
+        },
+    );
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = silent_source(100);
+    let handles: Vec<_> = [1., 0.2, 0.6]
+        .into_iter()
+        .enumerate()
+        .map(|(index, volume)| {
+            let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+                uuid: Uuid::from_u128(index as u128 + 1),
+            });
+            let settings = PartialSoundSettings {
+                volume: Some(Volume::Amplitude(volume)),
+                ..Default::default()
+            };
+            audio_output.play(
+                &channel,
+                &settings,
+                (&source).into(),
+                instance_handle.clone(),
+                &mut audio_instances,
+            );
+            instance_handle
+        })
+        .collect();
+
+    assert_eq!(
+        audio_output.instances[&channel],
+        vec![handles[0].clone(), handles[2].clone()]
+    );
+
+    audio_output
+        .channels
+        .get_mut(&channel)
+        .unwrap()
+        .instance_limit = Some((2, StealPolicy::RejectNew));
+    let rejected = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(4),
+    });
+    audio_output.play(
+        &channel,
+        &PartialSoundSettings::default(),
+        (&source).into(),
+        rejected.clone(),
+        &mut audio_instances,
+    );
+    assert!(audio_instances.get(&rejected).is_none());
+    assert_eq!(audio_output.instances[&channel].len(), 2);
+}
+
+#[test]
+fn fading_out_instances_stay_in_the_channel_without_counting_against_its_limit() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    audio_output.channels.insert(
+        channel.clone(),
+        ChannelState {
+            instance_limit: Some((1, StealPolicy::RejectNew)),
+            ..Default::default()
+        },
+    );
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = silent_source(1_000);
+    let play = |audio_output: &mut AudioOutput<MockBackend>,
+                audio_instances: &mut Assets<AudioInstance>,
+                id: u128| {
+        let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(id),
+        });
+        audio_output.play(
+            &channel,
+            &PartialSoundSettings::default(),
+            (&source).into(),
+            instance_handle.clone(),
+            audio_instances,
+        );
+        instance_handle
+    };
+    let fading = play(&mut audio_output, &mut audio_instances, 1);
+    play(&mut audio_output, &mut audio_instances, 2);
+    assert_eq!(audio_output.instances[&channel], vec![fading.clone()]);
+
+    audio_output.run_audio_command(
+        &AudioCommand::Stop(Some(AudioTween::linear(Duration::from_millis(500)))),
+        &Assets::default(),
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    render(&mut audio_output, 10);
+    assert_eq!(
+        audio_instances.get(&fading).unwrap().handle.state(),
+        kira::sound::PlaybackState::Stopping
+    );
+
+    let next = play(&mut audio_output, &mut audio_instances, 3);
+    assert!(audio_instances.get(&next).is_some());
+    // The fading instance still follows the commands of the channel
+    assert_eq!(audio_output.instances[&channel], vec![fading, next]);
+}
+
+#[test]
+fn rejected_sounds_are_reported_with_their_source() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    audio_output.channels.insert(
+        channel.clone(),
+        ChannelState {
+            instance_limit: Some((1, StealPolicy::RejectNew)),
+            ..Default::default()
+        },
+    );
+    let mut audio_sources = Assets::<AudioSource>::default();
+    let source = audio_sources.add(silent_source(1_000));
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let mut play = |id: u128, settings: PartialSoundSettings| {
+        let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(id),
+        });
+        audio_output.run_audio_command(
+            &AudioCommand::Play(PlayAudioSettings {
+                instance_handle: instance_handle.clone(),
+                source: source.clone(),
+                sequence: None,
+                streamed: None,
+                collection: None,
+                settings,
+            }),
+            &audio_sources,
+            &Assets::default(),
+            &mut audio_instances,
+            &channel,
+        );
+        instance_handle
+    };
+    play(1, PartialSoundSettings::default());
+    let rejected = play(2, PartialSoundSettings::default());
+    let after_end = play(
+        3,
+        PartialSoundSettings {
+            start_position: Some(2.),
+            ..Default::default()
+        },
+    );
+
+    let errors = std::mem::take(&mut audio_output.errors);
+    assert_eq!(errors.len(), 2);
+    assert!(matches!(
+        errors[0].kind,
+        AudioErrorKind::InstanceLimitReached
+    ));
+    assert_eq!(errors[0].instance, Some(rejected));
+    assert_eq!(errors[0].source, Some(source.clone().untyped()));
+    assert_eq!(errors[0].channel, channel);
+    assert!(matches!(errors[1].kind, AudioErrorKind::StartsAfterEnd));
+    assert_eq!(errors[1].instance, Some(after_end));
+}
+
+#[test]
+fn completion_is_reported_once_per_instance() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    // 0.01 seconds long
+    let source = silent_source(10);
+    let play = |audio_output: &mut AudioOutput<MockBackend>,
+                audio_instances: &mut Assets<AudioInstance>,
+                settings: PartialSoundSettings,
+                id: u128| {
+        let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(id),
+        });
+        audio_output.play(
+            &channel,
+            &settings,
+            (&source).into(),
+            instance_handle.clone(),
+            audio_instances,
+        );
+        instance_handle
+    };
+    let finished = play(
+        &mut audio_output,
+        &mut audio_instances,
+        PartialSoundSettings::default(),
+        1,
+    );
+    let looped = PartialSoundSettings {
+        loop_start: Some(0.),
+        ..Default::default()
+    };
+    let stopped = play(&mut audio_output, &mut audio_instances, looped.clone(), 2);
+    let playing = play(&mut audio_output, &mut audio_instances, looped, 3);
+    render(&mut audio_output, 100);
+    audio_instances
+        .get_mut(&stopped)
+        .unwrap()
+        .stop(AudioTween::default());
+    render(&mut audio_output, 100);
+    render(&mut audio_output, 100);
+
+    let mut completed = audio_output.cleanup_stopped_instances(&mut audio_instances);
+    completed.sort_by_key(|completed| completed.stopped);
+    assert_eq!(completed.len(), 2);
+    assert_eq!(completed[0].instance, finished);
+    assert!(!completed[0].stopped);
+    assert_eq!(completed[1].instance, stopped);
+    assert!(completed[1].stopped);
+    assert!(audio_output
+        .cleanup_stopped_instances(&mut audio_instances)
+        .is_empty());
+
+    // Instances of removed channels stop without being tracked by their channel
+    audio_output.remove_channel(&channel, &mut audio_instances, &None);
+    render(&mut audio_output, 100);
+    render(&mut audio_output, 100);
+    let completed = audio_output.cleanup_stopped_instances(&mut audio_instances);
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].instance, playing);
+    assert!(completed[0].stopped);
+}
+
+#[test]
+fn filters_of_instances_are_dropped_once_they_stopped() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = silent_source(10);
+    let mut play = |filter: Option<FilterSettings>, id: u128| {
+        let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(id),
+        });
+        let settings = PartialSoundSettings {
+            filter,
+            ..Default::default()
+        };
+        audio_output.play(
+            &channel,
+            &settings,
+            (&source).into(),
+            instance_handle.clone(),
+            &mut audio_instances,
+        );
+        instance_handle
+    };
+    let filtered = play(Some(FilterSettings::high_pass(200.)), 1);
+    let unfiltered = play(None, 2);
+    assert!(audio_instances.get(&filtered).unwrap().has_filter());
+    assert!(audio_instances.get(&unfiltered).unwrap().track.is_none());
+    assert!(audio_instances
+        .get_mut(&filtered)
+        .unwrap()
+        .set_filter_resonance(0.5, AudioTween::default())
+        .is_none());
+    assert!(matches!(
+        audio_instances
+            .get_mut(&unfiltered)
+            .unwrap()
+            .set_filter_cutoff(500., AudioTween::default()),
+        Some(AudioCommandError::NoFilter)
+    ));
+
+    let backend = audio_output.manager.as_mut().unwrap().backend_mut();
+    for _ in 0..2 {
+        backend.on_start_processing();
+        for _ in 0..100 {
+            backend.process();
+        }
+    }
+    assert_eq!(
+        audio_output
+            .cleanup_stopped_instances(&mut audio_instances)
+            .len(),
+        2
+    );
+    assert!(!audio_instances.get(&filtered).unwrap().has_filter());
+}
+
+#[test]
+fn sequences_play_once_all_parts_loaded_as_one_instance() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_sources = Assets::<AudioSource>::default();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let part = || silent_source(10);
+    let parts = vec![audio_sources.add(part()), audio_sources.add(part())];
+    let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    let play = |sequence: Vec<Handle<AudioSource>>| {
+        AudioCommand::Play(PlayAudioSettings {
+            instance_handle: instance_handle.clone(),
+            source: Handle::default(),
+            sequence: Some(sequence),
+            streamed: None,
+            collection: None,
+            settings: PartialSoundSettings::default(),
+        })
+    };
+
+    let mut unloaded = parts.clone();
+    unloaded.push(Handle::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(2),
+    }));
+    assert!(matches!(
+        audio_output.run_audio_command(
+            &play(unloaded),
+            &audio_sources,
+            &Assets::default(),
+            &mut audio_instances,
+            &channel
+        ),
+        AudioCommandResult::Retry
+    ));
+    assert!(matches!(
+        audio_output.run_audio_command(
+            &play(vec![]),
+            &audio_sources,
+            &Assets::default(),
+            &mut audio_instances,
+            &channel
+        ),
+        AudioCommandResult::Ok
+    ));
+    assert!(audio_instances.is_empty());
+
+    audio_output.run_audio_command(
+        &play(parts),
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    render(&mut audio_output, 15);
+    let instance = audio_instances.get(&instance_handle).unwrap();
+    assert_eq!(instance.handle.state(), kira::sound::PlaybackState::Playing);
+    assert!(instance.handle.position() > 0.01);
+}
+
+#[test]
+fn pitch_glides_interpolate_in_semitones() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = silent_source(3_000);
+    let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    audio_output.play(
+        &channel,
+        &PartialSoundSettings::default(),
+        (&source).into(),
+        instance_handle.clone(),
+        &mut audio_instances,
+    );
+    // Adds the sound, so it takes commands in the next round
+    audio_output
+        .manager
+        .as_mut()
+        .unwrap()
+        .backend_mut()
+        .on_start_processing();
+    audio_output.run_audio_command(
+        &AudioCommand::SetPlaybackRate(
+            PlaybackRate::Semitones(12.),
+            Some(AudioTween::linear(Duration::from_secs(1))),
+        ),
+        &Assets::default(),
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    let instance = audio_instances.get(&instance_handle).unwrap();
+    assert_eq!(instance.playback_rate, 2.);
+    assert!((instance.pitch_semitones() - 12.).abs() < 1e-9);
+
+    render(&mut audio_output, 1_000);
+    // The rate doubles every 12 semitones, instead of rising linearly to 2
+    let position = audio_instances
+        .get(&instance_handle)
+        .unwrap()
+        .handle
+        .position();
+    assert!((position - 1. / 2f64.ln()).abs() < 0.01, "{}", position);
+}
+
+#[test]
+fn instances_report_their_timing() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    // 0.1 seconds long
+    let source = silent_source(100);
+    let mut play = |id, settings: PartialSoundSettings| {
+        let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(id),
+        });
+        audio_output.play(
+            &channel,
+            &settings,
+            (&source).into(),
+            instance_handle.clone(),
+            &mut audio_instances,
+        );
+        instance_handle
+    };
+    let fading = play(
+        1,
+        PartialSoundSettings {
+            end_position: Some(0.08),
+            playback_rate: Some(2.),
+            fade_in: Some(AudioTween::linear(Duration::from_secs(10))),
+            ..Default::default()
+        },
+    );
+    let reversed = play(
+        2,
+        PartialSoundSettings {
+            reverse: Some(true),
+            ..Default::default()
+        },
+    );
+    let looping = play(
+        3,
+        PartialSoundSettings {
+            loop_start: Some(0.02),
+            ..Default::default()
+        },
+    );
+    render(&mut audio_output, 10);
+
+    let fading = audio_instances.get(&fading).unwrap();
+    assert_eq!(fading.duration(), 0.1);
+    assert!((fading.position() - 0.02).abs() < 1e-9);
+    // The rest of the region at twice the speed
+    assert!((fading.remaining().unwrap() - 0.03).abs() < 1e-9);
+    assert!(fading.is_fading());
+    assert_eq!(fading.loop_region(), None);
+
+    let reversed = audio_instances.get(&reversed).unwrap();
+    // Reversed sounds start on their last frame and play towards the start
+    assert!((reversed.remaining().unwrap() - 0.089).abs() < 1e-9);
+    assert!(!reversed.is_fading());
+
+    let looping = audio_instances.get(&looping).unwrap();
+    assert_eq!(looping.remaining(), None);
+    assert_eq!(looping.loop_region(), Some((0.02, 0.1)));
+}
+
+#[test]
+fn seeking_stops_at_the_end_and_stays_in_loop_regions() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = silent_source(1_000);
+    let once = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    let looped = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(2),
+    });
+    audio_output.play(
+        &channel,
+        &PartialSoundSettings::default(),
+        (&source).into(),
+        once.clone(),
+        &mut audio_instances,
+    );
+    audio_output.play(
+        &channel,
+        &PartialSoundSettings {
+            loop_start: Some(0.2),
+            loop_end: Some(0.6),
+            ..Default::default()
+        },
+        (&source).into(),
+        looped.clone(),
+        &mut audio_instances,
+    );
+    render(&mut audio_output, 100);
+
+    audio_output.run_audio_command(
+        &AudioCommand::SeekTo(5.),
+        &Assets::default(),
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    render(&mut audio_output, 100);
+    let completed = audio_output.cleanup_stopped_instances(&mut audio_instances);
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].instance, once);
+    assert!(!completed[0].stopped);
+    let position = audio_instances.get(&looped).unwrap().position();
+    assert!((0.2..0.6).contains(&position), "{}", position);
+
+    audio_output.run_audio_command(
+        &AudioCommand::SeekBy(-0.5),
+        &Assets::default(),
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    render(&mut audio_output, 100);
+    let instance = audio_instances.get(&looped).unwrap();
+    assert_eq!(instance.handle.state(), kira::sound::PlaybackState::Playing);
+    assert!((0.2..0.6).contains(&instance.position()));
+    assert!(audio_output
+        .track_loops(&mut audio_instances, Duration::from_millis(100))
+        .is_empty());
+}
+
+#[test]
+fn exiting_fades_out_and_closes_the_audio_manager() {
+    let mut audio_output = mock_audio_output();
+    assert_eq!(audio_output.fade_out_for_exit(), Duration::ZERO);
+    // No fade by default
+    assert_eq!(audio_output.fade_out_for_exit(), Duration::ZERO);
+
+    audio_output.settings.exit_fade = Duration::from_millis(100);
+    assert_eq!(audio_output.fade_out_for_exit(), Duration::from_millis(100));
+    let manager = audio_output.manager.as_mut().unwrap();
+    manager.backend_mut().on_start_processing();
+    assert_eq!(manager.state(), MainPlaybackState::Pausing);
+    audio_output.close();
+    assert!(audio_output.manager.is_none());
+}
+
+#[test]
+fn resumes_without_a_tween_use_the_default_resume_tween() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = silent_source(1_000);
+    let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    let run = |audio_output: &mut AudioOutput<MockBackend>,
+               audio_instances: &mut Assets<AudioInstance>,
+               command: AudioCommand| {
+        audio_output.run_audio_command(
+            &command,
+            &Assets::default(),
+            &Assets::default(),
+            audio_instances,
+            &channel,
+        );
+        render(audio_output, 20);
+        audio_instances.get_mut(&instance_handle).map(|instance| {
+            let fading = instance.is_fading();
+            instance.fading_until = None;
+            fading
+        })
+    };
+    run(
+        &mut audio_output,
+        &mut audio_instances,
+        AudioCommand::SetResumeTween(AudioTween::linear(Duration::from_secs(1))),
+    );
+    audio_output.play(
+        &channel,
+        &PartialSoundSettings::default(),
+        (&source).into(),
+        instance_handle.clone(),
+        &mut audio_instances,
+    );
+
+    // Commands only reach a sound after it was added
+    audio_output
+        .manager
+        .as_mut()
+        .unwrap()
+        .backend_mut()
+        .on_start_processing();
+
+    assert_eq!(
+        run(
+            &mut audio_output,
+            &mut audio_instances,
+            AudioCommand::Pause(None)
+        ),
+        Some(false)
+    );
+    assert_eq!(
+        run(
+            &mut audio_output,
+            &mut audio_instances,
+            AudioCommand::Resume(None)
+        ),
+        Some(true)
+    );
+    run(
+        &mut audio_output,
+        &mut audio_instances,
+        AudioCommand::Pause(None),
+    );
+    assert_eq!(
+        run(
+            &mut audio_output,
+            &mut audio_instances,
+            AudioCommand::Resume(Some(AudioTween::default()))
+        ),
+        Some(false)
+    );
+    run(
+        &mut audio_output,
+        &mut audio_instances,
+        AudioCommand::SetResumeTween(AudioTween::linear(Duration::ZERO)),
+    );
+    run(
+        &mut audio_output,
+        &mut audio_instances,
+        AudioCommand::Pause(None),
+    );
+    assert_eq!(
+        run(
+            &mut audio_output,
+            &mut audio_instances,
+            AudioCommand::Resume(None)
+        ),
+        Some(false)
+    );
+    assert!(audio_instances
+        .get(&instance_handle)
+        .unwrap()
+        .resume_tween
+        .is_none());
+}
+
+#[test]
+fn stopped_one_shots_are_removed_from_the_assets() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = silent_source(10);
+    let kept = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    audio_output.play(
+        &channel,
+        &PartialSoundSettings::default(),
+        (&source).into(),
+        kept.clone(),
+        &mut audio_instances,
+    );
+    let mut completed = 0;
+    for id in 2..200 {
+        audio_output.play(
+            &channel,
+            &PartialSoundSettings {
+                one_shot: true,
+                ..Default::default()
+            },
+            (&source).into(),
+            Handle::<AudioInstance>::Weak(AssetId::Uuid {
+                uuid: Uuid::from_u128(id),
+            }),
+            &mut audio_instances,
+        );
+        let backend = audio_output.manager.as_mut().unwrap().backend_mut();
+        backend.on_start_processing();
+        for _ in 0..5 {
+            backend.process();
+        }
+        completed += audio_output
+            .cleanup_stopped_instances(&mut audio_instances)
+            .len();
+        assert!(audio_instances.len() <= 5);
+    }
+
+    assert!(completed > 190);
+    assert!(audio_instances.get(&kept).is_some());
+}
+
+#[test]
+fn loop_iterations_are_counted_for_looping_instances_only() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    // 0.01 seconds long
+    let source = silent_source(10);
+    for (id, settings) in [
+        (
+            1,
+            PartialSoundSettings {
+                loop_start: Some(0.),
+                ..Default::default()
+            },
+        ),
+        (2, PartialSoundSettings::default()),
+    ] {
+        audio_output.play(
+            &channel,
+            &settings,
+            (&source).into(),
+            Handle::<AudioInstance>::Weak(AssetId::Uuid {
+                uuid: Uuid::from_u128(id),
+            }),
+            &mut audio_instances,
+        );
+    }
+    render(&mut audio_output, 35);
+
+    let completed = audio_output.track_loops(&mut audio_instances, Duration::from_millis(35));
+    assert_eq!(completed.len(), 1);
+    assert_eq!(completed[0].iteration, 3);
+}
+
+#[test]
+fn pause_for_resumes_unless_replaced_or_resumed_manually() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let audio_sources = Assets::<AudioSource>::default();
+    let source = silent_source(100);
+    let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    audio_output.play(
+        &channel,
+        &PartialSoundSettings::default(),
+        (&source).into(),
+        instance_handle.clone(),
+        &mut audio_instances,
+    );
+    let run = |audio_output: &mut AudioOutput<MockBackend>,
+               audio_instances: &mut Assets<AudioInstance>,
+               command: AudioCommand| {
+        audio_output.run_audio_command(
+            &command,
+            &audio_sources,
+            &Assets::default(),
+            audio_instances,
+            &channel,
+        );
+    };
+    let second = Duration::from_secs(1);
+    let paused = |audio_output: &AudioOutput<MockBackend>| {
+        audio_output.channels[&Channel::typed::<MainTrack>()].paused
+    };
+
+    run(
+        &mut audio_output,
+        &mut audio_instances,
+        AudioCommand::PauseFor(second * 2, AudioTween::default()),
+    );
+    audio_output.resume_paused_audio(&mut audio_instances, second);
+    assert!(paused(&audio_output));
+    // Restarts the time
+    run(
+        &mut audio_output,
+        &mut audio_instances,
+        AudioCommand::PauseFor(second * 2, AudioTween::default()),
+    );
+    audio_output.resume_paused_audio(&mut audio_instances, second);
+    assert!(paused(&audio_output));
+    audio_output.resume_paused_audio(&mut audio_instances, second);
+    assert!(!paused(&audio_output));
+
+    run(
+        &mut audio_output,
+        &mut audio_instances,
+        AudioCommand::PauseFor(second, AudioTween::default()),
+    );
+    run(
+        &mut audio_output,
+        &mut audio_instances,
+        AudioCommand::Resume(None),
+    );
+    run(
+        &mut audio_output,
+        &mut audio_instances,
+        AudioCommand::Pause(None),
+    );
+    audio_output.resume_paused_audio(&mut audio_instances, second * 2);
+    assert!(paused(&audio_output));
+
+    let instance = audio_instances.get_mut(&instance_handle).unwrap();
+    instance.pause_for(second, AudioTween::default());
+    audio_output.resume_paused_audio(&mut audio_instances, second / 2);
+    let instance = audio_instances.get_mut(&instance_handle).unwrap();
+    assert!(instance.scheduled_resume.is_some());
+    instance.resume(AudioTween::default());
+    assert!(instance.scheduled_resume.is_none());
+    instance.pause_for(second, AudioTween::default());
+    audio_output.resume_paused_audio(&mut audio_instances, second);
+    assert!(audio_instances
+        .get(&instance_handle)
+        .unwrap()
+        .scheduled_resume
+        .is_none());
+}
+
+#[test]
+fn channel_volumes_in_decibels_stay_in_decibels() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let audio_sources = Assets::<AudioSource>::default();
+    let source = silent_source(100);
+    let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    audio_output.play(
+        &channel,
+        &PartialSoundSettings {
+            volume: Some(Volume::Decibels(-6.)),
+            ..Default::default()
+        },
+        (&source).into(),
+        instance_handle.clone(),
+        &mut audio_instances,
+    );
+    assert_eq!(
+        audio_instances.get(&instance_handle).unwrap().volume,
+        Volume::Decibels(-6.)
+    );
+
+    audio_output.run_audio_command(
+        &AudioCommand::SetVolume(Volume::Decibels(f64::NEG_INFINITY), None),
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    let instance = audio_instances.get_mut(&instance_handle).unwrap();
+    assert_eq!(instance.volume.as_amplitude(), 0.);
+    instance.set_duck(0.5, AudioTween::default());
+    assert_eq!(instance.volume, Volume::Decibels(Volume::MIN_DECIBELS));
+}
+
+#[test]
+fn mute_and_solo_keep_the_channel_volume() {
+    struct Music;
+    let mut audio_output = mock_audio_output();
+    let sfx = Channel::typed::<MainTrack>();
+    let music = Channel::typed::<Music>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let audio_sources = Assets::<AudioSource>::default();
+    let source = silent_source(100);
+    let handles: Vec<_> = [&sfx, &music]
+        .into_iter()
+        .enumerate()
+        .map(|(index, channel)| {
+            let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+                uuid: Uuid::from_u128(index as u128 + 1),
+            });
+            audio_output.play(
+                channel,
+                &PartialSoundSettings::default(),
+                (&source).into(),
+                instance_handle.clone(),
+                &mut audio_instances,
+            );
+            instance_handle
+        })
+        .collect();
+    let mut run =
+        |audio_output: &mut AudioOutput<MockBackend>, command: AudioCommand, channel: &Channel| {
+            audio_output.run_audio_command(
+                &command,
+                &audio_sources,
+                &Assets::default(),
+                &mut audio_instances,
+                channel,
+            );
+            handles
+                .iter()
+                .map(|handle| {
+                    let instance = audio_instances.get(handle).unwrap();
+                    (instance.muted, instance.volume.as_amplitude())
+                })
+                .collect::<Vec<_>>()
+        };
+
+    run(&mut audio_output, AudioCommand::SetMuted(true, None), &sfx);
+    assert_eq!(
+        run(
+            &mut audio_output,
+            AudioCommand::SetVolume(Volume::Amplitude(0.5), None),
+            &sfx
+        ),
+        vec![(true, 0.5), (false, 1.)]
+    );
+    run(&mut audio_output, AudioCommand::SetSolo(true, None), &music);
+    // Still silenced by the solo of the music channel
+    assert_eq!(
+        run(&mut audio_output, AudioCommand::SetMuted(false, None), &sfx),
+        vec![(true, 0.5), (false, 1.)]
+    );
+    assert_eq!(
+        run(
+            &mut audio_output,
+            AudioCommand::SetSolo(false, None),
+            &music
+        ),
+        vec![(false, 0.5), (false, 1.)]
+    );
+}
+
+#[test]
+fn ducking_follows_the_trigger_channel() {
+    struct Music;
+    let mut audio_output = mock_audio_output();
+    let voice = Channel::typed::<MainTrack>();
+    let music = Channel::typed::<Music>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let audio_sources = Assets::<AudioSource>::default();
+    let source = silent_source(100);
+    let music_instance = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    audio_output.play(
+        &music,
+        &PartialSoundSettings::default(),
+        (&source).into(),
+        music_instance.clone(),
+        &mut audio_instances,
+    );
+    let settings = DuckSettings {
+        amount: 0.75,
+        ..Default::default()
+    };
+    audio_output.run_audio_command(
+        &AudioCommand::DuckBy(voice.clone(), Some(settings.clone())),
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &music,
+    );
+    let frame = Duration::from_millis(16);
+    audio_output.update_ducking(&mut audio_instances, frame);
+    assert_eq!(audio_instances.get(&music_instance).unwrap().duck, 1.);
+
+    audio_output.play(
+        &voice,
+        &PartialSoundSettings::default(),
+        (&source).into(),
+        Handle::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(2),
+        }),
+        &mut audio_instances,
+    );
+    audio_output.update_ducking(&mut audio_instances, frame);
+    assert_eq!(audio_instances.get(&music_instance).unwrap().duck, 0.25);
+    assert_eq!(audio_output.channels.get(&music).unwrap().duck, 0.25);
+
+    // A quiet trigger channel does not duck
+    audio_output.run_audio_command(
+        &AudioCommand::SetVolume(Volume::Amplitude(0.001), None),
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &voice,
+    );
+    audio_output.update_ducking(&mut audio_instances, frame);
+    assert_eq!(audio_instances.get(&music_instance).unwrap().duck, 1.);
+
+    audio_output.run_audio_command(
+        &AudioCommand::DuckFor(Duration::from_millis(20), settings),
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &music,
+    );
+    audio_output.update_ducking(&mut audio_instances, frame);
+    assert_eq!(audio_instances.get(&music_instance).unwrap().duck, 0.25);
+    audio_output.update_ducking(&mut audio_instances, frame);
+    assert_eq!(audio_instances.get(&music_instance).unwrap().duck, 1.);
+}
+
+#[test]
+fn missing_output_device_falls_back_to_the_default_device() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let audio_sources = Assets::<AudioSource>::default();
+    audio_output.run_audio_command(
+        &AudioCommand::SetOutputDevice(Some("no such device".to_string())),
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+
+    assert_eq!(
+        audio_output.channels.get(&channel).unwrap().output_device,
+        None
+    );
+    assert!(audio_output.devices.is_empty());
+}
+
+#[test]
+fn sounds_starting_after_their_end_only_play_when_looped() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    // 0.1 seconds long
+    let source = silent_source(100);
+    let mut play = |settings: PartialSoundSettings, id: u128| {
+        let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(id),
+        });
+        audio_output.play(
+            &channel,
+            &settings,
+            (&source).into(),
+            instance_handle.clone(),
+            &mut audio_instances,
+        );
+        audio_instances.contains(&instance_handle)
+    };
+
+    let start_from = |start_position: f64| PartialSoundSettings {
+        start_position: Some(start_position),
+        ..Default::default()
+    };
+    assert!(play(start_from(0.05), 1));
+    assert!(!play(start_from(0.1), 2));
+    assert!(play(
+        PartialSoundSettings {
+            loop_start: Some(0.),
+            ..start_from(0.25)
+        },
+        3
+    ));
+
+    let reversed = |settings: PartialSoundSettings| PartialSoundSettings {
+        reverse: Some(true),
+        ..settings
+    };
+    assert!(play(reversed(start_from(0.05)), 4));
+    assert!(!play(reversed(start_from(0.1)), 5));
+    assert!(play(
+        reversed(PartialSoundSettings {
+            loop_start: Some(0.),
+            ..start_from(0.25)
+        }),
+        6
+    ));
+}
+
+#[test]
+fn sounds_scheduled_for_the_same_clock_time_start_in_sync() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    // 0.1 seconds long
+    let source = silent_source(100);
+    let start = audio_output.clock.now() + Duration::from_millis(20);
+    let play = |audio_output: &mut AudioOutput<MockBackend>,
+                audio_instances: &mut Assets<AudioInstance>,
+                id: u128| {
+        let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(id),
+        });
+        audio_output.play(
+            &channel,
+            &PartialSoundSettings {
+                start_time: Some(ScheduledStart::At(start)),
+                ..Default::default()
+            },
+            (&source).into(),
+            instance_handle.clone(),
+            audio_instances,
+        );
+        instance_handle
+    };
+
+    let first = play(&mut audio_output, &mut audio_instances, 1);
+    render(&mut audio_output, 7);
+    let second = play(&mut audio_output, &mut audio_instances, 2);
+    let position =
+        |instance: &Handle<AudioInstance>| audio_instances.get(instance).unwrap().handle.position();
+    render(&mut audio_output, 5);
+    assert_eq!(position(&first), 0.);
+    assert_eq!(position(&second), 0.);
+
+    render(&mut audio_output, 30);
+    assert!(position(&first) > 0.);
+    assert_eq!(position(&first), position(&second));
+}
+
+#[test]
+fn sounds_start_on_ticks_of_audio_clocks() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        AudioPlugin::default(),
+    ));
+    let mut audio_sources = app.world.remove_resource::<Assets<AudioSource>>().unwrap();
+    let mut audio_instances = app
+        .world
+        .remove_resource::<Assets<AudioInstance>>()
+        .unwrap();
+    let source = audio_sources.add(silent_source(100));
+    let mut audio_output = mock_audio_output();
+    let sync = |audio_output: &mut AudioOutput<MockBackend>| {
+        audio_output
+            .clocks
+            .sync(audio_output.manager.as_mut().unwrap());
+    };
+
+    // A tick every 10 samples
+    let clock = audio_output.clocks.add(6_000.);
+    audio_output.clocks.start(clock);
+    let channel = AudioChannel::<Audio>::default();
+    let instance = channel.play(source).start_at_clock(clock, 3).handle();
+    audio_output.play_channel(
+        &audio_sources,
+        &Assets::default(),
+        &channel,
+        &mut audio_instances,
+    );
+    // The clock is not on the audio manager yet
+    assert_eq!(channel.commands.read().len(), 1);
+
+    sync(&mut audio_output);
+    audio_output.play_channel(
+        &audio_sources,
+        &Assets::default(),
+        &channel,
+        &mut audio_instances,
+    );
+    assert!(channel.commands.read().is_empty());
+    let position = |audio_instances: &Assets<AudioInstance>| {
+        audio_instances.get(&instance).unwrap().handle.position()
+    };
+    render(&mut audio_output, 15);
+    assert_eq!(audio_output.clocks.ticks(clock), Some(1));
+    assert_eq!(position(&audio_instances), 0.);
+
+    // A tick every sample
+    audio_output
+        .clocks
+        .set_tempo(clock, 60_000., AudioTween::linear(Duration::ZERO));
+    sync(&mut audio_output);
+    render(&mut audio_output, 10);
+    assert!(audio_output.clocks.ticks(clock).unwrap() > 3);
+    assert!(position(&audio_instances) > 0.);
+    assert_eq!(audio_output.clocks.tempo(clock), Some(60_000.));
+}
+
+#[test]
+fn queued_sounds_play_without_gaps() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_sources = Assets::<AudioSource>::default();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    // 0.01 seconds long
+    let source = || silent_source(10);
+    let first = audio_sources.add(source());
+    let second = audio_sources.add(source());
+    for source in [&first, &second] {
+        audio_output.run_audio_command(
+            &AudioCommand::Enqueue(source.clone()),
+            &audio_sources,
+            &Assets::default(),
+            &mut audio_instances,
+            &channel,
+        );
+    }
+
+    // The first sound starts 50 ticks from now
+    assert!(audio_output
+        .update_queues(&audio_sources, &mut audio_instances)
+        .is_empty());
+    render(&mut audio_output, 55);
+    let advanced = audio_output.update_queues(&audio_sources, &mut audio_instances);
+    assert_eq!(advanced.len(), 1);
+    assert_eq!(advanced[0].source, first);
+    let first_instance = advanced[0].instance.clone();
+    let first_position = audio_instances
+        .get(&first_instance)
+        .unwrap()
+        .handle
+        .position();
+    assert!(first_position > 0.);
+
+    render(&mut audio_output, 10);
+    let advanced = audio_output.update_queues(&audio_sources, &mut audio_instances);
+    assert_eq!(advanced.len(), 1);
+    assert_eq!(advanced[0].source, second);
+    let second_instance = audio_instances.get(&advanced[0].instance).unwrap();
+    // The second sound started on the tick that the first one ended on
+    assert_eq!(second_instance.handle.position(), first_position);
+    assert_eq!(
+        audio_instances.get(&first_instance).unwrap().handle.state(),
+        kira::sound::PlaybackState::Stopped
+    );
+
+    // A skipped sound that repeats is left behind
+    audio_output.run_audio_command(
+        &AudioCommand::SetQueueRepeat(QueueRepeat::One),
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    audio_output.run_audio_command(
+        &AudioCommand::Enqueue(first.clone()),
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    audio_output.update_queues(&audio_sources, &mut audio_instances);
+    let queue = audio_output.queues.get(&channel).unwrap();
+    assert_eq!(queue.next.as_ref().unwrap().source, second);
+    audio_output.run_audio_command(
+        &AudioCommand::SkipQueued,
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    let queue = audio_output.queues.get(&channel).unwrap();
+    assert!(queue.current.is_none());
+    assert_eq!(queue.upcoming, vec![first.clone()]);
+
+    audio_output.run_audio_command(
+        &AudioCommand::Stop(None),
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    assert!(audio_output.queues.get(&channel).is_none());
+}
+
+#[test]
+fn crossfades_stop_the_outgoing_instances_once_faded_out() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = silent_source(10);
+    let play = |audio_output: &mut AudioOutput<MockBackend>,
+                audio_instances: &mut Assets<AudioInstance>,
+                id: u128,
+                crossfade: Option<Duration>| {
+        let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(id),
+        });
+        audio_output.play(
+            &channel,
+            &PartialSoundSettings {
+                loop_start: Some(0.),
+                crossfade: crossfade.map(|duration| (duration, CrossfadeCurve::default())),
+                ..Default::default()
+            },
+            (&source).into(),
+            instance_handle.clone(),
+            audio_instances,
+        );
+        instance_handle
+    };
+    let state = |audio_instances: &Assets<AudioInstance>, instance: &Handle<AudioInstance>| {
+        audio_instances.get(instance).unwrap().handle.state()
+    };
+
+    let first = play(&mut audio_output, &mut audio_instances, 1, None);
+    render(&mut audio_output, 5);
+    let second = play(
+        &mut audio_output,
+        &mut audio_instances,
+        2,
+        Some(Duration::from_millis(100)),
+    );
+    render(&mut audio_output, 50);
+    assert_eq!(
+        state(&audio_instances, &first),
+        kira::sound::PlaybackState::Stopping
+    );
+    assert_eq!(
+        state(&audio_instances, &second),
+        kira::sound::PlaybackState::Playing
+    );
+
+    // The half faded-in instance becomes the outgoing one
+    let third = play(
+        &mut audio_output,
+        &mut audio_instances,
+        3,
+        Some(Duration::from_millis(100)),
+    );
+    render(&mut audio_output, 70);
+    assert_eq!(
+        state(&audio_instances, &first),
+        kira::sound::PlaybackState::Stopped
+    );
+    assert_eq!(
+        state(&audio_instances, &second),
+        kira::sound::PlaybackState::Stopping
+    );
+    render(&mut audio_output, 50);
+    assert_eq!(
+        state(&audio_instances, &second),
+        kira::sound::PlaybackState::Stopped
+    );
+    assert_eq!(
+        state(&audio_instances, &third),
+        kira::sound::PlaybackState::Playing
+    );
+}
+
+#[test]
+fn variations_compose_with_the_explicit_settings() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    // 1 second long
+    let source = silent_source(1_000);
+    let settings = PartialSoundSettings {
+        volume: Some(Volume::Amplitude(0.5)),
+        playback_rate: Some(2.),
+        pitch_variation: Some(12.),
+        volume_variation: Some(6.),
+        ..Default::default()
+    };
+    let mut play = |audio_output: &mut AudioOutput<MockBackend>, id: u128| {
+        let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(id),
+        });
+        audio_output.play(
+            &channel,
+            &settings,
+            (&source).into(),
+            instance_handle.clone(),
+            &mut audio_instances,
+        );
+        render(audio_output, 100);
+        let instance = audio_instances.get(&instance_handle).unwrap();
+        (instance.volume.as_amplitude(), instance.handle.position())
+    };
+
+    let (volume, position) = play(&mut audio_output, 1);
+    assert!(volume != 0.5 && (0.25..=1.).contains(&volume));
+    // A playback rate between 1 and 4
+    assert!(position != 0.2 && (0.1..=0.4).contains(&position));
+    assert_ne!(play(&mut audio_output, 2), (volume, position));
+
+    audio_output.rng.seed(0);
+    assert_eq!(play(&mut audio_output, 3), (volume, position));
+}
+
+#[test]
+fn positions_of_reversed_sounds_count_from_the_end() {
+    // 0.1 seconds long
+    let sound = silent_source(100).sound;
+    let mut reversed = sound.clone();
+    PartialSoundSettings {
+        reverse: Some(true),
+        start_position: Some(0.02),
+        end_position: Some(0.09),
+        loop_start: Some(0.03),
+        loop_end: Some(0.08),
+        ..Default::default()
+    }
+    .apply(&mut reversed);
+
+    let playback_region = reversed.settings.playback_region;
+    let loop_region = reversed.settings.loop_region.unwrap();
+    let seconds = |position| super::seconds(&reversed, position);
+    let end_seconds = |position| match position {
+        EndPosition::Custom(position) => seconds(position),
+        EndPosition::EndOfAudio => panic!("expected a custom end position"),
+    };
+    assert!((seconds(playback_region.start) - 0.01).abs() < 1e-9);
+    assert!((end_seconds(playback_region.end) - 0.08).abs() < 1e-9);
+    assert!((seconds(loop_region.start) - 0.02).abs() < 1e-9);
+    assert!((end_seconds(loop_region.end) - 0.07).abs() < 1e-9);
+    assert!(has_valid_loop_region(&reversed));
+
+    // Looping the whole sound in reverse
+    let mut looped = sound.clone();
+    PartialSoundSettings {
+        reverse: Some(true),
+        loop_start: Some(0.),
+        ..Default::default()
+    }
+    .apply(&mut looped);
+    assert!(has_valid_loop_region(&looped));
+    assert_eq!(
+        super::seconds(&looped, looped.settings.loop_region.unwrap().start),
+        0.
+    );
+}
+
+#[test]
+fn loop_regions_have_to_be_inside_the_sound() {
+    // 0.1 seconds long
+    let sound = silent_source(100).sound;
+    let with_loop = |start: f64, end: f64| {
+        let mut sound = sound.clone();
+        PartialSoundSettings {
+            loop_start: Some(start),
+            loop_end: Some(end),
+            ..Default::default()
+        }
+        .apply(&mut sound);
+        sound
+    };
+
+    assert!(has_valid_loop_region(&sound));
+    assert!(has_valid_loop_region(&with_loop(0.02, 0.1)));
+    assert!(!has_valid_loop_region(&with_loop(0.05, 0.05)));
+    assert!(!has_valid_loop_region(&with_loop(0.05, 0.2)));
+    assert!(!has_valid_loop_region(&with_loop(-0.01, 0.05)));
+}
+
+#[test]
+fn channel_reverb_track_is_kept_until_its_instances_stopped() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::dynamic("cave");
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let audio_sources = Assets::<AudioSource>::default();
+    audio_output.run_audio_command(
+        &AudioCommand::SetReverb(ChannelReverb::default()),
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    let track = audio_output.channel_tracks[&channel].track.id();
+    audio_output.run_audio_command(
+        &AudioCommand::SetReverb(ChannelReverb {
+            mix: 0.2,
+            ..Default::default()
+        }),
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    assert_eq!(audio_output.channel_tracks[&channel].track.id(), track);
+
+    // An effect chain replaces the reverb track, the old track waits for its sounds
+    let effects = EffectChain::new().with(FilterBuilder::new());
+    audio_output.run_audio_command(
+        &AudioCommand::SetEffects(Mutex::new(Some(effects.track))),
+        &audio_sources,
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    assert_ne!(audio_output.channel_tracks[&channel].track.id(), track);
+    assert!(audio_output.channel_tracks[&channel].reverb.is_none());
+    assert_eq!(audio_output.retired_tracks.len(), 1);
+    audio_output.cleanup_stopped_instances(&mut audio_instances);
+    assert!(audio_output.retired_tracks.is_empty());
+
+    let source = silent_source(100);
+    let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    audio_output.play(
+        &channel,
+        &PartialSoundSettings::default(),
+        (&source).into(),
+        instance_handle.clone(),
+        &mut audio_instances,
+    );
+    audio_output.remove_channel(&channel, &mut audio_instances, &None);
+    assert!(audio_output.channel_tracks.is_empty());
+    audio_output.cleanup_stopped_instances(&mut audio_instances);
+    assert_eq!(audio_output.retired_tracks.len(), 1);
+
+    audio_instances.remove(&instance_handle);
+    audio_output.cleanup_stopped_instances(&mut audio_instances);
+    assert!(audio_output.retired_tracks.is_empty());
+}
+
+#[test]
+fn switching_the_manager_loses_instances_but_keeps_channels() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::dynamic("music");
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    audio_output.run_audio_command(
+        &AudioCommand::SetVolume(Volume::Amplitude(0.5), None),
+        &Assets::default(),
+        &Assets::default(),
+        &mut audio_instances,
+        &channel,
+    );
+    let source = AudioSource::from_mono_frames(1_000, vec![0.; 1_000]).unwrap();
+    let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    audio_output.play(
+        &channel,
+        &PartialSoundSettings::default(),
+        (&source).into(),
+        instance_handle.clone(),
+        &mut audio_instances,
+    );
+
+    let manager = AudioManager::new(AudioManagerSettings::<MockBackend>::default()).unwrap();
+    audio_output.replace_manager(manager, &ChannelHierarchy::default(), &mut audio_instances);
+    let completed = audio_output.cleanup_stopped_instances(&mut audio_instances);
+    assert_eq!(completed.len(), 1);
+    assert!(completed[0].stopped);
+    assert_eq!(
+        audio_instances.get(&instance_handle).unwrap().state(),
+        crate::PlaybackState::Stopped
+    );
+    assert_eq!(
+        audio_output.channels[&channel].volume,
+        Volume::Amplitude(0.5)
+    );
+
+    // New sounds play on the new manager
+    let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(2),
+    });
+    audio_output.play(
+        &channel,
+        &PartialSoundSettings::default(),
+        (&source).into(),
+        instance_handle.clone(),
+        &mut audio_instances,
+    );
+    let instance = audio_instances.get(&instance_handle).unwrap();
+    assert!(!instance.handle.is_lost());
+    assert_eq!(instance.volume, Volume::Amplitude(0.5));
+}
+
+#[test]
+fn master_volume_scales_all_audio_and_carries_over_to_new_managers() {
+    let mut audio_output = mock_audio_output();
+    let channel = Channel::typed::<MainTrack>();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = AudioSource {
+        sound: StaticSoundData {
+            sample_rate: 1_000,
+            frames: Arc::from(vec![Frame::from_mono(0.8); 10]),
+            settings: StaticSoundSettings::default(),
+        },
+        loop_region: None,
+        gain: 1.,
+    };
+    let play = |audio_output: &mut AudioOutput<MockBackend>,
+                audio_instances: &mut Assets<AudioInstance>,
+                id: u128| {
+        let settings = PartialSoundSettings {
+            loop_start: Some(0.),
+            ..Default::default()
+        };
+        let handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(id),
+        });
+        audio_output.play(
+            &channel,
+            &settings,
+            (&source).into(),
+            handle,
+            audio_instances,
+        );
+    };
+    let output = |audio_output: &mut AudioOutput<MockBackend>| {
+        let backend = audio_output.manager.as_mut().unwrap().backend_mut();
+        backend.on_start_processing();
+        let mut frame = Frame::ZERO;
+        for _ in 0..50 {
+            frame = backend.process();
+        }
+        frame.left
+    };
+    let instant = AudioTween::new(Duration::ZERO, AudioEasing::Linear);
+
+    play(&mut audio_output, &mut audio_instances, 1);
+    assert!((output(&mut audio_output) - 0.8).abs() < 1e-4);
+    audio_output.set_master_volume(Volume::Amplitude(0.5), instant.into());
+    assert!((output(&mut audio_output) - 0.4).abs() < 1e-4);
+
+    audio_output.replace_manager(
+        mock_manager(),
+        &ChannelHierarchy::default(),
+        &mut audio_instances,
+    );
+    play(&mut audio_output, &mut audio_instances, 2);
+    assert!((output(&mut audio_output) - 0.4).abs() < 1e-4);
+}
+
+#[test]
+fn focus_loss_pauses_or_ducks_all_audio() {
+    let source = AudioSource {
+        sound: StaticSoundData {
+            sample_rate: 1_000,
+            frames: Arc::from(vec![Frame::from_mono(0.8); 10]),
+            settings: StaticSoundSettings::default(),
+        },
+        loop_region: None,
+        gain: 1.,
+    };
+    let output = |audio_output: &mut AudioOutput<MockBackend>| {
+        let backend = audio_output.manager.as_mut().unwrap().backend_mut();
+        backend.on_start_processing();
+        let mut frame = Frame::ZERO;
+        // Longer than the fade
+        for _ in 0..300 {
+            frame = backend.process();
+        }
+        frame.left
+    };
+    let half = Volume::Decibels(-6.).as_amplitude() as f32;
+    for focus_loss in [FocusLoss::Pause, FocusLoss::Duck { decibels: 6 }] {
+        let mut audio_output = mock_audio_output();
+        audio_output.settings.focus_loss = focus_loss;
+        let settings = PartialSoundSettings {
+            loop_start: Some(0.),
+            ..Default::default()
+        };
+        audio_output.play(
+            &Channel::typed::<MainTrack>(),
+            &settings,
+            (&source).into(),
+            Handle::<AudioInstance>::Weak(AssetId::Uuid {
+                uuid: Uuid::from_u128(1),
+            }),
+            &mut Assets::<AudioInstance>::default(),
+        );
+        assert!((output(&mut audio_output) - 0.8).abs() < 1e-4);
+
+        audio_output.set_focused(false);
+        let unfocused = output(&mut audio_output);
+        match focus_loss {
+            FocusLoss::Pause => assert_eq!(unfocused, 0.),
+            _ => assert!((unfocused - 0.8 * half).abs() < 1e-4),
+        }
+
+        audio_output.set_focused(true);
+        assert!((output(&mut audio_output) - 0.8).abs() < 1e-4);
+    }
+}
+
+#[test]
+fn following_channels_play_at_the_speed_of_virtual_time() {
+    let mut audio_output = mock_audio_output();
+    let following = Channel::dynamic("following");
+    audio_output.channels.insert(
+        following.clone(),
+        ChannelState {
+            follow_virtual_time: true,
+            ..Default::default()
+        },
+    );
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = silent_source(10_000);
+    let mut play = |channel: &Channel, id: u128| {
+        let handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(id),
+        });
+        audio_output.play(
+            channel,
+            &PartialSoundSettings::default(),
+            (&source).into(),
+            handle.clone(),
+            &mut audio_instances,
+        );
+        handle
+    };
+    let slowed = play(&following, 1);
+    let normal = play(&Channel::typed::<MainTrack>(), 2);
+    audio_instances
+        .get_mut(&slowed)
+        .unwrap()
+        .set_playback_rate(2., AudioTween::default());
+    let advance = |audio_output: &mut AudioOutput<MockBackend>,
+                   audio_instances: &Assets<AudioInstance>| {
+        let backend = audio_output.manager.as_mut().unwrap().backend_mut();
+        // Let the tween to the new rate finish first
+        backend.on_start_processing();
+        for _ in 0..100 {
+            backend.process();
+        }
+        backend.on_start_processing();
+        let positions =
+            || [&slowed, &normal].map(|handle| audio_instances.get(handle).unwrap().position());
+        let before = positions();
+        for _ in 0..100 {
+            backend.process();
+        }
+        backend.on_start_processing();
+        let after = positions();
+        [after[0] - before[0], after[1] - before[1]]
+    };
+
+    let [slowed_advance, normal_advance] = advance(&mut audio_output, &audio_instances);
+    assert!((slowed_advance - 0.2).abs() < 1e-2);
+    assert!((normal_advance - 0.1).abs() < 1e-2);
+
+    audio_output.set_time_speed(0.25, &mut audio_instances);
+    let [slowed_advance, normal_advance] = advance(&mut audio_output, &audio_instances);
+    assert!((slowed_advance - 0.05).abs() < 1e-2);
+    assert!((normal_advance - 0.1).abs() < 1e-2);
+    let instance = audio_instances.get(&slowed).unwrap();
+    assert_eq!(instance.playback_rate, 2.);
+
+    audio_output.set_time_speed(0., &mut audio_instances);
+    let [slowed_advance, _] = advance(&mut audio_output, &audio_instances);
+    assert_eq!(slowed_advance, 0.);
+
+    audio_output.set_time_speed(1., &mut audio_instances);
+    let [slowed_advance, _] = advance(&mut audio_output, &audio_instances);
+    assert!((slowed_advance - 0.2).abs() < 1e-2);
+}
+
+#[test]
+fn sounds_complete_even_without_an_audio_device() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        AudioPlugin::default(),
+    ));
+    let source = app
+        .world
+        .resource_mut::<Assets<AudioSource>>()
+        .add(silent_source(20));
+    let instance = app.world.resource::<Audio>().play(source).handle();
+
+    let mut reader = app
+        .world
+        .resource::<Events<PlaybackCompleted>>()
+        .get_reader();
+    let completed = (0..200).any(|_| {
+        std::thread::sleep(Duration::from_millis(5));
+        app.update();
+        let events = app.world.resource::<Events<PlaybackCompleted>>();
+        reader
+            .read(events)
+            .any(|completed| completed.instance == instance && !completed.stopped)
+    });
+    assert!(completed);
+}
+
+#[test]
+fn main_track_effects_are_built_with_the_manager() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        AudioPlugin::default(),
+    ));
+    assert!(app.world.resource::<MainEffects>().is_empty());
+
+    let mut app = App::new();
+    app.insert_resource(MainTrackEffects::new(|| {
+        EffectChain::new()
+            .with(CompressorBuilder::new())
+            .with(FilterBuilder::new())
+    }))
+    .add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        AudioPlugin::default(),
+    ));
+    let mut effects = app.world.resource_mut::<MainEffects>();
+    assert_eq!(effects.len(), 2);
+    assert!(effects.get_mut::<CompressorHandle>(0).is_some());
+    let filter = effects.get_mut::<FilterHandle>(1).unwrap();
+    assert!(filter.set_cutoff(400., Default::default()).is_ok());
+    assert!(!app.world.contains_resource::<MainTrackEffects>());
+}
+
+#[test]
+fn the_backend_status_matches_the_startup_event() {
+    let mut app = App::new();
+    app.add_plugins((
+        MinimalPlugins,
+        AssetPlugin::default(),
+        AudioPlugin::default(),
+    ));
+    app.update();
+
+    let events = app.world.resource::<Events<AudioBackendFailed>>();
+    let mut reader = events.get_reader();
+    let failed: Vec<_> = reader.read(events).collect();
+    match app.world.resource::<AudioBackendStatus>() {
+        AudioBackendStatus::Running { sample_rate } => {
+            assert!(*sample_rate > 0);
+            assert!(failed.is_empty());
+        }
+        AudioBackendStatus::Headless(error) | AudioBackendStatus::Unavailable(error) => {
+            assert_eq!(failed.len(), 1);
+            assert!(Arc::ptr_eq(&failed[0].error, error));
+        }
+    }
+}
+
+#[test]
+fn sends_reuse_the_send_track_until_a_target_is_added() {
+    struct Sfx;
+    struct Reverb;
+    struct Echo;
+    let mut audio_output = mock_audio_output();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let audio_sources = Assets::<AudioSource>::default();
+    let sfx = Channel::typed::<Sfx>();
+    let mut run = |audio_output: &mut AudioOutput<MockBackend>, command, channel: &Channel| {
+        audio_output.run_audio_command(
+            &command,
+            &audio_sources,
+            &Assets::default(),
+            &mut audio_instances,
+            channel,
+        );
+    };
+
+    // Channels without effects can't receive sends
+    run(
+        &mut audio_output,
+        AudioCommand::SetSend(Channel::typed::<Reverb>(), 0.5, None),
+        &sfx,
+    );
+    assert!(audio_output.send_tracks.is_empty());
+
+    for target in [Channel::typed::<Reverb>(), Channel::typed::<Echo>()] {
+        run(
+            &mut audio_output,
+            AudioCommand::SetReverb(ChannelReverb::default()),
+            &target,
+        );
+    }
+    run(
+        &mut audio_output,
+        AudioCommand::SetSend(Channel::typed::<Reverb>(), 0.5, None),
+        &sfx,
+    );
+    let send_track = audio_output.send_tracks[&sfx].track.id();
+    run(
+        &mut audio_output,
+        AudioCommand::SetSend(Channel::typed::<Reverb>(), 0., None),
+        &sfx,
+    );
+    assert_eq!(audio_output.send_tracks[&sfx].track.id(), send_track);
+
+    run(
+        &mut audio_output,
+        AudioCommand::SetSend(Channel::typed::<Echo>(), 0.2, None),
+        &sfx,
+    );
+    let send_track = &audio_output.send_tracks[&sfx];
+    assert_eq!(send_track.routes.len(), 2);
+    assert_eq!(send_track.levels[&Channel::typed::<Echo>()], 0.2);
+    assert_eq!(audio_output.retired_tracks.len(), 1);
+}
+
+#[test]
+fn streamed_sounds_can_not_play_backwards() {
+    let mut audio_output = mock_audio_output();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = StreamedAudioSource {
+        bytes: Arc::from(vec![]),
+        duration: Duration::from_secs(1),
+    };
+    let channel = Channel::typed::<MainTrack>();
+    for (index, settings) in [
+        PartialSoundSettings {
+            reverse: Some(true),
+            ..Default::default()
+        },
+        PartialSoundSettings {
+            playback_rate: Some(-1.),
+            ..Default::default()
+        },
+    ]
+    .iter()
+    .enumerate()
+    {
+        let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(index as u128),
+        });
+        audio_output.play(
+            &channel,
+            settings,
+            (&source).into(),
+            instance_handle.clone(),
+            &mut audio_instances,
+        );
+        assert!(audio_instances.get(&instance_handle).is_none());
+    }
+}
+
+#[cfg(feature = "ogg")]
+#[test]
+fn streamed_sounds_reject_negative_playback_rates() {
+    use crate::instance::AudioCommandError;
+
+    let mut audio_output = mock_audio_output();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = StreamedAudioSource {
+        bytes: Arc::from(include_bytes!("../../assets/sounds/plop.ogg").as_slice()),
+        duration: Duration::from_secs(1),
+    };
+    let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    let channel = Channel::typed::<MainTrack>();
+    audio_output.play(
+        &channel,
+        &PartialSoundSettings::default(),
+        (&source).into(),
+        instance_handle.clone(),
+        &mut audio_instances,
+    );
+    let instance = audio_instances.get_mut(&instance_handle).unwrap();
+    assert!(instance.handle.is_streamed());
+    assert!(matches!(
+        instance.set_playback_rate(-1., AudioTween::default()),
+        Some(AudioCommandError::NotSupportedForStreamedSound)
+    ));
+    assert!(instance
+        .set_playback_rate(2., AudioTween::default())
+        .is_none());
+
+    audio_output.set_playback_rate(
+        &channel,
+        &mut audio_instances,
+        PlaybackRate::Factor(-1.),
+        &None,
+    );
+    assert_eq!(
+        audio_instances.get(&instance_handle).unwrap().playback_rate,
+        2.
+    );
+}
+
+#[test]
+fn modulators_drive_instances_until_they_are_unbound() {
+    let mut audio_output = mock_audio_output();
+    let mut modulators = AudioModulators::default();
+    // Stays at the top of its swing
+    let lfo = modulators.add_lfo(LfoSettings {
+        waveform: Waveform::Pulse { width: 1. },
+        ..Default::default()
+    });
+    let removed = modulators.add_lfo(LfoSettings::default());
+    modulators.remove(removed);
+    audio_output.update_modulators(&mut modulators);
+    assert!(modulators.is_created(lfo));
+    assert!(!modulators.is_created(removed));
+
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let source = silent_source(10_000);
+    let instance_handle = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    let settings = PartialSoundSettings {
+        modulators: vec![(ModulatedParameter::PlaybackRate, lfo, 12.)],
+        ..Default::default()
+    };
+    audio_output.play(
+        &Channel::typed::<MainTrack>(),
+        &settings,
+        (&source).into(),
+        instance_handle.clone(),
+        &mut audio_instances,
+    );
+    let advance = |audio_output: &mut AudioOutput<MockBackend>,
+                   audio_instances: &Assets<AudioInstance>| {
+        let backend = audio_output.manager.as_mut().unwrap().backend_mut();
+        // Let the tween of a changed modulation finish first
+        backend.on_start_processing();
+        for _ in 0..100 {
+            backend.process();
+        }
+        backend.on_start_processing();
+        let before = audio_instances.get(&instance_handle).unwrap().position();
+        for _ in 0..100 {
+            backend.process();
+        }
+        backend.on_start_processing();
+        audio_instances.get(&instance_handle).unwrap().position() - before
+    };
+    assert!((advance(&mut audio_output, &audio_instances) - 0.2).abs() < 1e-2);
+
+    let instance = audio_instances.get_mut(&instance_handle).unwrap();
+    assert!(instance
+        .unbind_modulator(ModulatedParameter::PlaybackRate)
+        .is_none());
+    assert!(matches!(
+        instance.bind_modulator(&modulators, removed, ModulatedParameter::Volume, 0.5),
+        Some(AudioCommandError::NoModulator)
+    ));
+    assert!((advance(&mut audio_output, &audio_instances) - 0.1).abs() < 1e-2);
+
+    let instance = audio_instances.get_mut(&instance_handle).unwrap();
+    assert!(instance
+        .bind_modulator(&modulators, lfo, ModulatedParameter::PlaybackRate, 12.)
+        .is_none());
+    assert!((advance(&mut audio_output, &audio_instances) - 0.2).abs() < 1e-2);
+
+    // LFOs are created again for a new manager, with the settings they were changed to
+    modulators.set_frequency(lfo, 4., AudioTween::linear(Duration::from_millis(100)));
+    audio_output.update_modulators(&mut modulators);
+    audio_output.replace_manager(
+        mock_manager(),
+        &ChannelHierarchy::default(),
+        &mut audio_instances,
+    );
+    audio_output.update_modulators(&mut modulators);
+    assert!(modulators.is_created(lfo));
+    assert_eq!(audio_output.lfos.settings(&lfo).unwrap().frequency, 4.);
+}
+
+#[test]
+fn music_layers_start_together_and_stay_in_sync() {
+    let mut audio_output = mock_audio_output();
+    let mut audio_instances = Assets::<AudioInstance>::default();
+    let stem = || silent_source(2_000);
+    let mut audio_sources = Assets::<AudioSource>::default();
+    let drums = audio_sources.add(stem());
+    let pads = Handle::<AudioSource>::Weak(AssetId::Uuid {
+        uuid: Uuid::from_u128(1),
+    });
+    let mut layers = MusicLayers::default();
+    let music = layers.play_layers(&[drums, pads.clone()]);
+    layers.set_layer_volume(music, 1, 0.5, AudioTween::default());
+
+    layers.run_commands(&mut audio_output, &audio_sources, &mut audio_instances);
+    assert!(layers.instances(music).is_none());
+    assert_eq!(layers.commands.len(), 2);
+    audio_sources.insert(pads.id(), stem());
+    layers.run_commands(&mut audio_output, &audio_sources, &mut audio_instances);
+    let instances = layers.instances(music).unwrap().to_vec();
+    assert!(layers.commands.is_empty());
+    assert_eq!(
+        audio_instances.get(&instances[1]).unwrap().volume,
+        Volume::Amplitude(0.5)
+    );
+
+    let offset = |audio_instances: &Assets<AudioInstance>| {
+        let [drums, pads] = [&instances[0], &instances[1]]
+            .map(|instance| audio_instances.get(instance).unwrap().position());
+        assert!(drums > 0.);
+        drums - pads
+    };
+    render(&mut audio_output, 500);
+    assert!(offset(&audio_instances).abs() < 1e-9);
+
+    // A seek that reached the audio thread for one layer only
+    audio_instances
+        .get_mut(&instances[1])
+        .unwrap()
+        .seek_by(-0.1);
+    render(&mut audio_output, 10);
+    assert!((offset(&audio_instances) - 0.1).abs() < 1e-9);
+    layers.sync(&mut audio_instances);
+    render(&mut audio_output, 10);
+    assert!((offset(&audio_instances) - 0.1).abs() < 1e-9);
+    layers.sync(&mut audio_instances);
+    render(&mut audio_output, 10);
+    assert!(offset(&audio_instances).abs() < LAYER_SYNC_TOLERANCE);
+
+    // Over many loops the layers don't drift apart
+    render(&mut audio_output, 9_000);
+    assert!(offset(&audio_instances).abs() < 1e-9);
+
+    layers.pause(music, AudioTween::default());
+    layers.run_commands(&mut audio_output, &audio_sources, &mut audio_instances);
+    render(&mut audio_output, 100);
+    for instance in instances.iter() {
+        assert!(matches!(
+            audio_instances.get(instance).unwrap().state(),
+            crate::PlaybackState::Paused { .. }
+        ));
+    }
+    layers.stop(music, AudioTween::default());
+    layers.run_commands(&mut audio_output, &audio_sources, &mut audio_instances);
+    assert!(layers.instances(music).is_none());
+}
@@ -1,13 +1,25 @@
+use crate::output_device::{MainBackend, MainOutput, StreamOptions};
 use bevy::ecs::system::Resource;
 use bevy::utils::default;
+use cpal::Device;
 use kira::manager::backend::DefaultBackend;
 use kira::manager::{AudioManagerSettings, Capacities};
+use kira::track::TrackBuilder;
+use std::time::Duration;
 
 /// This resource is used to configure the audio backend at creation
 ///
 /// It needs to be inserted before adding the [`AudioPlugin`](crate::AudioPlugin) and will be
 /// consumed by it. Settings cannot be changed at run-time!
+///
+/// With the `serde` feature, the settings can be (de)serialized, for example as part of saved
+/// options. Missing fields keep their default.
 #[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct AudioSettings {
     /// The number of commands that can be sent to the audio backend at a time.
     ///
@@ -18,6 +30,59 @@ pub struct AudioSettings {
     pub command_capacity: usize,
     /// The maximum number of sounds that can be playing at a time.
     pub sound_capacity: usize,
+    /// Fade out all audio for this long when the app exits
+    ///
+    /// On [`AppExit`](bevy::app::AppExit), the plugin fades out the audio output, blocks until
+    /// the fade is done and then closes the audio backend. The default of zero closes it right away.
+    pub exit_fade: Duration,
+    /// The number of frames the audio thread renders at a time
+    ///
+    /// Smaller buffers lower the latency, but crackle sooner if the system is busy. The value is
+    /// limited to what the device supports. `None` leaves it to the device.
+    pub buffer_size: Option<u32>,
+    /// The sample rate to ask the output device for
+    ///
+    /// If the device does not support it, it plays at its default rate. `None` uses the default
+    /// rate of the device.
+    pub sample_rate: Option<u32>,
+    /// What happens to the audio while no window of the app has the focus
+    ///
+    /// By default, audio keeps playing.
+    pub focus_loss: FocusLoss,
+    /// Play all sounds at the speed of virtual time
+    ///
+    /// The playback rates of sounds are multiplied by the relative speed of
+    /// [`Time<Virtual>`](bevy::time::Virtual), so a slowed down game sounds slowed down as well.
+    /// While virtual time is paused, sounds stand still. To only follow virtual time on some
+    /// channels, see [`ChannelSettings::follow_virtual_time`](crate::ChannelSettings::follow_virtual_time).
+    pub follow_virtual_time: bool,
+    /// Play on a backend without output if no audio output can be opened (default `true`)
+    ///
+    /// Sounds still advance and complete, so events like
+    /// [`PlaybackCompleted`](crate::PlaybackCompleted) fire as usual, for example in tests or on
+    /// servers without audio devices. Without the fallback, the commands of all channels are
+    /// dropped. See [`AudioBackendStatus`](crate::AudioBackendStatus).
+    pub headless_fallback: bool,
+}
+
+/// What the plugin does with the audio while no window of the app has the focus
+///
+/// See [`AudioSettings::focus_loss`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FocusLoss {
+    /// Keep playing
+    #[default]
+    Ignore,
+    /// Fade all audio out and pause it until a window has the focus again
+    ///
+    /// Channels and sounds that the game paused itself stay paused once the focus returns.
+    Pause,
+    /// Lower the volume of all audio, on top of the [`MasterVolume`](crate::MasterVolume)
+    Duck {
+        /// How many decibels quieter the audio plays
+        decibels: u8,
+    },
 }
 
 impl Default for AudioSettings {
@@ -25,6 +90,54 @@ impl Default for AudioSettings {
         Self {
             command_capacity: 128,
             sound_capacity: 128,
+            exit_fade: Duration::ZERO,
+            buffer_size: None,
+            sample_rate: None,
+            focus_loss: FocusLoss::Ignore,
+            follow_virtual_time: false,
+            headless_fallback: true,
+        }
+    }
+}
+
+impl AudioSettings {
+    /// The settings of the main audio manager, playing on the given device or the default one
+    pub(crate) fn manager_settings(
+        &self,
+        device: Option<Device>,
+        main_track: TrackBuilder,
+    ) -> AudioManagerSettings<MainBackend> {
+        AudioManagerSettings {
+            capacities: self.capacities(),
+            main_track_builder: main_track,
+            backend_settings: MainOutput::Device(device, self.stream_options()),
+        }
+    }
+
+    /// The settings of a main audio manager without output, see [`headless_fallback`](Self::headless_fallback)
+    pub(crate) fn headless_manager_settings(
+        &self,
+        main_track: TrackBuilder,
+    ) -> AudioManagerSettings<MainBackend> {
+        AudioManagerSettings {
+            capacities: self.capacities(),
+            main_track_builder: main_track,
+            backend_settings: MainOutput::Headless,
+        }
+    }
+
+    pub(crate) fn stream_options(&self) -> StreamOptions {
+        StreamOptions {
+            buffer_size: self.buffer_size,
+            sample_rate: self.sample_rate,
+        }
+    }
+
+    pub(crate) fn capacities(&self) -> Capacities {
+        Capacities {
+            command_capacity: self.command_capacity,
+            sound_capacity: self.sound_capacity,
+            ..default()
         }
     }
 }
@@ -32,12 +145,35 @@ impl Default for AudioSettings {
 impl From<AudioSettings> for AudioManagerSettings<DefaultBackend> {
     fn from(settings: AudioSettings) -> Self {
         AudioManagerSettings {
-            capacities: Capacities {
-                command_capacity: settings.command_capacity,
-                sound_capacity: settings.sound_capacity,
-                ..default()
-            },
+            capacities: settings.capacities(),
             ..default()
         }
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn settings_round_trip_and_keep_defaults_of_missing_fields() {
+        let settings = AudioSettings {
+            sound_capacity: 256,
+            exit_fade: Duration::from_millis(300),
+            buffer_size: Some(512),
+            focus_loss: FocusLoss::Duck { decibels: 12 },
+            ..default()
+        };
+        let saved = ron::to_string(&settings).unwrap();
+        assert_eq!(ron::from_str::<AudioSettings>(&saved).unwrap(), settings);
+
+        let old = ron::from_str::<AudioSettings>("(sound_capacity: 64)").unwrap();
+        assert_eq!(
+            old,
+            AudioSettings {
+                sound_capacity: 64,
+                ..default()
+            }
+        );
+    }
+}
@@ -1,26 +1,146 @@
+pub mod ducking;
 pub mod dynamic;
+pub mod effects;
+pub mod hierarchy;
+pub(crate) mod limit;
+pub mod queue;
+pub mod snapshot;
 pub mod typed;
 
-use crate::audio::{AudioCommand, FadeIn, FadeOut, PlayAudioCommand, TweenCommand};
+use crate::audio::{AudioCommand, AudioTween, FadeIn, FadeOut, PlayAudioCommand, TweenCommand};
+use crate::channel::ducking::DuckSettings;
+use crate::channel::effects::ChannelReverb;
+use crate::channel::queue::QueueRepeat;
 use crate::instance::AudioInstance;
-use crate::{AudioSource, PlaybackState};
+use crate::source::SoundLayout;
+use crate::spacial::zone::ZoneMix;
+use crate::{
+    AudioBank, AudioBankError, AudioCollection, AudioSource, PlaybackState, StreamedAudioSource,
+};
 use bevy::asset::Handle;
-use kira::sound::static_sound::StaticSoundData;
+use bevy::reflect::Reflect;
 use kira::tween::Value;
 use kira::Volume;
 use std::any::TypeId;
+use std::time::Duration;
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+/// Identifies an audio channel
+///
+/// Used to select the channels that [`SpacialAudio`](crate::SpacialAudio) controls.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Channel {
+    /// A typed [`AudioChannel<T>`](crate::AudioChannel) by the [`TypeId`] of `T`
     Typed(TypeId),
+    /// A [`DynamicAudioChannel`](crate::DynamicAudioChannel) by its key
     Dynamic(String),
 }
 
+impl Channel {
+    /// The typed channel [`AudioChannel<T>`](crate::AudioChannel)
+    ///
+    /// The [`Audio`](crate::Audio) resource is `Channel::typed::<MainTrack>()`.
+    pub fn typed<T: 'static>() -> Self {
+        Channel::Typed(TypeId::of::<T>())
+    }
+
+    /// The dynamic channel with the given key
+    pub fn dynamic(key: impl Into<String>) -> Self {
+        Channel::Dynamic(key.into())
+    }
+}
+
+/// Initial settings of a channel
+///
+/// See [`AudioApp::add_audio_channel_with_settings`](crate::AudioApp::add_audio_channel_with_settings)
+/// and [`DynamicAudioChannels::create_channel_with_settings`](crate::DynamicAudioChannels::create_channel_with_settings).
+/// With the `serde` feature, the settings can be (de)serialized, for example as part of saved options.
+/// Missing fields keep their default.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ChannelSettings {
+    /// Volume of all sounds in the channel (default full volume)
+    pub volume: Volume,
+    /// Panning of all sounds in the channel, from `0.0` (hard left) to `1.0` (hard right)
+    ///
+    /// Default is `0.5`.
+    pub panning: f64,
+    /// Playback rate of all sounds in the channel (default `1.0`)
+    pub playback_rate: f64,
+    /// Whether sounds in the channel start paused (default `false`)
+    pub paused: bool,
+    /// Whether sounds in the channel play at the speed of virtual time (default `false`)
+    ///
+    /// See [`AudioSettings::follow_virtual_time`](crate::AudioSettings::follow_virtual_time).
+    pub follow_virtual_time: bool,
+}
+
+impl Default for ChannelSettings {
+    fn default() -> Self {
+        ChannelSettings {
+            volume: 1.0.into(),
+            panning: 0.5,
+            playback_rate: 1.0,
+            paused: false,
+            follow_virtual_time: false,
+        }
+    }
+}
+
+impl From<ChannelSettings> for ChannelState {
+    fn from(settings: ChannelSettings) -> Self {
+        ChannelState {
+            paused: settings.paused,
+            volume: settings.volume,
+            playback_rate: settings.playback_rate,
+            panning: settings.panning,
+            instance_limit: None,
+            muted: false,
+            duck: 1.,
+            zone: ZoneMix::default(),
+            output_device: None,
+            resume_tween: None,
+            follow_virtual_time: settings.follow_virtual_time,
+        }
+    }
+}
+
+/// What happens when a sound is played on a channel that is at its instance limit
+///
+/// See [`AudioControl::set_instance_limit`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StealPolicy {
+    /// Don't play the new sound
+    RejectNew,
+    /// Stop the instance that started playing first
+    StopOldest,
+    /// Stop the instance with the lowest volume
+    ///
+    /// The volume is the one the instance was played with, scaled by spacial audio.
+    StopQuietest,
+}
+
 pub(crate) struct ChannelState {
     pub(crate) paused: bool,
     pub(crate) volume: Volume,
     pub(crate) playback_rate: f64,
     pub(crate) panning: f64,
+    pub(crate) instance_limit: Option<(usize, StealPolicy)>,
+    pub(crate) muted: bool,
+    /// Factor of the volume while the channel is ducked
+    pub(crate) duck: f64,
+    /// The adjustments of the audio zones the receiver is in, see [`AudioZone`](crate::AudioZone)
+    pub(crate) zone: ZoneMix,
+    /// Name of the output device new sounds play on, if it is not the default device
+    pub(crate) output_device: Option<String>,
+    /// Tween of resumes without their own, see [`AudioControl::set_default_resume_tween`]
+    pub(crate) resume_tween: Option<AudioTween>,
+    /// See [`ChannelSettings::follow_virtual_time`]
+    pub(crate) follow_virtual_time: bool,
 }
 
 impl Default for ChannelState {
@@ -30,15 +150,23 @@ impl Default for ChannelState {
             volume: 1.0.into(),
             playback_rate: 1.0,
             panning: 0.5,
+            instance_limit: None,
+            muted: false,
+            duck: 1.,
+            zone: ZoneMix::default(),
+            output_device: None,
+            resume_tween: None,
+            follow_virtual_time: false,
         }
     }
 }
 
 impl ChannelState {
-    pub(crate) fn apply(&self, sound: &mut StaticSoundData) {
-        sound.settings.volume = Value::Fixed(self.volume);
-        sound.settings.playback_rate = self.playback_rate.into();
-        sound.settings.panning = Value::Fixed(self.panning);
+    pub(crate) fn apply(&self, sound: &mut impl SoundLayout) {
+        let settings = sound.settings_mut();
+        settings.volume = Value::Fixed(self.volume);
+        settings.playback_rate = self.playback_rate.into();
+        settings.panning = Value::Fixed(self.panning);
     }
 }
 
@@ -54,7 +182,137 @@ pub trait AudioControl {
     ///     audio.play(asset_server.load("audio.mp3"));
     /// }
     /// ```
-    fn play(&self, audio_source: Handle<AudioSource>) -> PlayAudioCommand;
+    fn play(&self, audio_source: Handle<AudioSource>) -> PlayAudioCommand<'_>;
+
+    /// Play several sounds back to back as one instance
+    ///
+    /// This is meant for phrases like dialogue lines split across files. The parts play without
+    /// gaps under a single [`AudioInstance`], so pausing, stopping or changing the volume affects
+    /// the whole phrase. Stopping it also cancels the parts that didn't start yet.
+    ///
+    /// The sequence plays once all parts are loaded, at the sample rate of the first part.
+    /// The settings of the parts, like their loop regions, are not used. Nothing plays for an
+    /// empty sequence.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    ///     let phrase = [
+    ///         asset_server.load("voice/you_found.ogg"),
+    ///         asset_server.load("voice/five.ogg"),
+    ///         asset_server.load("voice/coins.ogg"),
+    ///     ];
+    ///     let instance: Handle<AudioInstance> = audio.play_sequence(&phrase).handle();
+    /// }
+    /// ```
+    fn play_sequence(&self, audio_sources: &[Handle<AudioSource>]) -> PlayAudioCommand<'_>;
+
+    /// Play a sound that is decoded while it plays
+    ///
+    /// Use this for long music tracks that would take long to load and a lot of memory as
+    /// an [`AudioSource`]. Streamed sounds can't play in reverse or with a negative playback rate.
+    /// See [`StreamedAudioSource`](crate::StreamedAudioSource).
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    ///     audio.play_streamed(asset_server.load("music/theme.stream.ogg")).looped();
+    /// }
+    /// ```
+    fn play_streamed(&self, audio_source: Handle<StreamedAudioSource>) -> PlayAudioCommand<'_>;
+
+    /// Play a sound of an [`AudioBank`] with the settings of the bank
+    ///
+    /// The returned command can still change the settings. If the bank has no sound with the
+    /// given name, nothing plays and [`AudioBankError::MissingSound`] is returned.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// #[derive(Resource)]
+    /// struct Sfx(Handle<AudioBank>);
+    ///
+    /// fn my_system(sfx: Res<Sfx>, banks: Res<Assets<AudioBank>>, audio: Res<Audio>) {
+    ///     let Some(bank) = banks.get(&sfx.0) else {
+    ///         return;
+    ///     };
+    ///     if let Err(error) = audio.play_from_bank(bank, "footstep_grass") {
+    ///         warn!("{}", error);
+    ///     }
+    /// }
+    /// ```
+    fn play_from_bank(
+        &self,
+        bank: &AudioBank,
+        name: &str,
+    ) -> Result<PlayAudioCommand<'_>, AudioBankError>;
+
+    /// Play a variant of an [`AudioCollection`]
+    ///
+    /// Every call picks one variant by the [`SelectionMode`](crate::SelectionMode) of the
+    /// collection. The variant is picked once the collection loaded, right before it plays.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    ///     audio.play_collection(asset_server.load("sounds/footsteps.collection.ron"));
+    /// }
+    /// ```
+    fn play_collection(&self, collection: Handle<AudioCollection>) -> PlayAudioCommand<'_>;
+
+    /// Play audio without keeping its instance around
+    ///
+    /// This is meant for short sounds like UI blips that are never controlled after they started.
+    /// Their [`AudioInstance`] asset is removed as soon as they stopped, so spamming one-shots
+    /// doesn't grow [`Assets<AudioInstance>`](bevy::asset::Assets). A handle taken with
+    /// [`PlayAudioCommand::handle`] points to nothing once the sound stopped.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    ///     audio.play_one_shot(asset_server.load("sounds/click.ogg"));
+    /// }
+    /// ```
+    fn play_one_shot(&self, audio_source: Handle<AudioSource>) -> PlayAudioCommand<'_>;
+
+    /// Play audio, fading it in while all other audio of the channel fades out
+    ///
+    /// The new sound starts silent and fades to its volume over the given duration, while all
+    /// other playing sounds of the channel fade out and then stop. They report
+    /// [`PlaybackState::Stopping`] until then. A crossfade started during another one fades out
+    /// the half faded-in sound from its current volume, while the older sounds finish fading out.
+    ///
+    /// The fades follow [`CrossfadeCurve::EqualPower`](crate::CrossfadeCurve::EqualPower), unless
+    /// another curve is set with [`PlayAudioCommand::crossfade_curve`](crate::PlayAudioCommand::crossfade_curve).
+    /// The curve shapes fades of volumes in amplitude. Fades of volumes in decibels happen in decibels.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    /// #[derive(Resource)]
+    /// struct Music;
+    ///
+    /// fn enter_combat(asset_server: Res<AssetServer>, music: Res<AudioChannel<Music>>) {
+    ///     music
+    ///         .crossfade_to(asset_server.load("music/combat.ogg"), Duration::from_secs(2))
+    ///         .looped();
+    /// }
+    /// ```
+    fn crossfade_to(
+        &self,
+        audio_source: Handle<AudioSource>,
+        duration: Duration,
+    ) -> PlayAudioCommand<'_>;
 
     /// Stop all audio
     ///
@@ -66,7 +324,7 @@ pub trait AudioControl {
     ///     audio.stop();
     /// }
     /// ```
-    fn stop(&self) -> TweenCommand<FadeOut>;
+    fn stop(&self) -> TweenCommand<'_, FadeOut>;
 
     /// Pause all audio
     ///
@@ -78,10 +336,13 @@ pub trait AudioControl {
     ///     audio.pause();
     /// }
     /// ```
-    fn pause(&self) -> TweenCommand<FadeOut>;
+    fn pause(&self) -> TweenCommand<'_, FadeOut>;
 
     /// Resume all audio
     ///
+    /// Without a tween of its own, the resume fades with the
+    /// [default resume tween](Self::set_default_resume_tween) of the channel.
+    ///
     /// ```
     /// # use bevy::prelude::*;
     /// # use bevy_kira_audio::prelude::*;
@@ -90,22 +351,126 @@ pub trait AudioControl {
     ///     audio.resume();
     /// }
     /// ```
-    fn resume(&self) -> TweenCommand<FadeIn>;
+    fn resume(&self) -> TweenCommand<'_, FadeIn>;
+
+    /// Fade all audio out and pause it once it is silent
+    ///
+    /// The channel counts as paused as soon as the fade starts: sounds played during the fade,
+    /// or later, start paused and only play after the channel resumes.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(ambience: Res<Audio>) {
+    ///     ambience.pause_with_fade(AudioTween::linear(Duration::from_millis(500)));
+    /// }
+    /// ```
+    fn pause_with_fade(&self, tween: AudioTween);
+
+    /// Resume all audio and fade it back in, see [`pause_with_fade`](Self::pause_with_fade)
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(ambience: Res<Audio>) {
+    ///     ambience.resume_with_fade(AudioTween::linear(Duration::from_millis(500)));
+    /// }
+    /// ```
+    fn resume_with_fade(&self, tween: AudioTween);
+
+    /// Fade in with the given tween when audio is resumed without a tween of its own
+    ///
+    /// This applies to [`resume`](Self::resume) on the channel and to
+    /// [`AudioInstance::resume_with_default_fade`] on its instances, for example to resume
+    /// softly after a pause menu. Explicit tweens like the one of
+    /// [`resume_with_fade`](Self::resume_with_fade) are used as given.
+    /// A tween with a duration of zero restores the default behavior.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.set_default_resume_tween(AudioTween::linear(Duration::from_millis(300)));
+    /// }
+    /// ```
+    fn set_default_resume_tween(&self, tween: AudioTween);
+
+    /// Pause all audio now and resume it after the given time
+    ///
+    /// Both the pause and the resume fade with the given tween. The resume is scheduled in the
+    /// plugin, so nothing has to be done once the time is up. A manual [`resume`](Self::resume)
+    /// or [`pause`](Self::pause) before that cancels the scheduled resume, and another
+    /// `pause_for` replaces it.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.pause_for(Duration::from_secs(2), AudioTween::linear(Duration::from_millis(200)));
+    /// }
+    /// ```
+    fn pause_for(&self, duration: Duration, tween: AudioTween);
+
+    /// Set the playback position of all instances in the channel to the given time in seconds
+    ///
+    /// Instances that don't loop stop when seeking outside of their sound, like when reaching its
+    /// end. Looping instances wrap around into their loop region. A queued next sound is
+    /// scheduled again for the new end of the current one.
+    /// See [`AudioInstance::seek_to`] to seek a single instance.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.seek_to(30.);
+    /// }
+    /// ```
+    fn seek_to(&self, position: f64);
+
+    /// Move the playback position of all instances in the channel by the given time in seconds
+    ///
+    /// Negative amounts seek backwards. See [`seek_to`](Self::seek_to) for seeking outside of
+    /// the sounds.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn skip_forward(music: Res<Audio>) {
+    ///     music.seek_by(10.);
+    /// }
+    /// ```
+    fn seek_by(&self, amount: f64);
 
     /// Set the volume
     ///
     /// The default value is 1.
     /// This method supports setting the volume in Decibels or as Amplitude.
+    /// Fades to a volume in Decibels are interpolated in Decibels, which sounds more even.
+    /// `Volume::Decibels(f64::NEG_INFINITY)` is silent.
     ///
     /// ```
     /// # use bevy::prelude::*;
     /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
     ///
     /// fn my_system(audio: Res<Audio>) {
     ///     audio.set_volume(0.5);
+    ///     audio
+    ///         .set_volume(Volume::Decibels(-12.))
+    ///         .fade_in(AudioTween::linear(Duration::from_secs(2)));
     /// }
     /// ```
-    fn set_volume(&self, volume: impl Into<Volume>) -> TweenCommand<FadeIn>;
+    fn set_volume(&self, volume: impl Into<Volume>) -> TweenCommand<'_, FadeIn>;
 
     /// Set panning
     ///
@@ -121,7 +486,7 @@ pub trait AudioControl {
     ///     audio.set_panning(0.9);
     /// }
     /// ```
-    fn set_panning(&self, panning: f64) -> TweenCommand<FadeIn>;
+    fn set_panning(&self, panning: f64) -> TweenCommand<'_, FadeIn>;
 
     /// Set playback rate
     ///
@@ -135,7 +500,277 @@ pub trait AudioControl {
     ///     audio.set_playback_rate(2.0);
     /// }
     /// ```
-    fn set_playback_rate(&self, playback_rate: f64) -> TweenCommand<FadeIn>;
+    fn set_playback_rate(&self, playback_rate: f64) -> TweenCommand<'_, FadeIn>;
+
+    /// Set the pitch in semitones
+    ///
+    /// This sets the playback rate to `2^(semitones / 12)`. Tweens interpolate in semitones, so
+    /// glides sound even. The current playback rate has to be positive for that.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio
+    ///         .set_pitch_semitones(-12.)
+    ///         .fade_in(AudioTween::linear(Duration::from_secs(1)));
+    /// }
+    /// ```
+    fn set_pitch_semitones(&self, semitones: f64) -> TweenCommand<'_, FadeIn>;
+
+    /// Limit the number of instances that play in this channel at the same time
+    ///
+    /// The limit is checked for every new sound, including multiple sounds played in the same frame.
+    /// If the channel is full, the `policy` decides whether the new sound is not played or an
+    /// older instance is stopped to make room. Stopped instances count as [`PlaybackState::Stopped`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.set_instance_limit(8, StealPolicy::StopOldest);
+    /// }
+    /// ```
+    fn set_instance_limit(&self, limit: usize, policy: StealPolicy);
+
+    /// Remove the instance limit set with [`set_instance_limit`](Self::set_instance_limit)
+    fn remove_instance_limit(&self);
+
+    /// Play all sounds of this channel through a reverb
+    ///
+    /// Sounds that are already playing keep playing without the reverb.
+    /// Calling this again changes the settings of the existing reverb.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.set_reverb(ChannelReverb {
+    ///         feedback: 0.95,
+    ///         ..default()
+    ///     });
+    /// }
+    /// ```
+    fn set_reverb(&self, reverb: ChannelReverb);
+
+    /// Set the balance between the dry signal (`0.0`) and the reverberation (`1.0`)
+    ///
+    /// This does nothing if the channel has no reverb, see [`set_reverb`](Self::set_reverb).
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio
+    ///         .set_reverb_mix(0.8)
+    ///         .linear_fade_in(Duration::from_secs(1));
+    /// }
+    /// ```
+    fn set_reverb_mix(&self, mix: f64) -> TweenCommand<'_, FadeIn>;
+
+    /// Send the sounds of this channel to the effects of another channel, like an aux send
+    ///
+    /// The `target` channel needs effects, see [`set_reverb`](Self::set_reverb) and
+    /// [`EffectChain`](crate::EffectChain). The `level` scales the sent signal (`1.0` sends it
+    /// unchanged), and the dry signal of this channel is not affected. Calling this again for the
+    /// same target tweens to the new level. Sounds that started before a new target was added
+    /// keep their previous sends.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use bevy_kira_audio::Channel;
+    /// #[derive(Resource)]
+    /// struct SharedReverb;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.add_send(Channel::typed::<SharedReverb>(), 0.3);
+    /// }
+    /// ```
+    fn add_send(&self, target: Channel, level: f64) -> TweenCommand<'_, FadeIn>;
+
+    /// Fade out the send to `target`, see [`add_send`](Self::add_send)
+    fn remove_send(&self, target: Channel) -> TweenCommand<'_, FadeOut>;
+
+    /// Lower the volume of this channel while sounds play in the `trigger` channel
+    ///
+    /// While the `trigger` channel has a playing instance that is not muted and louder than the
+    /// threshold, this channel fades to `1.0 - amount` times its volume with the attack tween.
+    /// Once the trigger channel is quiet, it fades back with the release tween. Calling this again
+    /// for the same trigger replaces its settings. If several triggers are active, the strongest wins.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use bevy_kira_audio::Channel;
+    /// #[derive(Resource)]
+    /// struct Voice;
+    ///
+    /// fn my_system(music: Res<Audio>) {
+    ///     music.duck_by(
+    ///         Channel::typed::<Voice>(),
+    ///         DuckSettings {
+    ///             amount: 0.7,
+    ///             ..default()
+    ///         },
+    ///     );
+    /// }
+    /// ```
+    fn duck_by(&self, trigger: Channel, settings: DuckSettings);
+
+    /// Stop ducking this channel while sounds play in `trigger`, see [`duck_by`](Self::duck_by)
+    fn stop_ducking_by(&self, trigger: Channel);
+
+    /// Duck this channel for the given duration, see [`duck_by`](Self::duck_by)
+    ///
+    /// Calling this again before the duration ran out restarts it with the new settings.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(music: Res<Audio>) {
+    ///     music.duck_for(Duration::from_secs(2), DuckSettings::default());
+    /// }
+    /// ```
+    fn duck_for(&self, duration: Duration, settings: DuckSettings);
+
+    /// Play new sounds of this channel on the output device with the given name
+    ///
+    /// Use [`output_device_names`](crate::output_device_names) to list the devices. If the device
+    /// can't be opened, an error is logged and the channel keeps playing on the default device.
+    /// Sounds that are already playing stay on their device.
+    ///
+    /// Sounds on other devices play outside of the mixer of the default device, so they don't use
+    /// parent channels, channel effects, sends, low pass filters, ambient sends or
+    /// [`SpacialBackend::Kira`](crate::SpacialBackend::Kira). Volume, panning, playback rate,
+    /// muting and ducking work as usual.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// #[derive(Resource)]
+    /// struct Cue;
+    ///
+    /// fn my_system(cue: Res<AudioChannel<Cue>>) {
+    ///     cue.set_output_device("Headphones");
+    /// }
+    /// ```
+    fn set_output_device(&self, device: impl Into<String>);
+
+    /// Play new sounds of this channel on the default output device again
+    ///
+    /// See [`set_output_device`](Self::set_output_device).
+    fn reset_output_device(&self);
+
+    /// Add a sound to the queue of this channel
+    ///
+    /// Queued sounds play one after the other. Each next sound is scheduled on the
+    /// [`AudioClock`](crate::AudioClock) for the end of the current one, so there is no gap
+    /// between them. The first sound starts shortly after it was enqueued and loaded.
+    /// A [`QueueAdvanced`](crate::QueueAdvanced) event is sent whenever the next sound starts.
+    ///
+    /// Queued sounds play with the settings of the channel. A sound that loops plays until it is
+    /// skipped. Pausing the channel holds the queue, but the transition after resuming it is
+    /// only as exact as the position of the current sound.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// #[derive(Resource)]
+    /// struct Music;
+    ///
+    /// fn start_playlist(asset_server: Res<AssetServer>, music: Res<AudioChannel<Music>>) {
+    ///     music.enqueue(asset_server.load("music/intro.ogg"));
+    ///     music.enqueue(asset_server.load("music/loop.ogg"));
+    ///     music.set_queue_repeat(QueueRepeat::All);
+    /// }
+    /// ```
+    fn enqueue(&self, audio_source: Handle<AudioSource>);
+
+    /// Stop the current sound of the queue and start the next one
+    ///
+    /// This also leaves a sound behind that repeats with [`QueueRepeat::One`](crate::QueueRepeat::One).
+    fn skip_queued(&self);
+
+    /// Remove all upcoming sounds from the queue
+    ///
+    /// The current sound plays on. [`stop`](Self::stop) clears the queue as well and stops the
+    /// current sound.
+    fn clear_queue(&self);
+
+    /// Set what the queue does with the sounds it played
+    ///
+    /// See [`enqueue`](Self::enqueue).
+    fn set_queue_repeat(&self, repeat: QueueRepeat);
+
+    /// Mute all audio
+    ///
+    /// The channel keeps its volume, including volume changes while it is muted.
+    /// [`unmute`](Self::unmute) fades back to that volume.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.mute();
+    /// }
+    /// ```
+    fn mute(&self) -> TweenCommand<'_, FadeOut>;
+
+    /// Unmute all audio
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.unmute();
+    /// }
+    /// ```
+    fn unmute(&self) -> TweenCommand<'_, FadeIn>;
+
+    /// Solo this channel
+    ///
+    /// While any channel is soloed, all channels that are not soloed are muted.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.solo();
+    /// }
+    /// ```
+    fn solo(&self) -> TweenCommand<'_, FadeOut>;
+
+    /// Stop soloing this channel
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.unsolo();
+    /// }
+    /// ```
+    fn unsolo(&self) -> TweenCommand<'_, FadeIn>;
+
+    /// Returns `true` if the channel was muted with [`mute`](Self::mute)
+    ///
+    /// Channels silenced by the solo of another channel are not muted.
+    fn is_muted(&self) -> bool;
+
+    /// Returns `true` if the channel was soloed with [`solo`](Self::solo)
+    fn is_soloed(&self) -> bool;
 
     /// Get state for a playback instance.
     fn state(&self, instance_handle: &Handle<AudioInstance>) -> PlaybackState;
@@ -145,8 +780,90 @@ pub trait AudioControl {
     /// If there are only `Stopped`, `Paused`, or `Queued` sounds, the method will return `false`.
     /// The same result is returned if there are no sounds in the channel at all.
     fn is_playing_sound(&self) -> bool;
+
+    /// The number of instances in this channel that are `Playing`, `Pausing`, or `Stopping`
+    ///
+    /// Like [`state`](Self::state), this reflects the states as of the last run of the audio
+    /// systems in `PreUpdate`, so it can be slightly stale. Sounds played this frame are not counted yet.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(asset_server: Res<AssetServer>, music: Res<Audio>) {
+    ///     if music.playing_count() == 0 {
+    ///         music.play(asset_server.load("music.ogg"));
+    ///     }
+    /// }
+    /// ```
+    fn playing_count(&self) -> usize;
+
+    /// Handles of all instances in this channel that have not stopped
+    ///
+    /// Use [`state`](Self::state) to get the state of each instance.
+    /// Like the states, the list is updated by the audio systems in `PreUpdate` and can be slightly stale.
+    fn instances(&self) -> Vec<Handle<AudioInstance>>;
 }
 
 pub(crate) trait AudioCommandQue {
     fn que(&self, command: AudioCommand);
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod test {
+    use super::*;
+    use crate::channel::effects::ChannelReverb;
+    use crate::AudioEasing;
+
+    fn round_trip<T>(value: &T) -> T
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        ron::from_str(&ron::to_string(value).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn channel_settings_types_round_trip() {
+        let settings = ChannelSettings {
+            volume: Volume::Decibels(-6.),
+            panning: 0.2,
+            paused: true,
+            ..Default::default()
+        };
+        assert_eq!(round_trip(&settings), settings);
+        assert_eq!(
+            ron::from_str::<ChannelSettings>("(panning: 0.2)").unwrap(),
+            ChannelSettings {
+                panning: 0.2,
+                ..Default::default()
+            }
+        );
+
+        for policy in [
+            StealPolicy::RejectNew,
+            StealPolicy::StopOldest,
+            StealPolicy::StopQuietest,
+        ] {
+            assert_eq!(round_trip(&policy), policy);
+        }
+
+        let duck = DuckSettings {
+            amount: 0.8,
+            attack: AudioTween::new(Duration::from_millis(50), AudioEasing::OutPowi(2)),
+            ..Default::default()
+        };
+        assert_eq!(round_trip(&duck), duck);
+        assert_eq!(
+            ron::from_str::<DuckSettings>("(amount: 0.8)")
+                .unwrap()
+                .release,
+            DuckSettings::default().release
+        );
+
+        let reverb = ChannelReverb {
+            mix: 0.25,
+            ..Default::default()
+        };
+        assert_eq!(round_trip(&reverb), reverb);
+    }
+}
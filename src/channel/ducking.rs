@@ -0,0 +1,177 @@
+use crate::audio::AudioTween;
+use crate::audio_output::AudioOutput;
+use crate::channel::Channel;
+use crate::instance::AudioInstance;
+use bevy::asset::Assets;
+use bevy::ecs::change_detection::{NonSendMut, ResMut};
+use bevy::ecs::system::Res;
+use bevy::time::Time;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How a channel ducks while another channel plays, see [`AudioControl::duck_by`](crate::AudioControl::duck_by)
+///
+/// With the `serde` feature, the settings can be (de)serialized. Missing fields keep their default.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct DuckSettings {
+    /// How much the volume is reduced, from `0.0` (not at all) to `1.0` (silent)
+    ///
+    /// Default is `0.5`.
+    pub amount: f64,
+    /// Fade to the reduced volume
+    ///
+    /// Default is a linear fade over 0.1 seconds.
+    pub attack: AudioTween,
+    /// Fade back to the full volume
+    ///
+    /// Default is a linear fade over 0.5 seconds.
+    pub release: AudioTween,
+    /// Instances of the trigger channel with a lower volume (as amplitude) don't cause ducking
+    ///
+    /// Default is `0.01`.
+    pub threshold: f64,
+}
+
+impl DuckSettings {
+    /// The factor of the volume while ducked with these settings
+    pub(crate) fn factor(&self) -> f64 {
+        1. - self.amount.clamp(0., 1.)
+    }
+}
+
+impl Default for DuckSettings {
+    fn default() -> Self {
+        DuckSettings {
+            amount: 0.5,
+            attack: AudioTween::linear(Duration::from_millis(100)),
+            release: AudioTween::linear(Duration::from_millis(500)),
+            threshold: 0.01,
+        }
+    }
+}
+
+/// Ducking state of one channel
+#[derive(Default)]
+struct Ducking {
+    triggers: Vec<(Channel, DuckSettings)>,
+    /// Ducking from [`AudioControl::duck_for`](crate::AudioControl::duck_for) with the remaining time
+    manual: Option<(Duration, DuckSettings)>,
+    /// The settings the channel is currently ducked with
+    active: Option<DuckSettings>,
+}
+
+impl Ducking {
+    /// The strongest of the manual ducking and the ducking by the given active triggers
+    fn strongest(
+        &self,
+        mut is_active: impl FnMut(&Channel, &DuckSettings) -> bool,
+    ) -> Option<&DuckSettings> {
+        self.triggers
+            .iter()
+            .filter(|(trigger, settings)| is_active(trigger, settings))
+            .map(|(_, settings)| settings)
+            .chain(self.manual.as_ref().map(|(_, settings)| settings))
+            .fold(
+                None,
+                |strongest: Option<&DuckSettings>, settings| match strongest {
+                    Some(strongest) if strongest.amount >= settings.amount => Some(strongest),
+                    _ => Some(settings),
+                },
+            )
+    }
+
+    /// Nothing left to do for this channel
+    fn is_idle(&self) -> bool {
+        self.triggers.is_empty() && self.manual.is_none() && self.active.is_none()
+    }
+}
+
+/// Ducking state of all channels that duck or ducked
+#[derive(Default)]
+pub(crate) struct ChannelDucking {
+    channels: HashMap<Channel, Ducking>,
+}
+
+impl ChannelDucking {
+    /// Duck the channel while the trigger channel plays, or stop that for `None`
+    pub(crate) fn duck_by(
+        &mut self,
+        channel: &Channel,
+        trigger: &Channel,
+        settings: Option<&DuckSettings>,
+    ) {
+        let triggers = &mut self.channels.entry(channel.clone()).or_default().triggers;
+        triggers.retain(|(existing, _)| existing != trigger);
+        if let Some(settings) = settings {
+            triggers.push((trigger.clone(), settings.clone()));
+        }
+    }
+
+    /// Duck the channel for the given time, replacing an earlier [`duck_for`](Self::duck_for)
+    pub(crate) fn duck_for(
+        &mut self,
+        channel: &Channel,
+        duration: Duration,
+        settings: DuckSettings,
+    ) {
+        self.channels.entry(channel.clone()).or_default().manual = Some((duration, settings));
+    }
+
+    /// Forget a removed channel, both as ducked channel and as trigger
+    pub(crate) fn remove(&mut self, channel: &Channel) {
+        self.channels.remove(channel);
+        for ducking in self.channels.values_mut() {
+            ducking.triggers.retain(|(trigger, _)| trigger != channel);
+        }
+    }
+
+    /// Count down manual ducking and find the channels whose ducking changed
+    ///
+    /// Returns the new factor of their volume with the fade to it.
+    pub(crate) fn update(
+        &mut self,
+        delta: Duration,
+        mut is_playing: impl FnMut(&Channel, &DuckSettings) -> bool,
+    ) -> Vec<(Channel, f64, AudioTween)> {
+        let mut changes = vec![];
+        for (channel, ducking) in self.channels.iter_mut() {
+            if let Some((remaining, _)) = ducking.manual.as_mut() {
+                *remaining = remaining.saturating_sub(delta);
+                if remaining.is_zero() {
+                    ducking.manual = None;
+                }
+            }
+            let strongest = ducking.strongest(&mut is_playing).cloned();
+            let previous = ducking.active.as_ref().map_or(1., DuckSettings::factor);
+            let duck = strongest.as_ref().map_or(1., DuckSettings::factor);
+            if duck < previous {
+                let attack = strongest.as_ref().map(|settings| settings.attack.clone());
+                changes.push((channel.clone(), duck, attack.unwrap_or_default()));
+            } else if duck > previous {
+                let release = ducking
+                    .active
+                    .as_ref()
+                    .map(|settings| settings.release.clone());
+                changes.push((channel.clone(), duck, release.unwrap_or_default()));
+            }
+            ducking.active = strongest;
+        }
+        self.channels.retain(|_, ducking| !ducking.is_idle());
+
+        changes
+    }
+}
+
+/// Duck channels while their trigger channels play and count down manual ducking
+pub(crate) fn update_ducking(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+    time: Res<Time>,
+) {
+    audio_output.update_ducking(&mut audio_instances, time.delta());
+}
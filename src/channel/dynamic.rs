@@ -1,27 +1,51 @@
 use crate::audio::{
-    AudioCommand, FadeIn, FadeOut, PlayAudioCommand, PlayAudioSettings, TweenCommand,
-    TweenCommandKind,
+    AudioCommand, AudioTween, CrossfadeCurve, FadeIn, FadeOut, PlayAudioCommand, PlayAudioSettings,
+    TweenCommand, TweenCommandKind,
 };
-use crate::channel::AudioCommandQue;
+use crate::channel::ducking::DuckSettings;
+use crate::channel::effects::{ChannelReverb, EffectChain, EffectHandles};
+use crate::channel::queue::QueueRepeat;
+use crate::channel::snapshot::ChannelMix;
+use crate::channel::{AudioCommandQue, Channel, ChannelSettings, StealPolicy};
 use crate::instance::AudioInstance;
-use crate::{AudioControl, AudioSource, PlaybackState};
+use crate::{
+    AudioBank, AudioBankError, AudioCollection, AudioControl, AudioSource, PlaybackState,
+    StreamedAudioSource,
+};
 use bevy::asset::{AssetId, Handle};
 use bevy::ecs::system::Resource;
+use bevy::log::warn;
 use bevy::utils::hashbrown::hash_map::Iter;
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, HashSet};
+use kira::sound::PlaybackRate;
 use kira::Volume;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
 
 /// A dynamic channel to play and control audio
 #[derive(Default)]
 pub struct DynamicAudioChannel {
     pub(crate) commands: RwLock<VecDeque<AudioCommand>>,
     pub(crate) states: HashMap<AssetId<AudioInstance>, PlaybackState>,
+    pub(crate) instances: Vec<Handle<AudioInstance>>,
+    muted: AtomicBool,
+    soloed: AtomicBool,
+    effects: EffectHandles,
+    mix: RwLock<ChannelMix>,
+}
+
+impl DynamicAudioChannel {
+    /// The mix the channel was last told to have
+    pub(crate) fn mix(&self) -> ChannelMix {
+        *self.mix.read()
+    }
 }
 
 impl AudioCommandQue for DynamicAudioChannel {
     fn que(&self, command: AudioCommand) {
+        self.mix.write().record(&command);
         self.commands.write().push_front(command)
     }
 }
@@ -35,10 +59,158 @@ impl AudioControl for DynamicAudioChannel {
     ///     audio.play(asset_server.load("audio.mp3"));
     /// }
     /// ```
-    fn play(&self, audio_source: Handle<AudioSource>) -> PlayAudioCommand {
+    fn play(&self, audio_source: Handle<AudioSource>) -> PlayAudioCommand<'_> {
         PlayAudioCommand::new(audio_source, self)
     }
 
+    /// Play several sounds back to back as one instance
+    ///
+    /// This is meant for phrases like dialogue lines split across files. The parts play without
+    /// gaps under a single [`AudioInstance`], so pausing, stopping or changing the volume affects
+    /// the whole phrase. Stopping it also cancels the parts that didn't start yet.
+    ///
+    /// The sequence plays once all parts are loaded, at the sample rate of the first part.
+    /// The settings of the parts, like their loop regions, are not used. Nothing plays for an
+    /// empty sequence.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    ///     let phrase = [
+    ///         asset_server.load("voice/you_found.ogg"),
+    ///         asset_server.load("voice/five.ogg"),
+    ///         asset_server.load("voice/coins.ogg"),
+    ///     ];
+    ///     let instance: Handle<AudioInstance> = audio.play_sequence(&phrase).handle();
+    /// }
+    /// ```
+    fn play_sequence(&self, audio_sources: &[Handle<AudioSource>]) -> PlayAudioCommand<'_> {
+        PlayAudioCommand::sequence(audio_sources, self)
+    }
+
+    /// Play a sound that is decoded while it plays
+    ///
+    /// Use this for long music tracks that would take long to load and a lot of memory as
+    /// an [`AudioSource`]. Streamed sounds can't play in reverse or with a negative playback rate.
+    /// See [`StreamedAudioSource`](crate::StreamedAudioSource).
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    ///     audio.play_streamed(asset_server.load("music/theme.stream.ogg")).looped();
+    /// }
+    /// ```
+    fn play_streamed(&self, audio_source: Handle<StreamedAudioSource>) -> PlayAudioCommand<'_> {
+        PlayAudioCommand::streamed(audio_source, self)
+    }
+
+    /// Play a sound of an [`AudioBank`] with the settings of the bank
+    ///
+    /// The returned command can still change the settings. If the bank has no sound with the
+    /// given name, nothing plays and [`AudioBankError::MissingSound`] is returned.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// #[derive(Resource)]
+    /// struct Sfx(Handle<AudioBank>);
+    ///
+    /// fn my_system(sfx: Res<Sfx>, banks: Res<Assets<AudioBank>>, audio: Res<Audio>) {
+    ///     let Some(bank) = banks.get(&sfx.0) else {
+    ///         return;
+    ///     };
+    ///     if let Err(error) = audio.play_from_bank(bank, "footstep_grass") {
+    ///         warn!("{}", error);
+    ///     }
+    /// }
+    /// ```
+    fn play_from_bank(
+        &self,
+        bank: &AudioBank,
+        name: &str,
+    ) -> Result<PlayAudioCommand<'_>, AudioBankError> {
+        bank.play(name, self)
+    }
+
+    /// Play a variant of an [`AudioCollection`]
+    ///
+    /// Every call picks one variant by the [`SelectionMode`](crate::SelectionMode) of the
+    /// collection. The variant is picked once the collection loaded, right before it plays.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    ///     audio.play_collection(asset_server.load("sounds/footsteps.collection.ron"));
+    /// }
+    /// ```
+    fn play_collection(&self, collection: Handle<AudioCollection>) -> PlayAudioCommand<'_> {
+        PlayAudioCommand::collection(collection, self)
+    }
+
+    /// Play audio without keeping its instance around
+    ///
+    /// This is meant for short sounds like UI blips that are never controlled after they started.
+    /// Their [`AudioInstance`] asset is removed as soon as they stopped, so spamming one-shots
+    /// doesn't grow [`Assets<AudioInstance>`](bevy::asset::Assets). A handle taken with
+    /// [`PlayAudioCommand::handle`] points to nothing once the sound stopped.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    ///     audio.play_one_shot(asset_server.load("sounds/click.ogg"));
+    /// }
+    /// ```
+    fn play_one_shot(&self, audio_source: Handle<AudioSource>) -> PlayAudioCommand<'_> {
+        let mut command = PlayAudioCommand::new(audio_source, self);
+        command.settings.one_shot = true;
+
+        command
+    }
+
+    /// Play audio, fading it in while all other audio of the channel fades out
+    ///
+    /// The new sound starts silent and fades to its volume over the given duration, while all
+    /// other playing sounds of the channel fade out and then stop. They report
+    /// [`PlaybackState::Stopping`] until then. A crossfade started during another one fades out
+    /// the half faded-in sound from its current volume, while the older sounds finish fading out.
+    ///
+    /// The fades follow [`CrossfadeCurve::EqualPower`](crate::CrossfadeCurve::EqualPower), unless
+    /// another curve is set with [`PlayAudioCommand::crossfade_curve`](crate::PlayAudioCommand::crossfade_curve).
+    /// The curve shapes fades of volumes in amplitude. Fades of volumes in decibels happen in decibels.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    /// #[derive(Resource)]
+    /// struct Music;
+    ///
+    /// fn enter_combat(asset_server: Res<AssetServer>, music: Res<AudioChannel<Music>>) {
+    ///     music
+    ///         .crossfade_to(asset_server.load("music/combat.ogg"), Duration::from_secs(2))
+    ///         .looped();
+    /// }
+    /// ```
+    fn crossfade_to(
+        &self,
+        audio_source: Handle<AudioSource>,
+        duration: Duration,
+    ) -> PlayAudioCommand<'_> {
+        let mut command = PlayAudioCommand::new(audio_source, self);
+        command.settings.crossfade = Some((duration, CrossfadeCurve::default()));
+
+        command
+    }
+
     /// Stop all audio
     ///
     /// ```
@@ -49,7 +221,7 @@ impl AudioControl for DynamicAudioChannel {
     ///     audio.stop();
     /// }
     /// ```
-    fn stop(&self) -> TweenCommand<FadeOut> {
+    fn stop(&self) -> TweenCommand<'_, FadeOut> {
         TweenCommand::new(TweenCommandKind::Stop, self)
     }
 
@@ -63,12 +235,15 @@ impl AudioControl for DynamicAudioChannel {
     ///     audio.pause();
     /// }
     /// ```
-    fn pause(&self) -> TweenCommand<FadeOut> {
+    fn pause(&self) -> TweenCommand<'_, FadeOut> {
         TweenCommand::new(TweenCommandKind::Pause, self)
     }
 
     /// Resume all audio
     ///
+    /// Without a tween of its own, the resume fades with the
+    /// [default resume tween](Self::set_default_resume_tween) of the channel.
+    ///
     /// ```
     /// # use bevy::prelude::*;
     /// # use bevy_kira_audio::prelude::*;
@@ -77,24 +252,140 @@ impl AudioControl for DynamicAudioChannel {
     ///     audio.resume();
     /// }
     /// ```
-    fn resume(&self) -> TweenCommand<FadeIn> {
+    fn resume(&self) -> TweenCommand<'_, FadeIn> {
         TweenCommand::new(TweenCommandKind::Resume, self)
     }
 
+    /// Fade all audio out and pause it once it is silent
+    ///
+    /// The channel counts as paused as soon as the fade starts: sounds played during the fade,
+    /// or later, start paused and only play after the channel resumes.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(ambience: Res<Audio>) {
+    ///     ambience.pause_with_fade(AudioTween::linear(Duration::from_millis(500)));
+    /// }
+    /// ```
+    fn pause_with_fade(&self, tween: AudioTween) {
+        self.que(AudioCommand::Pause(Some(tween)));
+    }
+
+    /// Resume all audio and fade it back in, see [`pause_with_fade`](Self::pause_with_fade)
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(ambience: Res<Audio>) {
+    ///     ambience.resume_with_fade(AudioTween::linear(Duration::from_millis(500)));
+    /// }
+    /// ```
+    fn resume_with_fade(&self, tween: AudioTween) {
+        self.que(AudioCommand::Resume(Some(tween)));
+    }
+
+    /// Fade in with the given tween when audio is resumed without a tween of its own
+    ///
+    /// This applies to [`resume`](Self::resume) on the channel and to
+    /// [`AudioInstance::resume_with_default_fade`] on its instances, for example to resume
+    /// softly after a pause menu. Explicit tweens like the one of
+    /// [`resume_with_fade`](Self::resume_with_fade) are used as given.
+    /// A tween with a duration of zero restores the default behavior.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.set_default_resume_tween(AudioTween::linear(Duration::from_millis(300)));
+    /// }
+    /// ```
+    fn set_default_resume_tween(&self, tween: AudioTween) {
+        self.que(AudioCommand::SetResumeTween(tween));
+    }
+
+    /// Pause all audio now and resume it after the given time
+    ///
+    /// Both the pause and the resume fade with the given tween. The resume is scheduled in the
+    /// plugin, so nothing has to be done once the time is up. A manual [`resume`](Self::resume)
+    /// or [`pause`](Self::pause) before that cancels the scheduled resume, and another
+    /// `pause_for` replaces it.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.pause_for(Duration::from_secs(2), AudioTween::linear(Duration::from_millis(200)));
+    /// }
+    /// ```
+    fn pause_for(&self, duration: Duration, tween: AudioTween) {
+        self.que(AudioCommand::PauseFor(duration, tween));
+    }
+
+    /// Set the playback position of all instances in the channel to the given time in seconds
+    ///
+    /// Instances that don't loop stop when seeking outside of their sound, like when reaching its
+    /// end. Looping instances wrap around into their loop region. A queued next sound is
+    /// scheduled again for the new end of the current one.
+    /// See [`AudioInstance::seek_to`] to seek a single instance.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.seek_to(30.);
+    /// }
+    /// ```
+    fn seek_to(&self, position: f64) {
+        self.que(AudioCommand::SeekTo(position));
+    }
+
+    /// Move the playback position of all instances in the channel by the given time in seconds
+    ///
+    /// Negative amounts seek backwards. See [`seek_to`](Self::seek_to) for seeking outside of
+    /// the sounds.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn skip_forward(music: Res<Audio>) {
+    ///     music.seek_by(10.);
+    /// }
+    /// ```
+    fn seek_by(&self, amount: f64) {
+        self.que(AudioCommand::SeekBy(amount));
+    }
+
     /// Set the volume
     ///
     /// The default value is 1.
     /// This method supports setting the volume in Decibels or as Amplitude.
+    /// Fades to a volume in Decibels are interpolated in Decibels, which sounds more even.
+    /// `Volume::Decibels(f64::NEG_INFINITY)` is silent.
     ///
     /// ```
     /// # use bevy::prelude::*;
     /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
     ///
     /// fn my_system(audio: Res<Audio>) {
     ///     audio.set_volume(0.5);
+    ///     audio
+    ///         .set_volume(Volume::Decibels(-12.))
+    ///         .fade_in(AudioTween::linear(Duration::from_secs(2)));
     /// }
     /// ```
-    fn set_volume(&self, volume: impl Into<Volume>) -> TweenCommand<FadeIn> {
+    fn set_volume(&self, volume: impl Into<Volume>) -> TweenCommand<'_, FadeIn> {
         TweenCommand::new(TweenCommandKind::SetVolume(volume.into()), self)
     }
     /// Set panning
@@ -111,7 +402,7 @@ impl AudioControl for DynamicAudioChannel {
     ///     audio.set_panning(0.9);
     /// }
     /// ```
-    fn set_panning(&self, panning: f64) -> TweenCommand<FadeIn> {
+    fn set_panning(&self, panning: f64) -> TweenCommand<'_, FadeIn> {
         TweenCommand::new(TweenCommandKind::SetPanning(panning), self)
     }
     /// Set playback rate
@@ -126,8 +417,332 @@ impl AudioControl for DynamicAudioChannel {
     ///     audio.set_playback_rate(2.0);
     /// }
     /// ```
-    fn set_playback_rate(&self, playback_rate: f64) -> TweenCommand<FadeIn> {
-        TweenCommand::new(TweenCommandKind::SetPlaybackRate(playback_rate), self)
+    fn set_playback_rate(&self, playback_rate: f64) -> TweenCommand<'_, FadeIn> {
+        TweenCommand::new(
+            TweenCommandKind::SetPlaybackRate(PlaybackRate::Factor(playback_rate)),
+            self,
+        )
+    }
+
+    /// Set the pitch in semitones
+    ///
+    /// This sets the playback rate to `2^(semitones / 12)`. Tweens interpolate in semitones, so
+    /// glides sound even. The current playback rate has to be positive for that.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio
+    ///         .set_pitch_semitones(-12.)
+    ///         .fade_in(AudioTween::linear(Duration::from_secs(1)));
+    /// }
+    /// ```
+    fn set_pitch_semitones(&self, semitones: f64) -> TweenCommand<'_, FadeIn> {
+        TweenCommand::new(
+            TweenCommandKind::SetPlaybackRate(PlaybackRate::Semitones(semitones)),
+            self,
+        )
+    }
+
+    /// Limit the number of instances that play in this channel at the same time
+    ///
+    /// The limit is checked for every new sound, including multiple sounds played in the same frame.
+    /// If the channel is full, the `policy` decides whether the new sound is not played or an
+    /// older instance is stopped to make room. Stopped instances count as [`PlaybackState::Stopped`].
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.set_instance_limit(8, StealPolicy::StopOldest);
+    /// }
+    /// ```
+    fn set_instance_limit(&self, limit: usize, policy: StealPolicy) {
+        self.que(AudioCommand::SetInstanceLimit(Some((limit, policy))));
+    }
+
+    /// Remove the instance limit set with [`set_instance_limit`](Self::set_instance_limit)
+    fn remove_instance_limit(&self) {
+        self.que(AudioCommand::SetInstanceLimit(None));
+    }
+
+    /// Play all sounds of this channel through a reverb
+    ///
+    /// Sounds that are already playing keep playing without the reverb.
+    /// Calling this again changes the settings of the existing reverb.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.set_reverb(ChannelReverb {
+    ///         feedback: 0.95,
+    ///         ..default()
+    ///     });
+    /// }
+    /// ```
+    fn set_reverb(&self, reverb: ChannelReverb) {
+        self.que(AudioCommand::SetReverb(reverb));
+    }
+
+    /// Set the balance between the dry signal (`0.0`) and the reverberation (`1.0`)
+    ///
+    /// This does nothing if the channel has no reverb, see [`set_reverb`](Self::set_reverb).
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio
+    ///         .set_reverb_mix(0.8)
+    ///         .linear_fade_in(Duration::from_secs(1));
+    /// }
+    /// ```
+    fn set_reverb_mix(&self, mix: f64) -> TweenCommand<'_, FadeIn> {
+        TweenCommand::new(TweenCommandKind::SetReverbMix(mix), self)
+    }
+
+    /// Send the sounds of this channel to the effects of another channel, like an aux send
+    ///
+    /// The `target` channel needs effects, see [`set_reverb`](Self::set_reverb) and
+    /// [`EffectChain`](crate::EffectChain). The `level` scales the sent signal (`1.0` sends it
+    /// unchanged), and the dry signal of this channel is not affected. Calling this again for the
+    /// same target tweens to the new level. Sounds that started before a new target was added
+    /// keep their previous sends.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use bevy_kira_audio::Channel;
+    /// #[derive(Resource)]
+    /// struct SharedReverb;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.add_send(Channel::typed::<SharedReverb>(), 0.3);
+    /// }
+    /// ```
+    fn add_send(&self, target: Channel, level: f64) -> TweenCommand<'_, FadeIn> {
+        TweenCommand::new(TweenCommandKind::SetSend(target, level), self)
+    }
+
+    /// Fade out the send to `target`, see [`add_send`](Self::add_send)
+    fn remove_send(&self, target: Channel) -> TweenCommand<'_, FadeOut> {
+        TweenCommand::new(TweenCommandKind::SetSend(target, 0.), self)
+    }
+
+    /// Lower the volume of this channel while sounds play in the `trigger` channel
+    ///
+    /// While the `trigger` channel has a playing instance that is not muted and louder than the
+    /// threshold, this channel fades to `1.0 - amount` times its volume with the attack tween.
+    /// Once the trigger channel is quiet, it fades back with the release tween. Calling this again
+    /// for the same trigger replaces its settings. If several triggers are active, the strongest wins.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use bevy_kira_audio::Channel;
+    /// #[derive(Resource)]
+    /// struct Voice;
+    ///
+    /// fn my_system(music: Res<Audio>) {
+    ///     music.duck_by(
+    ///         Channel::typed::<Voice>(),
+    ///         DuckSettings {
+    ///             amount: 0.7,
+    ///             ..default()
+    ///         },
+    ///     );
+    /// }
+    /// ```
+    fn duck_by(&self, trigger: Channel, settings: DuckSettings) {
+        self.que(AudioCommand::DuckBy(trigger, Some(settings)));
+    }
+
+    /// Stop ducking this channel while sounds play in `trigger`, see [`duck_by`](Self::duck_by)
+    fn stop_ducking_by(&self, trigger: Channel) {
+        self.que(AudioCommand::DuckBy(trigger, None));
+    }
+
+    /// Duck this channel for the given duration, see [`duck_by`](Self::duck_by)
+    ///
+    /// Calling this again before the duration ran out restarts it with the new settings.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    ///
+    /// fn my_system(music: Res<Audio>) {
+    ///     music.duck_for(Duration::from_secs(2), DuckSettings::default());
+    /// }
+    /// ```
+    fn duck_for(&self, duration: Duration, settings: DuckSettings) {
+        self.que(AudioCommand::DuckFor(duration, settings));
+    }
+
+    /// Play new sounds of this channel on the output device with the given name
+    ///
+    /// Use [`output_device_names`](crate::output_device_names) to list the devices. If the device
+    /// can't be opened, an error is logged and the channel keeps playing on the default device.
+    /// Sounds that are already playing stay on their device.
+    ///
+    /// Sounds on other devices play outside of the mixer of the default device, so they don't use
+    /// parent channels, channel effects, sends, low pass filters, ambient sends or
+    /// [`SpacialBackend::Kira`](crate::SpacialBackend::Kira). Volume, panning, playback rate,
+    /// muting and ducking work as usual.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// #[derive(Resource)]
+    /// struct Cue;
+    ///
+    /// fn my_system(cue: Res<AudioChannel<Cue>>) {
+    ///     cue.set_output_device("Headphones");
+    /// }
+    /// ```
+    fn set_output_device(&self, device: impl Into<String>) {
+        self.que(AudioCommand::SetOutputDevice(Some(device.into())));
+    }
+
+    /// Play new sounds of this channel on the default output device again
+    ///
+    /// See [`set_output_device`](Self::set_output_device).
+    fn reset_output_device(&self) {
+        self.que(AudioCommand::SetOutputDevice(None));
+    }
+
+    /// Add a sound to the queue of this channel
+    ///
+    /// Queued sounds play one after the other. Each next sound is scheduled on the
+    /// [`AudioClock`](crate::AudioClock) for the end of the current one, so there is no gap
+    /// between them. The first sound starts shortly after it was enqueued and loaded.
+    /// A [`QueueAdvanced`](crate::QueueAdvanced) event is sent whenever the next sound starts.
+    ///
+    /// Queued sounds play with the settings of the channel. A sound that loops plays until it is
+    /// skipped. Pausing the channel holds the queue, but the transition after resuming it is
+    /// only as exact as the position of the current sound.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// #[derive(Resource)]
+    /// struct Music;
+    ///
+    /// fn start_playlist(asset_server: Res<AssetServer>, music: Res<AudioChannel<Music>>) {
+    ///     music.enqueue(asset_server.load("music/intro.ogg"));
+    ///     music.enqueue(asset_server.load("music/loop.ogg"));
+    ///     music.set_queue_repeat(QueueRepeat::All);
+    /// }
+    /// ```
+    fn enqueue(&self, audio_source: Handle<AudioSource>) {
+        self.que(AudioCommand::Enqueue(audio_source));
+    }
+
+    /// Stop the current sound of the queue and start the next one
+    ///
+    /// This also leaves a sound behind that repeats with [`QueueRepeat::One`](crate::QueueRepeat::One).
+    fn skip_queued(&self) {
+        self.que(AudioCommand::SkipQueued);
+    }
+
+    /// Remove all upcoming sounds from the queue
+    ///
+    /// The current sound plays on. [`stop`](Self::stop) clears the queue as well and stops the
+    /// current sound.
+    fn clear_queue(&self) {
+        self.que(AudioCommand::ClearQueue);
+    }
+
+    /// Set what the queue does with the sounds it played
+    ///
+    /// See [`enqueue`](Self::enqueue).
+    fn set_queue_repeat(&self, repeat: QueueRepeat) {
+        self.que(AudioCommand::SetQueueRepeat(repeat));
+    }
+
+    /// Mute all audio
+    ///
+    /// The channel keeps its volume, including volume changes while it is muted.
+    /// [`unmute`](Self::unmute) fades back to that volume.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.mute();
+    /// }
+    /// ```
+    fn mute(&self) -> TweenCommand<'_, FadeOut> {
+        self.muted.store(true, Ordering::Relaxed);
+        TweenCommand::new(TweenCommandKind::SetMuted(true), self)
+    }
+
+    /// Unmute all audio
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.unmute();
+    /// }
+    /// ```
+    fn unmute(&self) -> TweenCommand<'_, FadeIn> {
+        self.muted.store(false, Ordering::Relaxed);
+        TweenCommand::new(TweenCommandKind::SetMuted(false), self)
+    }
+
+    /// Solo this channel
+    ///
+    /// While any channel is soloed, all channels that are not soloed are muted.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.solo();
+    /// }
+    /// ```
+    fn solo(&self) -> TweenCommand<'_, FadeOut> {
+        self.soloed.store(true, Ordering::Relaxed);
+        TweenCommand::new(TweenCommandKind::SetSolo(true), self)
+    }
+
+    /// Stop soloing this channel
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(audio: Res<Audio>) {
+    ///     audio.unsolo();
+    /// }
+    /// ```
+    fn unsolo(&self) -> TweenCommand<'_, FadeIn> {
+        self.soloed.store(false, Ordering::Relaxed);
+        TweenCommand::new(TweenCommandKind::SetSolo(false), self)
+    }
+
+    /// Returns `true` if the channel was muted with [`mute`](Self::mute)
+    ///
+    /// Channels silenced by the solo of another channel are not muted.
+    fn is_muted(&self) -> bool {
+        self.muted.load(Ordering::Relaxed)
+    }
+
+    /// Returns `true` if the channel was soloed with [`solo`](Self::solo)
+    fn is_soloed(&self) -> bool {
+        self.soloed.load(Ordering::Relaxed)
     }
 
     /// Get state for a playback instance.
@@ -142,8 +757,7 @@ impl AudioControl for DynamicAudioChannel {
                     .find(|command| match command {
                         AudioCommand::Play(PlayAudioSettings {
                             instance_handle: handle,
-                            settings: _,
-                            source: _,
+                            ..
                         }) => handle.id() == instance_handle.id(),
                         _ => false,
                     })
@@ -166,6 +780,43 @@ impl AudioControl for DynamicAudioChannel {
                 _ => playing,
             })
     }
+
+    /// The number of instances in this channel that are `Playing`, `Pausing`, or `Stopping`
+    ///
+    /// Like [`state`](Self::state), this reflects the states as of the last run of the audio
+    /// systems in `PreUpdate`, so it can be slightly stale. Sounds played this frame are not counted yet.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    ///
+    /// fn my_system(asset_server: Res<AssetServer>, music: Res<Audio>) {
+    ///     if music.playing_count() == 0 {
+    ///         music.play(asset_server.load("music.ogg"));
+    ///     }
+    /// }
+    /// ```
+    fn playing_count(&self) -> usize {
+        self.states
+            .values()
+            .filter(|state| {
+                matches!(
+                    state,
+                    PlaybackState::Playing { .. }
+                        | PlaybackState::Pausing { .. }
+                        | PlaybackState::Stopping { .. }
+                )
+            })
+            .count()
+    }
+
+    /// Handles of all instances in this channel that have not stopped
+    ///
+    /// Use [`state`](Self::state) to get the state of each instance.
+    /// Like the states, the list is updated by the audio systems in `PreUpdate` and can be slightly stale.
+    fn instances(&self) -> Vec<Handle<AudioInstance>> {
+        self.instances.clone()
+    }
 }
 
 /// Resource to play and control audio in dynamic channels
@@ -175,6 +826,11 @@ impl AudioControl for DynamicAudioChannel {
 #[derive(Resource, Default)]
 pub struct DynamicAudioChannels {
     pub(crate) channels: HashMap<String, DynamicAudioChannel>,
+    /// Removed channels and the tween to stop their sounds with
+    pub(crate) removed: Vec<(String, Option<AudioTween>)>,
+    removed_keys: HashSet<String>,
+    /// Handed out for removed keys, its commands are dropped
+    pub(crate) detached: DynamicAudioChannel,
 }
 
 impl DynamicAudioChannels {
@@ -185,6 +841,7 @@ impl DynamicAudioChannels {
         if self.is_channel(key) {
             self.remove_channel(key);
         }
+        self.removed_keys.remove(key);
         self.channels
             .insert(key.to_owned(), DynamicAudioChannel::default());
         self.channels
@@ -192,15 +849,98 @@ impl DynamicAudioChannels {
             .expect("Failed to retrieve dynamic audio channel")
     }
 
+    /// Creates and returns an audio channel for the given key that starts with the given settings
+    ///
+    /// The settings are applied before anything is played in the channel, so there are no
+    /// transitions from the default settings.
+    /// If there already is a channel with the given key, it will be stopped and removed.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// fn setup(mut channels: ResMut<DynamicAudioChannels>, asset_server: Res<AssetServer>) {
+    ///     channels
+    ///         .create_channel_with_settings(
+    ///             "ambience",
+    ///             ChannelSettings {
+    ///                 volume: Volume::Amplitude(0.3),
+    ///                 ..default()
+    ///             },
+    ///         )
+    ///         .play(asset_server.load("sounds/loop.ogg"));
+    /// }
+    /// ```
+    pub fn create_channel_with_settings(
+        &mut self,
+        key: &str,
+        settings: ChannelSettings,
+    ) -> &DynamicAudioChannel {
+        let channel = self.create_channel(key);
+        channel.que(AudioCommand::ApplySettings(settings));
+
+        channel
+    }
+
+    /// Creates and returns an audio channel for the given key that plays all sounds through a chain of effects
+    ///
+    /// The handles of the effects are available through [`effects_mut`](Self::effects_mut).
+    /// If there already is a channel with the given key, it will be stopped and removed.
+    pub fn create_channel_with_effects(
+        &mut self,
+        key: &str,
+        effects: EffectChain,
+    ) -> &DynamicAudioChannel {
+        self.create_channel(key);
+        let channel = self
+            .channels
+            .get_mut(key)
+            .expect("Failed to retrieve dynamic audio channel");
+        channel.effects = effects.handles;
+        channel.que(AudioCommand::SetEffects(Mutex::new(Some(effects.track))));
+
+        channel
+    }
+
+    /// Handles of the effects of the channel behind the given key
+    ///
+    /// See [`create_channel_with_effects`](Self::create_channel_with_effects).
+    pub fn effects_mut(&mut self, key: &str) -> Option<&mut EffectHandles> {
+        self.channels
+            .get_mut(key)
+            .map(|channel| &mut channel.effects)
+    }
+
     /// Remove the channel behind the given key
     ///
-    /// All audio in the channel will be stopped before it is removed.
+    /// All audio in the channel will be stopped before it is removed, and the key can be
+    /// used for a new channel right away. Commands to the removed channel are ignored.
     /// This method will do nothing if there is no channel for the given key.
     pub fn remove_channel(&mut self, key: &str) {
-        if let Some(channel) = self.get_channel(key) {
-            channel.stop();
+        self.remove(key, None);
+    }
+
+    /// Remove the channel behind the given key after fading out its audio
+    ///
+    /// See [`remove_channel`](Self::remove_channel).
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use std::time::Duration;
+    /// fn leave_level(mut channels: ResMut<DynamicAudioChannels>) {
+    ///     channels.remove_channel_with_fade(
+    ///         "level_ambience",
+    ///         AudioTween::linear(Duration::from_secs(2)),
+    ///     );
+    /// }
+    /// ```
+    pub fn remove_channel_with_fade(&mut self, key: &str, tween: AudioTween) {
+        self.remove(key, Some(tween));
+    }
+
+    fn remove(&mut self, key: &str, tween: Option<AudioTween>) {
+        if self.channels.remove(key).is_some() {
+            self.removed.push((key.to_owned(), tween));
+            self.removed_keys.insert(key.to_owned());
         }
-        self.channels.remove(key);
     }
 
     /// Checks if there is a channel available for the given key.
@@ -210,11 +950,18 @@ impl DynamicAudioChannels {
 
     /// Get a channel to play and control audio in
     ///
+    /// For a key whose channel was [removed](Self::remove_channel), this logs a warning and
+    /// returns a channel that ignores all commands.
+    ///
     /// # Panics
-    /// This method will panic if there is no channel for the given key.
+    /// This method will panic if there is no channel for the given key, and there never was one.
     /// If you aren't sure that there is one, you can check with [`is_channel`](Self::is_channel),
     /// or use [`get_channel`](Self::get_channel) instead.
     pub fn channel(&self, key: &str) -> &DynamicAudioChannel {
+        if self.removed_keys.contains(key) {
+            warn!("Dynamic audio channel '{key}' was removed, commands to it are ignored.");
+            return &self.detached;
+        }
         self.channels.get(key).unwrap_or_else(|| {
             panic!("Attempting to access dynamic audio channel '{key:?}', which doesn't exist.")
         })
@@ -226,13 +973,14 @@ impl DynamicAudioChannels {
     }
 
     /// An iterator over the keys and dynamic audio channels
-    pub fn iter(&self) -> Iter<String, DynamicAudioChannel> {
+    pub fn iter(&self) -> Iter<'_, String, DynamicAudioChannel> {
         self.channels.iter()
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::audio::AudioTween;
     use crate::channel::dynamic::DynamicAudioChannels;
     use crate::channel::*;
     use bevy::asset::AssetId;
@@ -252,6 +1000,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn removed_keys_ignore_commands_until_recreated() {
+        let mut audio = DynamicAudioChannels::default();
+        audio.create_channel("level");
+        audio.remove_channel_with_fade("level", AudioTween::default());
+        assert!(!audio.is_channel("level"));
+        assert_eq!(audio.removed.len(), 1);
+
+        audio.channel("level").play(Handle::default());
+        assert_eq!(audio.detached.commands.read().len(), 1);
+
+        audio.create_channel("level").play(Handle::default());
+        assert_eq!(audio.channel("level").commands.read().len(), 1);
+    }
+
+    #[test]
+    fn settings_are_queued_before_the_first_command() {
+        let mut audio = DynamicAudioChannels::default();
+        let settings = ChannelSettings {
+            panning: 0.2,
+            paused: true,
+            ..Default::default()
+        };
+        audio
+            .create_channel_with_settings("test", settings)
+            .play(Handle::default());
+
+        let commands = audio.channel("test").commands.read();
+        assert_eq!(commands.len(), 2);
+        match commands.back() {
+            Some(AudioCommand::ApplySettings(applied)) => assert_eq!(*applied, settings),
+            _ => panic!("Expected the settings to be applied first"),
+        }
+    }
+
     #[test]
     fn state_is_stopped_if_command_is_not_queued_and_id_not_in_state_map() {
         let mut audio = DynamicAudioChannels::default();
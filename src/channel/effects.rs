@@ -0,0 +1,275 @@
+use bevy::ecs::system::Resource;
+use kira::track::effect::reverb::ReverbBuilder;
+use kira::track::effect::EffectBuilder;
+use kira::track::TrackBuilder;
+use std::any::Any;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+
+/// Settings of a reverb that all sounds of a channel play through
+///
+/// See [`AudioControl::set_reverb`](crate::AudioControl::set_reverb).
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ChannelReverb {
+    /// How much the room reverberates, from `0.0` to `1.0`
+    ///
+    /// Default is `0.9`.
+    pub feedback: f64,
+    /// How quickly high frequencies disappear from the reverberation, from `0.0` to `1.0`
+    ///
+    /// Default is `0.1`.
+    pub damping: f64,
+    /// Stereo width of the reverberation, from `0.0` (mono) to `1.0`
+    ///
+    /// Default is `1.0`.
+    pub stereo_width: f64,
+    /// Balance between the dry signal (`0.0`) and the reverberation (`1.0`)
+    ///
+    /// Default is `0.5`.
+    pub mix: f64,
+}
+
+impl Default for ChannelReverb {
+    fn default() -> Self {
+        ChannelReverb {
+            feedback: 0.9,
+            damping: 0.1,
+            stereo_width: 1.,
+            mix: 0.5,
+        }
+    }
+}
+
+impl From<ChannelReverb> for ReverbBuilder {
+    fn from(reverb: ChannelReverb) -> Self {
+        ReverbBuilder::new()
+            .feedback(reverb.feedback)
+            .damping(reverb.damping)
+            .stereo_width(reverb.stereo_width)
+            .mix(reverb.mix)
+    }
+}
+
+/// An ordered chain of kira effects that all sounds of a channel play through
+///
+/// The handles of the effects end up in [`ChannelEffects<T>`] for typed channels,
+/// or in [`DynamicAudioChannels::effects_mut`](crate::DynamicAudioChannels::effects_mut).
+/// They are indexed in the order the effects were added.
+/// ```
+/// # use bevy_kira_audio::prelude::*;
+/// use kira::track::effect::compressor::CompressorBuilder;
+/// use kira::track::effect::delay::DelayBuilder;
+/// use kira::track::effect::filter::FilterBuilder;
+///
+/// let chain = EffectChain::new()
+///     .with(FilterBuilder::new().cutoff(2_000.))
+///     .with(DelayBuilder::new())
+///     .with(CompressorBuilder::new());
+/// ```
+#[derive(Default)]
+pub struct EffectChain {
+    pub(crate) track: TrackBuilder,
+    pub(crate) handles: EffectHandles,
+}
+
+impl EffectChain {
+    /// An empty effect chain
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an effect at the end of the chain
+    pub fn with<B: EffectBuilder>(mut self, builder: B) -> Self
+    where
+        B::Handle: Send + Sync + 'static,
+    {
+        let handle = self.track.add_effect(builder);
+        self.handles.handles.push(Box::new(handle));
+
+        self
+    }
+}
+
+/// Handles to control the effects of an [`EffectChain`] at runtime
+#[derive(Default)]
+pub struct EffectHandles {
+    handles: Vec<Box<dyn Any + Send + Sync>>,
+}
+
+impl EffectHandles {
+    /// The handle of the effect at `index`, if it has the type `H`
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// use kira::track::effect::filter::FilterHandle;
+    /// use kira::tween::Tween;
+    ///
+    /// #[derive(Resource)]
+    /// struct Sfx;
+    ///
+    /// fn slow_motion(mut effects: ResMut<ChannelEffects<Sfx>>) {
+    ///     if let Some(filter) = effects.get_mut::<FilterHandle>(0) {
+    ///         filter.set_cutoff(500., Tween::default()).ok();
+    ///     }
+    /// }
+    /// ```
+    pub fn get_mut<H: 'static>(&mut self, index: usize) -> Option<&mut H> {
+        self.handles
+            .get_mut(index)
+            .and_then(|handle| handle.downcast_mut())
+    }
+
+    /// The number of effects in the chain
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Returns `true` if the chain has no effects
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+}
+
+/// Handles of the effects of the typed channel [`AudioChannel<T>`](crate::AudioChannel)
+///
+/// Inserted by [`add_audio_channel_with_effects`](crate::AudioApp::add_audio_channel_with_effects).
+#[derive(Resource)]
+pub struct ChannelEffects<T> {
+    handles: EffectHandles,
+    _marker: PhantomData<T>,
+}
+
+impl<T> ChannelEffects<T> {
+    pub(crate) fn new(handles: EffectHandles) -> Self {
+        ChannelEffects {
+            handles,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Deref for ChannelEffects<T> {
+    type Target = EffectHandles;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handles
+    }
+}
+
+impl<T> DerefMut for ChannelEffects<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.handles
+    }
+}
+
+/// Effects on the main track, that all audio of the plugin plays through
+///
+/// Insert the resource before adding the [`AudioPlugin`](crate::AudioPlugin), it is consumed by
+/// it. The chain is built again for every new output device, with fresh handles in
+/// [`MainEffects`]. Without this resource, the main track has no effects.
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// use kira::track::effect::compressor::CompressorBuilder;
+/// use kira::track::effect::filter::FilterBuilder;
+///
+/// App::new()
+///     .insert_resource(MainTrackEffects::new(|| {
+///         EffectChain::new()
+///             // A soft limiter, so stacked sounds don't clip
+///             .with(CompressorBuilder::new().threshold(-6.).ratio(20.))
+///             // Opens up fully, close it for muffled moments
+///             .with(FilterBuilder::new().cutoff(20_000.))
+///     }))
+///     .add_plugins((DefaultPlugins, AudioPlugin::default()));
+/// ```
+#[derive(Resource)]
+pub struct MainTrackEffects {
+    build: Box<dyn Fn() -> EffectChain + Send + Sync>,
+}
+
+impl MainTrackEffects {
+    /// Build the effects of the main track with the given function
+    pub fn new(build: impl Fn() -> EffectChain + Send + Sync + 'static) -> Self {
+        MainTrackEffects {
+            build: Box::new(build),
+        }
+    }
+
+    pub(crate) fn build(&self) -> EffectChain {
+        (self.build)()
+    }
+}
+
+/// Handles of the [`MainTrackEffects`], to change them at runtime
+///
+/// The handles are replaced when the output device changes, which also resets the effects to
+/// their initial settings.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// use kira::track::effect::filter::FilterHandle;
+/// use kira::tween::Tween;
+/// use std::time::Duration;
+///
+/// fn stunned(mut effects: ResMut<MainEffects>) {
+///     if let Some(filter) = effects.get_mut::<FilterHandle>(1) {
+///         let tween = Tween {
+///             duration: Duration::from_millis(300),
+///             ..default()
+///         };
+///         filter.set_cutoff(400., tween).ok();
+///     }
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub struct MainEffects {
+    handles: EffectHandles,
+}
+
+impl MainEffects {
+    pub(crate) fn new(handles: EffectHandles) -> Self {
+        MainEffects { handles }
+    }
+}
+
+impl Deref for MainEffects {
+    type Target = EffectHandles;
+
+    fn deref(&self) -> &Self::Target {
+        &self.handles
+    }
+}
+
+impl DerefMut for MainEffects {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.handles
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kira::track::effect::delay::{DelayBuilder, DelayHandle};
+    use kira::track::effect::filter::{FilterBuilder, FilterHandle};
+
+    #[test]
+    fn handles_are_indexed_in_chain_order() {
+        let mut handles = EffectChain::new()
+            .with(FilterBuilder::new())
+            .with(DelayBuilder::new())
+            .handles;
+
+        assert_eq!(handles.len(), 2);
+        assert!(handles.get_mut::<FilterHandle>(0).is_some());
+        assert!(handles.get_mut::<DelayHandle>(0).is_none());
+        assert!(handles.get_mut::<DelayHandle>(1).is_some());
+        assert!(handles.get_mut::<DelayHandle>(2).is_none());
+    }
+}
@@ -0,0 +1,162 @@
+use crate::channel::Channel;
+use bevy::ecs::system::Resource;
+use bevy::utils::HashMap;
+use thiserror::Error;
+
+/// Parent relationships between audio channels
+///
+/// The volume of a parent channel scales all sounds in its child channels, including sounds
+/// that are already playing. This allows bus layouts like `Master → Sfx → Footsteps`.
+/// Panning, playback rate and pausing are not inherited.
+///
+/// Parents only affect sounds that start playing after they were set.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// # use bevy_kira_audio::Channel;
+/// #[derive(Resource)]
+/// struct Sfx;
+/// #[derive(Resource)]
+/// struct Footsteps;
+///
+/// fn setup(mut hierarchy: ResMut<ChannelHierarchy>) {
+///     hierarchy
+///         .set_parent(Channel::typed::<Footsteps>(), Channel::typed::<Sfx>())
+///         .unwrap();
+/// }
+/// ```
+#[derive(Resource, Default, Debug)]
+pub struct ChannelHierarchy {
+    parents: HashMap<Channel, Channel>,
+}
+
+/// Errors that can occur when changing the [`ChannelHierarchy`]
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum ChannelHierarchyError {
+    /// The parent is the channel itself or one of its descendants
+    #[error("setting the parent of {child:?} to {parent:?} would create a cycle")]
+    Cycle {
+        /// The channel that should get a parent
+        child: Channel,
+        /// The requested parent
+        parent: Channel,
+    },
+}
+
+impl ChannelHierarchy {
+    /// Make `parent` the parent channel of `child`
+    ///
+    /// Replaces a previous parent of `child`. Returns [`ChannelHierarchyError::Cycle`] and
+    /// keeps the hierarchy unchanged if `parent` is `child` or one of its descendants.
+    pub fn set_parent(
+        &mut self,
+        child: Channel,
+        parent: Channel,
+    ) -> Result<(), ChannelHierarchyError> {
+        if self.ancestors(&parent).any(|ancestor| *ancestor == child) {
+            return Err(ChannelHierarchyError::Cycle { child, parent });
+        }
+        self.parents.insert(child, parent);
+
+        Ok(())
+    }
+
+    /// Remove the parent of `child`
+    pub fn remove_parent(&mut self, child: &Channel) -> Option<Channel> {
+        self.parents.remove(child)
+    }
+
+    /// Remove the parent of `channel` and detach all of its children
+    ///
+    /// Returns `true` if the hierarchy changed.
+    pub fn remove_channel(&mut self, channel: &Channel) -> bool {
+        let len = self.parents.len();
+        self.parents
+            .retain(|child, parent| child != channel && parent != channel);
+
+        self.parents.len() != len
+    }
+
+    /// The parent of `child`
+    pub fn parent(&self, child: &Channel) -> Option<&Channel> {
+        self.parents.get(child)
+    }
+
+    /// The channel itself followed by its parent, grandparent and so on
+    pub fn ancestors<'a>(&'a self, channel: &'a Channel) -> impl Iterator<Item = &'a Channel> {
+        std::iter::successors(Some(channel), |channel| self.parents.get(*channel))
+    }
+
+    /// The direct children of `parent`
+    pub(crate) fn children(&self, parent: &Channel) -> Vec<Channel> {
+        self.parents
+            .iter()
+            .filter(|(_, channel_parent)| *channel_parent == parent)
+            .map(|(child, _)| child.clone())
+            .collect()
+    }
+
+    /// All channels that have children, ordered so that parents come before their children
+    pub(crate) fn parents_in_order(&self) -> Vec<&Channel> {
+        let mut ordered: Vec<&Channel> = vec![];
+        for parent in self.parents.values() {
+            let mut chain: Vec<_> = self.ancestors(parent).collect();
+            chain.reverse();
+            for channel in chain {
+                if !ordered.contains(&channel) {
+                    ordered.push(channel);
+                }
+            }
+        }
+
+        ordered
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Master;
+    struct Sfx;
+    struct Footsteps;
+
+    #[test]
+    fn rejects_cycles() {
+        let mut hierarchy = ChannelHierarchy::default();
+        hierarchy
+            .set_parent(Channel::typed::<Sfx>(), Channel::typed::<Master>())
+            .unwrap();
+        hierarchy
+            .set_parent(Channel::typed::<Footsteps>(), Channel::typed::<Sfx>())
+            .unwrap();
+
+        assert_eq!(
+            hierarchy.set_parent(Channel::typed::<Master>(), Channel::typed::<Footsteps>()),
+            Err(ChannelHierarchyError::Cycle {
+                child: Channel::typed::<Master>(),
+                parent: Channel::typed::<Footsteps>(),
+            })
+        );
+        assert!(hierarchy
+            .set_parent(Channel::typed::<Sfx>(), Channel::typed::<Sfx>())
+            .is_err());
+        assert_eq!(hierarchy.parent(&Channel::typed::<Master>()), None);
+    }
+
+    #[test]
+    fn orders_parents_before_children() {
+        let mut hierarchy = ChannelHierarchy::default();
+        hierarchy
+            .set_parent(Channel::typed::<Footsteps>(), Channel::typed::<Sfx>())
+            .unwrap();
+        hierarchy
+            .set_parent(Channel::typed::<Sfx>(), Channel::typed::<Master>())
+            .unwrap();
+
+        assert_eq!(
+            hierarchy.parents_in_order(),
+            vec![&Channel::typed::<Master>(), &Channel::typed::<Sfx>()]
+        );
+    }
+}
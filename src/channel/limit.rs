@@ -0,0 +1,62 @@
+use crate::audio::AudioTween;
+use crate::channel::StealPolicy;
+use crate::instance::{has_stopped, AudioInstance};
+use bevy::asset::{Assets, Handle};
+use bevy::log::warn;
+
+/// Stop instances of a channel by the policy until a new one fits into the limit
+///
+/// Stopped instances are dropped from `instances`, and so are the ones stopped to make room.
+/// Returns `false` if the new sound should not be played.
+pub(crate) fn make_room(
+    instances: &mut Vec<Handle<AudioInstance>>,
+    limit: usize,
+    policy: StealPolicy,
+    audio_instances: &mut Assets<AudioInstance>,
+) -> bool {
+    instances.retain(|handle| !has_stopped(audio_instances, handle));
+    // Instances that fade out still get the commands of the channel, but leave room
+    let mut counted: Vec<Handle<AudioInstance>> = instances
+        .iter()
+        .filter(|handle| {
+            audio_instances.get(*handle).map_or(false, |instance| {
+                instance.handle.state() != kira::sound::PlaybackState::Stopping
+            })
+        })
+        .cloned()
+        .collect();
+    while counted.len() >= limit {
+        if counted.is_empty() {
+            return false;
+        }
+        let stolen = match policy {
+            StealPolicy::RejectNew => return false,
+            StealPolicy::StopOldest => 0,
+            StealPolicy::StopQuietest => counted
+                .iter()
+                .enumerate()
+                .map(|(index, handle)| {
+                    let volume = audio_instances.get(handle).map_or(0., |instance| {
+                        instance.base_volume * instance.spacial_volume
+                    });
+                    (index, volume)
+                })
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .map_or(0, |(index, _)| index),
+        };
+        // The stop only shows in the state of the instance once the audio thread handled it
+        let handle = counted.remove(stolen);
+        instances.retain(|other| *other != handle);
+        if let Some(error) = audio_instances
+            .get_mut(&handle)
+            .and_then(|instance| instance.stop(AudioTween::default()))
+        {
+            warn!(
+                "Failed to stop instance over the channel limit: {:?}",
+                error
+            );
+        }
+    }
+
+    true
+}
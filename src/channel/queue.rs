@@ -0,0 +1,487 @@
+//! Queues of sounds that a channel plays one after the other without gaps
+
+use crate::audio::{AudioTween, PartialSoundSettings};
+use crate::audio_output::AudioOutput;
+use crate::channel::Channel;
+use crate::clock::{AudioClockTime, ScheduledStart};
+use crate::instance::AudioInstance;
+use crate::source::AudioSource;
+use bevy::asset::{Assets, Handle};
+use bevy::ecs::event::Event;
+use bevy::log::warn;
+use bevy::reflect::Reflect;
+use bevy::utils::HashMap;
+use kira::manager::backend::Backend;
+use kira::sound::PlaybackState;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// What the queue of a channel does with the sounds it played
+///
+/// See [`AudioControl::enqueue`](crate::AudioControl::enqueue).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum QueueRepeat {
+    /// Play every queued sound once
+    #[default]
+    Off,
+    /// Enqueue every sound again once it started playing, so the whole queue repeats
+    All,
+    /// Repeat the current sound until it is skipped
+    One,
+}
+
+/// Sent when the next sound of a channel queue starts playing
+///
+/// See [`AudioControl::enqueue`](crate::AudioControl::enqueue).
+#[derive(Event, Clone, Debug)]
+pub struct QueueAdvanced {
+    /// The channel of the queue
+    pub channel: Channel,
+    /// The sound that started
+    pub source: Handle<AudioSource>,
+    /// The instance playing the sound
+    pub instance: Handle<AudioInstance>,
+}
+
+/// Delay before the first sound of a queue starts
+///
+/// Sounds are only started on a known time of the [`AudioClock`](crate::AudioClock),
+/// so the end of the sound and with it the start of the next one is known as well.
+pub(crate) const QUEUE_START_DELAY: Duration = Duration::from_millis(50);
+
+/// A sound of a queue that was handed to kira
+pub(crate) struct QueuedSound {
+    pub(crate) source: Handle<AudioSource>,
+    pub(crate) instance: Handle<AudioInstance>,
+    pub(crate) starts_at: AudioClockTime,
+    /// Unknown for looping sounds and after the channel was paused
+    pub(crate) ends_at: Option<AudioClockTime>,
+    pub(crate) looping: bool,
+    /// The repeat mode the sound was taken from the queue with
+    pub(crate) taken_with: QueueRepeat,
+}
+
+/// The queue of a channel
+#[derive(Default)]
+pub(crate) struct AudioQueue {
+    pub(crate) upcoming: VecDeque<Handle<AudioSource>>,
+    pub(crate) repeat: QueueRepeat,
+    pub(crate) current: Option<QueuedSound>,
+    /// Scheduled to start when the current sound ends
+    pub(crate) next: Option<QueuedSound>,
+}
+
+impl AudioQueue {
+    /// Take the next sound to play, keeping it in the queue if it repeats
+    pub(crate) fn take(&mut self) -> Option<Handle<AudioSource>> {
+        match self.repeat {
+            QueueRepeat::Off => self.upcoming.pop_front(),
+            QueueRepeat::All => {
+                let source = self.upcoming.pop_front()?;
+                self.upcoming.push_back(source.clone());
+                Some(source)
+            }
+            QueueRepeat::One => self.upcoming.front().cloned(),
+        }
+    }
+
+    /// Undo [`take`](Self::take) with the given repeat mode
+    fn put_back(&mut self, source: Handle<AudioSource>, taken_with: QueueRepeat) {
+        match taken_with {
+            QueueRepeat::Off => self.upcoming.push_front(source),
+            QueueRepeat::All => {
+                if let Some(index) = self.upcoming.iter().rposition(|queued| *queued == source) {
+                    self.upcoming.remove(index);
+                }
+                self.upcoming.push_front(source);
+            }
+            QueueRepeat::One => (),
+        }
+    }
+
+    /// Put the scheduled next sound back into the queue and return its instance to stop
+    pub(crate) fn cancel_next(&mut self) -> Option<Handle<AudioInstance>> {
+        let next = self.next.take()?;
+        self.put_back(next.source, next.taken_with);
+
+        Some(next.instance)
+    }
+
+    /// Move on to the sound after the current one and return the instances to stop
+    pub(crate) fn skip(&mut self) -> Vec<Handle<AudioInstance>> {
+        let mut stopped: Vec<_> = self.cancel_next().into_iter().collect();
+        if let Some(current) = self.current.take() {
+            // Even a repeating sound is left behind
+            self.put_back(current.source, current.taken_with);
+            let repeat = self.repeat;
+            self.repeat = match repeat {
+                QueueRepeat::One => QueueRepeat::Off,
+                repeat => repeat,
+            };
+            self.take();
+            self.repeat = repeat;
+            stopped.push(current.instance);
+        }
+
+        stopped
+    }
+
+    /// Remove all upcoming sounds and return the instance of the scheduled next sound to stop
+    ///
+    /// The current sound plays on.
+    pub(crate) fn clear(&mut self) -> Option<Handle<AudioInstance>> {
+        let next = self.next.take().map(|next| next.instance);
+        self.upcoming.clear();
+
+        next
+    }
+
+    /// Change the repeat mode and return the instance of the scheduled next sound to stop
+    ///
+    /// The current sound takes the place in the queue that it would have with the new mode.
+    pub(crate) fn set_repeat(&mut self, repeat: QueueRepeat) -> Option<Handle<AudioInstance>> {
+        let next = self.cancel_next();
+        if let Some((source, taken_with)) = self
+            .current
+            .as_ref()
+            .map(|current| (current.source.clone(), current.taken_with))
+        {
+            self.put_back(source, taken_with);
+            self.repeat = repeat;
+            self.take();
+            if let Some(current) = self.current.as_mut() {
+                current.taken_with = repeat;
+            }
+        } else {
+            self.repeat = repeat;
+        }
+
+        next
+    }
+}
+
+/// The queues of all channels
+#[derive(Default)]
+pub(crate) struct ChannelQueues {
+    queues: HashMap<Channel, AudioQueue>,
+}
+
+impl ChannelQueues {
+    #[cfg(test)]
+    pub(crate) fn get(&self, channel: &Channel) -> Option<&AudioQueue> {
+        self.queues.get(channel)
+    }
+
+    /// Drop the queue of the channel, its instances stop with the channel
+    pub(crate) fn remove(&mut self, channel: &Channel) {
+        self.queues.remove(channel);
+    }
+
+    pub(crate) fn enqueue(&mut self, channel: &Channel, source: Handle<AudioSource>) {
+        self.queues
+            .entry(channel.clone())
+            .or_default()
+            .upcoming
+            .push_back(source);
+    }
+
+    /// Move on to the sound after the current one
+    pub(crate) fn skip(&mut self, channel: &Channel, audio_instances: &mut Assets<AudioInstance>) {
+        if let Some(queue) = self.queues.get_mut(channel) {
+            stop_queued(queue.skip(), audio_instances);
+        }
+    }
+
+    /// Remove all upcoming sounds, the current sound plays on
+    pub(crate) fn clear(&mut self, channel: &Channel, audio_instances: &mut Assets<AudioInstance>) {
+        if let Some(queue) = self.queues.get_mut(channel) {
+            stop_queued(queue.clear(), audio_instances);
+        }
+    }
+
+    pub(crate) fn set_repeat(
+        &mut self,
+        channel: &Channel,
+        repeat: QueueRepeat,
+        audio_instances: &mut Assets<AudioInstance>,
+    ) {
+        let queue = self.queues.entry(channel.clone()).or_default();
+        stop_queued(queue.set_repeat(repeat), audio_instances);
+    }
+
+    /// Cancel the scheduled next sound of the queue, since the current one won't end in time
+    pub(crate) fn pause(&mut self, channel: &Channel, audio_instances: &mut Assets<AudioInstance>) {
+        let Some(queue) = self.queues.get_mut(channel) else {
+            return;
+        };
+        stop_queued(queue.cancel_next(), audio_instances);
+        if let Some(current) = queue.current.as_mut() {
+            current.ends_at = None;
+        }
+    }
+
+    /// Schedule the next sound of the queue again for the new end of the seeked current one
+    pub(crate) fn reschedule(
+        &mut self,
+        channel: &Channel,
+        now: AudioClockTime,
+        audio_instances: &mut Assets<AudioInstance>,
+    ) {
+        let Some(queue) = self.queues.get_mut(channel) else {
+            return;
+        };
+        stop_queued(queue.cancel_next(), audio_instances);
+        if let Some(current) = queue.current.as_mut() {
+            current.ends_at = audio_instances
+                .get(&current.instance)
+                .and_then(|instance| {
+                    instance.remaining_from(instance.seek_target.unwrap_or(instance.position()))
+                })
+                .map(|remaining| now + Duration::from_secs_f64(remaining));
+        }
+    }
+
+    /// Forget the sounds that were lost with the audio manager, the upcoming sounds are kept
+    pub(crate) fn release(&mut self, audio_instances: &Assets<AudioInstance>) {
+        let lost = |instance: &Handle<AudioInstance>| {
+            audio_instances
+                .get(instance)
+                .map_or(true, |instance| instance.handle.is_lost())
+        };
+        for queue in self.queues.values_mut() {
+            if queue
+                .next
+                .as_ref()
+                .map_or(false, |next| lost(&next.instance))
+            {
+                queue.cancel_next();
+            }
+            if queue
+                .current
+                .as_ref()
+                .map_or(false, |current| lost(&current.instance))
+            {
+                queue.current = None;
+            }
+        }
+    }
+
+    /// Start the next sounds of the queues and return the queues that advanced
+    ///
+    /// The next sound of a queue is scheduled on the audio clock for the end of the current one.
+    pub(crate) fn update<B: Backend>(
+        &mut self,
+        audio_output: &mut AudioOutput<B>,
+        audio_sources: &Assets<AudioSource>,
+        audio_instances: &mut Assets<AudioInstance>,
+    ) -> Vec<QueueAdvanced> {
+        if !audio_output.has_manager() {
+            return vec![];
+        }
+        let now = audio_output.now();
+        let mut advanced = vec![];
+        for (channel, queue) in self.queues.iter_mut() {
+            if queue
+                .next
+                .as_ref()
+                .map_or(false, |next| now >= next.starts_at)
+            {
+                queue.current = queue.next.take();
+                if let Some(current) = queue.current.as_ref() {
+                    advanced.push(QueueAdvanced {
+                        channel: channel.clone(),
+                        source: current.source.clone(),
+                        instance: current.instance.clone(),
+                    });
+                }
+            } else if queue.next.is_none() {
+                let finished = queue.current.as_ref().map_or(false, |current| {
+                    current.ends_at.map_or(false, |end| now >= end)
+                        || audio_instances
+                            .get(&current.instance)
+                            .map_or(true, |instance| {
+                                instance.handle.state() == PlaybackState::Stopped
+                            })
+                });
+                if finished {
+                    queue.current = None;
+                }
+            }
+
+            if audio_output.is_paused(channel) || queue.next.is_some() {
+                continue;
+            }
+            if let Some(current) = queue.current.as_mut() {
+                if current.ends_at.is_none() && !current.looping {
+                    // The channel was paused, so the end can only be estimated from the position
+                    current.ends_at = audio_instances
+                        .get(&current.instance)
+                        .and_then(remaining)
+                        .map(|remaining| now + remaining);
+                }
+            }
+            let start = match queue.current.as_ref() {
+                Some(current) => current.ends_at,
+                None => Some(now + QUEUE_START_DELAY),
+            };
+            let loaded = queue
+                .upcoming
+                .front()
+                .map_or(false, |source| audio_sources.contains(source));
+            if let (Some(start), true) = (start, loaded) {
+                let taken_with = queue.repeat;
+                if let Some(source) = queue.take() {
+                    queue.next = play_queued(
+                        audio_output,
+                        channel,
+                        source,
+                        taken_with,
+                        start,
+                        audio_sources,
+                        audio_instances,
+                    );
+                }
+            }
+        }
+
+        advanced
+    }
+}
+
+fn play_queued<B: Backend>(
+    audio_output: &mut AudioOutput<B>,
+    channel: &Channel,
+    source: Handle<AudioSource>,
+    taken_with: QueueRepeat,
+    start: AudioClockTime,
+    audio_sources: &Assets<AudioSource>,
+    audio_instances: &mut Assets<AudioInstance>,
+) -> Option<QueuedSound> {
+    let settings = PartialSoundSettings {
+        start_time: Some(ScheduledStart::At(start)),
+        ..Default::default()
+    };
+    let instance =
+        audio_output.play_source(channel, &settings, &source, audio_sources, audio_instances)?;
+    let playing = audio_instances.get(&instance)?;
+    let looping = audio_output.is_looping(&instance);
+    let ends_at = if looping {
+        None
+    } else {
+        remaining(playing).map(|remaining| start + remaining)
+    };
+
+    Some(QueuedSound {
+        source,
+        instance,
+        starts_at: start,
+        ends_at,
+        looping,
+        taken_with,
+    })
+}
+
+/// Stop instances of a channel queue
+fn stop_queued(
+    instances: impl IntoIterator<Item = Handle<AudioInstance>>,
+    audio_instances: &mut Assets<AudioInstance>,
+) {
+    for instance in instances {
+        if let Some(error) = audio_instances
+            .get_mut(&instance)
+            .and_then(|instance| instance.stop(AudioTween::default()))
+        {
+            warn!("Failed to stop queued instance: {:?}", error);
+        }
+    }
+}
+
+/// The time until an instance reaches the end of its playback region
+///
+/// `None` if the instance doesn't play forwards.
+fn remaining(instance: &AudioInstance) -> Option<Duration> {
+    let playback_rate = instance.scaled_playback_rate();
+    if playback_rate <= 0. {
+        return None;
+    }
+    let end = instance.timing.region.1;
+    let seconds = (end - instance.handle.position()).max(0.) / playback_rate;
+
+    Some(Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::asset::AssetId;
+    use bevy::utils::Uuid;
+
+    fn handle<A: bevy::asset::Asset>(id: u128) -> Handle<A> {
+        Handle::Weak(AssetId::Uuid {
+            uuid: Uuid::from_u128(id),
+        })
+    }
+
+    fn play_next(queue: &mut AudioQueue) -> Handle<AudioSource> {
+        let source = queue.take().unwrap();
+        queue.current = Some(QueuedSound {
+            source: source.clone(),
+            instance: handle(0),
+            starts_at: AudioClockTime::default(),
+            ends_at: None,
+            looping: false,
+            taken_with: queue.repeat,
+        });
+
+        source
+    }
+
+    fn queue_of(ids: &[u128], repeat: QueueRepeat) -> AudioQueue {
+        AudioQueue {
+            upcoming: ids.iter().map(|id| handle(*id)).collect(),
+            repeat,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn repeat_modes_take_sounds_in_order() {
+        let mut queue = queue_of(&[1, 2], QueueRepeat::Off);
+        assert_eq!(play_next(&mut queue), handle(1));
+        assert_eq!(play_next(&mut queue), handle(2));
+        assert!(queue.take().is_none());
+
+        let mut queue = queue_of(&[1, 2], QueueRepeat::All);
+        let played: Vec<_> = (0..3).map(|_| play_next(&mut queue)).collect();
+        assert_eq!(played, vec![handle(1), handle(2), handle(1)]);
+
+        let mut queue = queue_of(&[1, 2], QueueRepeat::One);
+        assert_eq!(play_next(&mut queue), handle(1));
+        assert_eq!(play_next(&mut queue), handle(1));
+        queue.skip();
+        assert_eq!(play_next(&mut queue), handle(2));
+    }
+
+    #[test]
+    fn cancelling_the_next_sound_keeps_the_order() {
+        for repeat in [QueueRepeat::Off, QueueRepeat::All, QueueRepeat::One] {
+            let mut queue = queue_of(&[1, 2, 3], repeat);
+            let upcoming = queue.upcoming.clone();
+            play_next(&mut queue);
+            queue.next = queue.current.take();
+            queue.cancel_next();
+            assert_eq!(queue.upcoming, upcoming, "{:?}", repeat);
+        }
+    }
+
+    #[test]
+    fn changing_the_repeat_mode_repeats_the_current_sound() {
+        let mut queue = queue_of(&[1, 2], QueueRepeat::Off);
+        play_next(&mut queue);
+        queue.set_repeat(QueueRepeat::One);
+        assert_eq!(play_next(&mut queue), handle(1));
+        queue.set_repeat(QueueRepeat::All);
+        assert_eq!(play_next(&mut queue), handle(2));
+        assert_eq!(play_next(&mut queue), handle(1));
+    }
+}
@@ -0,0 +1,230 @@
+use crate::audio::{AudioCommand, AudioTween};
+use crate::channel::dynamic::DynamicAudioChannels;
+use crate::channel::typed::AudioChannel;
+use crate::channel::AudioCommandQue;
+use bevy::ecs::system::Resource;
+use bevy::ecs::world::World;
+use kira::sound::PlaybackRate;
+use kira::Volume;
+use std::any::TypeId;
+use std::collections::BTreeMap;
+
+/// Volume, panning and playback rate of a channel, see [`ChannelStates`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ChannelMix {
+    /// Volume of all sounds in the channel (default full volume)
+    pub volume: Volume,
+    /// Panning of all sounds in the channel, from `0.0` (hard left) to `1.0` (hard right)
+    ///
+    /// Default is `0.5`.
+    pub panning: f64,
+    /// Playback rate of all sounds in the channel (default `1.0`)
+    pub playback_rate: f64,
+}
+
+impl Default for ChannelMix {
+    fn default() -> Self {
+        ChannelMix {
+            volume: 1.0.into(),
+            panning: 0.5,
+            playback_rate: 1.0,
+        }
+    }
+}
+
+impl ChannelMix {
+    /// Keep track of the mix a channel was told to have
+    pub(crate) fn record(&mut self, command: &AudioCommand) {
+        match command {
+            AudioCommand::SetVolume(volume, _) => self.volume = *volume,
+            AudioCommand::SetPanning(panning, _) => self.panning = *panning,
+            AudioCommand::SetPlaybackRate(playback_rate, _) => {
+                self.playback_rate = playback_rate.as_factor()
+            }
+            AudioCommand::ApplySettings(settings) => {
+                self.volume = settings.volume;
+                self.panning = settings.panning;
+                self.playback_rate = settings.playback_rate;
+            }
+            _ => (),
+        }
+    }
+
+    fn apply(&self, channel: &dyn AudioCommandQue, tween: &Option<AudioTween>) {
+        channel.que(AudioCommand::SetVolume(self.volume, tween.clone()));
+        channel.que(AudioCommand::SetPanning(self.panning, tween.clone()));
+        channel.que(AudioCommand::SetPlaybackRate(
+            PlaybackRate::Factor(self.playback_rate),
+            tween.clone(),
+        ));
+    }
+}
+
+/// A snapshot of the mix of all audio channels, for example to persist volume sliders
+///
+/// The snapshot holds the values that were last set on the channels, including commands that
+/// did not reach the audio backend yet. Typed channels are stored by the type name of their
+/// marker type, dynamic channels by their key.
+///
+/// With the `serde` feature, the snapshot can be (de)serialized.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// #[derive(Resource)]
+/// struct SavedMix(ChannelStates);
+///
+/// fn save(world: &World) {
+///     let states = ChannelStates::collect(world);
+///     // write `states` to the options file
+/// }
+///
+/// fn restore(world: &World, saved: Res<SavedMix>) {
+///     saved.0.apply(world, None);
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct ChannelStates {
+    /// Typed channels by the type name of their marker type
+    pub typed: BTreeMap<String, ChannelMix>,
+    /// Dynamic channels by their key
+    pub dynamic: BTreeMap<String, ChannelMix>,
+}
+
+impl ChannelStates {
+    /// Collect the mix of all typed and dynamic channels
+    pub fn collect(world: &World) -> Self {
+        let mut states = ChannelStates::default();
+        if let Some(typed_channels) = world.get_resource::<TypedAudioChannels>() {
+            for channel in typed_channels.channels.iter() {
+                if let Some(mix) = (channel.mix)(world) {
+                    states.typed.insert(channel.name.to_string(), mix);
+                }
+            }
+        }
+        if let Some(dynamic_channels) = world.get_resource::<DynamicAudioChannels>() {
+            for (key, channel) in dynamic_channels.iter() {
+                states.dynamic.insert(key.clone(), channel.mix());
+            }
+        }
+
+        states
+    }
+
+    /// Queue commands to restore the mix of all channels in the snapshot
+    ///
+    /// Like other channel commands, they are applied once the audio backend is ready.
+    /// Channels that don't exist are skipped, so dynamic channels need to be created first.
+    pub fn apply(&self, world: &World, tween: Option<AudioTween>) {
+        if let Some(typed_channels) = world.get_resource::<TypedAudioChannels>() {
+            for channel in typed_channels.channels.iter() {
+                if let Some(mix) = self.typed.get(channel.name) {
+                    (channel.apply)(world, mix, &tween);
+                }
+            }
+        }
+        if let Some(dynamic_channels) = world.get_resource::<DynamicAudioChannels>() {
+            for (key, mix) in self.dynamic.iter() {
+                if let Some(channel) = dynamic_channels.get_channel(key) {
+                    mix.apply(channel, &tween);
+                }
+            }
+        }
+    }
+}
+
+struct TypedChannel {
+    id: TypeId,
+    name: &'static str,
+    mix: fn(&World) -> Option<ChannelMix>,
+    apply: fn(&World, &ChannelMix, &Option<AudioTween>),
+}
+
+/// All typed channels added with [`AudioApp::add_audio_channel`](crate::AudioApp::add_audio_channel)
+#[derive(Resource, Default)]
+pub(crate) struct TypedAudioChannels {
+    channels: Vec<TypedChannel>,
+}
+
+impl TypedAudioChannels {
+    pub(crate) fn register<T: Resource>(&mut self) {
+        let name = std::any::type_name::<T>();
+        if self.channels.iter().any(|channel| channel.name == name) {
+            return;
+        }
+        self.channels.push(TypedChannel {
+            id: TypeId::of::<T>(),
+            name,
+            mix: |world| {
+                world
+                    .get_resource::<AudioChannel<T>>()
+                    .map(|channel| channel.mix())
+            },
+            apply: |world, mix, tween| {
+                if let Some(channel) = world.get_resource::<AudioChannel<T>>() {
+                    mix.apply(channel, tween);
+                }
+            },
+        });
+    }
+
+    /// The type name of the typed channel with the given type id
+    pub(crate) fn name(&self, id: TypeId) -> Option<&'static str> {
+        self.channels
+            .iter()
+            .find(|channel| channel.id == id)
+            .map(|channel| channel.name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AudioApp, AudioControl, MainTrack};
+    use bevy::app::App;
+
+    #[derive(Resource)]
+    struct Music;
+
+    #[test]
+    fn restores_the_mix_of_typed_and_dynamic_channels() {
+        let mut app = App::new();
+        app.add_audio_channel::<MainTrack>()
+            .add_audio_channel::<Music>()
+            .init_resource::<DynamicAudioChannels>();
+        app.world.resource::<AudioChannel<Music>>().set_volume(0.3);
+        app.world
+            .resource_mut::<DynamicAudioChannels>()
+            .create_channel("ambience")
+            .set_panning(0.2);
+        let states = ChannelStates::collect(&app.world);
+        assert_eq!(states.typed.len(), 2);
+        assert_eq!(states.dynamic["ambience"].panning, 0.2);
+
+        let mut restored = App::new();
+        restored
+            .add_audio_channel::<Music>()
+            .init_resource::<DynamicAudioChannels>();
+        restored
+            .world
+            .resource_mut::<DynamicAudioChannels>()
+            .create_channel("ambience");
+        states.apply(&restored.world, None);
+        let music = restored.world.resource::<AudioChannel<Music>>();
+        assert_eq!(music.mix().volume, Volume::Amplitude(0.3));
+        assert_eq!(music.commands.read().len(), 3);
+        assert_eq!(
+            ChannelStates::collect(&restored.world).dynamic,
+            states.dynamic
+        );
+    }
+}
@@ -0,0 +1,548 @@
+//! A clock on the audio thread to start sounds sample-accurately
+
+use crate::audio::AudioTween;
+use crate::audio_output::AudioOutput;
+use bevy::ecs::change_detection::NonSendMut;
+use bevy::ecs::event::{Event, EventWriter};
+use bevy::ecs::system::{Res, Resource};
+use bevy::log::{error, warn};
+use bevy::utils::HashMap;
+use kira::clock::{ClockHandle, ClockSpeed, ClockTime};
+use kira::manager::backend::Backend;
+use kira::manager::AudioManager;
+use kira::tween::Tween;
+use kira::StartTime;
+use parking_lot::Mutex;
+use std::ops::Add;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The clock of the audio thread
+///
+/// Sounds scheduled with [`PlayAudioCommand::start_at`](crate::PlayAudioCommand::start_at)
+/// start on the exact sample at which this clock reaches their start time, independent of
+/// when the app processes the play command. All sounds scheduled for the same time start in sync.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// # use std::time::Duration;
+/// fn play_stinger(asset_server: Res<AssetServer>, audio: Res<Audio>, clock: Res<AudioClock>) {
+///     let start = clock.now() + Duration::from_millis(500);
+///     audio.play(asset_server.load("sounds/stinger.ogg")).start_at(start);
+///     audio.play(asset_server.load("sounds/drums.ogg")).start_at(start);
+/// }
+/// ```
+///
+/// The clock ticks [`AudioClock::TICKS_PER_SECOND`] times per second. If the audio backend
+/// failed to start, the clock stays at zero.
+#[derive(Resource, Clone, Default)]
+pub struct AudioClock {
+    handle: Option<Arc<ClockHandle>>,
+}
+
+impl AudioClock {
+    /// Number of ticks per second
+    pub const TICKS_PER_SECOND: f64 = 1_000.;
+
+    /// Add and start a clock on the given audio manager
+    pub(crate) fn start<B: Backend>(manager: &mut AudioManager<B>) -> Self {
+        let handle = match manager.add_clock(ClockSpeed::TicksPerSecond(Self::TICKS_PER_SECOND)) {
+            Ok(handle) => handle,
+            Err(error) => {
+                bevy::log::error!("Failed to add the audio clock: {:?}", error);
+                return AudioClock::default();
+            }
+        };
+        if let Err(error) = handle.start() {
+            bevy::log::error!("Failed to start the audio clock: {:?}", error);
+        }
+
+        AudioClock {
+            handle: Some(Arc::new(handle)),
+        }
+    }
+
+    /// Whether the clock runs on an audio manager
+    pub(crate) fn is_running(&self) -> bool {
+        self.handle.is_some()
+    }
+
+    /// The current time of the clock
+    pub fn now(&self) -> AudioClockTime {
+        AudioClockTime {
+            ticks: self.handle.as_ref().map_or(0, |handle| handle.time().ticks),
+        }
+    }
+
+    /// The start time of a sound in kira
+    pub(crate) fn start_time(&self, time: AudioClockTime) -> StartTime {
+        match self.handle {
+            Some(ref handle) => StartTime::ClockTime(ClockTime {
+                clock: handle.id(),
+                ticks: time.ticks,
+            }),
+            None => StartTime::Immediate,
+        }
+    }
+}
+
+/// A time of the [`AudioClock`]
+///
+/// Add a [`Duration`] to get a later time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct AudioClockTime {
+    /// Elapsed ticks since the clock started
+    pub ticks: u64,
+}
+
+impl Add<Duration> for AudioClockTime {
+    type Output = AudioClockTime;
+
+    fn add(self, duration: Duration) -> Self::Output {
+        AudioClockTime {
+            ticks: self.ticks
+                + (duration.as_secs_f64() * AudioClock::TICKS_PER_SECOND).round() as u64,
+        }
+    }
+}
+
+/// When a sound should start
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum ScheduledStart {
+    /// At the given time of the audio clock
+    At(AudioClockTime),
+    /// The given time after the play command was processed
+    Delayed(Duration),
+    /// At the given tick of one of the [`AudioClocks`]
+    Clock(AudioClockId, u64),
+}
+
+impl ScheduledStart {
+    /// Whether the clock to start on runs on the audio manager yet
+    pub(crate) fn is_ready(&self, clocks: &AudioClocks) -> bool {
+        match *self {
+            ScheduledStart::Clock(id, _) => clocks.is_ready(id),
+            _ => true,
+        }
+    }
+
+    pub(crate) fn start_time(&self, clock: &AudioClock, clocks: &AudioClocks) -> StartTime {
+        match *self {
+            ScheduledStart::At(time) => clock.start_time(time),
+            ScheduledStart::Delayed(delay) => clock.start_time(clock.now() + delay),
+            ScheduledStart::Clock(id, ticks) => clocks.start_time(id, ticks),
+        }
+    }
+}
+
+/// A clock of [`AudioClocks`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AudioClockId(u64);
+
+/// Clocks with their own tempo on the audio thread, to schedule sounds on beats
+///
+/// Every clock ticks once per beat at its tempo in beats per minute. Sounds scheduled with
+/// [`PlayAudioCommand::start_at_clock`](crate::PlayAudioCommand::start_at_clock) start on the
+/// exact sample at which their clock reaches the tick, and changing the tempo moves all waiting
+/// sounds along. The clocks are shared, so all systems that look at the resource see the same
+/// musical timeline. New clocks and changes to them reach the audio thread in the next update.
+///
+/// While a clock ticks, the plugin sends a [`BeatEvent`] for every beat.
+///
+/// Besides the [`AudioClock`], kira has room for seven clocks. The clocks are restarted from
+/// zero when the output device changes.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// #[derive(Resource)]
+/// struct Song(AudioClockId);
+///
+/// fn start_song(mut commands: Commands, clocks: Res<AudioClocks>) {
+///     let clock = clocks.add(120.);
+///     clocks.start(clock);
+///     commands.insert_resource(Song(clock));
+/// }
+///
+/// fn play_on_next_beat(
+///     asset_server: Res<AssetServer>,
+///     audio: Res<Audio>,
+///     clocks: Res<AudioClocks>,
+///     song: Res<Song>,
+/// ) {
+///     let next_beat = clocks.ticks(song.0).unwrap_or_default() + 1;
+///     audio
+///         .play(asset_server.load("sounds/snare.ogg"))
+///         .start_at_clock(song.0, next_beat);
+/// }
+/// ```
+#[derive(Resource, Clone, Default)]
+pub struct AudioClocks {
+    state: Arc<Mutex<ClocksState>>,
+}
+
+#[derive(Default)]
+struct ClocksState {
+    next_id: u64,
+    clocks: HashMap<AudioClockId, MusicClock>,
+}
+
+struct MusicClock {
+    /// `None` until the clock was added to the audio manager
+    handle: Option<ClockHandle>,
+    speed: ClockSpeed,
+    ticking: bool,
+    beats_per_bar: u32,
+    /// The last tick that a [`BeatEvent`] was sent for
+    reported: Option<u64>,
+    /// Changes that did not reach the audio thread yet
+    commands: Vec<ClockCommand>,
+}
+
+impl MusicClock {
+    fn seconds_per_tick(&self) -> f64 {
+        match self.speed {
+            ClockSpeed::TicksPerMinute(ticks) => 60. / ticks,
+            ClockSpeed::TicksPerSecond(ticks) => 1. / ticks,
+            ClockSpeed::SecondsPerTick(seconds) => seconds,
+        }
+    }
+}
+
+enum ClockCommand {
+    Start,
+    Pause,
+    Stop,
+    SetSpeed(ClockSpeed, Tween),
+}
+
+impl AudioClocks {
+    /// Add a stopped clock with the given tempo in beats per minute and four beats per bar
+    pub fn add(&self, beats_per_minute: f64) -> AudioClockId {
+        let mut state = self.state.lock();
+        let id = AudioClockId(state.next_id);
+        state.next_id += 1;
+        state.clocks.insert(
+            id,
+            MusicClock {
+                handle: None,
+                speed: ClockSpeed::TicksPerMinute(beats_per_minute),
+                ticking: false,
+                beats_per_bar: 4,
+                reported: None,
+                commands: vec![],
+            },
+        );
+
+        id
+    }
+
+    /// Remove the clock
+    ///
+    /// Sounds that wait for a tick of the clock never start.
+    pub fn remove(&self, clock: AudioClockId) {
+        self.state.lock().clocks.remove(&clock);
+    }
+
+    /// Start the clock, or continue it after a pause
+    pub fn start(&self, clock: AudioClockId) {
+        self.command(clock, ClockCommand::Start);
+    }
+
+    /// Pause the clock, keeping its ticks
+    pub fn pause(&self, clock: AudioClockId) {
+        self.command(clock, ClockCommand::Pause);
+    }
+
+    /// Stop the clock and reset it to zero ticks
+    pub fn stop(&self, clock: AudioClockId) {
+        self.command(clock, ClockCommand::Stop);
+    }
+
+    /// Change the tempo of the clock in beats per minute
+    pub fn set_tempo(&self, clock: AudioClockId, beats_per_minute: f64, tween: AudioTween) {
+        self.command(
+            clock,
+            ClockCommand::SetSpeed(
+                ClockSpeed::TicksPerMinute(beats_per_minute),
+                (&tween).into(),
+            ),
+        );
+    }
+
+    /// The tempo of the clock in beats per minute, `None` if there is no such clock
+    ///
+    /// This is the target of a running tempo change.
+    pub fn tempo(&self, clock: AudioClockId) -> Option<f64> {
+        self.state
+            .lock()
+            .clocks
+            .get(&clock)
+            .map(|music_clock| 60. / music_clock.seconds_per_tick())
+    }
+
+    /// Set the number of beats in a bar of the clock, the upper number of the time signature
+    ///
+    /// The next [`BeatEvent`] counts from the start of the clock with the new bars.
+    pub fn set_beats_per_bar(&self, clock: AudioClockId, beats_per_bar: u32) {
+        if let Some(music_clock) = self.state.lock().clocks.get_mut(&clock) {
+            music_clock.beats_per_bar = beats_per_bar.max(1);
+        }
+    }
+
+    /// The time until the clock reaches the given tick at its current tempo
+    ///
+    /// Zero for ticks that already passed, `None` if there is no such clock. Use it to start
+    /// visuals ahead of a beat, so they line up with what the player hears.
+    pub fn time_until(&self, clock: AudioClockId, ticks: u64) -> Option<Duration> {
+        let state = self.state.lock();
+        let music_clock = state.clocks.get(&clock)?;
+        let position = music_clock.handle.as_ref().map_or(0., |handle| {
+            handle.time().ticks as f64 + handle.fractional_position()
+        });
+
+        Some(Duration::from_secs_f64(
+            (ticks as f64 - position).max(0.) * music_clock.seconds_per_tick(),
+        ))
+    }
+
+    /// Whether the clock is started
+    pub fn is_ticking(&self, clock: AudioClockId) -> bool {
+        self.state
+            .lock()
+            .clocks
+            .get(&clock)
+            .map_or(false, |music_clock| music_clock.ticking)
+    }
+
+    /// The elapsed ticks of the clock, `None` if there is no such clock
+    pub fn ticks(&self, clock: AudioClockId) -> Option<u64> {
+        self.state.lock().clocks.get(&clock).map(|music_clock| {
+            music_clock
+                .handle
+                .as_ref()
+                .map_or(0, |handle| handle.time().ticks)
+        })
+    }
+
+    /// The elapsed ticks of the clock including the progress to the next one
+    pub fn position(&self, clock: AudioClockId) -> Option<f64> {
+        self.state.lock().clocks.get(&clock).map(|music_clock| {
+            music_clock.handle.as_ref().map_or(0., |handle| {
+                handle.time().ticks as f64 + handle.fractional_position()
+            })
+        })
+    }
+
+    fn command(&self, clock: AudioClockId, command: ClockCommand) {
+        let mut state = self.state.lock();
+        let Some(music_clock) = state.clocks.get_mut(&clock) else {
+            warn!("There is no audio clock {:?}", clock);
+            return;
+        };
+        match &command {
+            ClockCommand::Start => music_clock.ticking = true,
+            ClockCommand::Pause | ClockCommand::Stop => music_clock.ticking = false,
+            ClockCommand::SetSpeed(speed, _) => music_clock.speed = *speed,
+        }
+        music_clock.commands.push(command);
+    }
+
+    fn is_ready(&self, clock: AudioClockId) -> bool {
+        self.state
+            .lock()
+            .clocks
+            .get(&clock)
+            .map_or(true, |music_clock| music_clock.handle.is_some())
+    }
+
+    fn start_time(&self, clock: AudioClockId, ticks: u64) -> StartTime {
+        let state = self.state.lock();
+        match state
+            .clocks
+            .get(&clock)
+            .and_then(|music_clock| music_clock.handle.as_ref())
+        {
+            Some(handle) => StartTime::ClockTime(ClockTime {
+                clock: handle.id(),
+                ticks,
+            }),
+            None => {
+                warn!(
+                    "There is no audio clock {:?}, starting the sound right away",
+                    clock
+                );
+                StartTime::Immediate
+            }
+        }
+    }
+
+    /// Add new clocks to the audio manager and hand changes to the audio thread
+    pub(crate) fn sync<B: Backend>(&self, manager: &mut AudioManager<B>) {
+        let mut state = self.state.lock();
+        for music_clock in state.clocks.values_mut() {
+            if music_clock.handle.is_none() {
+                match manager.add_clock(music_clock.speed) {
+                    Ok(handle) => music_clock.handle = Some(handle),
+                    Err(error) => {
+                        error!("Failed to add an audio clock: {:?}", error);
+                        continue;
+                    }
+                }
+                // The new clock already has the latest speed
+                music_clock.commands.clear();
+                if music_clock.ticking {
+                    music_clock.commands.push(ClockCommand::Start);
+                }
+            }
+            let Some(handle) = music_clock.handle.as_ref() else {
+                continue;
+            };
+            for command in music_clock.commands.drain(..) {
+                let result = match command {
+                    ClockCommand::Start => handle.start(),
+                    ClockCommand::Pause => handle.pause(),
+                    ClockCommand::Stop => handle.stop(),
+                    ClockCommand::SetSpeed(speed, tween) => handle.set_speed(speed, tween),
+                };
+                if let Err(error) = result {
+                    error!("Failed to change an audio clock: {:?}", error);
+                }
+            }
+        }
+    }
+
+    /// The beats that the clocks reached since the last call
+    fn beats(&self) -> Vec<BeatEvent> {
+        let mut beats = vec![];
+        for (id, music_clock) in self.state.lock().clocks.iter_mut() {
+            let Some(handle) = music_clock.handle.as_ref() else {
+                continue;
+            };
+            let ticks = handle.time().ticks;
+            if !handle.ticking() && ticks == 0 {
+                // Stopped, so the start is a beat again
+                music_clock.reported = None;
+                continue;
+            }
+            let first = match music_clock.reported {
+                Some(reported) if reported <= ticks => reported + 1,
+                // Stopped and started again since the last call
+                _ => 0,
+            };
+            let position = ticks as f64 + handle.fractional_position();
+            let beats_per_bar = u64::from(music_clock.beats_per_bar);
+            beats.extend((first..=ticks).map(|tick| BeatEvent {
+                clock: *id,
+                tick,
+                beat: (tick % beats_per_bar) as u32,
+                bar: (tick / beats_per_bar) as u32,
+                late_by: Duration::from_secs_f64(
+                    (position - tick as f64) * music_clock.seconds_per_tick(),
+                ),
+            }));
+            music_clock.reported = Some(ticks);
+        }
+
+        beats
+    }
+
+    /// Forget the clocks of a released audio manager, so they are added to the next one
+    pub(crate) fn release(&self) {
+        for music_clock in self.state.lock().clocks.values_mut() {
+            music_clock.handle = None;
+            music_clock.reported = None;
+        }
+    }
+}
+
+/// Sent for every beat of a ticking clock of the [`AudioClocks`]
+///
+/// The events are sent at the start of the frame after the audio thread reached the beat.
+/// [`late_by`](Self::late_by) is how long ago that was, so animations can be advanced by it.
+/// To line visuals up with the beat itself, start them ahead with
+/// [`AudioClocks::time_until`] instead.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn pulse_on_beat(mut beats: EventReader<BeatEvent>, mut pulse: Query<&mut Transform>) {
+///     for beat in beats.read() {
+///         let scale = if beat.beat == 0 { 1.5 } else { 1.2 };
+///         pulse.single_mut().scale = Vec3::splat(scale);
+///     }
+/// }
+/// ```
+#[derive(Event, Clone, Copy, Debug, PartialEq)]
+pub struct BeatEvent {
+    /// The clock that reached the beat
+    pub clock: AudioClockId,
+    /// The tick of the clock, counting all beats since it started
+    pub tick: u64,
+    /// The beat in its bar, starting at `0`
+    pub beat: u32,
+    /// The bar of the beat, starting at `0`
+    pub bar: u32,
+    /// How long ago the audio thread reached the beat
+    pub late_by: Duration,
+}
+
+pub(crate) fn send_beat_events(clocks: Res<AudioClocks>, mut beats: EventWriter<BeatEvent>) {
+    beats.send_batch(clocks.beats());
+}
+
+/// Add new [`AudioClocks`] to the audio manager and hand changes to the audio thread
+pub(crate) fn update_audio_clocks(mut audio_output: NonSendMut<AudioOutput>) {
+    audio_output.sync_clocks();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kira::manager::backend::mock::{MockBackend, MockBackendSettings};
+    use kira::manager::AudioManagerSettings;
+
+    #[test]
+    fn beats_count_bars_and_restart_with_the_clock() {
+        let mut manager = AudioManager::new(AudioManagerSettings::<MockBackend> {
+            backend_settings: MockBackendSettings { sample_rate: 1_000 },
+            ..Default::default()
+        })
+        .unwrap();
+        let process = |manager: &mut AudioManager<MockBackend>, samples: usize| {
+            let backend = manager.backend_mut();
+            backend.on_start_processing();
+            for _ in 0..samples {
+                backend.process();
+            }
+            // Shares the new time of the clock
+            backend.on_start_processing();
+        };
+        let clocks = AudioClocks::default();
+        // A beat every 10 samples
+        let clock = clocks.add(6_000.);
+        clocks.set_beats_per_bar(clock, 3);
+        clocks.start(clock);
+        clocks.sync(&mut manager);
+        process(&mut manager, 1);
+        let beats = clocks.beats();
+        assert_eq!(beats.len(), 1);
+        assert_eq!((beats[0].tick, beats[0].beat, beats[0].bar), (0, 0, 0));
+
+        process(&mut manager, 35);
+        let beats: Vec<_> = clocks
+            .beats()
+            .iter()
+            .map(|beat| (beat.tick, beat.beat, beat.bar))
+            .collect();
+        assert_eq!(beats, vec![(1, 1, 0), (2, 2, 0), (3, 0, 1)]);
+        assert!(clocks.beats().is_empty());
+
+        clocks.stop(clock);
+        clocks.start(clock);
+        clocks.sync(&mut manager);
+        process(&mut manager, 1);
+        let beats = clocks.beats();
+        assert_eq!(beats.len(), 1);
+        assert_eq!(beats[0].tick, 0);
+        assert!(clocks.time_until(clock, 1).unwrap() <= Duration::from_millis(10));
+    }
+}
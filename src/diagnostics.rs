@@ -0,0 +1,219 @@
+//! Numbers of the audio engine for the diagnostics of Bevy
+
+use crate::audio_output::AudioOutput;
+use crate::channel::snapshot::TypedAudioChannels;
+use crate::channel::Channel;
+use crate::instance::AudioInstance;
+use crate::AudioSystemSet;
+use bevy::app::{App, Plugin, PostUpdate};
+use bevy::asset::Assets;
+use bevy::diagnostic::{
+    Diagnostic, DiagnosticId, DiagnosticMeasurement, DiagnosticsStore, RegisterDiagnostic,
+};
+use bevy::ecs::schedule::IntoSystemConfigs;
+use bevy::ecs::system::{NonSend, Res, ResMut};
+use bevy::utils::Instant;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Adds diagnostics of the audio engine, for example to log them with the
+/// [`LogDiagnosticsPlugin`](bevy::diagnostic::LogDiagnosticsPlugin)
+///
+/// Add it after the [`AudioPlugin`](crate::AudioPlugin). Besides the totals, every channel gets
+/// a diagnostic with the number of its playing sounds, see
+/// [`channel_instances`](Self::channel_instances).
+/// ```no_run
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// use bevy::diagnostic::LogDiagnosticsPlugin;
+///
+/// App::new()
+///     .add_plugins((DefaultPlugins, AudioPlugin::default()))
+///     .add_plugins((AudioDiagnosticsPlugin, LogDiagnosticsPlugin::default()));
+/// ```
+#[derive(Default)]
+pub struct AudioDiagnosticsPlugin;
+
+impl Plugin for AudioDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_diagnostic(Diagnostic::new(
+            Self::PLAYING_INSTANCES,
+            "audio_playing_instances",
+            20,
+        ))
+        .register_diagnostic(Diagnostic::new(
+            Self::INSTANCE_ASSETS,
+            "audio_instance_assets",
+            20,
+        ))
+        .register_diagnostic(Diagnostic::new(
+            Self::PENDING_COMMANDS,
+            "audio_pending_commands",
+            20,
+        ))
+        .register_diagnostic(
+            Diagnostic::new(Self::DROPPED_COMMANDS, "audio_dropped_commands", 1)
+                .with_smoothing_factor(0.),
+        )
+        .add_systems(
+            PostUpdate,
+            Self::diagnostic_system
+                .after(AudioSystemSet::PlayTypedChannels)
+                .after(AudioSystemSet::PlayDynamicChannels),
+        );
+    }
+}
+
+impl AudioDiagnosticsPlugin {
+    /// The number of sounds that play or are paused, on all channels
+    pub const PLAYING_INSTANCES: DiagnosticId =
+        DiagnosticId::from_u128(0x6f1c_2a8e_51d4_4b7a_9c3e_0d8f_7a21_b640);
+    /// The number of [`AudioInstance`] assets, including those of stopped sounds
+    pub const INSTANCE_ASSETS: DiagnosticId =
+        DiagnosticId::from_u128(0x3b9e_74c1_0a6f_4d28_8e15_c7b2_94d0_6e13);
+    /// The number of commands that wait in the queues of all channels, for example for a sound
+    /// that is still loading
+    pub const PENDING_COMMANDS: DiagnosticId =
+        DiagnosticId::from_u128(0xd24a_8f06_3c71_4e9b_a5d8_1f6e_0b37_c982);
+    /// The number of play commands that failed since the start, for example because kira was
+    /// out of room for sounds
+    pub const DROPPED_COMMANDS: DiagnosticId =
+        DiagnosticId::from_u128(0x89f3_1d5b_e640_4a72_b01c_6d9a_3e85_f417);
+
+    /// The diagnostic with the number of sounds that play or are paused on the given channel
+    ///
+    /// It is added once something played on the channel.
+    pub fn channel_instances(channel: &Channel) -> DiagnosticId {
+        let mut hasher = DefaultHasher::new();
+        channel.hash(&mut hasher);
+
+        DiagnosticId::from_u128(0x5a07_c3e9_2b84_4f1d_0000_0000_0000_0000 | hasher.finish() as u128)
+    }
+
+    fn diagnostic_system(
+        mut store: ResMut<DiagnosticsStore>,
+        audio_output: NonSend<AudioOutput>,
+        audio_instances: Res<Assets<AudioInstance>>,
+        typed_channels: Option<Res<TypedAudioChannels>>,
+    ) {
+        let mut playing = 0;
+        for (channel, instances) in audio_output.instance_counts() {
+            playing += instances;
+            let id = Self::channel_instances(channel);
+            if store.get(id).is_none() {
+                let name = channel_name(channel, typed_channels.as_deref());
+                store.add(Diagnostic::new(id, format!("audio_channel_{}", name), 20));
+            }
+            measure(&mut store, id, instances as f64);
+        }
+        measure(&mut store, Self::PLAYING_INSTANCES, playing as f64);
+        measure(
+            &mut store,
+            Self::INSTANCE_ASSETS,
+            audio_instances.len() as f64,
+        );
+        measure(
+            &mut store,
+            Self::PENDING_COMMANDS,
+            audio_output.pending_commands() as f64,
+        );
+        measure(
+            &mut store,
+            Self::DROPPED_COMMANDS,
+            audio_output.dropped_commands() as f64,
+        );
+    }
+}
+
+fn measure(store: &mut DiagnosticsStore, id: DiagnosticId, value: f64) {
+    if let Some(diagnostic) = store.get_mut(id).filter(|diagnostic| diagnostic.is_enabled) {
+        diagnostic.add_measurement(DiagnosticMeasurement {
+            time: Instant::now(),
+            value,
+        });
+    }
+}
+
+/// The type name of the marker of a typed channel without its module path, or the key of a
+/// dynamic channel
+pub(crate) fn channel_name(
+    channel: &Channel,
+    typed_channels: Option<&TypedAudioChannels>,
+) -> String {
+    match channel {
+        Channel::Typed(type_id) => typed_channels
+            .and_then(|channels| channels.name(*type_id))
+            .map_or_else(|| "typed".to_owned(), short_type_name),
+        Channel::Dynamic(key) => key.clone(),
+    }
+}
+
+/// The name of a type without the path of its module
+fn short_type_name(name: &str) -> String {
+    let generics = name.find('<').unwrap_or(name.len());
+    let start = name[..generics].rfind("::").map_or(0, |index| index + 2);
+
+    name[start..].to_owned()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Audio, AudioControl, AudioPlugin, AudioSource, MainTrack};
+    use bevy::asset::{AssetPlugin, Handle};
+    use bevy::prelude::MinimalPlugins;
+    use kira::dsp::Frame;
+    use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+    use std::sync::Arc;
+
+    #[test]
+    fn counts_instances_and_waiting_commands() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            AudioPlugin::default(),
+        ))
+        .add_plugins(AudioDiagnosticsPlugin);
+        let source = app
+            .world
+            .resource_mut::<Assets<AudioSource>>()
+            .add(AudioSource {
+                sound: StaticSoundData {
+                    sample_rate: 1_000,
+                    frames: Arc::from(vec![Frame::ZERO; 10_000]),
+                    settings: StaticSoundSettings::default(),
+                },
+                loop_region: None,
+                gain: 1.,
+            });
+        let audio = app.world.resource::<Audio>();
+        audio.play(source);
+        // Never loads
+        audio.play(Handle::default());
+        app.update();
+
+        let store = app.world.resource::<DiagnosticsStore>();
+        let value = |id| store.get(id).and_then(Diagnostic::value);
+        assert_eq!(value(AudioDiagnosticsPlugin::PLAYING_INSTANCES), Some(1.));
+        assert_eq!(value(AudioDiagnosticsPlugin::INSTANCE_ASSETS), Some(1.));
+        assert_eq!(value(AudioDiagnosticsPlugin::PENDING_COMMANDS), Some(1.));
+        assert_eq!(value(AudioDiagnosticsPlugin::DROPPED_COMMANDS), Some(0.));
+        let channel = store
+            .get(AudioDiagnosticsPlugin::channel_instances(
+                &Channel::typed::<MainTrack>(),
+            ))
+            .unwrap();
+        assert_eq!(channel.name, "audio_channel_MainTrack");
+        assert_eq!(channel.value(), Some(1.));
+    }
+
+    #[test]
+    fn type_names_lose_their_module_path() {
+        assert_eq!(short_type_name("game::audio::Music"), "Music");
+        assert_eq!(
+            short_type_name("game::Layer<game::audio::Music>"),
+            "Layer<game::audio::Music>"
+        );
+    }
+}
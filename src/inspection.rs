@@ -0,0 +1,181 @@
+//! A live view of the audio state for inspectors, like the one of `bevy-inspector-egui`
+
+use crate::audio_output::AudioOutput;
+use crate::channel::snapshot::TypedAudioChannels;
+use crate::channel::ChannelState;
+use crate::diagnostics::channel_name;
+use crate::instance::AudioInstance;
+use crate::PlaybackState;
+use bevy::asset::{Assets, Handle};
+use bevy::ecs::system::{NonSend, Res, ResMut, Resource};
+use bevy::prelude::ReflectResource;
+use bevy::reflect::Reflect;
+
+/// The mix of a channel, see [`AudioInspection`]
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct ChannelInspection {
+    /// The type name of the marker of a typed channel without its module path, or the key of a
+    /// dynamic channel
+    pub name: String,
+    /// Volume of the channel as amplitude
+    pub volume: f64,
+    /// Panning from `0.0` (hard left) to `1.0` (hard right)
+    pub panning: f64,
+    /// Playback rate of the channel
+    pub playback_rate: f64,
+    /// The channel is paused
+    pub paused: bool,
+    /// The channel is muted, see [`AudioControl::mute`](crate::AudioControl::mute)
+    pub muted: bool,
+    /// The number of instances that did not stop yet
+    pub instances: usize,
+}
+
+/// A playing or paused audio instance, see [`AudioInspection`]
+#[derive(Clone, Debug, PartialEq, Reflect)]
+pub struct InstanceInspection {
+    /// The handle of the instance
+    pub instance: Handle<AudioInstance>,
+    /// The [name](ChannelInspection::name) of its channel
+    pub channel: String,
+    /// The playback state including the position in seconds
+    pub state: PlaybackState,
+    /// The duration of the sound in seconds
+    pub duration: f64,
+    /// The playback rate set on the instance or its channel
+    pub playback_rate: f64,
+    /// The volume set on the instance or its channel as amplitude
+    pub volume: f64,
+}
+
+/// The state of all audio channels and their instances, refreshed every frame
+///
+/// It only mirrors the state for inspectors and debug UIs. Changing it has no effect, use the
+/// channels and [`AudioInstance`]s instead. Channels are sorted by name.
+///
+/// Only available with the `inspection` feature, since the plugin collects it every frame.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn show_music(inspection: Res<AudioInspection>) {
+///     for instance in inspection.instances.iter().filter(|instance| instance.channel == "Music") {
+///         info!("{:?}", instance.state);
+///     }
+/// }
+/// ```
+#[derive(Resource, Clone, Debug, Default, Reflect)]
+#[reflect(Resource)]
+pub struct AudioInspection {
+    /// All channels that got a command or played a sound
+    pub channels: Vec<ChannelInspection>,
+    /// All instances that did not stop yet, in the order of their channels
+    pub instances: Vec<InstanceInspection>,
+}
+
+pub(crate) fn update_audio_inspection(
+    audio_output: NonSend<AudioOutput>,
+    audio_instances: Res<Assets<AudioInstance>>,
+    typed_channels: Option<Res<TypedAudioChannels>>,
+    mut inspection: ResMut<AudioInspection>,
+) {
+    let mut channels: Vec<_> = audio_output
+        .inspected_channels()
+        .map(|(channel, channel_state, instances)| {
+            (
+                channel_name(channel, typed_channels.as_deref()),
+                channel_state,
+                instances,
+            )
+        })
+        .collect();
+    channels.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let inspection = inspection.as_mut();
+    inspection.channels.clear();
+    inspection.instances.clear();
+    let default_state = ChannelState::default();
+    for (name, channel_state, instances) in channels {
+        let channel_state = channel_state.unwrap_or(&default_state);
+        inspection.channels.push(ChannelInspection {
+            name: name.clone(),
+            volume: channel_state.volume.as_amplitude(),
+            panning: channel_state.panning,
+            playback_rate: channel_state.playback_rate,
+            paused: channel_state.paused,
+            muted: channel_state.muted,
+            instances: instances.len(),
+        });
+        for handle in instances {
+            let Some(instance) = audio_instances.get(handle) else {
+                continue;
+            };
+            inspection.instances.push(InstanceInspection {
+                instance: handle.clone_weak(),
+                channel: name.clone(),
+                state: instance.state(),
+                duration: instance.duration(),
+                playback_rate: instance.playback_rate,
+                volume: instance.volume.as_amplitude(),
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Audio, AudioControl, AudioPlugin, AudioSource};
+    use bevy::app::App;
+    use bevy::asset::AssetPlugin;
+    use bevy::ecs::reflect::AppTypeRegistry;
+    use bevy::prelude::MinimalPlugins;
+    use kira::dsp::Frame;
+    use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+    use std::any::TypeId;
+    use std::sync::Arc;
+
+    #[test]
+    fn mirrors_channels_and_instances() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            AudioPlugin::default(),
+        ));
+        let source = app
+            .world
+            .resource_mut::<Assets<AudioSource>>()
+            .add(AudioSource {
+                sound: StaticSoundData {
+                    sample_rate: 1_000,
+                    frames: Arc::from(vec![Frame::ZERO; 10_000]),
+                    settings: StaticSoundSettings::default(),
+                },
+                loop_region: None,
+                gain: 1.,
+            });
+        let audio = app.world.resource::<Audio>();
+        audio.set_volume(0.5);
+        let instance = audio.play(source).with_playback_rate(2.).handle();
+        app.update();
+
+        let inspection = app.world.resource::<AudioInspection>();
+        let main_track = inspection
+            .channels
+            .iter()
+            .find(|channel| channel.name == "MainTrack")
+            .unwrap();
+        assert_eq!(main_track.volume, 0.5);
+        assert_eq!(main_track.instances, 1);
+        assert_eq!(inspection.instances.len(), 1);
+        assert_eq!(inspection.instances[0].instance, instance);
+        assert_eq!(inspection.instances[0].channel, "MainTrack");
+        assert_eq!(inspection.instances[0].playback_rate, 2.);
+        assert_eq!(inspection.instances[0].duration, 10.);
+
+        let registry = app.world.resource::<AppTypeRegistry>().read();
+        assert!(registry
+            .get_type_data::<ReflectResource>(TypeId::of::<AudioInspection>())
+            .is_some());
+    }
+}
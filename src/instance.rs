@@ -1,14 +1,500 @@
+use crate::audio_output::AudioOutput;
+use crate::channel::Channel;
+use crate::modulator::{AudioModulator, AudioModulators, ModulatedParameter, Modulations};
+use crate::source::streamed::StreamError;
+use crate::spacial::interaural_delay::InterauralDelayHandle;
+use crate::volume::{finite_volume, scale_volume};
 use crate::{AudioTween, PlaybackState};
-use bevy::asset::{Asset, Assets, Handle};
+use bevy::asset::{Asset, Assets, Handle, UntypedHandle};
+use bevy::ecs::change_detection::{NonSendMut, ResMut};
+use bevy::ecs::event::{Event, EventWriter};
+use bevy::ecs::system::Res;
+use bevy::time::{Real, Time};
+use bevy::utils::{HashMap, HashSet, Instant};
+use kira::manager::error::PlaySoundError;
 use kira::sound::static_sound::StaticSoundHandle;
-use kira::tween::Value;
+use kira::sound::streaming::StreamingSoundHandle;
+use kira::sound::PlaybackRate;
+use kira::track::effect::filter::FilterHandle;
+use kira::track::{SetRouteError, TrackHandle, TrackId};
+use kira::tween::{Tween, Value};
 use kira::{CommandError, Volume};
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Asset, bevy::reflect::TypePath)]
 /// Asset for direct audio control
 pub struct AudioInstance {
-    pub(crate) handle: StaticSoundHandle,
+    pub(crate) handle: SoundHandle,
+    pub(crate) track: Option<InstanceTrack>,
+    pub(crate) base_volume: f64,
+    /// The last direct volume set by spacial audio relative to the base volume
+    pub(crate) spacial_volume: f64,
+    /// The volume to play at while the instance is not muted
+    pub(crate) volume: Volume,
+    /// Muted by its channel, see [`AudioControl::mute`](crate::AudioControl::mute)
+    pub(crate) muted: bool,
+    /// Factor of the volume while its channel is ducked, see [`AudioControl::duck_by`](crate::AudioControl::duck_by),
+    /// or adjusted by an [`AudioZone`](crate::AudioZone)
+    pub(crate) duck: f64,
+    /// Factor of the volume from the loudness normalization of the source, see
+    /// [`AudioSource::gain`](crate::AudioSource::gain)
+    pub(crate) gain: f64,
+    /// The channel the instance was played on
+    pub(crate) channel: Channel,
+    /// Played silent, waiting for spacial audio to set the volume
+    pub(crate) started_silent: bool,
+    /// Routed to a kira emitter by [`SpacialBackend::Kira`](crate::SpacialBackend::Kira)
+    pub(crate) in_kira_scene: bool,
+    /// Plays on the output device of its channel, see [`AudioControl::set_output_device`](crate::AudioControl::set_output_device)
+    pub(crate) on_output_device: bool,
+    /// Resume with the remaining time, see [`AudioInstance::pause_for`]
+    pub(crate) scheduled_resume: Option<(Duration, AudioTween)>,
+    /// The instance was told to stop, see [`PlaybackCompleted::stopped`]
+    pub(crate) stop_requested: bool,
+    /// The last playback rate the instance was given, ignoring running tweens
+    pub(crate) playback_rate: f64,
+    /// The last panning the instance was given, ignoring running tweens
+    pub(crate) panning: f64,
+    /// Modulators bound to the parameters, see [`AudioInstance::bind_modulator`]
+    pub(crate) modulations: Modulations,
+    /// Factor of the playback rate from the speed of virtual time, see
+    /// [`AudioSettings::follow_virtual_time`](crate::AudioSettings::follow_virtual_time)
+    pub(crate) time_scale: f64,
+    /// Factor of the playback rate from the Doppler effect of spacial audio, see
+    /// [`SpacialAudio::doppler`](crate::SpacialAudio::doppler)
+    pub(crate) doppler: f64,
+    /// Paused by [`CullMode::Pause`](crate::CullMode::Pause), cleared by pausing it by hand
+    pub(crate) culled: bool,
+    /// Where the played sound starts and ends, see [`AudioInstance::duration`]
+    pub(crate) timing: SoundTiming,
+    /// The end of the last volume fade, see [`AudioInstance::is_fading`]
+    pub(crate) fading_until: Option<Instant>,
+    /// The default resume tween of the channel, see [`AudioInstance::resume_with_default_fade`]
+    pub(crate) resume_tween: Option<AudioTween>,
+    /// The position of the last seek in seconds, taken by the loop tracker of looping instances
+    pub(crate) seek_target: Option<f64>,
+}
+
+/// The regions of a played sound in seconds
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct SoundTiming {
+    pub(crate) duration: f64,
+    /// Start and end of the playback region
+    pub(crate) region: (f64, f64),
+    pub(crate) reverse: bool,
+    pub(crate) loop_region: Option<(f64, f64)>,
+}
+
+/// Sent once an audio instance stopped playing
+///
+/// It is sent exactly once for every played instance, in the [`PreUpdate`](bevy::app::PreUpdate)
+/// schedule after the instance stopped. The [`AudioInstance`] asset is still available then.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn next_line(mut completed: EventReader<PlaybackCompleted>) {
+///     for event in completed.read() {
+///         if !event.stopped {
+///             // play the next line of dialogue
+///         }
+///     }
+/// }
+/// ```
+#[derive(Event, Clone, Debug)]
+pub struct PlaybackCompleted {
+    /// The instance that stopped
+    pub instance: Handle<AudioInstance>,
+    /// The channel the instance played on
+    pub channel: Channel,
+    /// The instance was stopped before the end of its sound, for example with
+    /// [`AudioInstance::stop`], [`AudioControl::stop`](crate::AudioControl::stop) or by the
+    /// instance limit of its channel
+    ///
+    /// `false` if the sound played until its end.
+    pub stopped: bool,
+}
+
+/// Sent each time a looping audio instance completes an iteration of its loop
+///
+/// The plugin compares the positions of looping instances once per frame. A loop that is shorter
+/// than a frame can complete several iterations in between. Only one event is sent then, and
+/// its `iteration` includes all of them. Sounds that don't loop never send this event.
+#[derive(Event, Clone, Debug)]
+pub struct LoopCompleted {
+    /// The looping instance
+    pub instance: Handle<AudioInstance>,
+    /// The number of completed loop iterations, starting at `1`
+    pub iteration: u64,
+}
+
+/// Follows the position of a looping instance, see [`LoopCompleted`]
+#[derive(Default)]
+pub(crate) struct LoopTracker {
+    /// Start and end of the loop region in seconds
+    region: Option<(f64, f64)>,
+    /// The sound was played with [`PlayAudioCommand::reverse`](crate::PlayAudioCommand::reverse)
+    reverse: bool,
+    last_position: f64,
+    iterations: u64,
+    /// The wrapped position of a seek and the number of updates left to wait for it
+    seek: Option<(f64, u8)>,
+}
+
+/// How far the first position after a seek may be off its target in seconds
+///
+/// The audio thread handles a seek with its next block of samples.
+const SEEK_TOLERANCE: f64 = 0.05;
+
+/// Updates after which a seek that was not seen yet no longer suppresses wraps
+const SEEK_UPDATES: u8 = 8;
+
+impl LoopTracker {
+    pub(crate) fn new(region: Option<(f64, f64)>, reverse: bool, position: f64) -> Self {
+        LoopTracker {
+            region,
+            reverse,
+            last_position: position,
+            iterations: 0,
+            seek: None,
+        }
+    }
+
+    /// Expect the position to jump to the given one without completing an iteration
+    pub(crate) fn seek(&mut self, target: f64) {
+        let Some((start, end)) = self.region else {
+            return;
+        };
+        let length = end - start;
+        // Kira wraps seeks past the loop end, and backward seeks before its start
+        let target = if length > 0. && (target >= end || target < start.min(self.last_position)) {
+            start + (target - start).rem_euclid(length)
+        } else {
+            target
+        };
+        self.seek = Some((target, SEEK_UPDATES));
+    }
+
+    pub(crate) fn is_looping(&self) -> bool {
+        self.region.is_some()
+    }
+
+    /// Count the loop iterations completed since the last update
+    ///
+    /// `advance` is the time of the sound that played since then, in seconds. It is negative
+    /// for negative playback rates. Returns the new number of iterations if any completed.
+    pub(crate) fn update(&mut self, position: f64, advance: f64) -> Option<u64> {
+        let last_position = std::mem::replace(&mut self.last_position, position);
+        if let Some((target, updates)) = self.seek.as_mut() {
+            // Positions before the seek reached the audio thread and the jump itself don't count
+            if (position - *target).abs() <= advance.abs() + SEEK_TOLERANCE || *updates == 0 {
+                self.seek = None;
+            } else {
+                *updates -= 1;
+            }
+            return None;
+        }
+        let advance = if self.reverse { -advance } else { advance };
+        let (start, end) = self.region?;
+        let length = end - start;
+        if length <= 0. {
+            return None;
+        }
+        // Without a wrap, the sound would be at the expected position
+        let expected = last_position + advance;
+        let mut wraps = ((expected - position) / length).round().abs();
+        let jumped_back = if advance >= 0. {
+            position < last_position
+        } else {
+            position > last_position
+        };
+        if jumped_back {
+            wraps = wraps.max(1.);
+        }
+        if wraps < 1. {
+            return None;
+        }
+        self.iterations += wraps as u64;
+
+        Some(self.iterations)
+    }
+}
+/// Played instances until they complete, see [`PlaybackCompleted`] and [`LoopCompleted`]
+#[derive(Default)]
+pub(crate) struct InstanceCompletions {
+    /// All played instances that did not complete yet with their channel
+    uncompleted: HashMap<Handle<AudioInstance>, Channel>,
+    /// Instances whose asset is removed once they stopped, see [`AudioControl::play_one_shot`](crate::AudioControl::play_one_shot)
+    one_shots: HashSet<Handle<AudioInstance>>,
+    /// Looping instances
+    loops: HashMap<Handle<AudioInstance>, LoopTracker>,
+}
+
+impl InstanceCompletions {
+    /// Follow a new instance until it completes
+    pub(crate) fn track(
+        &mut self,
+        instance: &Handle<AudioInstance>,
+        channel: &Channel,
+        one_shot: bool,
+        loops: LoopTracker,
+    ) {
+        self.uncompleted.insert(instance.clone(), channel.clone());
+        if one_shot {
+            self.one_shots.insert(instance.clone());
+        }
+        if loops.is_looping() {
+            self.loops.insert(instance.clone(), loops);
+        }
+    }
+
+    /// Whether the instance loops, see [`LoopCompleted`]
+    pub(crate) fn is_looping(&self, instance: &Handle<AudioInstance>) -> bool {
+        self.loops.contains_key(instance)
+    }
+
+    /// All instances that did not complete yet
+    pub(crate) fn uncompleted(&self) -> impl Iterator<Item = &Handle<AudioInstance>> {
+        self.uncompleted.keys()
+    }
+
+    /// Count the completed iterations of looping instances since the last update
+    ///
+    /// `delta` is the real time since then.
+    pub(crate) fn track_loops(
+        &mut self,
+        instances: &mut Assets<AudioInstance>,
+        delta: Duration,
+    ) -> Vec<LoopCompleted> {
+        let mut completed = vec![];
+        for (handle, loops) in self.loops.iter_mut() {
+            let Some(instance) = instances.get(handle) else {
+                continue;
+            };
+            if instance.seek_target.is_some() {
+                if let Some(target) = instances
+                    .get_mut(handle)
+                    .and_then(|instance| instance.seek_target.take())
+                {
+                    loops.seek(target);
+                }
+            }
+            let Some(instance) = instances.get(handle) else {
+                continue;
+            };
+            let position = instance.handle.position();
+            let advance = if instance.handle.state() == kira::sound::PlaybackState::Playing {
+                delta.as_secs_f64() * instance.scaled_playback_rate()
+            } else {
+                0.
+            };
+            if let Some(iteration) = loops.update(position, advance) {
+                completed.push(LoopCompleted {
+                    instance: handle.clone(),
+                    iteration,
+                });
+            }
+        }
+
+        completed
+    }
+
+    /// Forget stopped instances and return their completion events
+    ///
+    /// The assets of stopped one-shots are removed.
+    pub(crate) fn complete_stopped(
+        &mut self,
+        instances: &mut Assets<AudioInstance>,
+    ) -> Vec<PlaybackCompleted> {
+        let mut completed = vec![];
+        self.loops
+            .retain(|handle, _| !has_stopped(instances, handle));
+        self.uncompleted.retain(|handle, channel| {
+            if !has_stopped(instances, handle) {
+                return true;
+            }
+            completed.push(PlaybackCompleted {
+                instance: handle.clone(),
+                channel: channel.clone(),
+                stopped: instances
+                    .get(handle)
+                    .map_or(true, |instance| instance.stop_requested),
+            });
+            false
+        });
+        let stopped_one_shots: Vec<_> = self
+            .one_shots
+            .iter()
+            .filter(|handle| has_stopped(instances, handle))
+            .cloned()
+            .collect();
+        for handle in stopped_one_shots {
+            self.one_shots.remove(&handle);
+            instances.remove(&handle);
+        }
+        // The tracks of stopped instances would stay in the mixer until the asset is dropped
+        for PlaybackCompleted { instance, .. } in &completed {
+            if let Some(instance) = instances
+                .get_mut(instance)
+                .filter(|instance| instance.track.is_some())
+            {
+                instance.track = None;
+            }
+        }
+
+        completed
+    }
+}
+
+/// Whether the instance stopped, or its asset is gone
+pub(crate) fn has_stopped(
+    instances: &Assets<AudioInstance>,
+    instance: &Handle<AudioInstance>,
+) -> bool {
+    instances.get(instance).map_or(true, |instance| {
+        instance.handle.state() == kira::sound::PlaybackState::Stopped
+    })
+}
+
+/// Forget stopped instances and send [`PlaybackCompleted`] and [`LoopCompleted`] events
+pub(crate) fn cleanup_stopped_instances(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut instances: ResMut<Assets<AudioInstance>>,
+    mut completed: EventWriter<PlaybackCompleted>,
+    mut loops_completed: EventWriter<LoopCompleted>,
+    time: Res<Time<Real>>,
+) {
+    loops_completed.send_batch(audio_output.track_loops(&mut instances, time.delta()));
+    completed.send_batch(audio_output.cleanup_stopped_instances(&mut instances));
+}
+
+/// A sub track that only this instance plays on
+pub(crate) struct InstanceTrack {
+    /// The track is removed when this handle is dropped
+    pub(crate) handle: TrackHandle,
+    pub(crate) filter: Option<FilterHandle>,
+    /// The last cutoff the filter was given, which audio zones restore when they are left
+    pub(crate) cutoff: f64,
+    /// The track this track sends its direct signal to
+    pub(crate) direct_route: TrackId,
+    /// The reverb track this track sends part of its signal to
+    pub(crate) ambient_route: Option<TrackId>,
+    pub(crate) interaural_delay: Option<InterauralDelayHandle>,
+}
+
+/// The kira handle of a played sound
+pub(crate) enum SoundHandle {
+    Static(StaticSoundHandle),
+    /// Decoded while playing, see [`StreamedAudioSource`](crate::StreamedAudioSource)
+    Streamed(StreamingSoundHandle<StreamError>),
+    /// Played by an audio manager that was replaced, see [`AudioDevice`](crate::AudioDevice)
+    ///
+    /// The sound is gone, so the handle counts as stopped and ignores all commands.
+    Lost {
+        position: f64,
+    },
+}
+
+impl SoundHandle {
+    pub(crate) fn is_streamed(&self) -> bool {
+        matches!(self, SoundHandle::Streamed(_))
+    }
+
+    pub(crate) fn is_lost(&self) -> bool {
+        matches!(self, SoundHandle::Lost { .. })
+    }
+
+    pub(crate) fn state(&self) -> kira::sound::PlaybackState {
+        match self {
+            SoundHandle::Static(handle) => handle.state(),
+            SoundHandle::Streamed(handle) => handle.state(),
+            SoundHandle::Lost { .. } => kira::sound::PlaybackState::Stopped,
+        }
+    }
+
+    pub(crate) fn position(&self) -> f64 {
+        match self {
+            SoundHandle::Static(handle) => handle.position(),
+            SoundHandle::Streamed(handle) => handle.position(),
+            SoundHandle::Lost { position } => *position,
+        }
+    }
+
+    pub(crate) fn set_volume(
+        &mut self,
+        volume: impl Into<Value<Volume>>,
+        tween: Tween,
+    ) -> Result<(), CommandError> {
+        match self {
+            SoundHandle::Static(handle) => handle.set_volume(volume, tween),
+            SoundHandle::Streamed(handle) => handle.set_volume(volume, tween),
+            SoundHandle::Lost { .. } => Ok(()),
+        }
+    }
+
+    pub(crate) fn set_playback_rate(
+        &mut self,
+        playback_rate: impl Into<Value<PlaybackRate>>,
+        tween: Tween,
+    ) -> Result<(), CommandError> {
+        match self {
+            SoundHandle::Static(handle) => handle.set_playback_rate(playback_rate, tween),
+            SoundHandle::Streamed(handle) => handle.set_playback_rate(playback_rate, tween),
+            SoundHandle::Lost { .. } => Ok(()),
+        }
+    }
+
+    pub(crate) fn set_panning(
+        &mut self,
+        panning: impl Into<Value<f64>>,
+        tween: Tween,
+    ) -> Result<(), CommandError> {
+        match self {
+            SoundHandle::Static(handle) => handle.set_panning(panning, tween),
+            SoundHandle::Streamed(handle) => handle.set_panning(panning, tween),
+            SoundHandle::Lost { .. } => Ok(()),
+        }
+    }
+
+    pub(crate) fn pause(&mut self, tween: Tween) -> Result<(), CommandError> {
+        match self {
+            SoundHandle::Static(handle) => handle.pause(tween),
+            SoundHandle::Streamed(handle) => handle.pause(tween),
+            SoundHandle::Lost { .. } => Ok(()),
+        }
+    }
+
+    pub(crate) fn resume(&mut self, tween: Tween) -> Result<(), CommandError> {
+        match self {
+            SoundHandle::Static(handle) => handle.resume(tween),
+            SoundHandle::Streamed(handle) => handle.resume(tween),
+            SoundHandle::Lost { .. } => Ok(()),
+        }
+    }
+
+    pub(crate) fn stop(&mut self, tween: Tween) -> Result<(), CommandError> {
+        match self {
+            SoundHandle::Static(handle) => handle.stop(tween),
+            SoundHandle::Streamed(handle) => handle.stop(tween),
+            SoundHandle::Lost { .. } => Ok(()),
+        }
+    }
+
+    pub(crate) fn seek_to(&mut self, position: f64) -> Result<(), CommandError> {
+        match self {
+            SoundHandle::Static(handle) => handle.seek_to(position),
+            SoundHandle::Streamed(handle) => handle.seek_to(position),
+            SoundHandle::Lost { .. } => Ok(()),
+        }
+    }
+
+    pub(crate) fn seek_by(&mut self, amount: f64) -> Result<(), CommandError> {
+        match self {
+            SoundHandle::Static(handle) => handle.seek_by(amount),
+            SoundHandle::Streamed(handle) => handle.seek_by(amount),
+            SoundHandle::Lost { .. } => Ok(()),
+        }
+    }
 }
 
 /// Errors that can occur when directly controlling audio
@@ -21,6 +507,22 @@ pub enum AudioCommandError {
     /// Something went wrong when handling the command in the audio thread
     #[error("an error occurred while handling the command in the audio thread")]
     AudioThreadError,
+
+    /// The instance was not played with a filter
+    #[error("the audio instance has no filter")]
+    NoFilter,
+
+    /// The instance was not played with an ambient send
+    #[error("the audio instance has no ambient send")]
+    NoAmbientSend,
+
+    /// The modulator was not created yet or was removed, see [`AudioModulators`]
+    #[error("the modulator does not exist (yet)")]
+    NoModulator,
+
+    /// Streamed sounds can't play backwards, see [`StreamedAudioSource`](crate::StreamedAudioSource)
+    #[error("streamed sounds can't play in reverse or with a negative playback rate")]
+    NotSupportedForStreamedSound,
 }
 
 impl From<CommandError> for AudioCommandError {
@@ -32,92 +534,684 @@ impl From<CommandError> for AudioCommandError {
     }
 }
 
+impl From<SetRouteError> for AudioCommandError {
+    fn from(kira_error: SetRouteError) -> Self {
+        match kira_error {
+            SetRouteError::NonexistentRoute => AudioCommandError::NoAmbientSend,
+            SetRouteError::CommandError(command_error) => command_error.into(),
+        }
+    }
+}
+
+/// Sent when the plugin failed to run an audio command or rejected a sound
+///
+/// The failure is still logged as well. The event is sent in the
+/// [`PostUpdate`](bevy::app::PostUpdate) schedule of the frame the command was handled in.
+/// Sounds that wait for their source to load don't fail, they play once it is loaded.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn voice_line_errors(mut errors: EventReader<AudioError>) {
+///     for error in errors.read() {
+///         if error.instance.is_some() {
+///             // show the subtitle for a bit longer instead
+///         }
+///     }
+/// }
+/// ```
+#[derive(Event, Debug)]
+pub struct AudioError {
+    /// What went wrong
+    pub kind: AudioErrorKind,
+    /// The channel of the command
+    pub channel: Channel,
+    /// The source of a sound that was not played
+    ///
+    /// `None` for commands on playing instances and for sequences.
+    pub source: Option<UntypedHandle>,
+    /// The instance the command was for
+    ///
+    /// For a sound that was not played, this is the handle returned by
+    /// [`PlayAudioCommand::handle`](crate::PlayAudioCommand::handle). It never gets an asset.
+    pub instance: Option<Handle<AudioInstance>>,
+}
+
+impl AudioError {
+    pub(crate) fn new(
+        kind: impl Into<AudioErrorKind>,
+        channel: &Channel,
+        instance: Option<Handle<AudioInstance>>,
+    ) -> Self {
+        AudioError {
+            kind: kind.into(),
+            channel: channel.clone(),
+            source: None,
+            instance,
+        }
+    }
+}
+
+/// The kind of an [`AudioError`]
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum AudioErrorKind {
+    /// A command for the audio thread failed
+    #[error(transparent)]
+    Command(#[from] AudioCommandError),
+
+    /// Kira already plays as many sounds as its capacities allow
+    #[error("the maximum number of playing sounds is reached")]
+    SoundLimitReached,
+
+    /// The channel plays as many instances as its limit allows and rejects new ones,
+    /// see [`StealPolicy::RejectNew`](crate::StealPolicy::RejectNew)
+    #[error("the instance limit of the channel is reached")]
+    InstanceLimitReached,
+
+    /// The sound would start after the end of its playback region
+    #[error("the start position of the sound is after its end")]
+    StartsAfterEnd,
+
+    /// A streamed sound could not be decoded
+    #[error("the streamed sound could not be decoded: {0}")]
+    Decode(String),
+}
+
+impl From<CommandError> for AudioErrorKind {
+    fn from(kira_error: CommandError) -> Self {
+        AudioErrorKind::Command(kira_error.into())
+    }
+}
+
+impl<E: std::fmt::Debug> From<PlaySoundError<E>> for AudioErrorKind {
+    fn from(kira_error: PlaySoundError<E>) -> Self {
+        match kira_error {
+            PlaySoundError::SoundLimitReached => AudioErrorKind::SoundLimitReached,
+            PlaySoundError::CommandError(command_error) => command_error.into(),
+            PlaySoundError::IntoSoundError(error) => AudioErrorKind::Decode(format!("{:?}", error)),
+            _ => AudioErrorKind::Command(AudioCommandError::AudioThreadError),
+        }
+    }
+}
+
 impl AudioInstance {
+    /// Remember a volume fade with the given tween, see [`is_fading`](Self::is_fading)
+    ///
+    /// Tweens as short as the default one only avoid artifacts, so they don't count as fades.
+    pub(crate) fn start_fade(&mut self, tween: &Tween) {
+        if tween.duration <= Tween::default().duration {
+            return;
+        }
+        let until = Instant::now() + tween.duration;
+        self.fading_until = Some(self.fading_until.map_or(until, |fading| fading.max(until)));
+    }
+
     /// Pause the audio instance with the given easing
     pub fn pause(&mut self, tween: AudioTween) -> Option<AudioCommandError> {
+        self.scheduled_resume = None;
+        self.culled = false;
+        let tween = tween.into();
+        self.start_fade(&tween);
         self.handle
-            .pause(tween.into())
+            .pause(tween)
             .err()
             .map(|kira_error| kira_error.into())
     }
 
     /// Resume the audio instance with the given easing
     pub fn resume(&mut self, tween: AudioTween) -> Option<AudioCommandError> {
+        self.scheduled_resume = None;
+        let tween = tween.into();
+        self.start_fade(&tween);
         self.handle
-            .resume(tween.into())
+            .resume(tween)
             .err()
             .map(|kira_error| kira_error.into())
     }
 
+    /// Resume the audio instance with the default resume tween of its channel
+    ///
+    /// See [`AudioControl::set_default_resume_tween`](crate::AudioControl::set_default_resume_tween).
+    /// Without one, this is the same as resuming with [`AudioTween::default`].
+    pub fn resume_with_default_fade(&mut self) -> Option<AudioCommandError> {
+        self.resume(self.resume_tween.clone().unwrap_or_default())
+    }
+
+    /// Pause the audio instance now and resume it after the given time
+    ///
+    /// Both the pause and the resume fade with the given tween. The plugin resumes the
+    /// instance, unless [`resume`](Self::resume) or [`pause`](Self::pause) are called before.
+    /// Another `pause_for` replaces the scheduled resume.
+    pub fn pause_for(
+        &mut self,
+        duration: Duration,
+        tween: AudioTween,
+    ) -> Option<AudioCommandError> {
+        let error = self.pause(tween.clone());
+        self.scheduled_resume = Some((duration, tween));
+
+        error
+    }
+
     /// Stop the audio instance with the given easing
     pub fn stop(&mut self, tween: AudioTween) -> Option<AudioCommandError> {
+        self.stop_requested = true;
+        let tween = tween.into();
+        self.start_fade(&tween);
         self.handle
-            .stop(tween.into())
+            .stop(tween)
             .err()
             .map(|kira_error| kira_error.into())
     }
 
     /// Get the state of the audio instance
     pub fn state(&self) -> PlaybackState {
-        (&self.handle).into()
+        PlaybackState::new(self.handle.state(), self.handle.position())
+    }
+
+    /// The position in the sound in seconds
+    ///
+    /// It is updated each time the audio thread processes a batch of samples. Within an
+    /// iteration of a loop, it only moves in the direction the sound plays.
+    pub fn position(&self) -> f64 {
+        self.handle.position()
+    }
+
+    /// The duration of the played sound in seconds
+    ///
+    /// This is the [`AudioSource::duration`](crate::AudioSource::duration) of the played source,
+    /// or the [`StreamedAudioSource::duration`](crate::StreamedAudioSource::duration) read from
+    /// the header of a streamed file. For a [slice](crate::AudioSource::slice), it is the
+    /// duration of the whole sound, since positions count from its start.
+    pub fn duration(&self) -> f64 {
+        self.timing.duration
+    }
+
+    /// The time in seconds until the instance reaches the end of its sound
+    ///
+    /// This takes the end position, reversing and the last playback rate into account, but not
+    /// running tweens of the playback rate. Looping instances and instances with a playback rate
+    /// of `0` never end and return `None`.
+    pub fn remaining(&self) -> Option<f64> {
+        self.remaining_from(self.position())
+    }
+
+    /// The time in seconds from the given position until the end of the sound
+    pub(crate) fn remaining_from(&self, position: f64) -> Option<f64> {
+        let playback_rate = self.scaled_playback_rate();
+        if self.timing.loop_region.is_some() || playback_rate == 0. {
+            return None;
+        }
+        let (start, end) = self.timing.region;
+        let backwards = self.timing.reverse != (playback_rate < 0.);
+        let left = if backwards {
+            position - start
+        } else {
+            end - position
+        };
+
+        Some(left.max(0.) / playback_rate.abs())
+    }
+
+    /// The rate the sound plays at, with the speed of virtual time and the Doppler effect
+    pub(crate) fn scaled_playback_rate(&self) -> f64 {
+        self.playback_rate * self.time_scale * self.doppler
+    }
+
+    /// Scale the given playback rate by the speed of virtual time, keeping its unit
+    pub(crate) fn scale_rate(&self, rate: PlaybackRate) -> PlaybackRate {
+        scale_playback_rate(rate, self.time_scale)
+    }
+
+    /// Follow a new speed of virtual time
+    pub(crate) fn set_time_scale(
+        &mut self,
+        time_scale: f64,
+        tween: Tween,
+    ) -> Result<(), CommandError> {
+        self.time_scale = time_scale;
+        let rate = self.scale_rate(PlaybackRate::Factor(self.playback_rate));
+        self.set_handle_playback_rate(rate, tween)
+    }
+
+    /// Follow a new Doppler factor of spacial audio, keeping the playback rate of the instance
+    pub(crate) fn set_doppler(
+        &mut self,
+        doppler: f64,
+        tween: AudioTween,
+    ) -> Option<AudioCommandError> {
+        self.doppler = doppler;
+        let rate = self.scale_rate(PlaybackRate::Factor(self.playback_rate));
+        self.set_handle_playback_rate(rate, tween.into())
+            .err()
+            .map(|kira_error| kira_error.into())
+    }
+
+    /// Set the volume of the sound handle, oscillating around fixed volumes with a bound modulator
+    pub(crate) fn set_handle_volume(
+        &mut self,
+        volume: impl Into<Value<Volume>>,
+        tween: Tween,
+    ) -> Result<(), CommandError> {
+        let volume = self.modulations.volume(volume);
+        self.handle.set_volume(volume, tween)
+    }
+
+    /// Set the playback rate of the sound handle, shifted by the Doppler effect and oscillating
+    /// with a bound modulator
+    pub(crate) fn set_handle_playback_rate(
+        &mut self,
+        playback_rate: PlaybackRate,
+        tween: Tween,
+    ) -> Result<(), CommandError> {
+        let playback_rate = scale_playback_rate(playback_rate, self.doppler);
+        let playback_rate = self.modulations.playback_rate(playback_rate);
+        self.handle.set_playback_rate(playback_rate, tween)
+    }
+
+    /// Set the panning of the sound handle, oscillating with a bound modulator
+    pub(crate) fn set_handle_panning(
+        &mut self,
+        panning: f64,
+        tween: Tween,
+    ) -> Result<(), CommandError> {
+        self.panning = panning;
+        let panning = self.modulations.panning(panning);
+        self.handle.set_panning(panning, tween)
+    }
+
+    /// Start and end of the loop region in seconds, if the instance loops
+    pub fn loop_region(&self) -> Option<(f64, f64)> {
+        self.timing.loop_region
+    }
+
+    /// Returns `true` while the volume of the instance fades
+    ///
+    /// This covers fading in, fading out by pausing or stopping, and volume changes with a tween
+    /// longer than the default one, including changes to the channel.
+    pub fn is_fading(&self) -> bool {
+        matches!(
+            self.handle.state(),
+            kira::sound::PlaybackState::Pausing | kira::sound::PlaybackState::Stopping
+        ) || self
+            .fading_until
+            .map_or(false, |until| Instant::now() < until)
     }
 
     /// Set the volume of the audio instance
     ///
     /// Default is `1.0`
+    ///
+    /// If the channel of the instance is muted, the volume is applied once it is unmuted.
     pub fn set_volume(
         &mut self,
         volume: impl Into<Value<Volume>>,
         tween: AudioTween,
     ) -> Option<AudioCommandError> {
-        self.handle
-            .set_volume(volume, tween.into())
+        let mut volume = volume.into();
+        if let Value::Fixed(fixed) = volume {
+            self.volume = finite_volume(fixed);
+            volume = Value::Fixed(scale_volume(self.volume, self.duck * self.gain));
+        }
+        if self.muted {
+            return None;
+        }
+        let tween = tween.into();
+        self.start_fade(&tween);
+        self.set_handle_volume(volume, tween)
             .err()
             .map(|kira_error| kira_error.into())
     }
 
+    /// Silence the instance without forgetting its volume, or restore the volume
+    pub(crate) fn set_muted(
+        &mut self,
+        muted: bool,
+        tween: AudioTween,
+    ) -> Option<AudioCommandError> {
+        if self.muted == muted {
+            return None;
+        }
+        self.muted = muted;
+        let volume = if muted {
+            Volume::Amplitude(0.)
+        } else {
+            scale_volume(self.volume, self.duck * self.gain)
+        };
+        let tween = tween.into();
+        self.start_fade(&tween);
+        self.set_handle_volume(volume, tween)
+            .err()
+            .map(|kira_error| kira_error.into())
+    }
+
+    /// Scale the volume while the channel of the instance is ducked
+    pub(crate) fn set_duck(&mut self, duck: f64, tween: AudioTween) -> Option<AudioCommandError> {
+        self.duck = duck;
+        if self.muted {
+            return None;
+        }
+        let tween = tween.into();
+        self.start_fade(&tween);
+        self.set_handle_volume(scale_volume(self.volume, duck * self.gain), tween)
+            .err()
+            .map(|kira_error| kira_error.into())
+    }
+
+    /// The volume that spacial audio scales by distance and direction
+    ///
+    /// Defaults to the volume the instance was played with (including the channel volume).
+    pub fn base_volume(&self) -> f64 {
+        self.base_volume
+    }
+
+    /// Set the volume that spacial audio scales by distance and direction
+    ///
+    /// Use this instead of [`set_volume`](Self::set_volume) for instances on an
+    /// [`AudioEmitter`](crate::prelude::AudioEmitter), since spacial audio overwrites their volume.
+    pub fn set_base_volume(&mut self, volume: f64) {
+        self.base_volume = volume;
+    }
+
     /// Sets the playback rate of the sound.
     ///
     /// Changing the playback rate will change both the speed
-    /// and pitch of the sound.
+    /// and pitch of the sound. Negative playback rates play the sound backwards,
+    /// so tweening from `1.0` to `-1.0` slows the sound down and then rewinds it.
+    /// Streamed sounds can't play backwards and return
+    /// [`AudioCommandError::NotSupportedForStreamedSound`] for negative rates.
     pub fn set_playback_rate(
         &mut self,
         playback_rate: f64,
         tween: AudioTween,
     ) -> Option<AudioCommandError> {
-        self.handle
-            .set_playback_rate(playback_rate, tween.into())
+        if playback_rate < 0. && self.handle.is_streamed() {
+            return Some(AudioCommandError::NotSupportedForStreamedSound);
+        }
+        self.playback_rate = playback_rate;
+        self.set_handle_playback_rate(
+            self.scale_rate(PlaybackRate::Factor(playback_rate)),
+            tween.into(),
+        )
+        .err()
+        .map(|kira_error| kira_error.into())
+    }
+
+    /// Sets the pitch of the sound in semitones.
+    ///
+    /// This sets the playback rate to `2^(semitones / 12)`. Tweens interpolate in semitones,
+    /// so glides sound even. The current playback rate has to be positive for that.
+    pub fn set_pitch_semitones(
+        &mut self,
+        semitones: f64,
+        tween: AudioTween,
+    ) -> Option<AudioCommandError> {
+        let playback_rate = PlaybackRate::Semitones(semitones);
+        self.playback_rate = playback_rate.as_factor();
+        self.set_handle_playback_rate(self.scale_rate(playback_rate), tween.into())
             .err()
             .map(|kira_error| kira_error.into())
     }
 
+    /// The pitch of the sound in semitones, from the last playback rate it was given
+    ///
+    /// Playback rates of `0` and below have no pitch and return `NaN`.
+    pub fn pitch_semitones(&self) -> f64 {
+        if self.playback_rate <= 0. {
+            return f64::NAN;
+        }
+        PlaybackRate::Factor(self.playback_rate).as_semitones()
+    }
+
     /// Sets the panning of the sound
     ///
     /// `0.0` is hard left,
     /// `0.5` is center (default)
     /// `1.0` is hard right.
     pub fn set_panning(&mut self, panning: f64, tween: AudioTween) -> Option<AudioCommandError> {
-        self.handle
-            .set_panning(panning, tween.into())
+        self.set_handle_panning(panning, tween.into())
             .err()
             .map(|kira_error| kira_error.into())
     }
 
-    /// Sets the playback position to the specified time in seconds.
-    pub fn seek_to(&mut self, position: f64) -> Option<AudioCommandError> {
-        self.handle
-            .seek_to(position)
+    /// Let a modulator oscillate a parameter of the instance
+    ///
+    /// The parameter moves around the value it was given by the depth, see
+    /// [`ModulatedParameter`]. Binding another modulator to the same parameter replaces the first
+    /// one. Returns [`AudioCommandError::NoModulator`] if the modulator was not created yet,
+    /// which happens at the end of the frame it was added in, or if the instance plays on
+    /// another output device than the default one.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// #[derive(Resource)]
+    /// struct Tremolo(AudioModulator);
+    ///
+    /// fn start_tremolo(
+    ///     mut audio_instances: ResMut<Assets<AudioInstance>>,
+    ///     modulators: Res<AudioModulators>,
+    ///     tremolo: Res<Tremolo>,
+    /// ) {
+    ///     for (_, instance) in audio_instances.iter_mut() {
+    ///         instance.bind_modulator(&modulators, tremolo.0, ModulatedParameter::Volume, 0.3);
+    ///     }
+    /// }
+    /// ```
+    pub fn bind_modulator(
+        &mut self,
+        modulators: &AudioModulators,
+        modulator: AudioModulator,
+        parameter: ModulatedParameter,
+        depth: f64,
+    ) -> Option<AudioCommandError> {
+        let Some(id) = modulators.ids.get(&modulator) else {
+            return Some(AudioCommandError::NoModulator);
+        };
+        if self.on_output_device {
+            return Some(AudioCommandError::NoModulator);
+        }
+        self.modulations.bind(parameter, Some((*id, depth)));
+        self.apply_modulation(parameter)
+    }
+
+    /// Stop the modulation of the parameter, which returns to the value it oscillated around
+    pub fn unbind_modulator(&mut self, parameter: ModulatedParameter) -> Option<AudioCommandError> {
+        self.modulations.bind(parameter, None);
+        self.apply_modulation(parameter)
+    }
+
+    /// Hand the parameter with its modulation to the audio thread
+    fn apply_modulation(&mut self, parameter: ModulatedParameter) -> Option<AudioCommandError> {
+        let tween = Tween::default();
+        match parameter {
+            ModulatedParameter::Volume if self.muted => Ok(()),
+            ModulatedParameter::Volume => {
+                let volume = scale_volume(self.volume, self.duck * self.gain);
+                self.set_handle_volume(volume, tween)
+            }
+            ModulatedParameter::Panning => self.set_handle_panning(self.panning, tween),
+            ModulatedParameter::PlaybackRate => {
+                let rate = self.scale_rate(PlaybackRate::Factor(self.playback_rate));
+                self.set_handle_playback_rate(rate, tween)
+            }
+        }
+        .err()
+        .map(|kira_error| kira_error.into())
+    }
+
+    /// Returns `true` if the instance was played with a filter and did not stop yet
+    ///
+    /// See [`PlayAudioCommand::with_filter`](crate::PlayAudioCommand::with_filter)
+    pub fn has_filter(&self) -> bool {
+        self.track
+            .as_ref()
+            .map_or(false, |track| track.filter.is_some())
+    }
+
+    /// Sets the cutoff frequency of the instance's filter in Hz
+    ///
+    /// Returns [`AudioCommandError::NoFilter`] if the instance was not played with a filter.
+    pub fn set_filter_cutoff(
+        &mut self,
+        cutoff: f64,
+        tween: AudioTween,
+    ) -> Option<AudioCommandError> {
+        let Some(track) = self.track.as_mut().filter(|track| track.filter.is_some()) else {
+            return Some(AudioCommandError::NoFilter);
+        };
+        track.cutoff = cutoff;
+        track
+            .filter
+            .as_mut()
+            .unwrap()
+            .set_cutoff(cutoff, tween.into())
             .err()
             .map(|kira_error| kira_error.into())
     }
 
-    /// Moves the playback position by the specified amount of time in seconds.
-    pub fn seek_by(&mut self, amount: f64) -> Option<AudioCommandError> {
-        self.handle
-            .seek_by(amount)
+    /// Move the filter to the cutoff of an audio zone, or back to its own cutoff
+    pub(crate) fn set_zone_cutoff(
+        &mut self,
+        cutoff: Option<f64>,
+        tween: AudioTween,
+    ) -> Option<AudioCommandError> {
+        let track = self.track.as_mut()?;
+        let cutoff = cutoff.unwrap_or(track.cutoff);
+        track
+            .filter
+            .as_mut()?
+            .set_cutoff(cutoff, tween.into())
+            .err()
+            .map(|kira_error| kira_error.into())
+    }
+
+    /// Sets the resonance of the instance's filter
+    ///
+    /// Returns [`AudioCommandError::NoFilter`] if the instance was not played with a filter.
+    pub fn set_filter_resonance(
+        &mut self,
+        resonance: f64,
+        tween: AudioTween,
+    ) -> Option<AudioCommandError> {
+        let Some(filter) = self.track.as_mut().and_then(|track| track.filter.as_mut()) else {
+            return Some(AudioCommandError::NoFilter);
+        };
+        filter
+            .set_resonance(resonance, tween.into())
+            .err()
+            .map(|kira_error| kira_error.into())
+    }
+
+    /// Returns `true` if the instance sends part of its signal to the spacial reverb
+    ///
+    /// See [`PlayAudioCommand::with_ambient_send`](crate::PlayAudioCommand::with_ambient_send)
+    pub fn has_ambient_send(&self) -> bool {
+        self.track
+            .as_ref()
+            .map_or(false, |track| track.ambient_route.is_some())
+    }
+
+    /// Sets the volume of the signal sent to the spacial reverb
+    ///
+    /// Returns [`AudioCommandError::NoAmbientSend`] if the instance has no ambient send.
+    pub fn set_ambient_send(
+        &mut self,
+        volume: impl Into<Value<Volume>>,
+        tween: AudioTween,
+    ) -> Option<AudioCommandError> {
+        let Some((track, route)) = self
+            .track
+            .as_ref()
+            .and_then(|track| track.ambient_route.map(|route| (track, route)))
+        else {
+            return Some(AudioCommandError::NoAmbientSend);
+        };
+        track
+            .handle
+            .set_route(route, volume, tween.into())
             .err()
             .map(|kira_error| kira_error.into())
     }
+
+    /// Returns `true` if the instance was played with an interaural delay
+    ///
+    /// See [`PlayAudioCommand::with_interaural_delay`](crate::PlayAudioCommand::with_interaural_delay)
+    pub fn has_interaural_delay(&self) -> bool {
+        self.track
+            .as_ref()
+            .map_or(false, |track| track.interaural_delay.is_some())
+    }
+
+    /// Sets the interaural delay in seconds, positive values delay the left ear
+    ///
+    /// Does nothing for instances without an interaural delay.
+    pub(crate) fn set_interaural_delay(&mut self, delay: f32) {
+        if let Some(interaural_delay) = self
+            .track
+            .as_ref()
+            .and_then(|track| track.interaural_delay.as_ref())
+        {
+            interaural_delay.set_delay(delay);
+        }
+    }
+
+    /// Sets the direct and ambient volume computed by spacial audio relative to the base volume
+    ///
+    /// Instances without an ambient send only get the direct volume.
+    pub(crate) fn set_spacial_volume(
+        &mut self,
+        direct: f64,
+        ambient: f64,
+        tween: AudioTween,
+    ) -> Option<AudioCommandError> {
+        self.spacial_volume = direct;
+        let Some(track) = self
+            .track
+            .as_ref()
+            .filter(|track| track.ambient_route.is_some())
+        else {
+            return self.set_volume(self.base_volume * direct, tween);
+        };
+        // The sound keeps its base volume and the track splits it into direct and ambient parts
+        if let Err(kira_error) =
+            track
+                .handle
+                .set_route(track.direct_route, direct, tween.clone().into())
+        {
+            return Some(kira_error.into());
+        }
+        if let Some(error) = self.set_ambient_send(ambient, tween.clone()) {
+            return Some(error);
+        }
+        self.set_volume(self.base_volume, tween)
+    }
+
+    /// Sets the playback position to the specified time in seconds.
+    ///
+    /// Seeking outside of the playback region of a sound that does not loop stops it like
+    /// reaching its end. Looping sounds seeking past their loop region wrap around into it.
+    /// [`position`](Self::position) reports the new position once the audio thread handled the
+    /// seek, which can take a frame.
+    pub fn seek_to(&mut self, position: f64) -> Option<AudioCommandError> {
+        if let Err(kira_error) = self.handle.seek_to(position) {
+            return Some(kira_error.into());
+        }
+        self.seek_target = Some(position);
+
+        None
+    }
+
+    /// Moves the playback position by the specified amount of time in seconds.
+    ///
+    /// See [`seek_to`](Self::seek_to) for seeking outside of the sound.
+    pub fn seek_by(&mut self, amount: f64) -> Option<AudioCommandError> {
+        if let Err(kira_error) = self.handle.seek_by(amount) {
+            return Some(kira_error.into());
+        }
+        self.seek_target = Some(self.position() + amount);
+
+        None
+    }
 }
 
 /// Extension trait to remove some boilerplate when
@@ -138,3 +1232,66 @@ impl AudioInstanceAssetsExt for Assets<AudioInstance> {
             .unwrap_or(PlaybackState::Stopped)
     }
 }
+
+/// Multiply a playback rate by a factor, keeping its unit
+fn scale_playback_rate(rate: PlaybackRate, factor: f64) -> PlaybackRate {
+    if factor == 1. {
+        return rate;
+    }
+    match rate {
+        PlaybackRate::Factor(rate) => PlaybackRate::Factor(rate * factor),
+        PlaybackRate::Semitones(_) if factor <= 0. => PlaybackRate::Factor(0.),
+        PlaybackRate::Semitones(semitones) => {
+            PlaybackRate::Semitones(semitones + 12. * factor.log2())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::LoopTracker;
+
+    #[test]
+    fn loop_tracker_counts_wraps_of_short_loops() {
+        // Loop from 1 to 1.1 seconds, starting at 0.5 seconds
+        let mut loops = LoopTracker::new(Some((1., 1.1)), false, 0.5);
+        assert_eq!(loops.update(0.9, 0.4), None);
+        assert_eq!(loops.update(1.05, 0.15), None);
+        // Crossed the loop point
+        assert_eq!(loops.update(1.01, 0.06), Some(1));
+        // Three wraps within a single update
+        assert_eq!(loops.update(1.02, 0.31), Some(4));
+        // Paused
+        assert_eq!(loops.update(1.02, 0.), None);
+    }
+
+    #[test]
+    fn loop_tracker_follows_reversed_sounds() {
+        let mut loops = LoopTracker::new(Some((0., 1.)), true, 1.);
+        assert_eq!(loops.update(0.2, 0.8), None);
+        assert_eq!(loops.update(0.9, 0.3), Some(1));
+        // A negative playback rate plays a reversed sound forwards again
+        assert_eq!(loops.update(0.1, -0.2), Some(2));
+    }
+
+    #[test]
+    fn loop_tracker_ignores_seeks() {
+        let mut loops = LoopTracker::new(Some((0., 1.)), false, 0.2);
+        loops.seek(0.1);
+        // The audio thread did not handle the seek yet
+        assert_eq!(loops.update(0.3, 0.1), None);
+        assert_eq!(loops.update(0.12, 0.1), None);
+        assert_eq!(loops.update(0.5, 0.38), None);
+        // Seeking past the loop end wraps into the region
+        loops.seek(1.7);
+        assert_eq!(loops.update(0.72, 0.02), None);
+        assert_eq!(loops.update(0.1, 0.38), Some(1));
+    }
+
+    #[test]
+    fn loop_tracker_ignores_sounds_without_loop() {
+        let mut loops = LoopTracker::new(None, false, 0.);
+        assert_eq!(loops.update(0.5, 0.5), None);
+        assert_eq!(loops.update(0.1, 0.5), None);
+    }
+}
@@ -0,0 +1,373 @@
+//! Adaptive music made of stems that start on the same sample and fade individually
+
+use crate::audio::{AudioTween, PartialSoundSettings};
+use crate::audio_output::AudioOutput;
+use crate::channel::Channel;
+use crate::clock::ScheduledStart;
+use crate::instance::{AudioCommandError, AudioInstance};
+use crate::source::AudioSource;
+use crate::MainTrack;
+use bevy::asset::{Assets, Handle};
+use bevy::ecs::system::{NonSendMut, Res, ResMut, Resource};
+use bevy::log::{error, warn};
+use bevy::utils::{HashMap, HashSet};
+use kira::manager::backend::Backend;
+use kira::sound::PlaybackState;
+use kira::Volume;
+use std::collections::VecDeque;
+
+/// Layers whose offset to the first layer stays below this many seconds are left alone
+pub(crate) const LAYER_SYNC_TOLERANCE: f64 = 0.002;
+
+/// A group of stems played with [`MusicLayers::play_layers`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct LayeredMusicHandle(u64);
+
+pub(crate) enum LayerCommand {
+    Play {
+        music: LayeredMusicHandle,
+        channel: Channel,
+        stems: Vec<Handle<AudioSource>>,
+    },
+    SetLayerVolume(LayeredMusicHandle, usize, Volume, AudioTween),
+    Pause(LayeredMusicHandle, AudioTween),
+    Resume(LayeredMusicHandle, AudioTween),
+    Stop(LayeredMusicHandle, AudioTween),
+}
+
+impl LayerCommand {
+    pub(crate) fn music(&self) -> LayeredMusicHandle {
+        match self {
+            LayerCommand::Play { music, .. }
+            | LayerCommand::SetLayerVolume(music, ..)
+            | LayerCommand::Pause(music, _)
+            | LayerCommand::Resume(music, _)
+            | LayerCommand::Stop(music, _) => *music,
+        }
+    }
+}
+
+/// The instances of a started group
+pub(crate) struct LayerGroup {
+    pub(crate) instances: Vec<Handle<AudioInstance>>,
+    /// The offset of every layer to the first one in the last sync
+    offsets: Vec<Option<f64>>,
+}
+
+impl LayerGroup {
+    pub(crate) fn new(instances: Vec<Handle<AudioInstance>>) -> Self {
+        LayerGroup {
+            offsets: vec![None; instances.len()],
+            instances,
+        }
+    }
+
+    /// Run the same command on all layers in the same frame
+    pub(crate) fn control(
+        &self,
+        audio_instances: &mut Assets<AudioInstance>,
+        control: impl Fn(&mut AudioInstance) -> Option<AudioCommandError>,
+    ) {
+        for instance in self.instances.iter() {
+            let Some(instance) = audio_instances.get_mut(instance) else {
+                continue;
+            };
+            if let Some(error) = control(instance) {
+                error!("Failed to control music layer: {:?}", error);
+            }
+        }
+    }
+}
+
+/// Music delivered as stems, like drums, pads and lead, that play as one looping piece
+///
+/// All stems of a group wait until every one of them loaded and are then scheduled on the same
+/// tick of the [`AudioClock`](crate::AudioClock), so they start on the same sample. Each layer
+/// can fade on its own with [`set_layer_volume`](Self::set_layer_volume), for example to follow
+/// the intensity of the game, while pausing, resuming and stopping always affects the whole
+/// group. Like channel commands, the commands run at the end of the frame.
+///
+/// Once started, the layers advance by the same time for every sample, so they can only drift
+/// apart if a command reaches the audio thread for one layer a batch of samples earlier than for
+/// another, for example a pause, a resume or a changed playback rate of their channel. The plugin
+/// compares the positions of the layers every frame and moves layers that are off by more than
+/// two milliseconds back to the first layer. Since the audio thread publishes the positions one
+/// after the other, an offset has to be seen in two updates in a row before it is corrected.
+/// Stems should have the same duration, since every layer loops on its own.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// #[derive(Resource)]
+/// struct Music(LayeredMusicHandle);
+///
+/// fn start_music(
+///     mut commands: Commands,
+///     mut layers: ResMut<MusicLayers>,
+///     asset_server: Res<AssetServer>,
+/// ) {
+///     let music = layers.play_layers(&[
+///         asset_server.load("music/drums.ogg"),
+///         asset_server.load("music/pads.ogg"),
+///         asset_server.load("music/lead.ogg"),
+///     ]);
+///     // Only bring in the lead once the action starts
+///     layers.set_layer_volume(music, 2, 0., AudioTween::default());
+///     commands.insert_resource(Music(music));
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub struct MusicLayers {
+    next: u64,
+    pub(crate) commands: VecDeque<LayerCommand>,
+    pub(crate) groups: HashMap<LayeredMusicHandle, LayerGroup>,
+}
+
+impl MusicLayers {
+    /// Play the stems as layers of one looping piece in the main channel
+    pub fn play_layers(&mut self, stems: &[Handle<AudioSource>]) -> LayeredMusicHandle {
+        self.play_layers_in(Channel::typed::<MainTrack>(), stems)
+    }
+
+    /// Play the stems as layers of one looping piece in the given channel
+    ///
+    /// The volume, panning and playback rate of the channel apply to all layers.
+    pub fn play_layers_in(
+        &mut self,
+        channel: Channel,
+        stems: &[Handle<AudioSource>],
+    ) -> LayeredMusicHandle {
+        let music = LayeredMusicHandle(self.next);
+        self.next += 1;
+        self.commands.push_back(LayerCommand::Play {
+            music,
+            channel,
+            stems: stems.to_vec(),
+        });
+
+        music
+    }
+
+    /// Fade the layer with the given index in the stems to a volume
+    pub fn set_layer_volume(
+        &mut self,
+        music: LayeredMusicHandle,
+        index: usize,
+        volume: impl Into<Volume>,
+        tween: AudioTween,
+    ) {
+        self.commands.push_back(LayerCommand::SetLayerVolume(
+            music,
+            index,
+            volume.into(),
+            tween,
+        ));
+    }
+
+    /// Pause all layers
+    pub fn pause(&mut self, music: LayeredMusicHandle, tween: AudioTween) {
+        self.commands.push_back(LayerCommand::Pause(music, tween));
+    }
+
+    /// Resume all layers
+    pub fn resume(&mut self, music: LayeredMusicHandle, tween: AudioTween) {
+        self.commands.push_back(LayerCommand::Resume(music, tween));
+    }
+
+    /// Stop all layers and forget the group
+    pub fn stop(&mut self, music: LayeredMusicHandle, tween: AudioTween) {
+        self.commands.push_back(LayerCommand::Stop(music, tween));
+    }
+
+    /// The instances of the layers in the order of the stems, once the group started
+    pub fn instances(&self, music: LayeredMusicHandle) -> Option<&[Handle<AudioInstance>]> {
+        self.groups
+            .get(&music)
+            .map(|group| group.instances.as_slice())
+    }
+
+    /// Start groups once all their stems loaded and run the commands
+    pub(crate) fn run_commands<B: Backend>(
+        &mut self,
+        audio_output: &mut AudioOutput<B>,
+        audio_sources: &Assets<AudioSource>,
+        audio_instances: &mut Assets<AudioInstance>,
+    ) {
+        if !audio_output.has_manager() {
+            return;
+        }
+        let mut waiting = HashSet::default();
+        let mut commands_to_retry = VecDeque::new();
+        while let Some(command) = self.commands.pop_front() {
+            let music = command.music();
+            if waiting.contains(&music) {
+                commands_to_retry.push_back(command);
+                continue;
+            }
+            match &command {
+                LayerCommand::Play { channel, stems, .. } => {
+                    let sources = stems
+                        .iter()
+                        .map(|stem| audio_sources.get(stem))
+                        .collect::<Option<Vec<_>>>();
+                    let Some((sources, start)) = sources.zip(audio_output.synced_start()) else {
+                        waiting.insert(music);
+                        commands_to_retry.push_back(command);
+                        continue;
+                    };
+                    if sources
+                        .windows(2)
+                        .any(|pair| pair[0].duration() != pair[1].duration())
+                    {
+                        warn!("The stems of layered music have different durations, so their loops drift apart");
+                    }
+                    // All stems start on this tick of the audio clock
+                    let settings = PartialSoundSettings {
+                        loop_start: Some(0.),
+                        start_time: Some(ScheduledStart::At(start)),
+                        ..Default::default()
+                    };
+                    let instances = stems
+                        .iter()
+                        .filter_map(|stem| {
+                            audio_output.play_source(
+                                channel,
+                                &settings,
+                                stem,
+                                audio_sources,
+                                audio_instances,
+                            )
+                        })
+                        .collect();
+                    self.groups.insert(music, LayerGroup::new(instances));
+                }
+                LayerCommand::SetLayerVolume(_, index, volume, tween) => {
+                    let layer = self
+                        .groups
+                        .get(&music)
+                        .and_then(|group| group.instances.get(*index));
+                    if let Some(instance) = layer.and_then(|layer| audio_instances.get_mut(layer)) {
+                        if let Some(error) = instance.set_volume(*volume, tween.clone()) {
+                            error!("Failed to set volume of music layer: {:?}", error);
+                        }
+                    }
+                }
+                LayerCommand::Pause(_, tween) => {
+                    if let Some(group) = self.groups.get(&music) {
+                        group.control(audio_instances, |instance| instance.pause(tween.clone()));
+                    }
+                }
+                LayerCommand::Resume(_, tween) => {
+                    if let Some(group) = self.groups.get(&music) {
+                        group.control(audio_instances, |instance| instance.resume(tween.clone()));
+                    }
+                }
+                LayerCommand::Stop(_, tween) => {
+                    if let Some(group) = self.groups.remove(&music) {
+                        group.control(audio_instances, |instance| instance.stop(tween.clone()));
+                    }
+                }
+            }
+        }
+        self.commands = commands_to_retry;
+    }
+
+    /// Move layers that drifted away from the first layer back to it
+    pub(crate) fn sync(&mut self, audio_instances: &mut Assets<AudioInstance>) {
+        self.groups.retain(|_, group| {
+            group.instances.iter().any(|instance| {
+                audio_instances.get(instance).map_or(false, |instance| {
+                    instance.handle.state() != PlaybackState::Stopped
+                })
+            })
+        });
+        for group in self.groups.values_mut() {
+            let Some(leader) = group
+                .instances
+                .first()
+                .and_then(|instance| audio_instances.get(instance))
+            else {
+                continue;
+            };
+            if leader.handle.state() != PlaybackState::Playing {
+                group.offsets.fill(None);
+                continue;
+            }
+            let leader_position = leader.position();
+            let loop_duration = leader.loop_region().map(|(start, end)| end - start);
+            for (instance, last_offset) in
+                group.instances.iter().zip(group.offsets.iter_mut()).skip(1)
+            {
+                let Some(instance) = audio_instances.get_mut(instance) else {
+                    continue;
+                };
+                if instance.handle.state() != PlaybackState::Playing {
+                    *last_offset = None;
+                    continue;
+                }
+                let offset = layer_offset(leader_position, instance.position(), loop_duration);
+                if offset.abs() <= LAYER_SYNC_TOLERANCE {
+                    *last_offset = None;
+                    continue;
+                }
+                match *last_offset {
+                    Some(last) if (offset - last).abs() <= LAYER_SYNC_TOLERANCE => {
+                        // Relative to where the layer is when the audio thread gets the seek
+                        instance.seek_by(offset);
+                        *last_offset = None;
+                    }
+                    _ => *last_offset = Some(offset),
+                }
+            }
+        }
+    }
+}
+
+/// How far the layer is behind the leader in seconds, the shorter way around their loop
+pub(crate) fn layer_offset(leader: f64, layer: f64, loop_duration: Option<f64>) -> f64 {
+    let offset = leader - layer;
+    match loop_duration {
+        Some(duration) if duration > 0. => {
+            (offset + duration / 2.).rem_euclid(duration) - duration / 2.
+        }
+        _ => offset,
+    }
+}
+
+/// Start the groups of [`MusicLayers`] and run their commands
+pub(crate) fn play_music_layers(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut layers: ResMut<MusicLayers>,
+    audio_sources: Res<Assets<AudioSource>>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    if layers.commands.is_empty() {
+        return;
+    }
+    layers.run_commands(&mut audio_output, &audio_sources, &mut audio_instances);
+}
+
+/// Keep the layers of all groups in sync
+pub(crate) fn sync_music_layers(
+    mut layers: ResMut<MusicLayers>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    if layers.groups.is_empty() {
+        return;
+    }
+    layers.sync(&mut audio_instances);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn offsets_take_the_shorter_way_around_the_loop() {
+        assert_eq!(layer_offset(1.5, 1.25, None), 0.25);
+        assert_eq!(layer_offset(1.25, 1.5, None), -0.25);
+        // The leader already looped, the layer did not yet
+        assert!((layer_offset(0.1, 3.9, Some(4.)) - 0.2).abs() < 1e-9);
+        assert!((layer_offset(3.9, 0.1, Some(4.)) + 0.2).abs() < 1e-9);
+        assert!((layer_offset(2., 1., Some(4.)) - 1.).abs() < 1e-9);
+    }
+}
@@ -16,7 +16,7 @@
 //! # /*
 //!         .add_plugins(DefaultPlugins)
 //! # */
-//!         .add_plugins(AudioPlugin)
+//!         .add_plugins(AudioPlugin::default())
 //! #       .add_systems(Update, stop)
 //!         .add_systems(Startup, start_background_audio)
 //!         .run();
@@ -38,42 +38,169 @@ mod audio;
 mod audio_output;
 mod backend_settings;
 mod channel;
+mod clock;
+mod diagnostics;
+#[cfg(feature = "inspection")]
+mod inspection;
 mod instance;
+mod layers;
+mod main_playback;
+#[cfg(feature = "mock-backend")]
+mod mock;
+mod modulator;
+mod output_device;
 mod source;
 mod spacial;
+mod variation;
+mod volume;
 
 pub use audio::{
-    AudioApp, AudioEasing, AudioTween, FadeIn, FadeOut, PlayAudioCommand, PlaybackState,
-    TweenCommand,
+    AudioApp, AudioEasing, AudioTween, CrossfadeCurve, FadeIn, FadeOut, FilterSettings,
+    PlayAudioCommand, PlaybackState, TweenCommand,
 };
-pub use backend_settings::AudioSettings;
-use bevy::app::{PostUpdate, PreUpdate};
+pub use backend_settings::{AudioSettings, FocusLoss};
+use bevy::app::{Last, PostUpdate, PreUpdate, Startup};
 use bevy::asset::AssetApp;
+use bevy::ecs::event::Events;
+use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+use bevy::window::WindowFocused;
 pub use channel::AudioControl;
-pub use source::AudioSource;
-use spacial::cleanup_stopped_spacial_instances;
+pub use clock::{AudioClock, AudioClockId, AudioClockTime, AudioClocks, BeatEvent};
+pub use diagnostics::AudioDiagnosticsPlugin;
+pub use layers::{LayeredMusicHandle, MusicLayers};
+pub use modulator::{AudioModulator, AudioModulators, LfoSettings, ModulatedParameter};
+pub use output_device::{
+    available_output_devices, output_device_names, AudioBackendFailed, AudioBackendStatus,
+    AudioContextState, AudioDevice, AudioDeviceChanged, AudioDeviceFailed, AudioDeviceInfo,
+    AudioDeviceLost, AudioDevices, OutputDeviceError,
+};
+pub use source::bank::{AudioBank, AudioBankError, BankSound};
+pub use source::collection::{AudioCollection, SelectionMode};
+#[cfg(any(
+    feature = "aiff",
+    feature = "flac",
+    feature = "mp3",
+    feature = "ogg",
+    feature = "wav"
+))]
+pub use source::decode::{DecodeProgress, DecodeStatus};
+pub use source::streamed::StreamedAudioSource;
+pub use source::{AudioLoaderSettings, AudioSource, FromFramesError, SliceError, TargetLoudness};
+use spacial::despawn::despawn_on_audio_complete;
+pub use spacial::despawn::DespawnOnAudioComplete;
+use spacial::scene::play_spacial_audio_sources;
+pub use spacial::scene::SpacialAudioSource;
+use spacial::zone::update_audio_zones;
+pub use spacial::zone::{AudioZone, ChannelAdjustment, ReverbZone, ZoneExtent};
+use spacial::{cleanup_stopped_spacial_instances, stop_removed_emitter_instances};
+pub use spacial::{
+    AttenuationModel, CullMode, Doppler, EmitterCone, OcclusionFactor, PanningLaw, PrimaryReceiver,
+    ReceiverSelection, SpacialAttenuationFn, SpacialBackend, SpacialMode, SpacialOutput,
+    SpacialReverb, SpacialVelocity,
+};
+pub use variation::AudioRng;
+pub use volume::MasterVolume;
+
+#[cfg(feature = "inspection")]
+pub use inspection::{AudioInspection, ChannelInspection, InstanceInspection};
+#[cfg(feature = "mock-backend")]
+pub use mock::{LoggedAudioCommand, MockAudio, MockAudioCommand, MockPlay};
+#[cfg(feature = "debug")]
+pub use spacial::debug::{SpacialAudioDebug, SpacialEmitterDebug};
+#[cfg(feature = "propagation")]
+use spacial::propagation::propagate_sound;
+#[cfg(feature = "propagation")]
+pub use spacial::propagation::{AudioOccluder, AudioPortal};
 
 /// Most commonly used types
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::audio::{
-        AudioApp, AudioEasing, AudioTween, FadeIn, FadeOut, PlayAudioCommand, PlaybackState,
-        TweenCommand,
+        AudioApp, AudioEasing, AudioTween, CrossfadeCurve, FadeIn, FadeOut, FilterSettings,
+        PlayAudioCommand, PlaybackState, TweenCommand,
     };
     #[doc(hidden)]
-    pub use crate::backend_settings::AudioSettings;
+    pub use crate::backend_settings::{AudioSettings, FocusLoss};
+    #[doc(hidden)]
+    pub use crate::channel::ducking::DuckSettings;
     #[doc(hidden)]
     pub use crate::channel::dynamic::{DynamicAudioChannel, DynamicAudioChannels};
     #[doc(hidden)]
+    pub use crate::channel::effects::{
+        ChannelEffects, ChannelReverb, EffectChain, MainEffects, MainTrackEffects,
+    };
+    #[doc(hidden)]
+    pub use crate::channel::hierarchy::{ChannelHierarchy, ChannelHierarchyError};
+    #[doc(hidden)]
+    pub use crate::channel::queue::{QueueAdvanced, QueueRepeat};
+    #[doc(hidden)]
+    pub use crate::channel::snapshot::{ChannelMix, ChannelStates};
+    #[doc(hidden)]
     pub use crate::channel::typed::AudioChannel;
     #[doc(hidden)]
-    pub use crate::channel::AudioControl;
+    pub use crate::channel::{AudioControl, ChannelSettings, StealPolicy};
+    #[doc(hidden)]
+    pub use crate::clock::{AudioClock, AudioClockId, AudioClockTime, AudioClocks, BeatEvent};
+    #[doc(hidden)]
+    pub use crate::diagnostics::AudioDiagnosticsPlugin;
+    #[cfg(feature = "inspection")]
+    #[doc(hidden)]
+    pub use crate::inspection::AudioInspection;
     #[doc(hidden)]
-    pub use crate::instance::{AudioCommandError, AudioInstance, AudioInstanceAssetsExt};
+    pub use crate::instance::{
+        AudioCommandError, AudioError, AudioErrorKind, AudioInstance, AudioInstanceAssetsExt,
+        LoopCompleted, PlaybackCompleted,
+    };
+    #[doc(hidden)]
+    pub use crate::layers::{LayeredMusicHandle, MusicLayers};
+    #[cfg(feature = "mock-backend")]
     #[doc(hidden)]
-    pub use crate::source::AudioSource;
+    pub use crate::mock::MockAudio;
     #[doc(hidden)]
-    pub use crate::spacial::{AudioEmitter, AudioReceiver, SpacialAudio};
+    pub use crate::modulator::{AudioModulator, AudioModulators, LfoSettings, ModulatedParameter};
+    #[doc(hidden)]
+    pub use crate::output_device::{
+        AudioBackendFailed, AudioBackendStatus, AudioContextState, AudioDevice, AudioDeviceChanged,
+        AudioDeviceFailed, AudioDeviceInfo, AudioDeviceLost, AudioDevices,
+    };
+    #[doc(hidden)]
+    pub use crate::source::bank::{AudioBank, AudioBankError, BankSound};
+    #[doc(hidden)]
+    pub use crate::source::collection::{AudioCollection, SelectionMode};
+    #[doc(hidden)]
+    #[cfg(any(
+        feature = "aiff",
+        feature = "flac",
+        feature = "mp3",
+        feature = "ogg",
+        feature = "wav"
+    ))]
+    pub use crate::source::decode::{DecodeProgress, DecodeStatus};
+    #[doc(hidden)]
+    pub use crate::source::streamed::StreamedAudioSource;
+    #[doc(hidden)]
+    pub use crate::source::{
+        AudioLoaderSettings, AudioSource, FromFramesError, SliceError, TargetLoudness,
+    };
+    #[doc(hidden)]
+    pub use crate::spacial::despawn::DespawnOnAudioComplete;
+    #[cfg(feature = "propagation")]
+    #[doc(hidden)]
+    pub use crate::spacial::propagation::{AudioOccluder, AudioPortal};
+    #[doc(hidden)]
+    pub use crate::spacial::scene::SpacialAudioSource;
+    #[doc(hidden)]
+    pub use crate::spacial::zone::{AudioZone, ChannelAdjustment, ReverbZone, ZoneExtent};
+    #[doc(hidden)]
+    pub use crate::spacial::{
+        AttenuationModel, AudioEmitter, AudioReceiver, CullMode, Doppler, EmitterCone,
+        OcclusionFactor, PanningLaw, PrimaryReceiver, ReceiverSelection, SpacialAudio,
+        SpacialBackend, SpacialMode, SpacialOutput, SpacialVelocity,
+    };
+    #[doc(hidden)]
+    pub use crate::variation::AudioRng;
+    #[doc(hidden)]
+    pub use crate::volume::MasterVolume;
     #[doc(hidden)]
     pub use crate::{Audio, AudioPlugin, MainTrack};
     pub use kira::{
@@ -86,8 +213,29 @@ pub mod prelude {
     };
 }
 
-use crate::audio_output::{cleanup_stopped_instances, play_dynamic_channels, AudioOutput};
+use crate::audio_output::{
+    advance_headless_audio, close_on_exit, play_dynamic_channels, recover_audio_device,
+    report_audio_errors, resume_paused_audio, switch_audio_device, sync_kira_spacial_scene,
+    unlock_audio_context, update_channel_buses, update_dynamic_instance_states,
+    update_main_effects, update_queues, update_spacial_reverb, AudioOutput,
+};
+use crate::channel::ducking::update_ducking;
 
+use crate::clock::{send_beat_events, update_audio_clocks};
+#[cfg(feature = "inspection")]
+use crate::inspection::update_audio_inspection;
+use crate::instance::cleanup_stopped_instances;
+use crate::layers::{play_music_layers, sync_music_layers};
+use crate::main_playback::{follow_virtual_time, follow_window_focus, update_master_volume};
+use crate::modulator::update_audio_modulators;
+use crate::output_device::{refresh_audio_devices, report_audio_backend};
+#[cfg(feature = "aiff")]
+use crate::source::aiff_loader::AiffLoader;
+#[cfg(feature = "bank")]
+use crate::source::bank_loader::AudioBankLoader;
+use crate::source::collection::update_audio_collections;
+#[cfg(feature = "bank")]
+use crate::source::collection_loader::AudioCollectionLoader;
 #[cfg(feature = "flac")]
 use crate::source::flac_loader::FlacLoader;
 #[cfg(feature = "mp3")]
@@ -96,15 +244,39 @@ use crate::source::mp3_loader::Mp3Loader;
 use crate::source::ogg_loader::OggLoader;
 #[cfg(feature = "settings_loader")]
 use crate::source::settings_loader::SettingsLoader;
+#[cfg(any(feature = "flac", feature = "mp3", feature = "ogg", feature = "wav"))]
+use crate::source::streamed_loader::StreamedLoader;
 #[cfg(feature = "wav")]
 use crate::source::wav_loader::WavLoader;
-use crate::spacial::{run_spacial_audio, SpacialAudio};
-use bevy::prelude::{resource_exists, App, IntoSystemConfigs, Plugin, Resource, SystemSet};
+#[cfg(feature = "debug")]
+use crate::spacial::debug::{draw_spacial_audio_gizmos, record_spacial_audio_debug};
+#[cfg(debug_assertions)]
+use crate::spacial::warn_about_shared_instances;
+use crate::spacial::{
+    reset_disabled_spacial_audio, run_doppler_effect, run_spacial_audio, spacial_audio_enabled,
+    AudioEmitter, AudioReceiver, SpacialAudio, SpacialAudioState,
+};
+#[cfg(feature = "debug")]
+use bevy::gizmos::GizmoConfig;
+use bevy::prelude::{
+    any_with_component, resource_changed, resource_equals, resource_exists, App, Condition,
+    IntoSystemConfigs, Plugin, Resource, SystemSet,
+};
+pub use channel::ducking::DuckSettings;
 pub use channel::dynamic::DynamicAudioChannel;
 pub use channel::dynamic::DynamicAudioChannels;
+pub use channel::effects::{
+    ChannelEffects, ChannelReverb, EffectChain, EffectHandles, MainEffects, MainTrackEffects,
+};
+pub use channel::hierarchy::{ChannelHierarchy, ChannelHierarchyError};
+pub use channel::queue::{QueueAdvanced, QueueRepeat};
+pub use channel::snapshot::{ChannelMix, ChannelStates};
 pub use channel::typed::AudioChannel;
+pub use channel::{Channel, ChannelSettings, StealPolicy};
 pub use instance::AudioInstance;
 pub use instance::AudioInstanceAssetsExt;
+pub use instance::{AudioCommandError, AudioError, AudioErrorKind};
+pub use instance::{LoopCompleted, PlaybackCompleted};
 
 /// A Bevy plugin for audio
 ///
@@ -120,7 +292,7 @@ pub use instance::AudioInstanceAssetsExt;
 ///    app
 ///         .add_plugins(MinimalPlugins)
 ///         .add_plugins(AssetPlugin::default())
-///         .add_plugins(AudioPlugin)
+///         .add_plugins(AudioPlugin::default())
 /// #       .add_systems(Update, stop)
 ///         .add_systems(Startup, start_background_audio);
 ///    app.run();
@@ -134,15 +306,60 @@ pub use instance::AudioInstanceAssetsExt;
 /// #     events.send(AppExit)
 /// # }
 /// ```
-#[derive(Default)]
-pub struct AudioPlugin;
+pub struct AudioPlugin {
+    /// The schedule that runs the spacial audio systems (default [`PostUpdate`])
+    ///
+    /// The systems in this schedule are in [`SpacialAudioSet::Update`] and
+    /// [`SpacialAudioSet::Cleanup`]. The cleanup of instances that stopped always runs in
+    /// [`PreUpdate`].
+    pub spacial_schedule: InternedScheduleLabel,
+}
+
+impl AudioPlugin {
+    /// The plugin with the spacial audio systems in the given schedule
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy::asset::AssetPlugin;
+    /// # use bevy_kira_audio::prelude::*;
+    /// App::new().add_plugins((
+    ///     MinimalPlugins,
+    ///     AssetPlugin::default(),
+    ///     AudioPlugin::with_spacial_schedule(Update),
+    /// ));
+    /// ```
+    pub fn with_spacial_schedule(schedule: impl ScheduleLabel) -> Self {
+        AudioPlugin {
+            spacial_schedule: schedule.intern(),
+        }
+    }
+}
+
+impl Default for AudioPlugin {
+    fn default() -> Self {
+        AudioPlugin::with_spacial_schedule(PostUpdate)
+    }
+}
 
 impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
         app.init_non_send_resource::<AudioOutput>()
             .init_asset::<AudioSource>()
-            .init_asset::<AudioInstance>();
+            .init_asset::<AudioInstance>()
+            .init_asset::<StreamedAudioSource>()
+            .init_asset::<AudioBank>()
+            .init_asset::<AudioCollection>()
+            .add_event::<PlaybackCompleted>()
+            .add_event::<LoopCompleted>()
+            .add_event::<QueueAdvanced>()
+            .add_event::<AudioDeviceFailed>()
+            .add_event::<AudioDeviceLost>()
+            .add_event::<AudioDeviceChanged>()
+            .add_event::<AudioBackendFailed>()
+            .add_event::<BeatEvent>()
+            .add_event::<AudioError>();
 
+        #[cfg(feature = "aiff")]
+        app.init_asset_loader::<AiffLoader>();
         #[cfg(feature = "mp3")]
         app.init_asset_loader::<Mp3Loader>();
         #[cfg(feature = "ogg")]
@@ -151,29 +368,261 @@ impl Plugin for AudioPlugin {
         app.init_asset_loader::<WavLoader>();
         #[cfg(feature = "flac")]
         app.init_asset_loader::<FlacLoader>();
+        #[cfg(any(feature = "flac", feature = "mp3", feature = "ogg", feature = "wav"))]
+        app.init_asset_loader::<StreamedLoader>();
 
         #[cfg(feature = "settings_loader")]
         app.init_asset_loader::<SettingsLoader>();
+        #[cfg(feature = "bank")]
+        app.init_asset_loader::<AudioBankLoader>();
+        #[cfg(feature = "bank")]
+        app.init_asset_loader::<AudioCollectionLoader>();
 
         app.init_resource::<DynamicAudioChannels>()
+            .init_resource::<AudioDevices>()
+            .init_resource::<MasterVolume>()
+            .init_resource::<ChannelHierarchy>()
+            .init_resource::<SpacialAudioState>()
+            .init_resource::<AudioModulators>()
+            .init_resource::<MusicLayers>()
+            .register_type::<SpacialAudio>()
+            .register_type::<AudioEmitter>()
+            .register_type::<AudioReceiver>()
+            .register_type::<PrimaryReceiver>()
+            .register_type::<OcclusionFactor>()
+            .register_type::<SpacialVelocity>()
+            .register_type::<AttenuationModel>()
+            .register_type::<Option<AttenuationModel>>()
+            .register_type::<EmitterCone>()
+            .register_type::<Option<EmitterCone>>()
+            .register_type::<Doppler>()
+            .register_type::<Option<Doppler>>()
+            .register_type::<SpacialReverb>()
+            .register_type::<Option<SpacialReverb>>()
+            .register_type::<Option<f32>>()
+            .register_type::<CullMode>()
+            .register_type::<PanningLaw>()
+            .register_type::<ReceiverSelection>()
+            .register_type::<SpacialMode>()
+            .register_type::<SpacialBackend>()
+            .register_type::<PlaybackState>()
+            .register_type::<StealPolicy>()
+            .register_type::<QueueRepeat>()
             .add_systems(
                 PostUpdate,
                 play_dynamic_channels.in_set(AudioSystemSet::PlayDynamicChannels),
             )
+            .add_systems(Startup, report_audio_backend)
+            .add_systems(PreUpdate, (refresh_audio_devices, send_beat_events))
+            .add_systems(
+                PreUpdate,
+                recover_audio_device.before(AudioSystemSet::InstanceCleanup),
+            )
+            .add_systems(PreUpdate, update_main_effects.after(recover_audio_device))
+            .add_systems(
+                PreUpdate,
+                advance_headless_audio.before(AudioSystemSet::InstanceCleanup),
+            )
+            .add_systems(
+                PreUpdate,
+                unlock_audio_context.run_if(resource_equals(AudioContextState::Suspended)),
+            )
+            .add_systems(
+                PostUpdate,
+                follow_window_focus.run_if(resource_exists::<Events<WindowFocused>>()),
+            )
+            .add_systems(
+                PostUpdate,
+                follow_virtual_time
+                    .before(AudioSystemSet::PlayTypedChannels)
+                    .before(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                PostUpdate,
+                update_audio_modulators
+                    .after(switch_audio_device)
+                    .before(AudioSystemSet::PlayTypedChannels)
+                    .before(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                PostUpdate,
+                update_audio_collections
+                    .before(AudioSystemSet::PlayTypedChannels)
+                    .before(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                PostUpdate,
+                play_music_layers
+                    .after(update_audio_clocks)
+                    .before(AudioSystemSet::PlayTypedChannels)
+                    .before(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                self.spacial_schedule,
+                update_audio_zones
+                    .in_set(SpacialAudioSet::Update)
+                    .before(AudioSystemSet::PlayTypedChannels)
+                    .before(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                PostUpdate,
+                sync_music_layers
+                    .after(AudioSystemSet::PlayTypedChannels)
+                    .after(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                PostUpdate,
+                update_audio_clocks
+                    .after(switch_audio_device)
+                    .before(AudioSystemSet::PlayTypedChannels)
+                    .before(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                PostUpdate,
+                update_master_volume
+                    .run_if(resource_changed::<MasterVolume>())
+                    .after(switch_audio_device)
+                    .before(AudioSystemSet::PlayTypedChannels)
+                    .before(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                PostUpdate,
+                switch_audio_device
+                    .run_if(resource_changed::<AudioDevice>())
+                    .before(update_channel_buses)
+                    .before(AudioSystemSet::PlayTypedChannels)
+                    .before(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                PostUpdate,
+                update_channel_buses
+                    .run_if(resource_changed::<ChannelHierarchy>())
+                    .before(AudioSystemSet::PlayTypedChannels)
+                    .before(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                PostUpdate,
+                update_ducking
+                    .after(AudioSystemSet::PlayTypedChannels)
+                    .after(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                PostUpdate,
+                resume_paused_audio
+                    .after(AudioSystemSet::PlayTypedChannels)
+                    .after(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                PostUpdate,
+                update_queues
+                    .after(AudioSystemSet::PlayTypedChannels)
+                    .after(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                PostUpdate,
+                report_audio_errors
+                    .after(AudioSystemSet::PlayTypedChannels)
+                    .after(AudioSystemSet::PlayDynamicChannels)
+                    .after(update_queues),
+            )
             .add_systems(
                 PreUpdate,
                 cleanup_stopped_instances.in_set(AudioSystemSet::InstanceCleanup),
             )
+            .add_systems(Last, close_on_exit)
+            .add_systems(
+                PreUpdate,
+                update_dynamic_instance_states.after(AudioSystemSet::InstanceCleanup),
+            )
             .add_audio_channel::<MainTrack>()
             .add_systems(
                 PreUpdate,
                 cleanup_stopped_spacial_instances
                     .in_set(AudioSystemSet::InstanceCleanup)
-                    .run_if(resource_exists::<SpacialAudio>()),
+                    .in_set(SpacialAudioSet::Cleanup)
+                    .run_if(
+                        resource_exists::<SpacialAudio>()
+                            .or_else(any_with_component::<DespawnOnAudioComplete>()),
+                    ),
             )
+            .add_systems(
+                PreUpdate,
+                despawn_on_audio_complete.after(AudioSystemSet::InstanceCleanup),
+            )
+            .add_systems(
+                self.spacial_schedule,
+                (update_spacial_reverb, sync_kira_spacial_scene)
+                    .in_set(SpacialAudioSet::Update)
+                    .run_if(resource_exists::<SpacialAudio>())
+                    .before(AudioSystemSet::PlayTypedChannels)
+                    .before(AudioSystemSet::PlayDynamicChannels),
+            )
+            .add_systems(
+                self.spacial_schedule,
+                (
+                    stop_removed_emitter_instances
+                        .in_set(SpacialAudioSet::Cleanup)
+                        .run_if(resource_exists::<SpacialAudio>()),
+                    (
+                        reset_disabled_spacial_audio.run_if(resource_exists::<SpacialAudio>()),
+                        (run_spacial_audio, run_doppler_effect)
+                            .chain()
+                            .run_if(spacial_audio_enabled),
+                    )
+                        .chain()
+                        .in_set(SpacialAudioSet::Update),
+                )
+                    .chain(),
+            )
+            .register_type::<SpacialAudioSource>()
+            .register_type::<DespawnOnAudioComplete>()
+            .add_systems(
+                self.spacial_schedule,
+                play_spacial_audio_sources
+                    .before(SpacialAudioSet::Cleanup)
+                    .before(AudioSystemSet::PlayTypedChannels),
+            );
+
+        #[cfg(debug_assertions)]
+        app.add_systems(
+            self.spacial_schedule,
+            warn_about_shared_instances
+                .before(SpacialAudioSet::Update)
+                .run_if(resource_exists::<SpacialAudio>()),
+        );
+
+        #[cfg(feature = "inspection")]
+        app.init_resource::<AudioInspection>()
+            .register_type::<AudioInspection>()
             .add_systems(
                 PostUpdate,
-                run_spacial_audio.run_if(resource_exists::<SpacialAudio>()),
+                update_audio_inspection
+                    .after(AudioSystemSet::PlayTypedChannels)
+                    .after(AudioSystemSet::PlayDynamicChannels)
+                    .after(update_queues),
+            );
+
+        #[cfg(feature = "debug")]
+        app.init_resource::<SpacialAudioDebug>().add_systems(
+            self.spacial_schedule,
+            (
+                record_spacial_audio_debug.run_if(spacial_audio_enabled),
+                draw_spacial_audio_gizmos.run_if(resource_exists::<GizmoConfig>()),
+            )
+                .chain()
+                .after(SpacialAudioSet::Update),
+        );
+
+        #[cfg(feature = "propagation")]
+        app.register_type::<AudioOccluder>()
+            .register_type::<AudioPortal>()
+            .register_type::<ZoneExtent>()
+            .add_systems(
+                self.spacial_schedule,
+                propagate_sound
+                    .in_set(SpacialAudioSet::Update)
+                    .before(run_spacial_audio)
+                    .run_if(spacial_audio_enabled),
             );
     }
 }
@@ -189,6 +638,33 @@ pub enum AudioSystemSet {
     PlayTypedChannels,
 }
 
+/// Labels for spacial audio systems
+///
+/// The spacial audio systems run in the [`AudioPlugin::spacial_schedule`], [`PostUpdate`] by
+/// default, without any ordering relative to transform propagation. Use these sets to order your
+/// own systems against them, or to run spacial audio after the transforms of this frame were
+/// propagated:
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::transform::TransformSystem;
+/// # use bevy_kira_audio::prelude::*;
+/// # use bevy_kira_audio::SpacialAudioSet;
+/// # let mut app = App::new();
+/// app.configure_sets(
+///     PostUpdate,
+///     SpacialAudioSet::Update.after(TransformSystem::TransformPropagate),
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub enum SpacialAudioSet {
+    /// Label for systems in the [`AudioPlugin::spacial_schedule`] that apply spacial audio to
+    /// emitter instances
+    Update,
+    /// Label for systems in [`PreUpdate`] and the [`AudioPlugin::spacial_schedule`] that clean up
+    /// emitter instances
+    Cleanup,
+}
+
 /// The default audio channel
 ///
 /// Alias for the [`AudioChannel<MainTrack>`] resource. Use it to play and control sound on the main track.
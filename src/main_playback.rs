@@ -0,0 +1,143 @@
+//! The playback state that all audio managers share on their main track
+
+use crate::audio_output::AudioOutput;
+use crate::backend_settings::FocusLoss;
+use crate::instance::AudioInstance;
+use crate::volume::{finite_volume, scale_volume, MasterVolume};
+use bevy::asset::Assets;
+use bevy::ecs::change_detection::{NonSendMut, ResMut};
+use bevy::ecs::event::EventReader;
+use bevy::ecs::system::Res;
+use bevy::log::error;
+use bevy::time::{Time, Virtual};
+use bevy::window::WindowFocused;
+use kira::manager::backend::Backend;
+use kira::manager::AudioManager;
+use kira::tween::Tween;
+use kira::Volume;
+use std::time::Duration;
+
+/// The fade of pausing and ducking audio when the focus changes
+pub(crate) const FOCUS_FADE: Duration = Duration::from_millis(200);
+
+/// Master volume, window focus and speed of virtual time
+pub(crate) struct MainPlayback {
+    /// The volume of the main tracks of all managers, see [`MasterVolume`]
+    master_volume: Volume,
+    /// Whether no window has the focus, see [`AudioSettings::focus_loss`](crate::AudioSettings::focus_loss)
+    unfocused: bool,
+    /// The relative speed of virtual time, `0` while it is paused
+    time_speed: f64,
+}
+
+impl Default for MainPlayback {
+    fn default() -> Self {
+        MainPlayback {
+            master_volume: Volume::Amplitude(1.),
+            unfocused: false,
+            time_speed: 1.,
+        }
+    }
+}
+
+impl MainPlayback {
+    pub(crate) fn time_speed(&self) -> f64 {
+        self.time_speed
+    }
+
+    /// Take over the speed of virtual time, returns whether it changed
+    pub(crate) fn set_time_speed(&mut self, speed: f64) -> bool {
+        let changed = speed != self.time_speed;
+        self.time_speed = speed;
+
+        changed
+    }
+
+    pub(crate) fn set_master_volume(&mut self, volume: Volume) {
+        self.master_volume = finite_volume(volume);
+    }
+
+    pub(crate) fn is_unfocused(&self) -> bool {
+        self.unfocused
+    }
+
+    /// Take over whether a window has the focus, returns whether it changed
+    pub(crate) fn set_focused(&mut self, focused: bool) -> bool {
+        let changed = self.unfocused == focused;
+        self.unfocused = !focused;
+
+        changed
+    }
+
+    /// The volume of the main tracks, with the master volume ducked while unfocused
+    pub(crate) fn main_volume(&self, focus_loss: FocusLoss) -> Volume {
+        match (self.unfocused, focus_loss) {
+            (true, FocusLoss::Duck { decibels }) => scale_volume(
+                self.master_volume,
+                Volume::Decibels(-f64::from(decibels)).as_amplitude(),
+            ),
+            _ => self.master_volume,
+        }
+    }
+
+    /// Give a new audio manager the volume and pause of the others
+    pub(crate) fn start<B: Backend>(&self, manager: &AudioManager<B>, focus_loss: FocusLoss) {
+        let instant = Tween {
+            duration: Duration::ZERO,
+            ..Default::default()
+        };
+        set_main_volume(manager, self.main_volume(focus_loss), instant);
+        if self.unfocused && focus_loss == FocusLoss::Pause {
+            pause_manager(manager, true, instant);
+        }
+    }
+}
+
+pub(crate) fn pause_manager<B: Backend>(manager: &AudioManager<B>, paused: bool, tween: Tween) {
+    let result = if paused {
+        manager.pause(tween)
+    } else {
+        manager.resume(tween)
+    };
+    if let Err(error) = result {
+        error!(
+            "Failed to pause or resume audio for the window focus: {:?}",
+            error
+        );
+    }
+}
+
+pub(crate) fn set_main_volume<B: Backend>(manager: &AudioManager<B>, volume: Volume, tween: Tween) {
+    if let Err(error) = manager.main_track().set_volume(volume, tween) {
+        error!("Failed to set the master volume: {:?}", error);
+    }
+}
+
+pub(crate) fn follow_virtual_time(
+    mut audio_output: NonSendMut<AudioOutput>,
+    time: Res<Time<Virtual>>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    let speed = if time.is_paused() {
+        0.
+    } else {
+        time.relative_speed_f64()
+    };
+    audio_output.set_time_speed(speed, &mut audio_instances);
+}
+
+pub(crate) fn follow_window_focus(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut focus: EventReader<WindowFocused>,
+) {
+    if let Some(event) = focus.read().last() {
+        audio_output.set_focused(event.focused);
+    }
+}
+
+pub(crate) fn update_master_volume(
+    mut audio_output: NonSendMut<AudioOutput>,
+    master: Res<MasterVolume>,
+) {
+    audio_output.set_master_volume(master.volume(), master.tween().into());
+}
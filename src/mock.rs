@@ -0,0 +1,325 @@
+//! A deterministic backend for tests of code that plays audio
+
+use crate::audio::{AudioCommand, PlayAudioSettings};
+use crate::channel::Channel;
+use crate::instance::AudioInstance;
+use crate::AudioTween;
+use bevy::asset::{Handle, UntypedAssetId, UntypedHandle};
+use bevy::ecs::system::Resource;
+use kira::Volume;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Play all audio on a backend without output that only advances when told to
+///
+/// Insert it before adding the [`AudioPlugin`](crate::AudioPlugin). The plugin then never opens
+/// an output device. Sounds are rendered silently, but only for the time passed to
+/// [`advance`](Self::advance), so the states of instances and events like
+/// [`PlaybackCompleted`](crate::PlaybackCompleted) don't depend on how fast the test runs.
+/// The [`AudioBackendStatus`](crate::AudioBackendStatus) is `Running`, like with a device.
+///
+/// Every channel command that the plugin ran is recorded in a log, see
+/// [`commands`](Self::commands). Commands of sounds that did not load yet are recorded once they
+/// ran. Only available with the `mock-backend` feature.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// # use bevy_kira_audio::Channel;
+/// #[derive(Resource)]
+/// struct Jump(Handle<AudioSource>);
+///
+/// fn jump(audio: Res<Audio>, sound: Res<Jump>) {
+///     audio.play(sound.0.clone()).with_volume(0.4);
+/// }
+///
+/// let mock = MockAudio::default();
+/// let mut app = App::new();
+/// app.insert_resource(mock.clone())
+///     .add_plugins((MinimalPlugins, AssetPlugin::default(), AudioPlugin::default()))
+///     .add_systems(Update, jump);
+/// let sound = AudioSource::from_frames(1_000, vec![[0.; 2]; 500]).unwrap();
+/// let sound = app.world.resource_mut::<Assets<AudioSource>>().add(sound);
+/// app.insert_resource(Jump(sound.clone()));
+/// app.update();
+///
+/// let plays = mock.plays_of(&sound);
+/// assert_eq!(plays.len(), 1);
+/// assert_eq!(plays[0].0, Channel::typed::<MainTrack>());
+/// assert_eq!(plays[0].1.amplitude(), 0.4);
+/// ```
+#[derive(Resource, Clone, Default)]
+pub struct MockAudio {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    log: Vec<LoggedAudioCommand>,
+    elapsed: Duration,
+    /// Time passed to `advance` that the backend did not render yet
+    unrendered: Duration,
+}
+
+/// A channel command that the plugin ran, see [`MockAudio::commands`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LoggedAudioCommand {
+    /// The channel of the command
+    pub channel: Channel,
+    /// The [elapsed](MockAudio::elapsed) time when the command ran
+    pub at: Duration,
+    /// The command with its parameters
+    pub command: MockAudioCommand,
+}
+
+/// A channel command with its parameters, see [`LoggedAudioCommand`]
+#[non_exhaustive]
+#[derive(Clone, Debug, PartialEq)]
+pub enum MockAudioCommand {
+    /// Play a sound
+    Play(MockPlay),
+    /// Set the volume of the channel
+    SetVolume(Volume, Option<AudioTween>),
+    /// Set the panning of the channel
+    SetPanning(f64, Option<AudioTween>),
+    /// Set the playback rate of the channel as factor
+    SetPlaybackRate(f64, Option<AudioTween>),
+    /// Stop all sounds of the channel
+    Stop(Option<AudioTween>),
+    /// Pause the channel
+    Pause(Option<AudioTween>),
+    /// Resume the channel
+    Resume(Option<AudioTween>),
+    /// Seek all sounds of the channel to the position in seconds
+    SeekTo(f64),
+    /// Seek all sounds of the channel by the amount in seconds
+    SeekBy(f64),
+    /// Add a sound to the queue of the channel
+    Enqueue(Handle<crate::AudioSource>),
+    /// Any other command, for example one that changes effects, sends or ducking
+    Other,
+}
+
+/// A sound that was played, see [`MockAudioCommand::Play`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct MockPlay {
+    /// The [`AudioSource`](crate::AudioSource) or
+    /// [`StreamedAudioSource`](crate::StreamedAudioSource), the first part of a sequence
+    pub source: UntypedHandle,
+    /// The instance playing the sound
+    pub instance: Handle<AudioInstance>,
+    /// The volume of the command, `None` for the default
+    pub volume: Option<Volume>,
+    /// The playback rate of the command, `None` for the default
+    pub playback_rate: Option<f64>,
+    /// The panning of the command, `None` for the default
+    pub panning: Option<f64>,
+    /// The fade in of the command
+    pub fade_in: Option<AudioTween>,
+    /// The sound loops
+    pub looped: bool,
+    /// The sound started paused
+    pub paused: bool,
+}
+
+impl MockPlay {
+    /// The volume of the command as amplitude
+    pub fn amplitude(&self) -> f64 {
+        self.volume.map_or(1., |volume| volume.as_amplitude())
+    }
+}
+
+impl MockAudio {
+    /// Let the given time pass for the audio
+    ///
+    /// The backend renders it in the next update, before the states of instances are updated.
+    pub fn advance(&self, delta: Duration) {
+        let mut state = self.state.lock();
+        state.elapsed += delta;
+        state.unrendered += delta;
+    }
+
+    /// The total time passed to [`advance`](Self::advance)
+    pub fn elapsed(&self) -> Duration {
+        self.state.lock().elapsed
+    }
+
+    /// All channel commands that ran since the start or the last [`clear`](Self::clear)
+    pub fn commands(&self) -> Vec<LoggedAudioCommand> {
+        self.state.lock().log.clone()
+    }
+
+    /// All played sounds with their channel
+    pub fn plays(&self) -> Vec<(Channel, MockPlay)> {
+        self.state
+            .lock()
+            .log
+            .iter()
+            .filter_map(|logged| match &logged.command {
+                MockAudioCommand::Play(play) => Some((logged.channel.clone(), play.clone())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The plays of the given source with their channel
+    pub fn plays_of(&self, source: impl Into<UntypedAssetId>) -> Vec<(Channel, MockPlay)> {
+        let source = source.into();
+        let mut plays = self.plays();
+        plays.retain(|(_, play)| play.source.id() == source);
+
+        plays
+    }
+
+    /// Forget all logged commands
+    pub fn clear(&self) {
+        self.state.lock().log.clear();
+    }
+
+    /// Take the time that the backend has to render
+    pub(crate) fn take_unrendered(&self) -> Duration {
+        std::mem::take(&mut self.state.lock().unrendered)
+    }
+
+    pub(crate) fn log(&self, channel: &Channel, command: &AudioCommand) {
+        let mut state = self.state.lock();
+        let at = state.elapsed;
+        state.log.push(LoggedAudioCommand {
+            channel: channel.clone(),
+            at,
+            command: command.into(),
+        });
+    }
+}
+
+impl From<&AudioCommand> for MockAudioCommand {
+    fn from(command: &AudioCommand) -> Self {
+        match command {
+            AudioCommand::Play(play) => MockAudioCommand::Play(play.into()),
+            AudioCommand::SetVolume(volume, tween) => {
+                MockAudioCommand::SetVolume(*volume, tween.clone())
+            }
+            AudioCommand::SetPanning(panning, tween) => {
+                MockAudioCommand::SetPanning(*panning, tween.clone())
+            }
+            AudioCommand::SetPlaybackRate(rate, tween) => {
+                MockAudioCommand::SetPlaybackRate(rate.as_factor(), tween.clone())
+            }
+            AudioCommand::Stop(tween) => MockAudioCommand::Stop(tween.clone()),
+            AudioCommand::Pause(tween) => MockAudioCommand::Pause(tween.clone()),
+            AudioCommand::Resume(tween) => MockAudioCommand::Resume(tween.clone()),
+            AudioCommand::SeekTo(position) => MockAudioCommand::SeekTo(*position),
+            AudioCommand::SeekBy(amount) => MockAudioCommand::SeekBy(*amount),
+            AudioCommand::Enqueue(source) => MockAudioCommand::Enqueue(source.clone()),
+            _ => MockAudioCommand::Other,
+        }
+    }
+}
+
+impl From<&PlayAudioSettings> for MockPlay {
+    fn from(play: &PlayAudioSettings) -> Self {
+        let source = match (&play.streamed, &play.sequence) {
+            (Some(streamed), _) => streamed.clone().untyped(),
+            (None, Some(parts)) if !parts.is_empty() => parts[0].clone().untyped(),
+            _ => play.source.clone().untyped(),
+        };
+        let settings = &play.settings;
+        MockPlay {
+            source,
+            instance: play.instance_handle.clone(),
+            volume: settings.volume,
+            playback_rate: settings.playback_rate,
+            panning: settings.panning,
+            fade_in: settings.fade_in.clone(),
+            looped: settings.loop_start.is_some() || settings.source_loop,
+            paused: settings.paused,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::prelude::*;
+    use crate::{Channel, MockAudio, MockAudioCommand, PlaybackCompleted};
+    use bevy::prelude::*;
+    use std::time::Duration;
+
+    fn app_with_sound(mock: &MockAudio) -> (App, Handle<AudioSource>) {
+        let mut app = App::new();
+        app.insert_resource(mock.clone()).add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            AudioPlugin::default(),
+        ));
+        let sound = AudioSource::from_frames(1_000, vec![[0.; 2]; 1_000]).unwrap();
+        let sound = app.world.resource_mut::<Assets<AudioSource>>().add(sound);
+
+        (app, sound)
+    }
+
+    #[test]
+    fn commands_are_logged_with_their_parameters() {
+        let mock = MockAudio::default();
+        let (mut app, sound) = app_with_sound(&mock);
+        let audio = app.world.resource::<Audio>();
+        audio.play(sound.clone()).with_volume(0.4).looped();
+        audio
+            .set_panning(0.2)
+            .fade_in(AudioTween::linear(Duration::from_millis(300)));
+        app.update();
+
+        let commands = mock.commands();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(commands[0].channel, Channel::typed::<MainTrack>());
+        let MockAudioCommand::Play(play) = &commands[0].command else {
+            panic!("expected a play command");
+        };
+        assert_eq!(play.source, sound.clone().untyped());
+        assert_eq!(play.amplitude(), 0.4);
+        assert!(play.looped);
+        assert_eq!(
+            commands[1].command,
+            MockAudioCommand::SetPanning(0.2, Some(AudioTween::linear(Duration::from_millis(300))))
+        );
+
+        mock.clear();
+        app.world.resource::<Audio>().stop();
+        app.update();
+        assert_eq!(mock.commands()[0].command, MockAudioCommand::Stop(None));
+        assert!(mock.plays_of(&sound).is_empty());
+    }
+
+    #[test]
+    fn sounds_only_advance_with_the_mock_time() {
+        let mock = MockAudio::default();
+        let (mut app, sound) = app_with_sound(&mock);
+        let instance = app.world.resource::<Audio>().play(sound).handle();
+        for _ in 0..5 {
+            app.update();
+        }
+        let position = |app: &App| {
+            app.world
+                .resource::<Assets<AudioInstance>>()
+                .state(&instance)
+                .position()
+        };
+        assert_eq!(position(&app), Some(0.));
+
+        mock.advance(Duration::from_millis(600));
+        app.update();
+        assert!((position(&app).unwrap() - 0.6).abs() < 0.01);
+        assert!(app.world.resource::<Events<PlaybackCompleted>>().is_empty());
+
+        mock.advance(Duration::from_millis(500));
+        app.update();
+        assert_eq!(
+            app.world
+                .resource::<Assets<AudioInstance>>()
+                .state(&instance),
+            PlaybackState::Stopped
+        );
+        assert_eq!(mock.elapsed(), Duration::from_millis(1_100));
+        let completed = app.world.resource::<Events<PlaybackCompleted>>();
+        assert_eq!(completed.len(), 1);
+    }
+}
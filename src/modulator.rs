@@ -0,0 +1,427 @@
+//! Low frequency oscillators that drive the parameters of instances in the audio thread
+
+use crate::audio_output::AudioOutput;
+use crate::AudioTween;
+use bevy::ecs::system::{NonSendMut, ResMut, Resource};
+use bevy::log::{error, warn};
+use bevy::utils::HashMap;
+use kira::manager::backend::Backend;
+use kira::manager::AudioManager;
+use kira::modulator::lfo::{LfoBuilder, LfoHandle, Waveform};
+use kira::modulator::ModulatorId;
+use kira::sound::PlaybackRate;
+use kira::tween::{ModulatorMapping, Value};
+use kira::Volume;
+use std::collections::VecDeque;
+
+/// A modulator created with [`AudioModulators::add_lfo`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AudioModulator(u64);
+
+/// The oscillation of an LFO, see [`AudioModulators::add_lfo`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct LfoSettings {
+    /// The shape of the oscillation
+    pub waveform: Waveform,
+    /// Oscillations per second
+    pub frequency: f64,
+    /// How much of the depth of bound parameters is used, from `0.0` to `1.0`
+    ///
+    /// Tween it with [`AudioModulators::set_amplitude`] to fade the modulation in or out.
+    pub amplitude: f64,
+}
+
+impl Default for LfoSettings {
+    fn default() -> Self {
+        LfoSettings {
+            waveform: Waveform::Sine,
+            frequency: 2.,
+            amplitude: 1.,
+        }
+    }
+}
+
+impl From<LfoSettings> for LfoBuilder {
+    fn from(settings: LfoSettings) -> Self {
+        LfoBuilder::new()
+            .waveform(settings.waveform)
+            .frequency(settings.frequency)
+            .amplitude(settings.amplitude)
+    }
+}
+
+/// A parameter of an instance that a modulator can drive
+///
+/// See [`PlayAudioCommand::with_modulator`](crate::PlayAudioCommand::with_modulator) and
+/// [`AudioInstance::bind_modulator`](crate::AudioInstance::bind_modulator).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ModulatedParameter {
+    /// The depth is the share of the amplitude that the volume moves up and down, from `0.0` to
+    /// `1.0`, for tremolo
+    Volume,
+    /// The depth is how far the panning moves to either side, from `0.0` to `0.5`
+    Panning,
+    /// The depth is how many semitones the pitch moves up and down, for vibrato
+    PlaybackRate,
+}
+
+pub(crate) enum ModulatorCommand {
+    Add(AudioModulator, LfoSettings),
+    SetFrequency(AudioModulator, f64, AudioTween),
+    SetAmplitude(AudioModulator, f64, AudioTween),
+    SetWaveform(AudioModulator, Waveform),
+    Remove(AudioModulator),
+}
+
+/// Modulators that oscillate the volume, panning or playback rate of instances
+///
+/// Kira computes the modulation in the audio thread, so vibrato and tremolo need no system
+/// changing the instances every frame. Like channel commands, the LFOs are created and changed
+/// at the end of the frame.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn play_with_vibrato(
+///     mut modulators: ResMut<AudioModulators>,
+///     asset_server: Res<AssetServer>,
+///     audio: Res<Audio>,
+/// ) {
+///     let lfo = modulators.add_lfo(LfoSettings {
+///         frequency: 5.,
+///         ..default()
+///     });
+///     audio
+///         .play(asset_server.load("sounds/loop.ogg"))
+///         .with_modulator(ModulatedParameter::PlaybackRate, lfo, 0.5);
+/// }
+/// ```
+#[derive(Resource, Default)]
+pub struct AudioModulators {
+    next: u64,
+    pub(crate) commands: VecDeque<ModulatorCommand>,
+    /// The kira modulators of all created modulators
+    pub(crate) ids: HashMap<AudioModulator, ModulatorId>,
+}
+
+impl AudioModulators {
+    /// Create an LFO
+    ///
+    /// It can be bound to instances played in the same frame. To bind it to an
+    /// [`AudioInstance`](crate::AudioInstance) yourself, wait until it was created in the next frame.
+    pub fn add_lfo(&mut self, settings: LfoSettings) -> AudioModulator {
+        let modulator = AudioModulator(self.next);
+        self.next += 1;
+        self.commands
+            .push_back(ModulatorCommand::Add(modulator, settings));
+
+        modulator
+    }
+
+    /// Change how often the LFO oscillates per second
+    pub fn set_frequency(&mut self, modulator: AudioModulator, frequency: f64, tween: AudioTween) {
+        self.commands
+            .push_back(ModulatorCommand::SetFrequency(modulator, frequency, tween));
+    }
+
+    /// Change how much of the depth of bound parameters the LFO uses
+    pub fn set_amplitude(&mut self, modulator: AudioModulator, amplitude: f64, tween: AudioTween) {
+        self.commands
+            .push_back(ModulatorCommand::SetAmplitude(modulator, amplitude, tween));
+    }
+
+    /// Change the shape of the oscillation
+    pub fn set_waveform(&mut self, modulator: AudioModulator, waveform: Waveform) {
+        self.commands
+            .push_back(ModulatorCommand::SetWaveform(modulator, waveform));
+    }
+
+    /// Remove the LFO, bound parameters keep the value they had last
+    pub fn remove(&mut self, modulator: AudioModulator) {
+        self.commands.push_back(ModulatorCommand::Remove(modulator));
+    }
+
+    /// Whether the LFO was created, so it can be bound to instances
+    pub fn is_created(&self, modulator: AudioModulator) -> bool {
+        self.ids.contains_key(&modulator)
+    }
+}
+
+/// The LFOs of the main manager, see [`AudioModulators`]
+#[derive(Default)]
+pub(crate) struct Lfos {
+    lfos: HashMap<AudioModulator, Lfo>,
+}
+
+impl Lfos {
+    /// Run the commands of the modulators and hand them the ids of the created LFOs
+    pub(crate) fn update<B: Backend>(
+        &mut self,
+        modulators: &mut AudioModulators,
+        mut manager: Option<&mut AudioManager<B>>,
+    ) {
+        for command in modulators.commands.drain(..) {
+            match command {
+                ModulatorCommand::Add(modulator, settings) => {
+                    let mut lfo = Lfo::new(settings);
+                    if let Some(manager) = manager.as_deref_mut() {
+                        lfo.start(manager);
+                    }
+                    self.lfos.insert(modulator, lfo);
+                }
+                ModulatorCommand::Remove(modulator) => {
+                    self.lfos.remove(&modulator);
+                }
+                ModulatorCommand::SetFrequency(modulator, ..)
+                | ModulatorCommand::SetAmplitude(modulator, ..)
+                | ModulatorCommand::SetWaveform(modulator, _) => {
+                    if let Some(lfo) = self.lfos.get_mut(&modulator) {
+                        lfo.run(command);
+                    }
+                }
+            }
+        }
+        modulators.ids = self
+            .lfos
+            .iter()
+            .filter_map(|(modulator, lfo)| lfo.id().map(|id| (*modulator, id)))
+            .collect();
+    }
+
+    /// The kira modulator of the LFO, if it exists in the current manager
+    pub(crate) fn id(&self, modulator: &AudioModulator) -> Option<ModulatorId> {
+        self.lfos.get(modulator).and_then(Lfo::id)
+    }
+
+    /// Add all LFOs to a new manager
+    pub(crate) fn start<B: Backend>(&mut self, manager: &mut AudioManager<B>) {
+        for lfo in self.lfos.values_mut() {
+            lfo.start(manager);
+        }
+    }
+
+    /// Forget the handles of all LFOs, once their manager is gone
+    pub(crate) fn release(&mut self) {
+        for lfo in self.lfos.values_mut() {
+            lfo.release();
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn settings(&self, modulator: &AudioModulator) -> Option<LfoSettings> {
+        self.lfos.get(modulator).map(|lfo| lfo.settings)
+    }
+}
+
+/// Create and change the LFOs of [`AudioModulators`]
+pub(crate) fn update_audio_modulators(
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut modulators: ResMut<AudioModulators>,
+) {
+    audio_output.update_modulators(&mut modulators);
+}
+
+/// An LFO of the audio manager with the settings to create it again for a new manager
+struct Lfo {
+    settings: LfoSettings,
+    handle: Option<LfoHandle>,
+}
+
+impl Lfo {
+    fn new(settings: LfoSettings) -> Self {
+        Lfo {
+            settings,
+            handle: None,
+        }
+    }
+
+    fn id(&self) -> Option<ModulatorId> {
+        self.handle.as_ref().map(LfoHandle::id)
+    }
+
+    /// Add the LFO to the given manager, dropping it from the one it was added to before
+    fn start<B: Backend>(&mut self, manager: &mut AudioManager<B>) {
+        match manager.add_modulator(LfoBuilder::from(self.settings)) {
+            Ok(handle) => self.handle = Some(handle),
+            Err(error) => {
+                self.handle = None;
+                warn!("Failed to create LFO due to {:?}", error);
+            }
+        }
+    }
+
+    /// Forget the handle, once its manager is gone
+    fn release(&mut self) {
+        self.handle = None;
+    }
+
+    fn run(&mut self, command: ModulatorCommand) {
+        let result = match command {
+            ModulatorCommand::SetFrequency(_, frequency, tween) => {
+                self.settings.frequency = frequency;
+                self.handle
+                    .as_mut()
+                    .map(|handle| handle.set_frequency(frequency, (&tween).into()))
+            }
+            ModulatorCommand::SetAmplitude(_, amplitude, tween) => {
+                self.settings.amplitude = amplitude;
+                self.handle
+                    .as_mut()
+                    .map(|handle| handle.set_amplitude(amplitude, (&tween).into()))
+            }
+            ModulatorCommand::SetWaveform(_, waveform) => {
+                self.settings.waveform = waveform;
+                self.handle
+                    .as_mut()
+                    .map(|handle| handle.set_waveform(waveform))
+            }
+            ModulatorCommand::Add(..) | ModulatorCommand::Remove(_) => None,
+        };
+        if let Some(Err(error)) = result {
+            error!("Failed to change LFO: {:?}", error);
+        }
+    }
+}
+
+/// The modulators bound to the parameters of an instance
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Modulations {
+    volume: Option<(ModulatorId, f64)>,
+    panning: Option<(ModulatorId, f64)>,
+    playback_rate: Option<(ModulatorId, f64)>,
+}
+
+impl Modulations {
+    pub(crate) fn bind(
+        &mut self,
+        parameter: ModulatedParameter,
+        binding: Option<(ModulatorId, f64)>,
+    ) {
+        match parameter {
+            ModulatedParameter::Volume => {
+                self.volume = binding.map(|(id, depth)| (id, depth.clamp(0., 1.)));
+            }
+            ModulatedParameter::Panning => {
+                self.panning = binding.map(|(id, depth)| (id, depth.clamp(0., 0.5)));
+            }
+            ModulatedParameter::PlaybackRate => self.playback_rate = binding,
+        }
+    }
+
+    /// The volume oscillating around the given fixed volume
+    pub(crate) fn volume(&self, volume: impl Into<Value<Volume>>) -> Value<Volume> {
+        match (volume.into(), self.volume) {
+            (Value::Fixed(volume), Some((id, depth))) => {
+                let amplitude = volume.as_amplitude();
+                Value::from_modulator(
+                    id,
+                    mapping((
+                        Volume::Amplitude(amplitude * (1. - depth)),
+                        Volume::Amplitude(amplitude * (1. + depth)),
+                    )),
+                )
+            }
+            (volume, _) => volume,
+        }
+    }
+
+    /// The panning oscillating around the given panning
+    pub(crate) fn panning(&self, panning: f64) -> Value<f64> {
+        match self.panning {
+            Some((id, depth)) => Value::from_modulator(
+                id,
+                mapping(((panning - depth).max(0.), (panning + depth).min(1.))),
+            ),
+            None => Value::Fixed(panning),
+        }
+    }
+
+    /// The playback rate oscillating around the given fixed rate
+    pub(crate) fn playback_rate(
+        &self,
+        playback_rate: impl Into<Value<PlaybackRate>>,
+    ) -> Value<PlaybackRate> {
+        match (playback_rate.into(), self.playback_rate) {
+            (Value::Fixed(PlaybackRate::Semitones(semitones)), Some((id, depth))) => {
+                Value::from_modulator(
+                    id,
+                    mapping((
+                        PlaybackRate::Semitones(semitones - depth),
+                        PlaybackRate::Semitones(semitones + depth),
+                    )),
+                )
+            }
+            (Value::Fixed(PlaybackRate::Factor(factor)), Some((id, depth))) => {
+                let ratio = 2f64.powf(depth / 12.);
+                Value::from_modulator(
+                    id,
+                    mapping((
+                        PlaybackRate::Factor(factor / ratio),
+                        PlaybackRate::Factor(factor * ratio),
+                    )),
+                )
+            }
+            (playback_rate, _) => playback_rate,
+        }
+    }
+}
+
+/// Map the full swing of an LFO to the given range
+fn mapping<T>(output_range: (T, T)) -> ModulatorMapping<T> {
+    ModulatorMapping {
+        input_range: (-1., 1.),
+        output_range,
+        clamp_bottom: false,
+        clamp_top: false,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kira::manager::backend::mock::MockBackend;
+    use kira::manager::{AudioManager, AudioManagerSettings};
+
+    #[test]
+    fn bound_parameters_oscillate_around_their_value() {
+        let mut manager =
+            AudioManager::<MockBackend>::new(AudioManagerSettings::default()).unwrap();
+        let lfo = manager.add_modulator(LfoBuilder::new()).unwrap();
+        let mut modulations = Modulations::default();
+        assert_eq!(
+            modulations.volume(Volume::Amplitude(0.5)),
+            Value::Fixed(Volume::Amplitude(0.5))
+        );
+
+        modulations.bind(ModulatedParameter::Volume, Some((lfo.id(), 2.)));
+        modulations.bind(ModulatedParameter::PlaybackRate, Some((lfo.id(), 12.)));
+        let Value::FromModulator { mapping, .. } = modulations.volume(Volume::Amplitude(0.5))
+        else {
+            panic!("expected a modulated volume");
+        };
+        assert_eq!(
+            mapping.output_range,
+            (Volume::Amplitude(0.), Volume::Amplitude(1.))
+        );
+        let Value::FromModulator { mapping, .. } =
+            modulations.playback_rate(PlaybackRate::Factor(1.))
+        else {
+            panic!("expected a modulated playback rate");
+        };
+        assert_eq!(
+            mapping.output_range,
+            (PlaybackRate::Factor(0.5), PlaybackRate::Factor(2.))
+        );
+
+        modulations.bind(ModulatedParameter::Volume, None);
+        assert_eq!(
+            modulations.volume(Volume::Amplitude(0.5)),
+            Value::Fixed(Volume::Amplitude(0.5))
+        );
+        assert_eq!(modulations.panning(0.5), Value::Fixed(0.5));
+    }
+}
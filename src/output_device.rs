@@ -0,0 +1,850 @@
+//! Choosing the output device of the plugin and playing audio channels on other devices
+
+use crate::backend_settings::AudioSettings;
+use bevy::ecs::change_detection::{DetectChangesMut, Res, ResMut};
+use bevy::ecs::event::{Event, EventWriter};
+use bevy::ecs::system::Resource;
+use bevy::log::{error, warn};
+use bevy::tasks::IoTaskPool;
+use bevy::utils::HashMap;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{
+    BufferSize, BuildStreamError, DefaultStreamConfigError, Device, PlayStreamError, SampleRate,
+    Stream, StreamConfig, StreamError, SupportedBufferSize,
+};
+use kira::manager::backend::cpal::{CpalBackend, Error as CpalError};
+use kira::manager::backend::{Backend, Renderer};
+use kira::manager::{AudioManager, AudioManagerSettings};
+use kira::track::TrackBuilder;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
+
+/// The output device the plugin plays on
+///
+/// Change the name to switch the device at runtime, `None` plays on the default device of the
+/// system. [`AudioDevices`] lists the devices, for example for a dropdown in the settings of a
+/// game. Insert the resource before adding the [`AudioPlugin`](crate::AudioPlugin)
+/// to start on a device other than the default one.
+///
+/// Sounds playing while the device changes are lost, they complete as stopped. Channel settings,
+/// commands that were not processed yet and queued sounds carry over to the new device. Effect
+/// chains and sends of channels have to be set again. If the device fails to open, the plugin
+/// keeps playing on the previous one, resets the name and sends an [`AudioDeviceFailed`] event.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn select_device(
+///     In(index): In<usize>,
+///     devices: Res<AudioDevices>,
+///     mut device: ResMut<AudioDevice>,
+/// ) {
+///     device.name = devices.devices().get(index).map(|info| info.name.clone());
+/// }
+/// ```
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct AudioDevice {
+    /// The name of the device, `None` for the default device
+    pub name: Option<String>,
+}
+
+/// Sent when switching to another output device failed, see [`AudioDevice`]
+#[derive(Event, Debug)]
+pub struct AudioDeviceFailed {
+    /// The device that failed to open, `None` for the default device
+    pub device: Option<String>,
+    /// Why the device could not be opened
+    pub error: OutputDeviceError,
+}
+
+/// Whether the plugin plays on an audio output
+///
+/// Without an output, sounds play silently on a headless backend, see
+/// [`AudioSettings::headless_fallback`]. Check this resource to tell players that audio is
+/// unavailable, or to assert on it in headless tests. If no output could be opened at startup,
+/// the plugin also sends an [`AudioBackendFailed`] event.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn audio_hint(status: Res<AudioBackendStatus>, mut text: Query<&mut Text>) {
+///     if let Some(error) = status.error() {
+///         text.single_mut().sections[0].value = format!("Audio unavailable: {}", error);
+///     }
+/// }
+/// ```
+#[derive(Resource, Clone, Debug)]
+pub enum AudioBackendStatus {
+    /// Audio plays on the output
+    Running {
+        /// The sample rate of the output
+        sample_rate: u32,
+    },
+    /// There is no audio output, sounds play silently on a headless backend
+    Headless(Arc<OutputDeviceError>),
+    /// There is no audio output, the commands of all channels are dropped
+    Unavailable(Arc<OutputDeviceError>),
+}
+
+impl AudioBackendStatus {
+    /// Whether audio plays on the output
+    pub fn is_running(&self) -> bool {
+        matches!(self, AudioBackendStatus::Running { .. })
+    }
+
+    /// Why there is no audio output
+    pub fn error(&self) -> Option<&OutputDeviceError> {
+        match self {
+            AudioBackendStatus::Running { .. } => None,
+            AudioBackendStatus::Headless(error) | AudioBackendStatus::Unavailable(error) => {
+                Some(error)
+            }
+        }
+    }
+}
+
+/// Sent at startup if the plugin could not open an audio output, see [`AudioBackendStatus`]
+#[derive(Event, Clone, Debug)]
+pub struct AudioBackendFailed {
+    /// Why the output could not be opened
+    pub error: Arc<OutputDeviceError>,
+}
+
+pub(crate) fn report_audio_backend(
+    status: Res<AudioBackendStatus>,
+    mut failed: EventWriter<AudioBackendFailed>,
+) {
+    if let AudioBackendStatus::Headless(error) | AudioBackendStatus::Unavailable(error) = &*status {
+        failed.send(AudioBackendFailed {
+            error: error.clone(),
+        });
+    }
+}
+
+/// Whether the browser lets the plugin play audio
+///
+/// Browsers only start audio after the player interacted with the page. Until then, the plugin
+/// holds back play commands and starts them once audio runs, so sounds played at startup are not
+/// lost. The first click, touch or key press resumes the audio. Use this resource to show a
+/// "click to enable sound" prompt. Outside the browser, the state is always
+/// [`Running`](Self::Running).
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn sound_prompt(state: Res<AudioContextState>, mut prompt: Query<&mut Visibility>) {
+///     if state.is_changed() {
+///         *prompt.single_mut() = match *state {
+///             AudioContextState::Suspended => Visibility::Visible,
+///             AudioContextState::Running => Visibility::Hidden,
+///         };
+///     }
+/// }
+/// ```
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AudioContextState {
+    /// The browser did not allow audio yet, play commands wait
+    Suspended,
+    /// Audio plays
+    #[default]
+    Running,
+}
+
+/// Errors that can occur when opening an output device
+///
+/// See [`AudioControl::set_output_device`](crate::AudioControl::set_output_device).
+#[derive(Error, Debug)]
+pub enum OutputDeviceError {
+    /// There is no output device with the given name
+    #[error("there is no audio output device named {0:?}")]
+    NotFound(String),
+    /// The output devices could not be listed
+    #[error("failed to list the audio output devices: {0}")]
+    Devices(#[from] cpal::DevicesError),
+    /// The default configuration of the device could not be determined
+    #[error("failed to get the configuration of the audio output device: {0}")]
+    Config(#[from] DefaultStreamConfigError),
+    /// The audio stream could not be built
+    #[error("failed to build the audio stream: {0}")]
+    BuildStream(#[from] BuildStreamError),
+    /// The audio stream could not be started
+    #[error("failed to start the audio stream: {0}")]
+    PlayStream(#[from] PlayStreamError),
+    /// The default output device could not be opened
+    #[error("failed to open the default audio output device: {0}")]
+    Default(kira::manager::backend::cpal::Error),
+}
+
+/// The names of all available output devices
+///
+/// Use them with [`AudioControl::set_output_device`](crate::AudioControl::set_output_device).
+pub fn output_device_names() -> Vec<String> {
+    match cpal::default_host().output_devices() {
+        Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+        Err(error) => {
+            error!("Failed to list the audio output devices: {}", error);
+            vec![]
+        }
+    }
+}
+
+/// An output device that audio can play on
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AudioDeviceInfo {
+    /// The name to select the device with, see [`AudioDevice`]
+    pub name: String,
+    /// Whether this is the default device of the system
+    pub is_default: bool,
+    /// The ranges of sample rates the device supports, empty if it doesn't report them
+    pub sample_rates: Vec<RangeInclusive<u32>>,
+}
+
+/// All available output devices
+///
+/// Listing the devices can block for a while, the [`AudioDevices`] resource does it on a task.
+/// In the browser, there is only the default device.
+pub fn available_output_devices() -> Vec<AudioDeviceInfo> {
+    if cfg!(target_arch = "wasm32") {
+        return vec![AudioDeviceInfo {
+            name: "Default".to_owned(),
+            is_default: true,
+            sample_rates: vec![],
+        }];
+    }
+    let host = cpal::default_host();
+    let default_name = host
+        .default_output_device()
+        .and_then(|device| device.name().ok());
+    let devices = match host.output_devices() {
+        Ok(devices) => devices,
+        Err(error) => {
+            error!("Failed to list the audio output devices: {}", error);
+            return vec![];
+        }
+    };
+
+    devices
+        .filter_map(|device| {
+            let name = device.name().ok()?;
+            let mut sample_rates: Vec<_> = device
+                .supported_output_configs()
+                .map(|configs| {
+                    configs
+                        .map(|config| config.min_sample_rate().0..=config.max_sample_rate().0)
+                        .collect()
+                })
+                .unwrap_or_default();
+            sample_rates.sort_by_key(|range| (*range.start(), *range.end()));
+            sample_rates.dedup();
+
+            Some(AudioDeviceInfo {
+                is_default: default_name.as_ref() == Some(&name),
+                name,
+                sample_rates,
+            })
+        })
+        .collect()
+}
+
+/// The available output devices, listed in the background
+///
+/// The list is filled shortly after the app starts. Call [`refresh`](Self::refresh) to list
+/// the devices again, for example when a settings menu opens. The resource only counts as
+/// changed once a new list arrived.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn update_device_picker(devices: Res<AudioDevices>) {
+///     if devices.is_changed() {
+///         for device in devices.devices() {
+///             info!("{} (default: {})", device.name, device.is_default);
+///         }
+///     }
+/// }
+/// ```
+#[derive(Resource, Debug)]
+pub struct AudioDevices {
+    devices: Vec<AudioDeviceInfo>,
+    refresh: DeviceRefresh,
+}
+
+#[derive(Debug)]
+enum DeviceRefresh {
+    Requested,
+    Running(async_channel::Receiver<Vec<AudioDeviceInfo>>),
+    Done,
+}
+
+impl Default for AudioDevices {
+    fn default() -> Self {
+        AudioDevices {
+            devices: vec![],
+            refresh: DeviceRefresh::Requested,
+        }
+    }
+}
+
+impl AudioDevices {
+    /// The devices of the last completed listing
+    pub fn devices(&self) -> &[AudioDeviceInfo] {
+        &self.devices
+    }
+
+    /// The default device of the system, if it was listed
+    pub fn default_device(&self) -> Option<&AudioDeviceInfo> {
+        self.devices.iter().find(|device| device.is_default)
+    }
+
+    /// List the devices again
+    ///
+    /// Does nothing while a listing is running.
+    pub fn refresh(&mut self) {
+        if matches!(self.refresh, DeviceRefresh::Done) {
+            self.refresh = DeviceRefresh::Requested;
+        }
+    }
+
+    /// Whether the devices are being listed
+    pub fn is_refreshing(&self) -> bool {
+        !matches!(self.refresh, DeviceRefresh::Done)
+    }
+
+    /// Start a requested listing and take the result of a finished one
+    fn poll(&mut self) -> Option<Vec<AudioDeviceInfo>> {
+        if matches!(self.refresh, DeviceRefresh::Requested) {
+            // Single threaded task pools don't hand out awaitable tasks
+            let (sender, receiver) = async_channel::bounded(1);
+            IoTaskPool::get()
+                .spawn(async move {
+                    let _ = sender.send(available_output_devices()).await;
+                })
+                .detach();
+            self.refresh = DeviceRefresh::Running(receiver);
+        }
+        let DeviceRefresh::Running(receiver) = &self.refresh else {
+            return None;
+        };
+        match receiver.try_recv() {
+            Ok(devices) => {
+                self.refresh = DeviceRefresh::Done;
+                Some(devices)
+            }
+            Err(async_channel::TryRecvError::Empty) => None,
+            Err(async_channel::TryRecvError::Closed) => {
+                self.refresh = DeviceRefresh::Done;
+                None
+            }
+        }
+    }
+}
+
+pub(crate) fn refresh_audio_devices(mut devices: ResMut<AudioDevices>) {
+    if let Some(listed) = devices.bypass_change_detection().poll() {
+        devices.devices = listed;
+    }
+}
+
+/// The output device with the given name
+pub(crate) fn find_output_device(name: &str) -> Result<Device, OutputDeviceError> {
+    cpal::default_host()
+        .output_devices()?
+        .find(|device| {
+            device
+                .name()
+                .map_or(false, |device_name| device_name == name)
+        })
+        .ok_or_else(|| OutputDeviceError::NotFound(name.to_owned()))
+}
+
+/// Open an audio manager that plays on the output device with the given name
+pub(crate) fn open_output_device(
+    name: &str,
+    settings: &AudioSettings,
+) -> Result<AudioManager<DeviceBackend>, OutputDeviceError> {
+    AudioManager::new(AudioManagerSettings {
+        capacities: settings.capacities(),
+        main_track_builder: TrackBuilder::default(),
+        backend_settings: (find_output_device(name)?, settings.stream_options()),
+    })
+}
+
+/// How the stream of an output device is opened, see [`AudioSettings`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct StreamOptions {
+    pub(crate) buffer_size: Option<u32>,
+    pub(crate) sample_rate: Option<u32>,
+}
+
+/// Where the main audio manager plays
+pub(crate) enum MainOutput {
+    /// On the given device, or the default one
+    Device(Option<Device>, StreamOptions),
+    /// Nowhere, see [`AudioSettings::headless_fallback`]
+    Headless,
+}
+
+/// The backend of the main audio manager, see [`AudioDevice`]
+///
+/// Without a device and [`StreamOptions`], it is the default backend of kira, which follows the
+/// default device of the system.
+pub(crate) enum MainBackend {
+    Default(CpalBackend, u32),
+    Device(DeviceBackend),
+    Headless(HeadlessBackend),
+}
+
+impl MainBackend {
+    /// Whether the stream reported that the device is gone
+    ///
+    /// The default backend restarts its stream by itself, so it only fails by stalling the
+    /// audio thread, see [`DeviceWatchdog`].
+    pub(crate) fn has_failed(&self) -> bool {
+        match self {
+            MainBackend::Default(..) | MainBackend::Headless(_) => false,
+            MainBackend::Device(backend) => backend.has_failed(),
+        }
+    }
+
+    /// Start the stream again, after the browser suspended it, see [`AudioContextState`]
+    pub(crate) fn resume(&self) {
+        if let MainBackend::Device(backend) = self {
+            backend.resume();
+        }
+    }
+
+    pub(crate) fn sample_rate(&self) -> u32 {
+        match self {
+            MainBackend::Default(_, sample_rate) => *sample_rate,
+            MainBackend::Device(backend) => backend.config.sample_rate.0,
+            MainBackend::Headless(_) => HEADLESS_SAMPLE_RATE,
+        }
+    }
+}
+
+/// The sample rate of the headless backend, low to keep rendering cheap
+const HEADLESS_SAMPLE_RATE: u32 = 1_000;
+
+/// A backend without output, see [`AudioSettings::headless_fallback`]
+///
+/// It renders the audio in the main thread as time passes, so sounds advance and complete.
+#[derive(Default)]
+pub(crate) struct HeadlessBackend {
+    renderer: Option<Box<Renderer>>,
+    /// Frames of the passed time that are not rendered yet
+    unrendered: f64,
+}
+
+impl HeadlessBackend {
+    /// Render the audio of the given time
+    pub(crate) fn advance(&mut self, delta: Duration) {
+        let Some(renderer) = self.renderer.as_mut() else {
+            return;
+        };
+        renderer.on_start_processing();
+        self.unrendered += delta.as_secs_f64() * f64::from(HEADLESS_SAMPLE_RATE);
+        while self.unrendered >= 1. {
+            renderer.process();
+            self.unrendered -= 1.;
+        }
+        // Sounds and clocks share their positions when processing starts
+        renderer.on_start_processing();
+    }
+}
+
+/// Sent when the output device the plugin played on is gone, for example because it was unplugged
+///
+/// Sounds that were playing are lost. The plugin tries to open the default device of the system
+/// and sends [`AudioDeviceChanged`] once it plays again. If the lost device was the one of a
+/// single channel, see [`AudioControl::set_output_device`](crate::AudioControl::set_output_device),
+/// the channel plays on the main device from then on.
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct AudioDeviceLost {
+    /// The name of the lost device, `None` for the default device
+    pub device: Option<String>,
+}
+
+/// Sent when the plugin started to play on another output device
+///
+/// This happens after [`AudioDevice`] was changed and after recovering from an
+/// [`AudioDeviceLost`].
+#[derive(Event, Clone, Debug, PartialEq, Eq)]
+pub struct AudioDeviceChanged {
+    /// The name of the new device, `None` for the default device
+    pub device: Option<String>,
+}
+
+/// How long the audio clock may stand still before the audio thread counts as dead
+const STALL_LIMIT: Duration = Duration::from_secs(2);
+/// The wait before the second attempt to reopen a lost device, doubled after each failure
+const FIRST_RETRY: Duration = Duration::from_millis(500);
+const MAX_RETRY: Duration = Duration::from_secs(10);
+
+/// Detects a dead audio thread and times the attempts to reopen the output
+///
+/// The audio clock ticks while the audio thread processes samples. If it stands still for a
+/// while, the stream is gone, even if the backend didn't report it.
+#[derive(Debug, Default)]
+pub(crate) struct DeviceWatchdog {
+    last_ticks: u64,
+    stalled: Duration,
+    /// Time until the next attempt to reopen the output, `None` while it works
+    retry_in: Option<Duration>,
+    backoff: Duration,
+}
+
+impl DeviceWatchdog {
+    /// Whether the clock stood still for too long, given its ticks and the real time since the
+    /// last observation
+    pub(crate) fn is_stalled(&mut self, ticks: u64, delta: Duration) -> bool {
+        if ticks != self.last_ticks {
+            self.last_ticks = ticks;
+            self.stalled = Duration::ZERO;
+            return false;
+        }
+        self.stalled += delta;
+
+        self.stalled > STALL_LIMIT
+    }
+
+    /// Try to reopen the output right away
+    pub(crate) fn lost(&mut self) {
+        self.retry_in = Some(Duration::ZERO);
+        self.backoff = FIRST_RETRY;
+    }
+
+    /// Whether the next attempt to reopen the output is due
+    pub(crate) fn retry_due(&mut self, delta: Duration) -> bool {
+        let Some(retry_in) = self.retry_in.as_mut() else {
+            return false;
+        };
+        *retry_in = retry_in.saturating_sub(delta);
+
+        retry_in.is_zero()
+    }
+
+    pub(crate) fn retry_failed(&mut self) {
+        self.retry_in = Some(self.backoff);
+        self.backoff = (self.backoff * 2).min(MAX_RETRY);
+    }
+
+    /// The output plays again, starting with a new clock
+    pub(crate) fn recovered(&mut self) {
+        *self = DeviceWatchdog::default();
+    }
+}
+
+/// The output devices that the audio managers play on
+///
+/// Channels with an [`AudioControl::set_output_device`](crate::AudioControl::set_output_device)
+/// play on managers of their own device, without the tracks of the main manager.
+#[derive(Default)]
+pub(crate) struct OutputDevices {
+    /// The name of the device the main manager plays on, see [`AudioDevice`]
+    pub(crate) main: Option<String>,
+    pub(crate) watchdog: DeviceWatchdog,
+    /// Audio managers of other output devices than the main one by device name
+    others: HashMap<String, AudioManager<DeviceBackend>>,
+}
+
+impl OutputDevices {
+    pub(crate) fn new(main: Option<String>) -> Self {
+        OutputDevices {
+            main,
+            ..Default::default()
+        }
+    }
+
+    /// Open the device with the given name for channels to play on
+    ///
+    /// Returns `true` if the device was not open yet.
+    pub(crate) fn open(
+        &mut self,
+        name: &str,
+        settings: &AudioSettings,
+    ) -> Result<bool, OutputDeviceError> {
+        if self.others.contains_key(name) {
+            return Ok(false);
+        }
+        let manager = open_output_device(name, settings)?;
+        self.others.insert(name.to_string(), manager);
+
+        Ok(true)
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&AudioManager<DeviceBackend>> {
+        self.others.get(name)
+    }
+
+    pub(crate) fn get_mut(&mut self, name: &str) -> Option<&mut AudioManager<DeviceBackend>> {
+        self.others.get_mut(name)
+    }
+
+    /// The managers of the other devices than the main one
+    pub(crate) fn managers(&self) -> impl Iterator<Item = &AudioManager<DeviceBackend>> {
+        self.others.values()
+    }
+
+    pub(crate) fn managers_mut(
+        &mut self,
+    ) -> impl Iterator<Item = &mut AudioManager<DeviceBackend>> {
+        self.others.values_mut()
+    }
+
+    /// Drop the managers of devices that are gone and return their names
+    ///
+    /// Their channels play on the main device afterwards.
+    pub(crate) fn remove_failed(&mut self) -> Vec<String> {
+        let failed: Vec<String> = self
+            .others
+            .iter_mut()
+            .filter_map(|(name, manager)| manager.backend_mut().has_failed().then(|| name.clone()))
+            .collect();
+        for name in failed.iter() {
+            self.others.remove(name);
+        }
+
+        failed
+    }
+
+    /// Drop the managers of the other devices, which stops their audio threads
+    pub(crate) fn close(&mut self) {
+        self.others.clear();
+    }
+
+    #[cfg(test)]
+    pub(crate) fn is_empty(&self) -> bool {
+        self.others.is_empty()
+    }
+}
+
+impl Backend for MainBackend {
+    type Settings = MainOutput;
+
+    type Error = OutputDeviceError;
+
+    fn setup(output: Self::Settings) -> Result<(Self, u32), Self::Error> {
+        let (device, options) = match output {
+            MainOutput::Device(device, options) => (device, options),
+            MainOutput::Headless => {
+                return Ok((
+                    MainBackend::Headless(HeadlessBackend::default()),
+                    HEADLESS_SAMPLE_RATE,
+                ))
+            }
+        };
+        let device = match device {
+            Some(device) => device,
+            // The default backend of kira can't be configured, and in the browser its stream
+            // can't be resumed after the first interaction
+            None if options == StreamOptions::default() && !cfg!(target_arch = "wasm32") => {
+                return CpalBackend::setup(())
+                    .map(|(backend, sample_rate)| {
+                        (MainBackend::Default(backend, sample_rate), sample_rate)
+                    })
+                    .map_err(OutputDeviceError::Default);
+            }
+            None => cpal::default_host()
+                .default_output_device()
+                .ok_or(OutputDeviceError::Default(CpalError::NoDefaultOutputDevice))?,
+        };
+
+        DeviceBackend::setup((device, options))
+            .map(|(backend, sample_rate)| (MainBackend::Device(backend), sample_rate))
+    }
+
+    fn start(&mut self, renderer: Renderer) -> Result<(), Self::Error> {
+        match self {
+            MainBackend::Default(backend, _) => {
+                backend.start(renderer).map_err(OutputDeviceError::Default)
+            }
+            MainBackend::Device(backend) => backend.start(renderer),
+            MainBackend::Headless(backend) => {
+                backend.renderer = Some(Box::new(renderer));
+                Ok(())
+            }
+        }
+    }
+}
+
+/// A cpal backend for a specific output device
+///
+/// Unlike the default backend of kira, it does not follow the device when it is disconnected.
+/// The plugin checks [`has_failed`](Self::has_failed) instead.
+pub(crate) struct DeviceBackend {
+    device: Device,
+    config: StreamConfig,
+    stream: Option<Stream>,
+    /// Set by the stream once the device is gone
+    failed: Arc<AtomicBool>,
+}
+
+impl DeviceBackend {
+    pub(crate) fn has_failed(&self) -> bool {
+        self.failed.load(Ordering::Relaxed)
+    }
+
+    fn resume(&self) {
+        if let Some(Err(error)) = self.stream.as_ref().map(StreamTrait::play) {
+            warn!("Failed to resume the audio stream: {}", error);
+        }
+    }
+}
+
+impl Backend for DeviceBackend {
+    type Settings = (Device, StreamOptions);
+
+    type Error = OutputDeviceError;
+
+    fn setup((device, options): Self::Settings) -> Result<(Self, u32), Self::Error> {
+        let default_config = device.default_output_config()?;
+        let mut config = default_config.config();
+        if let Some(sample_rate) = options.sample_rate {
+            let supported = device
+                .supported_output_configs()
+                .into_iter()
+                .flatten()
+                .filter(|range| range.channels() == config.channels)
+                .find_map(|range| range.try_with_sample_rate(SampleRate(sample_rate)));
+            match supported {
+                Some(supported) => config.sample_rate = supported.sample_rate(),
+                None => warn!(
+                    "The audio output device does not support a sample rate of {}, playing at {}",
+                    sample_rate, config.sample_rate.0
+                ),
+            }
+        }
+        if let Some(frames) = options.buffer_size {
+            config.buffer_size = BufferSize::Fixed(match default_config.buffer_size() {
+                SupportedBufferSize::Range { min, max } => frames.max(*min).min(*max),
+                SupportedBufferSize::Unknown => frames,
+            });
+        }
+        let sample_rate = config.sample_rate.0;
+
+        Ok((
+            DeviceBackend {
+                device,
+                config,
+                stream: None,
+                failed: Arc::new(AtomicBool::new(false)),
+            },
+            sample_rate,
+        ))
+    }
+
+    fn start(&mut self, mut renderer: Renderer) -> Result<(), Self::Error> {
+        let channels = self.config.channels as usize;
+        let stream = self.device.build_output_stream(
+            &self.config,
+            move |data: &mut [f32], _| {
+                renderer.on_start_processing();
+                for frame in data.chunks_exact_mut(channels) {
+                    let out = renderer.process();
+                    if channels == 1 {
+                        frame[0] = (out.left + out.right) / 2.;
+                    } else {
+                        frame[0] = out.left;
+                        frame[1] = out.right;
+                        // Other channels would play garbage if they were not silenced
+                        for channel in frame.iter_mut().skip(2) {
+                            *channel = 0.;
+                        }
+                    }
+                }
+            },
+            {
+                let failed = self.failed.clone();
+                move |error| {
+                    error!("Error in the audio stream of an output device: {}", error);
+                    if let StreamError::DeviceNotAvailable = error {
+                        failed.store(true, Ordering::Relaxed);
+                    }
+                }
+            },
+            None,
+        )?;
+        stream.play()?;
+        self.stream = Some(stream);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::tasks::TaskPool;
+    use kira::dsp::Frame;
+    use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+    use kira::sound::PlaybackState;
+
+    #[test]
+    fn devices_are_listed_on_a_task() {
+        IoTaskPool::get_or_init(TaskPool::default);
+        let mut devices = AudioDevices::default();
+        assert!(devices.is_refreshing());
+        let listed = (0..1_000)
+            .find_map(|_| {
+                let listed = devices.poll();
+                if listed.is_none() {
+                    std::thread::sleep(std::time::Duration::from_millis(5));
+                }
+                listed
+            })
+            .unwrap();
+        assert!(!devices.is_refreshing());
+        assert!(listed.iter().filter(|device| device.is_default).count() <= 1);
+
+        devices.refresh();
+        assert!(devices.is_refreshing());
+    }
+
+    #[test]
+    fn the_headless_backend_plays_sounds_to_their_end() {
+        let mut manager = AudioManager::new(
+            AudioSettings::default().headless_manager_settings(TrackBuilder::default()),
+        )
+        .unwrap();
+        let handle = manager
+            .play(StaticSoundData {
+                sample_rate: 1_000,
+                frames: Arc::from(vec![Frame::ZERO; 100]),
+                settings: StaticSoundSettings::default(),
+            })
+            .unwrap();
+        let MainBackend::Headless(backend) = manager.backend_mut() else {
+            panic!("expected the headless backend");
+        };
+
+        backend.advance(Duration::from_millis(50));
+        assert_eq!(handle.state(), PlaybackState::Playing);
+        backend.advance(Duration::from_millis(60));
+        assert_eq!(handle.state(), PlaybackState::Stopped);
+    }
+
+    #[test]
+    fn watchdog_backs_off_between_retries() {
+        let mut watchdog = DeviceWatchdog::default();
+        assert!(!watchdog.is_stalled(5, Duration::from_secs(1)));
+        assert!(!watchdog.is_stalled(5, Duration::from_secs(2)));
+        assert!(watchdog.is_stalled(5, Duration::from_millis(1)));
+        assert!(!watchdog.retry_due(Duration::ZERO));
+
+        watchdog.lost();
+        assert!(watchdog.retry_due(Duration::ZERO));
+        watchdog.retry_failed();
+        assert!(!watchdog.retry_due(Duration::from_millis(400)));
+        assert!(watchdog.retry_due(Duration::from_millis(100)));
+        watchdog.retry_failed();
+        assert!(!watchdog.retry_due(Duration::from_millis(900)));
+        assert!(watchdog.retry_due(Duration::from_millis(100)));
+        for _ in 0..10 {
+            watchdog.retry_failed();
+        }
+        assert!(watchdog.retry_due(MAX_RETRY));
+
+        watchdog.recovered();
+        assert!(!watchdog.retry_due(Duration::ZERO));
+        assert!(!watchdog.is_stalled(0, Duration::from_secs(1)));
+    }
+}
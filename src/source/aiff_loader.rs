@@ -0,0 +1,332 @@
+use anyhow::Result;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::ecs::world::{FromWorld, World};
+use bevy::utils::BoxedFuture;
+use kira::dsp::Frame;
+use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::source::decode::DecodeProgress;
+use crate::source::{AudioLoaderSettings, AudioSource};
+
+/// Possible errors that can be produced by [`AiffLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum AiffLoaderError {
+    /// An [IO Error](std::io::Error)
+    #[error("Could not read the file: {0}")]
+    Io(#[from] std::io::Error),
+    /// The file does not start with an AIFF `FORM` header
+    #[error("The file is no AIFF file")]
+    NotAiff,
+    /// A chunk that every AIFF file needs is missing
+    #[error("The file has no {0} chunk")]
+    MissingChunk(&'static str),
+    /// A chunk is shorter than its content or than its size says
+    #[error("The {0} chunk is cut off")]
+    TruncatedChunk(String),
+    /// Only 16 and 24 bit samples are supported
+    #[error("Samples with {0} bits are not supported")]
+    UnsupportedSampleSize(i16),
+    /// Only mono and stereo sounds are supported
+    #[error("Sounds with {0} channels are not supported")]
+    UnsupportedChannels(i16),
+    /// The sample rate is zero, negative or too large
+    #[error("The sample rate {0} is not supported")]
+    InvalidSampleRate(f64),
+}
+
+/// Loader for uncompressed AIFF files with 16 or 24 bit samples
+pub struct AiffLoader {
+    progress: DecodeProgress,
+}
+
+impl FromWorld for AiffLoader {
+    fn from_world(world: &mut World) -> Self {
+        AiffLoader {
+            progress: world
+                .get_resource_or_insert_with(DecodeProgress::default)
+                .clone(),
+        }
+    }
+}
+
+impl AssetLoader for AiffLoader {
+    type Asset = AudioSource;
+    type Settings = AudioLoaderSettings;
+    type Error = AiffLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        settings: &'a AudioLoaderSettings,
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut sound_bytes = vec![];
+            reader.read_to_end(&mut sound_bytes).await?;
+            self.progress
+                .source_with(
+                    load_context.asset_path(),
+                    sound_bytes,
+                    settings,
+                    decode_sound,
+                )
+                .await
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["aiff", "aif"]
+    }
+}
+
+/// The format of the samples from the `COMM` chunk
+struct Common {
+    channels: i16,
+    frames: u32,
+    sample_size: i16,
+    sample_rate: f64,
+}
+
+fn decode_sound(
+    bytes: Vec<u8>,
+    settings: StaticSoundSettings,
+) -> Result<StaticSoundData, AiffLoaderError> {
+    let (sample_rate, frames) = decode_aiff(&bytes)?;
+    Ok(StaticSoundData {
+        sample_rate,
+        frames: Arc::from(frames),
+        settings,
+    })
+}
+
+/// The sample rate and frames of an AIFF file
+fn decode_aiff(bytes: &[u8]) -> Result<(u32, Vec<Frame>), AiffLoaderError> {
+    if bytes.len() < 12 || &bytes[..4] != b"FORM" || &bytes[8..12] != b"AIFF" {
+        return Err(AiffLoaderError::NotAiff);
+    }
+    let mut common = None;
+    let mut sound_data = None;
+    let mut rest = &bytes[12..];
+    while !rest.is_empty() {
+        let Some(header) = rest.get(..8) else {
+            return Err(AiffLoaderError::TruncatedChunk("last".to_string()));
+        };
+        let id = String::from_utf8_lossy(&header[..4]).into_owned();
+        let size = u32::from_be_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        let Some(data) = 8usize.checked_add(size).and_then(|end| rest.get(8..end)) else {
+            return Err(AiffLoaderError::TruncatedChunk(id));
+        };
+        match &header[..4] {
+            b"COMM" => common = Some(parse_common(data)?),
+            b"SSND" => {
+                if data.len() < 8 {
+                    return Err(AiffLoaderError::TruncatedChunk(id));
+                }
+                let offset = u32::from_be_bytes([data[0], data[1], data[2], data[3]]) as usize;
+                sound_data = Some(
+                    8usize
+                        .checked_add(offset)
+                        .and_then(|start| data.get(start..))
+                        .ok_or(AiffLoaderError::TruncatedChunk(id))?,
+                );
+            }
+            _ => (),
+        }
+        // Chunks are padded to an even size, the padding of the last one may be missing
+        rest = 8usize
+            .checked_add(size)
+            .and_then(|end| end.checked_add(size % 2))
+            .and_then(|end| rest.get(end..))
+            .unwrap_or_default();
+    }
+    let common = common.ok_or(AiffLoaderError::MissingChunk("COMM"))?;
+    let sound_data = sound_data.ok_or(AiffLoaderError::MissingChunk("SSND"))?;
+
+    if !(1. ..=u32::MAX as f64).contains(&common.sample_rate) {
+        return Err(AiffLoaderError::InvalidSampleRate(common.sample_rate));
+    }
+    let sample_bytes = match common.sample_size {
+        16 => 2,
+        24 => 3,
+        size => return Err(AiffLoaderError::UnsupportedSampleSize(size)),
+    };
+    let channels = match common.channels {
+        1 | 2 => common.channels as usize,
+        channels => return Err(AiffLoaderError::UnsupportedChannels(channels)),
+    };
+    let frame_bytes = sample_bytes * channels;
+    let Some(samples) = (common.frames as usize)
+        .checked_mul(frame_bytes)
+        .and_then(|length| sound_data.get(..length))
+    else {
+        return Err(AiffLoaderError::TruncatedChunk("SSND".to_string()));
+    };
+    let sample = |bytes: &[u8]| match bytes {
+        [high, low] => i16::from_be_bytes([*high, *low]) as f32 / 32_768.,
+        // Shifting the bytes to the top of an i32 keeps the sign
+        [high, middle, low] => {
+            (i32::from_be_bytes([*high, *middle, *low, 0]) >> 8) as f32 / 8_388_608.
+        }
+        _ => unreachable!("samples have two or three bytes"),
+    };
+    let frames = samples
+        .chunks_exact(frame_bytes)
+        .map(|frame| {
+            let left = sample(&frame[..sample_bytes]);
+            if channels == 1 {
+                Frame::from_mono(left)
+            } else {
+                Frame::new(left, sample(&frame[sample_bytes..]))
+            }
+        })
+        .collect();
+
+    Ok((common.sample_rate.round() as u32, frames))
+}
+
+fn parse_common(data: &[u8]) -> Result<Common, AiffLoaderError> {
+    if data.len() < 18 {
+        return Err(AiffLoaderError::TruncatedChunk("COMM".to_string()));
+    }
+    Ok(Common {
+        channels: i16::from_be_bytes([data[0], data[1]]),
+        frames: u32::from_be_bytes([data[2], data[3], data[4], data[5]]),
+        sample_size: i16::from_be_bytes([data[6], data[7]]),
+        sample_rate: extended_to_f64(data[8..18].try_into().unwrap()),
+    })
+}
+
+/// Convert an 80 bit IEEE 754 extended precision number, which AIFF uses for the sample rate
+fn extended_to_f64(bytes: [u8; 10]) -> f64 {
+    let sign = if bytes[0] & 0x80 == 0 { 1. } else { -1. };
+    let exponent = (u16::from_be_bytes([bytes[0], bytes[1]]) & 0x7fff) as i32;
+    let mantissa = u64::from_be_bytes(bytes[2..].try_into().unwrap());
+    if exponent == 0 && mantissa == 0 {
+        return 0.;
+    }
+
+    sign * mantissa as f64 * 2f64.powi(exponent - 16_383 - 63)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::source::decode::DecodeStatus;
+    use bevy::asset::AssetPath;
+    use bevy::tasks::{block_on, AsyncComputeTaskPool, TaskPool};
+
+    fn chunk(id: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        let mut chunk = id.to_vec();
+        chunk.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        chunk.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            chunk.push(0);
+        }
+        chunk
+    }
+
+    fn aiff(channels: i16, sample_size: i16, samples: &[u8]) -> Vec<u8> {
+        let frames = samples.len() / (channels as usize * sample_size as usize / 8);
+        let mut common = channels.to_be_bytes().to_vec();
+        common.extend_from_slice(&(frames as u32).to_be_bytes());
+        common.extend_from_slice(&sample_size.to_be_bytes());
+        // 44100 as 80 bit extended
+        common.extend_from_slice(&[0x40, 0x0e, 0xac, 0x44, 0, 0, 0, 0, 0, 0]);
+        let mut sound_data = vec![0; 8];
+        sound_data.extend_from_slice(samples);
+
+        let mut chunks = b"AIFF".to_vec();
+        chunks.extend(chunk(b"COMM", &common));
+        chunks.extend(chunk(b"SSND", &sound_data));
+        let mut file = b"FORM".to_vec();
+        file.extend_from_slice(&(chunks.len() as u32).to_be_bytes());
+        file.extend(chunks);
+        file
+    }
+
+    #[test]
+    fn decodes_16_bit_stereo() {
+        let file = aiff(2, 16, &[0x40, 0, 0xc0, 0, 0x7f, 0xff, 0, 0]);
+        let (sample_rate, frames) = decode_aiff(&file).unwrap();
+        assert_eq!(sample_rate, 44_100);
+        assert_eq!(
+            frames,
+            vec![Frame::new(0.5, -0.5), Frame::new(32_767. / 32_768., 0.)]
+        );
+    }
+
+    #[test]
+    fn decodes_24_bit_mono() {
+        let file = aiff(1, 24, &[0x40, 0, 0, 0xff, 0xff, 0xff, 0]);
+        let (_, frames) = decode_aiff(&file).unwrap();
+        assert_eq!(
+            frames,
+            vec![Frame::from_mono(0.5), Frame::from_mono(-1. / 8_388_608.)]
+        );
+    }
+
+    #[test]
+    fn files_are_decoded_on_the_task_pool() {
+        AsyncComputeTaskPool::get_or_init(TaskPool::default);
+        let progress = DecodeProgress::default();
+        let path = AssetPath::from("sounds/test.aiff");
+        let file = aiff(1, 16, &[0x40, 0, 0xc0, 0]);
+
+        let source = block_on(progress.source_with(
+            &path,
+            file.clone(),
+            &AudioLoaderSettings::default(),
+            decode_sound,
+        ))
+        .unwrap();
+        assert_eq!(source.sound.frames.len(), 2);
+        assert_eq!(progress.status_of_path(&path), Some(DecodeStatus::Ready));
+
+        let result = block_on(progress.source_with(
+            &path,
+            file[..20].to_vec(),
+            &AudioLoaderSettings::default(),
+            decode_sound,
+        ));
+        assert!(matches!(result, Err(AiffLoaderError::TruncatedChunk(_))));
+        assert_eq!(progress.status_of_path(&path), Some(DecodeStatus::Failed));
+    }
+
+    #[test]
+    fn malformed_files_are_errors() {
+        assert!(matches!(
+            decode_aiff(b"RIFF\0\0\0\0WAVE"),
+            Err(AiffLoaderError::NotAiff)
+        ));
+
+        let file = aiff(2, 16, &[0; 8]);
+        assert!(matches!(
+            decode_aiff(&file[..file.len() - 2]),
+            Err(AiffLoaderError::TruncatedChunk(id)) if id == "SSND"
+        ));
+
+        let mut file = b"FORM\0\0\0\x04AIFF".to_vec();
+        file.extend(chunk(b"SSND", &[0; 8]));
+        assert!(matches!(
+            decode_aiff(&file),
+            Err(AiffLoaderError::MissingChunk("COMM"))
+        ));
+
+        let mut file = aiff(1, 16, &[0; 4]);
+        // A frame count that doesn't fit into the memory
+        file[22..26].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(matches!(
+            decode_aiff(&file),
+            Err(AiffLoaderError::TruncatedChunk(id)) if id == "SSND"
+        ));
+
+        assert!(matches!(
+            decode_aiff(&aiff(2, 8, &[0; 4])),
+            Err(AiffLoaderError::UnsupportedSampleSize(8))
+        ));
+    }
+}
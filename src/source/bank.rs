@@ -0,0 +1,148 @@
+//! Banks of named sounds that load from one manifest
+
+use crate::audio::PlayAudioCommand;
+use crate::channel::AudioCommandQue;
+use crate::source::AudioSource;
+use bevy::asset::{Asset, Handle};
+use bevy::reflect::TypePath;
+use bevy::utils::HashMap;
+use kira::Volume;
+use thiserror::Error;
+
+/// Named sounds that load as one asset
+///
+/// With the `bank` feature, banks load from `*.bank.ron` manifests listing the sound files and
+/// the settings they play with. The sounds are labeled assets of the bank, like
+/// `sfx.bank.ron#footstep_grass`. Changing one of the files reloads the bank.
+/// ```ron
+/// (
+///     sounds: {
+///         "footstep_grass": (
+///             file: "sounds/footstep_grass.ogg",
+///             volume: 0.8,
+///             pitch_variation: Some(1.),
+///         ),
+///         "campfire": (file: "sounds/campfire.ogg", looped: true),
+///     },
+/// )
+/// ```
+/// Play the sounds with [`AudioControl::play_from_bank`](crate::AudioControl::play_from_bank).
+#[derive(Asset, TypePath, Clone, Default)]
+pub struct AudioBank {
+    pub(crate) sounds: HashMap<String, BankSound>,
+}
+
+/// A sound of an [`AudioBank`] and the settings it plays with
+#[derive(Clone, Debug)]
+pub struct BankSound {
+    /// The source of the sound
+    pub source: Handle<AudioSource>,
+    /// Amplitude multiplier
+    pub volume: f64,
+    /// Random variation of the playback rate in semitones, see
+    /// [`PlayAudioCommand::pitch_variation`]
+    pub pitch_variation: Option<f64>,
+    /// Whether the sound loops, see [`PlayAudioCommand::looped`]
+    pub looped: bool,
+    /// Start and end of the looped part in seconds, see [`PlayAudioCommand::loop_region`]
+    pub loop_region: Option<(f64, f64)>,
+}
+
+/// Errors that can occur when playing from an [`AudioBank`]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum AudioBankError {
+    /// The bank has no sound with the given name
+    #[error("the audio bank has no sound named {0:?}")]
+    MissingSound(String),
+}
+
+impl AudioBank {
+    /// The sound with the given name
+    pub fn get(&self, name: &str) -> Result<&BankSound, AudioBankError> {
+        self.sounds
+            .get(name)
+            .ok_or_else(|| AudioBankError::MissingSound(name.to_owned()))
+    }
+
+    /// The names of all sounds in the bank
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.sounds.keys().map(String::as_str)
+    }
+
+    /// Add a sound to the bank, replacing the sound with the same name
+    pub fn insert(&mut self, name: impl Into<String>, sound: BankSound) {
+        self.sounds.insert(name.into(), sound);
+    }
+
+    pub(crate) fn play<'a>(
+        &self,
+        name: &str,
+        que: &'a dyn AudioCommandQue,
+    ) -> Result<PlayAudioCommand<'a>, AudioBankError> {
+        let sound = self.get(name)?;
+        let mut command = PlayAudioCommand::new(sound.source.clone(), que);
+        command.with_volume(Volume::Amplitude(sound.volume));
+        if let Some(semitones) = sound.pitch_variation {
+            command.pitch_variation(semitones);
+        }
+        if sound.looped {
+            command.looped();
+        }
+        if let Some((start, end)) = sound.loop_region {
+            command.loop_region(start, end);
+        }
+
+        Ok(command)
+    }
+}
+
+impl BankSound {
+    /// A sound playing the source with default settings
+    pub fn new(source: Handle<AudioSource>) -> Self {
+        BankSound {
+            source,
+            volume: 1.,
+            pitch_variation: None,
+            looped: false,
+            loop_region: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::audio::AudioCommand;
+    use crate::{Audio, AudioControl};
+
+    #[test]
+    fn bank_sounds_play_with_their_settings() {
+        let mut bank = AudioBank::default();
+        bank.insert(
+            "campfire",
+            BankSound {
+                volume: 0.5,
+                pitch_variation: Some(2.),
+                looped: true,
+                ..BankSound::new(Handle::default())
+            },
+        );
+        let audio = Audio::default();
+
+        assert_eq!(
+            audio.play_from_bank(&bank, "footstep").err(),
+            Some(AudioBankError::MissingSound("footstep".to_string()))
+        );
+        audio.play_from_bank(&bank, "campfire").unwrap();
+        let commands = audio.commands.read();
+        assert_eq!(commands.len(), 1);
+        match commands.front() {
+            Some(AudioCommand::Play(settings)) => {
+                assert_eq!(settings.settings.volume, Some(Volume::Amplitude(0.5)));
+                assert_eq!(settings.settings.pitch_variation, Some(2.));
+                assert!(settings.settings.source_loop);
+            }
+            _ => panic!("Expected a play command"),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext, ReadAssetBytesError};
+use bevy::utils::{BoxedFuture, HashMap};
+use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+use kira::sound::FromFileError;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::source::bank::{AudioBank, BankSound};
+use crate::AudioSource;
+
+#[derive(Default)]
+pub struct AudioBankLoader;
+
+/// The manifest of a `*.bank.ron` file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BankManifest {
+    sounds: HashMap<String, BankEntry>,
+}
+
+/// A sound in a bank manifest
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BankEntry {
+    /// Location of the sound file.
+    file: PathBuf,
+
+    /// Amplitude multiplier
+    #[serde(default = "default_one")]
+    volume: f64,
+
+    /// Random variation of the playback rate in semitones
+    #[serde(default)]
+    pitch_variation: Option<f64>,
+
+    /// Loop the sound
+    #[serde(default)]
+    looped: bool,
+
+    /// Start and end of the looped part in seconds
+    #[serde(default)]
+    loop_region: Option<(f64, f64)>,
+}
+
+fn default_one() -> f64 {
+    1.0
+}
+
+/// Possible errors that can be produced by [`AudioBankLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum AudioBankLoaderError {
+    /// An [IO Error](std::io::Error)
+    #[error("Could not read the file: {0}")]
+    Io(#[from] std::io::Error),
+    /// An Error loading sound from a file. See [`FromFileError`]
+    #[error("Error while loading a sound: {0}")]
+    FileError(#[from] FromFileError),
+    /// Failed to read audio asset
+    #[error("Error while loading audio asset: {0}")]
+    ReadAssetError(#[from] ReadAssetBytesError),
+    /// A [RON Error](serde_ron::error::SpannedError)
+    #[error("Could not parse RON: {0}")]
+    RonError(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for AudioBankLoader {
+    type Asset = AudioBank;
+    type Settings = ();
+    type Error = AudioBankLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let manifest: BankManifest = ron::de::from_bytes(&bytes)?;
+            let mut bank = AudioBank::default();
+            for (name, entry) in manifest.sounds {
+                // Reading the file through the load context reloads the bank when it changes
+                let sound_bytes = load_context.read_asset_bytes(entry.file).await?;
+                let sound = StaticSoundData::from_cursor(
+                    Cursor::new(sound_bytes),
+                    StaticSoundSettings::default(),
+                )?;
+                let source = load_context.add_labeled_asset(
+                    name.clone(),
+                    AudioSource {
+                        sound,
+                        loop_region: None,
+                        gain: 1.,
+                    },
+                );
+                bank.insert(
+                    name,
+                    BankSound {
+                        source,
+                        volume: entry.volume,
+                        pitch_variation: entry.pitch_variation,
+                        looped: entry.looped,
+                        loop_region: entry.loop_region,
+                    },
+                );
+            }
+
+            Ok(bank)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["bank.ron"]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn manifests_default_missing_settings() {
+        let manifest: BankManifest = ron::de::from_str(
+            r#"(
+                sounds: {
+                    "footstep": (file: "sounds/plop.ogg", pitch_variation: Some(1.)),
+                    "music": (file: "sounds/loop.ogg", volume: 0.5, loop_region: Some((1., 2.))),
+                },
+            )"#,
+        )
+        .unwrap();
+
+        let footstep = &manifest.sounds["footstep"];
+        assert_eq!(footstep.volume, 1.);
+        assert_eq!(footstep.pitch_variation, Some(1.));
+        assert!(!footstep.looped);
+        assert_eq!(manifest.sounds["music"].loop_region, Some((1., 2.)));
+        assert!(ron::de::from_str::<BankManifest>(r#"(sounds: {"a": (path: "a.ogg")})"#).is_err());
+    }
+}
@@ -0,0 +1,333 @@
+//! Collections of interchangeable sounds that play one variant at a time
+
+use crate::audio_output::AudioOutput;
+use crate::source::AudioSource;
+use crate::variation::AudioRng;
+use bevy::asset::{Asset, AssetId, Assets, Handle};
+use bevy::ecs::change_detection::{DetectChanges, NonSendMut};
+use bevy::ecs::system::Res;
+use bevy::reflect::TypePath;
+use bevy::utils::HashMap;
+use serde::Deserialize;
+
+/// How an [`AudioCollection`] picks the variant to play
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+pub enum SelectionMode {
+    /// A random variant, but never the one that played last (default)
+    #[default]
+    RandomNoRepeat,
+    /// All variants one after the other in the order of the collection
+    RoundRobin,
+    /// All variants in a random order before any of them plays again
+    ///
+    /// A new round never starts with the variant that ended the last one.
+    Shuffle,
+}
+
+/// Variants of a sound, like footsteps, of which every play picks one
+///
+/// Play a collection with [`AudioControl::play_collection`](crate::AudioControl::play_collection).
+/// The [`SelectionMode`] decides which variant plays. The weights of the variants make the random
+/// modes pick them more or less often, round robin ignores them. No mode plays the same variant
+/// twice in a row, unless the collection has only one.
+///
+/// Build collections in code or, with the `bank` feature, load them from `*.collection.ron` files.
+/// ```ron
+/// (
+///     mode: Shuffle,
+///     variants: [
+///         (file: "sounds/footstep_1.ogg"),
+///         (file: "sounds/footstep_2.ogg"),
+///         (file: "sounds/footstep_3.ogg", weight: 0.5),
+///     ],
+/// )
+/// ```
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// #[derive(Resource)]
+/// struct Footsteps(Handle<AudioCollection>);
+///
+/// fn setup(
+///     mut commands: Commands,
+///     mut collections: ResMut<Assets<AudioCollection>>,
+///     asset_server: Res<AssetServer>,
+/// ) {
+///     let footsteps = AudioCollection::new(SelectionMode::RandomNoRepeat)
+///         .with(asset_server.load("sounds/footstep_1.ogg"), 1.)
+///         .with(asset_server.load("sounds/footstep_2.ogg"), 1.)
+///         .with(asset_server.load("sounds/footstep_3.ogg"), 0.5);
+///     commands.insert_resource(Footsteps(collections.add(footsteps)));
+/// }
+///
+/// fn step(footsteps: Res<Footsteps>, audio: Res<Audio>) {
+///     audio.play_collection(footsteps.0.clone()).with_volume(0.6);
+/// }
+/// ```
+#[derive(Asset, TypePath, Clone, Debug, Default, PartialEq)]
+pub struct AudioCollection {
+    pub(crate) variants: Vec<(Handle<AudioSource>, f64)>,
+    /// How the variant of every play is picked
+    pub mode: SelectionMode,
+}
+
+impl AudioCollection {
+    /// An empty collection with the given selection mode
+    pub fn new(mode: SelectionMode) -> Self {
+        AudioCollection {
+            variants: vec![],
+            mode,
+        }
+    }
+
+    /// A collection of equally weighted variants
+    pub fn from_sources(
+        sources: impl IntoIterator<Item = Handle<AudioSource>>,
+        mode: SelectionMode,
+    ) -> Self {
+        AudioCollection {
+            variants: sources.into_iter().map(|source| (source, 1.)).collect(),
+            mode,
+        }
+    }
+
+    /// Add a variant with the given weight
+    pub fn with(mut self, source: Handle<AudioSource>, weight: f64) -> Self {
+        self.push(source, weight);
+        self
+    }
+
+    /// Add a variant with the given weight
+    pub fn push(&mut self, source: Handle<AudioSource>, weight: f64) {
+        self.variants.push((source, weight));
+    }
+
+    /// The variants with their weights in the order of the collection
+    pub fn variants(&self) -> impl Iterator<Item = (&Handle<AudioSource>, f64)> {
+        self.variants
+            .iter()
+            .map(|(source, weight)| (source, *weight))
+    }
+
+    /// The number of variants
+    pub fn len(&self) -> usize {
+        self.variants.len()
+    }
+
+    /// Whether the collection has no variants
+    pub fn is_empty(&self) -> bool {
+        self.variants.is_empty()
+    }
+}
+
+/// A collection and what it played so far
+pub(crate) struct CollectionPicker {
+    collection: AudioCollection,
+    last: Option<usize>,
+    /// The next variant in round robin mode
+    next: usize,
+    /// Variants that did not play in the current round in shuffle mode
+    round: Vec<usize>,
+}
+
+impl CollectionPicker {
+    pub(crate) fn new(collection: AudioCollection) -> Self {
+        CollectionPicker {
+            collection,
+            last: None,
+            next: 0,
+            round: vec![],
+        }
+    }
+
+    /// Take over a changed collection, starting over if it is a different one
+    pub(crate) fn update(&mut self, collection: &AudioCollection) {
+        if self.collection != *collection {
+            *self = CollectionPicker::new(collection.clone());
+        }
+    }
+
+    /// The source of the next play, `None` for empty collections
+    pub(crate) fn pick(&mut self, rng: &AudioRng) -> Option<Handle<AudioSource>> {
+        let len = self.collection.len();
+        if len == 0 {
+            return None;
+        }
+        let index = match self.collection.mode {
+            SelectionMode::RandomNoRepeat => {
+                let candidates: Vec<usize> = (0..len)
+                    .filter(|index| len == 1 || Some(*index) != self.last)
+                    .collect();
+                self.weighted(&candidates, rng)
+            }
+            SelectionMode::RoundRobin => {
+                let index = self.next % len;
+                self.next = index + 1;
+                index
+            }
+            SelectionMode::Shuffle => {
+                if self.round.is_empty() {
+                    let candidates: Vec<usize> = (0..len)
+                        .filter(|index| len == 1 || Some(*index) != self.last)
+                        .collect();
+                    let index = self.weighted(&candidates, rng);
+                    // The last variant of the previous round joins once the new round started
+                    self.round = (0..len).filter(|other| *other != index).collect();
+                    index
+                } else {
+                    let index = self.weighted(&self.round, rng);
+                    self.round.retain(|other| *other != index);
+                    index
+                }
+            }
+        };
+        self.last = Some(index);
+
+        Some(self.collection.variants[index].0.clone())
+    }
+
+    /// A random one of the candidates, by weight or uniformly if none has a positive weight
+    fn weighted(&self, candidates: &[usize], rng: &AudioRng) -> usize {
+        let weight = |index: usize| self.collection.variants[index].1.max(0.);
+        let total: f64 = candidates.iter().map(|index| weight(*index)).sum();
+        if total <= 0. {
+            let position = (rng.fraction() * candidates.len() as f64) as usize;
+            return candidates[position.min(candidates.len() - 1)];
+        }
+        let mut remaining = rng.fraction() * total;
+        for index in candidates {
+            remaining -= weight(*index);
+            if remaining < 0. {
+                return *index;
+            }
+        }
+        // Rounding left a bit of the total, take the last variant that can play
+        *candidates
+            .iter()
+            .rev()
+            .find(|index| weight(**index) > 0.)
+            .unwrap_or(&candidates[candidates.len() - 1])
+    }
+}
+
+/// The loaded collections with the variants they played
+#[derive(Default)]
+pub(crate) struct CollectionPickers {
+    pickers: HashMap<AssetId<AudioCollection>, CollectionPicker>,
+}
+
+impl CollectionPickers {
+    /// Mirror the loaded collections, keeping what unchanged ones played so far
+    pub(crate) fn update(&mut self, collections: &Assets<AudioCollection>) {
+        self.pickers.retain(|id, _| collections.contains(*id));
+        for (id, collection) in collections.iter() {
+            match self.pickers.get_mut(&id) {
+                Some(picker) => picker.update(collection),
+                None => {
+                    self.pickers
+                        .insert(id, CollectionPicker::new(collection.clone()));
+                }
+            }
+        }
+    }
+
+    /// The picker of the collection, `None` while it is not loaded
+    pub(crate) fn get_mut(
+        &mut self,
+        collection: AssetId<AudioCollection>,
+    ) -> Option<&mut CollectionPicker> {
+        self.pickers.get_mut(&collection)
+    }
+}
+
+/// Hand loaded and changed [`AudioCollection`]s to the audio output
+pub(crate) fn update_audio_collections(
+    mut audio_output: NonSendMut<AudioOutput>,
+    collections: Res<Assets<AudioCollection>>,
+) {
+    if collections.is_changed() {
+        audio_output.update_collections(&collections);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::asset::AssetId;
+    use bevy::utils::Uuid;
+
+    fn collection(mode: SelectionMode, weights: &[f64]) -> AudioCollection {
+        let mut collection = AudioCollection::new(mode);
+        for (index, weight) in weights.iter().enumerate() {
+            collection.push(
+                Handle::Weak(AssetId::Uuid {
+                    uuid: Uuid::from_u128(index as u128 + 1),
+                }),
+                *weight,
+            );
+        }
+        collection
+    }
+
+    fn picks(collection: AudioCollection, count: usize) -> Vec<usize> {
+        let rng = AudioRng::seeded(7);
+        let mut picker = CollectionPicker::new(collection.clone());
+        (0..count)
+            .map(|_| {
+                let source = picker.pick(&rng).unwrap();
+                collection
+                    .variants()
+                    .position(|(variant, _)| *variant == source)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn random_variants_never_repeat_immediately() {
+        let picked = picks(
+            collection(SelectionMode::RandomNoRepeat, &[1., 1., 8.]),
+            1_000,
+        );
+        assert!(picked.windows(2).all(|pair| pair[0] != pair[1]));
+        // Every variant still gets its turn
+        assert!((0..3).all(|index| picked.contains(&index)));
+
+        let two = picks(collection(SelectionMode::RandomNoRepeat, &[1., 1.]), 100);
+        assert!(two.windows(2).all(|pair| pair[0] != pair[1]));
+        let single = picks(collection(SelectionMode::RandomNoRepeat, &[1.]), 3);
+        assert_eq!(single, vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn weights_make_random_variants_more_likely() {
+        let picked = picks(
+            collection(SelectionMode::RandomNoRepeat, &[0., 1., 1., 6.]),
+            400,
+        );
+        assert!(!picked.contains(&0));
+        let heavy = picked.iter().filter(|index| **index == 3).count();
+        let light = picked.iter().filter(|index| **index == 1).count();
+        assert!(heavy > light);
+    }
+
+    #[test]
+    fn round_robin_and_shuffle_play_every_variant_once_per_round() {
+        let picked = picks(collection(SelectionMode::RoundRobin, &[1., 5., 1.]), 7);
+        assert_eq!(picked, vec![0, 1, 2, 0, 1, 2, 0]);
+
+        let picked = picks(collection(SelectionMode::Shuffle, &[1., 1., 1., 1.]), 400);
+        for round in picked.chunks(4) {
+            let mut sorted = round.to_vec();
+            sorted.sort();
+            assert_eq!(sorted, vec![0, 1, 2, 3]);
+        }
+        assert!(picked.windows(2).all(|pair| pair[0] != pair[1]));
+    }
+
+    #[test]
+    fn empty_collections_pick_nothing() {
+        let mut picker = CollectionPicker::new(AudioCollection::default());
+        assert_eq!(picker.pick(&AudioRng::seeded(0)), None);
+    }
+}
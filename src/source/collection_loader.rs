@@ -0,0 +1,111 @@
+use std::path::PathBuf;
+
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::utils::BoxedFuture;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::source::collection::{AudioCollection, SelectionMode};
+use crate::AudioSource;
+
+#[derive(Default)]
+pub struct AudioCollectionLoader;
+
+/// The manifest of a `*.collection.ron` file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CollectionManifest {
+    /// How the variant of every play is picked
+    #[serde(default)]
+    mode: SelectionMode,
+
+    variants: Vec<CollectionEntry>,
+}
+
+/// A variant in a collection manifest
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CollectionEntry {
+    /// Location of the sound file.
+    file: PathBuf,
+
+    /// How likely the variant is picked in the random modes
+    #[serde(default = "default_one")]
+    weight: f64,
+}
+
+fn default_one() -> f64 {
+    1.0
+}
+
+/// Possible errors that can be produced by [`AudioCollectionLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum AudioCollectionLoaderError {
+    /// An [IO Error](std::io::Error)
+    #[error("Could not read the file: {0}")]
+    Io(#[from] std::io::Error),
+    /// A [RON Error](serde_ron::error::SpannedError)
+    #[error("Could not parse RON: {0}")]
+    RonError(#[from] ron::error::SpannedError),
+}
+
+impl AssetLoader for AudioCollectionLoader {
+    type Asset = AudioCollection;
+    type Settings = ();
+    type Error = AudioCollectionLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            let manifest: CollectionManifest = ron::de::from_bytes(&bytes)?;
+            let mut collection = AudioCollection::new(manifest.mode);
+            for entry in manifest.variants {
+                // The variants load with the loaders of their formats
+                let source = load_context.load::<AudioSource>(entry.file);
+                collection.push(source, entry.weight);
+            }
+
+            Ok(collection)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["collection.ron"]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn manifests_default_the_mode_and_weights() {
+        let manifest: CollectionManifest = ron::de::from_str(
+            r#"(
+                variants: [
+                    (file: "sounds/step_1.ogg"),
+                    (file: "sounds/step_2.ogg", weight: 0.5),
+                ],
+            )"#,
+        )
+        .unwrap();
+
+        assert_eq!(manifest.mode, SelectionMode::RandomNoRepeat);
+        assert_eq!(manifest.variants[0].weight, 1.);
+        assert_eq!(manifest.variants[1].weight, 0.5);
+        let shuffled: CollectionManifest =
+            ron::de::from_str(r#"(mode: Shuffle, variants: [(file: "a.ogg")])"#).unwrap();
+        assert_eq!(shuffled.mode, SelectionMode::Shuffle);
+        assert!(
+            ron::de::from_str::<CollectionManifest>(r#"(variants: [(path: "a.ogg")])"#).is_err()
+        );
+    }
+}
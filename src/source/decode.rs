@@ -0,0 +1,295 @@
+//! Decoding of loaded sound files away from the asset IO tasks
+
+use crate::source::{AudioLoaderSettings, AudioSource};
+use bevy::asset::{AssetPath, AssetServer, Handle};
+use bevy::ecs::system::Resource;
+use bevy::tasks::AsyncComputeTaskPool;
+use bevy::utils::HashMap;
+use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+#[cfg(any(feature = "flac", feature = "mp3", feature = "ogg", feature = "wav"))]
+use kira::sound::FromFileError;
+use parking_lot::Mutex;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+#[cfg(any(feature = "flac", feature = "mp3", feature = "ogg", feature = "wav"))]
+use std::io::Cursor;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+/// How far the decoding of a sound file got
+///
+/// See [`DecodeProgress`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeStatus {
+    /// The file is read and waits for a thread to decode it
+    Queued,
+    /// The file is being decoded
+    Decoding,
+    /// The sound is decoded and about to be added as an asset
+    Ready,
+    /// The file could not be decoded, the asset fails to load
+    Failed,
+}
+
+/// The decoding status of loaded sound files, for loading screens
+///
+/// The ogg, wav, flac, mp3 and aiff loaders decode sounds on the [`AsyncComputeTaskPool`], since
+/// decoding long files takes a while. Their status is kept per asset path until the next load
+/// of the same file.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// #[derive(Resource)]
+/// struct Music(Handle<AudioSource>);
+///
+/// fn loading_screen(music: Res<Music>, progress: Res<DecodeProgress>, assets: Res<AssetServer>) {
+///     if progress.status(&music.0, &assets) == Some(DecodeStatus::Decoding) {
+///         info!("Decoding the music...");
+///     }
+/// }
+/// ```
+#[derive(Resource, Clone, Default)]
+pub struct DecodeProgress {
+    statuses: Arc<Mutex<HashMap<AssetPath<'static>, DecodeStatus>>>,
+    /// Normalization gains of the last load of each file
+    gains: Arc<Mutex<HashMap<AssetPath<'static>, CachedGain>>>,
+}
+
+/// A normalization gain and what it was measured for
+#[derive(Clone, Copy, Debug)]
+struct CachedGain {
+    /// Hash of the file content and the target loudness
+    content: u64,
+    gain: f64,
+}
+
+impl DecodeProgress {
+    /// The status of the sound file loaded for the given handle
+    ///
+    /// `None` if the file wasn't read yet or isn't decoded by this plugin.
+    pub fn status(
+        &self,
+        handle: &Handle<AudioSource>,
+        asset_server: &AssetServer,
+    ) -> Option<DecodeStatus> {
+        self.status_of_path(&asset_server.get_path(handle.id())?)
+    }
+
+    /// The status of the sound file at the given asset path
+    pub fn status_of_path(&self, path: &AssetPath) -> Option<DecodeStatus> {
+        self.statuses.lock().get(path).copied()
+    }
+
+    fn set(&self, path: &AssetPath<'static>, status: DecodeStatus) {
+        self.statuses.lock().insert(path.clone(), status);
+    }
+
+    /// Decode the file into a source with the given loader settings
+    #[cfg(any(feature = "flac", feature = "mp3", feature = "ogg", feature = "wav"))]
+    pub(crate) async fn source(
+        &self,
+        path: &AssetPath<'static>,
+        bytes: Vec<u8>,
+        settings: &AudioLoaderSettings,
+    ) -> Result<AudioSource, FromFileError> {
+        self.source_with(path, bytes, settings, decode_file).await
+    }
+
+    /// Decode the file into a source with the given decoder and loader settings
+    ///
+    /// The normalization gain is cached per asset path, so hot reloading a file that did not
+    /// change doesn't scan all of its samples again.
+    pub(crate) async fn source_with<E: From<std::io::Error> + Send + 'static>(
+        &self,
+        path: &AssetPath<'static>,
+        bytes: Vec<u8>,
+        settings: &AudioLoaderSettings,
+        decoder: Decoder<E>,
+    ) -> Result<AudioSource, E> {
+        let content = settings.normalize.map(|target| {
+            let mut hasher = DefaultHasher::new();
+            bytes.hash(&mut hasher);
+            target.rms.to_bits().hash(&mut hasher);
+            target.peak_ceiling.to_bits().hash(&mut hasher);
+            hasher.finish()
+        });
+        let sound = self
+            .decode_with(path, bytes, settings.sound_settings(), decoder)
+            .await?;
+        let gain = match (content, settings.normalize) {
+            (Some(content), Some(target)) => {
+                let cached = self
+                    .gains
+                    .lock()
+                    .get(path)
+                    .filter(|cached| cached.content == content)
+                    .map(|cached| cached.gain);
+                cached.unwrap_or_else(|| {
+                    let gain = target.gain(&sound.frames);
+                    self.gains
+                        .lock()
+                        .insert(path.clone(), CachedGain { content, gain });
+                    gain
+                })
+            }
+            _ => {
+                self.gains.lock().remove(path);
+                1.
+            }
+        };
+
+        Ok(AudioSource {
+            sound,
+            loop_region: None,
+            gain,
+        })
+    }
+
+    /// Decode the file with the given decoder on the async compute task pool
+    ///
+    /// Panics of the decoder become errors, so broken files fail to load like any other.
+    pub(crate) async fn decode_with<E: From<std::io::Error> + Send + 'static>(
+        &self,
+        path: &AssetPath<'static>,
+        bytes: Vec<u8>,
+        settings: StaticSoundSettings,
+        decoder: Decoder<E>,
+    ) -> Result<StaticSoundData, E> {
+        self.set(path, DecodeStatus::Queued);
+        let progress = self.clone();
+        let task_path = path.clone();
+        // Single threaded task pools don't hand out awaitable tasks
+        let (sender, receiver) = async_channel::bounded(1);
+        AsyncComputeTaskPool::get()
+            .spawn(async move {
+                progress.set(&task_path, DecodeStatus::Decoding);
+                let result = catch_unwind(AssertUnwindSafe(|| decoder(bytes, settings)))
+                    .unwrap_or_else(|panic| {
+                        let message = panic
+                            .downcast_ref::<&str>()
+                            .map(|message| message.to_string())
+                            .or_else(|| panic.downcast_ref::<String>().cloned())
+                            .unwrap_or_default();
+                        Err(E::from(std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            format!("the decoder panicked: {}", message),
+                        )))
+                    });
+                let _ = sender.send(result).await;
+            })
+            .detach();
+        let result = receiver.recv().await.unwrap_or_else(|_| {
+            Err(E::from(std::io::Error::new(
+                std::io::ErrorKind::Interrupted,
+                "the decoding task was dropped",
+            )))
+        });
+        self.set(
+            path,
+            if result.is_ok() {
+                DecodeStatus::Ready
+            } else {
+                DecodeStatus::Failed
+            },
+        );
+
+        result
+    }
+}
+
+/// Turns the bytes of a sound file into sound data
+pub(crate) type Decoder<E> = fn(Vec<u8>, StaticSoundSettings) -> Result<StaticSoundData, E>;
+
+/// Decode a file in any format that kira supports
+#[cfg(any(feature = "flac", feature = "mp3", feature = "ogg", feature = "wav"))]
+fn decode_file(
+    bytes: Vec<u8>,
+    settings: StaticSoundSettings,
+) -> Result<StaticSoundData, FromFileError> {
+    StaticSoundData::from_cursor(Cursor::new(bytes), settings)
+}
+
+#[cfg(all(test, feature = "ogg"))]
+mod test {
+    use super::*;
+    use crate::source::TargetLoudness;
+    use bevy::tasks::{block_on, TaskPool};
+
+    #[test]
+    fn broken_files_fail_to_decode() {
+        AsyncComputeTaskPool::get_or_init(TaskPool::default);
+        let progress = DecodeProgress::default();
+        let path = AssetPath::from("sounds/broken.ogg");
+        let mut bytes = include_bytes!("../../assets/sounds/plop.ogg").to_vec();
+        bytes.truncate(100);
+
+        let result = block_on(progress.decode_with(
+            &path,
+            bytes,
+            StaticSoundSettings::default(),
+            decode_file,
+        ));
+        assert!(result.is_err());
+        assert_eq!(progress.status_of_path(&path), Some(DecodeStatus::Failed));
+
+        let bytes = include_bytes!("../../assets/sounds/plop.ogg").to_vec();
+        let result = block_on(progress.decode_with(
+            &path,
+            bytes,
+            StaticSoundSettings::default(),
+            decode_file,
+        ));
+        assert!(result.is_ok());
+        assert_eq!(progress.status_of_path(&path), Some(DecodeStatus::Ready));
+    }
+
+    #[test]
+    fn normalization_gains_are_cached() {
+        AsyncComputeTaskPool::get_or_init(TaskPool::default);
+        let progress = DecodeProgress::default();
+        let path = AssetPath::from("sounds/plop.ogg");
+        let bytes = include_bytes!("../../assets/sounds/plop.ogg").to_vec();
+        let settings = AudioLoaderSettings {
+            normalize: Some(TargetLoudness::default()),
+            ..Default::default()
+        };
+
+        let source = block_on(progress.source(&path, bytes.clone(), &settings)).unwrap();
+        assert_ne!(source.gain, 1.);
+        assert_eq!(progress.gains.lock().len(), 1);
+
+        // A reload of the same file takes the cached gain instead of measuring it
+        progress
+            .gains
+            .lock()
+            .values_mut()
+            .for_each(|cached| cached.gain = 0.5);
+        let source = block_on(progress.source(&path, bytes.clone(), &settings)).unwrap();
+        assert_eq!(source.gain, 0.5);
+
+        let source = block_on(progress.source(&path, bytes, &AudioLoaderSettings::default()));
+        assert_eq!(source.unwrap().gain, 1.);
+        assert!(progress.gains.lock().is_empty());
+    }
+
+    #[test]
+    fn changed_files_replace_their_cached_gain() {
+        AsyncComputeTaskPool::get_or_init(TaskPool::default);
+        let progress = DecodeProgress::default();
+        let path = AssetPath::from("sounds/edited.ogg");
+        let settings = AudioLoaderSettings {
+            normalize: Some(TargetLoudness::default()),
+            ..Default::default()
+        };
+
+        let bytes = include_bytes!("../../assets/sounds/plop.ogg").to_vec();
+        let before = block_on(progress.source(&path, bytes, &settings)).unwrap();
+        let bytes = include_bytes!("../../assets/sounds/sound.ogg").to_vec();
+        let after = block_on(progress.source(&path, bytes, &settings)).unwrap();
+
+        assert_ne!(before.gain, after.gain);
+        let gains = progress.gains.lock();
+        assert_eq!(gains.len(), 1);
+        assert_eq!(gains.get(&path).unwrap().gain, after.gain);
+    }
+}
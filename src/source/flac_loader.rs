@@ -1,13 +1,13 @@
 use anyhow::Result;
 use bevy::asset::io::Reader;
 use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::ecs::world::{FromWorld, World};
 use bevy::utils::BoxedFuture;
-use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
 use kira::sound::FromFileError;
-use std::io::Cursor;
 use thiserror::Error;
 
-use crate::source::AudioSource;
+use crate::source::decode::DecodeProgress;
+use crate::source::{AudioLoaderSettings, AudioSource};
 
 /// Possible errors that can be produced by [`FlacLoader`]
 #[non_exhaustive]
@@ -21,28 +21,38 @@ pub enum FlacLoaderError {
     FileError(#[from] FromFileError),
 }
 
-#[derive(Default)]
-pub struct FlacLoader;
+pub struct FlacLoader {
+    progress: DecodeProgress,
+}
+
+impl FromWorld for FlacLoader {
+    fn from_world(world: &mut World) -> Self {
+        FlacLoader {
+            progress: world
+                .get_resource_or_insert_with(DecodeProgress::default)
+                .clone(),
+        }
+    }
+}
 
 impl AssetLoader for FlacLoader {
     type Asset = AudioSource;
-    type Settings = ();
+    type Settings = AudioLoaderSettings;
     type Error = FlacLoaderError;
 
     fn load<'a>(
         &'a self,
         reader: &'a mut Reader,
-        _settings: &'a (),
-        _load_context: &'a mut LoadContext,
+        settings: &'a AudioLoaderSettings,
+        load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
         Box::pin(async move {
             let mut sound_bytes = vec![];
             reader.read_to_end(&mut sound_bytes).await?;
-            let sound = StaticSoundData::from_cursor(
-                Cursor::new(sound_bytes),
-                StaticSoundSettings::default(),
-            )?;
-            Ok(AudioSource { sound })
+            Ok(self
+                .progress
+                .source(load_context.asset_path(), sound_bytes, settings)
+                .await?)
         })
     }
 
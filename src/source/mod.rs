@@ -1,5 +1,21 @@
 //! Asset loaders for commonly used audio file formats
 
+#[cfg(feature = "aiff")]
+pub mod aiff_loader;
+pub mod bank;
+#[cfg(feature = "bank")]
+pub mod bank_loader;
+pub mod collection;
+#[cfg(feature = "bank")]
+pub mod collection_loader;
+#[cfg(any(
+    feature = "aiff",
+    feature = "flac",
+    feature = "mp3",
+    feature = "ogg",
+    feature = "wav"
+))]
+pub mod decode;
 #[cfg(feature = "flac")]
 pub mod flac_loader;
 #[cfg(feature = "mp3")]
@@ -8,16 +24,748 @@ pub mod mp3_loader;
 pub mod ogg_loader;
 #[cfg(feature = "settings_loader")]
 pub mod settings_loader;
+pub mod streamed;
+#[cfg(any(feature = "flac", feature = "mp3", feature = "ogg", feature = "wav"))]
+pub mod streamed_loader;
 #[cfg(feature = "wav")]
 pub mod wav_loader;
 
+use crate::instance::{AudioErrorKind, SoundHandle};
+use crate::source::streamed::StreamedAudioSource;
 use bevy::asset::Asset;
 use bevy::reflect::TypePath;
-use kira::sound::static_sound::StaticSoundData;
+use kira::dsp::Frame;
+use kira::manager::backend::Backend;
+use kira::manager::AudioManager;
+use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+use kira::sound::streaming::StreamingSoundSettings;
+use kira::sound::{EndPosition, PlaybackPosition, PlaybackRate, Region};
+use kira::Volume;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+use thiserror::Error;
 
 /// A source of audio data
 #[derive(Clone, Asset, TypePath)]
 pub struct AudioSource {
     /// The Kira sound making up this `AudioSource`
     pub sound: StaticSoundData,
+    /// The region [`PlayAudioCommand::looped`](crate::PlayAudioCommand::looped) loops
+    ///
+    /// The ogg loader reads it from `LOOPSTART` and `LOOPLENGTH` comments in the file.
+    /// Without it, looped sounds loop as a whole.
+    pub loop_region: Option<Region>,
+    /// Amplitude factor the sound plays with on top of its volume
+    ///
+    /// Loaders set it to normalize the loudness of sounds, see [`AudioLoaderSettings::normalize`].
+    pub gain: f64,
+}
+
+/// Default settings of a sound file, applied when it is loaded
+///
+/// Set them in the `.meta` file next to the sound, so they can be tweaked without touching code.
+/// Sounds play with these settings unless [`PlayAudioCommand`](crate::PlayAudioCommand) or
+/// the channel override them.
+/// ```ron
+/// (
+///     meta_format_version: "1.0",
+///     asset: Load(
+///         loader: "bevy_kira_audio::source::ogg_loader::OggLoader",
+///         settings: (
+///             loop_region: Some((1.5, 12.)),
+///             volume: 0.8,
+///         ),
+///     ),
+/// )
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AudioLoaderSettings {
+    /// Start and end of the looped part in seconds, the sound does not loop if this is `None`
+    pub loop_region: Option<(f64, f64)>,
+    /// Amplitude multiplier
+    pub volume: f64,
+    /// Factor of the speed and pitch of the sound
+    pub playback_rate: f64,
+    /// Normalize the loudness of the sound by setting its [`AudioSource::gain`]
+    pub normalize: Option<TargetLoudness>,
+}
+
+impl Default for AudioLoaderSettings {
+    fn default() -> Self {
+        AudioLoaderSettings {
+            loop_region: None,
+            volume: 1.,
+            playback_rate: 1.,
+            normalize: None,
+        }
+    }
+}
+
+impl AudioLoaderSettings {
+    #[cfg_attr(
+        not(any(
+            feature = "aiff",
+            feature = "flac",
+            feature = "mp3",
+            feature = "ogg",
+            feature = "wav"
+        )),
+        allow(dead_code)
+    )]
+    pub(crate) fn sound_settings(&self) -> StaticSoundSettings {
+        let mut settings = StaticSoundSettings::new()
+            .volume(Volume::Amplitude(self.volume))
+            .playback_rate(PlaybackRate::Factor(self.playback_rate));
+        settings.loop_region = self.loop_region.map(|(start, end)| Region {
+            start: PlaybackPosition::Seconds(start),
+            end: EndPosition::Custom(PlaybackPosition::Seconds(end)),
+        });
+
+        settings
+    }
+}
+
+/// The loudness to normalize sounds to while loading them
+///
+/// The loudness is measured as the root mean square of all samples, which is a rough but cheap
+/// approximation of how loud a sound is perceived.
+/// ```ron
+/// (
+///     meta_format_version: "1.0",
+///     asset: Load(
+///         loader: "bevy_kira_audio::source::ogg_loader::OggLoader",
+///         settings: (normalize: Some((rms: -18.))),
+///     ),
+/// )
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TargetLoudness {
+    /// The root mean square level to reach in decibels relative to full scale
+    pub rms: f64,
+    /// The highest peak after normalization in decibels relative to full scale
+    ///
+    /// Sounds with loud peaks get less gain than needed to reach the level, so they don't clip.
+    pub peak_ceiling: f64,
+}
+
+impl Default for TargetLoudness {
+    fn default() -> Self {
+        TargetLoudness {
+            rms: -20.,
+            peak_ceiling: -1.,
+        }
+    }
+}
+
+impl TargetLoudness {
+    /// The amplitude factor that brings the frames to the target
+    ///
+    /// Silence keeps its level.
+    #[cfg_attr(
+        not(any(
+            feature = "aiff",
+            feature = "flac",
+            feature = "mp3",
+            feature = "ogg",
+            feature = "wav"
+        )),
+        allow(dead_code)
+    )]
+    pub(crate) fn gain(&self, frames: &[Frame]) -> f64 {
+        let (square_sum, peak) = frames.iter().fold((0., 0f32), |(square_sum, peak), frame| {
+            (
+                square_sum + (frame.left as f64).powi(2) + (frame.right as f64).powi(2),
+                peak.max(frame.left.abs()).max(frame.right.abs()),
+            )
+        });
+        if peak == 0. {
+            return 1.;
+        }
+        let rms = (square_sum / (frames.len() * 2) as f64).sqrt();
+        let gain = 10f64.powf(self.rms / 20.) / rms;
+        let peak_limit = 10f64.powf(self.peak_ceiling / 20.) / peak as f64;
+
+        gain.min(peak_limit)
+    }
+}
+
+/// Errors of [`AudioSource::slice`]
+#[derive(Error, Debug, Clone, Copy, PartialEq)]
+pub enum SliceError {
+    /// The slice would be empty or end before it starts
+    #[error("a slice from {start}s to {end}s is empty")]
+    Inverted {
+        /// The requested start in seconds
+        start: f64,
+        /// The requested end in seconds
+        end: f64,
+    },
+    /// The slice does not lie within the sound
+    #[error("a slice from {start}s to {end}s does not fit into a sound of {length}s")]
+    OutOfRange {
+        /// The requested start in seconds
+        start: f64,
+        /// The requested end in seconds
+        end: f64,
+        /// The duration of the whole sound in seconds
+        length: f64,
+    },
+}
+
+/// Errors that can occur when building an [`AudioSource`] from samples
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromFramesError {
+    /// There are no frames to play
+    #[error("an audio source needs at least one frame")]
+    NoFrames,
+    /// A sample rate of zero has no duration
+    #[error("the sample rate of an audio source can't be zero")]
+    ZeroSampleRate,
+}
+
+impl AudioSource {
+    /// A source playing the given stereo frames of `[left, right]` samples
+    ///
+    /// Use this for audio generated at runtime, like synthesized tones or voice chat. Add the
+    /// source to `Assets<AudioSource>` to play it like a loaded file.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// fn play_tone(mut audio_sources: ResMut<Assets<AudioSource>>, audio: Res<Audio>) {
+    ///     let frames = (0..44_100)
+    ///         .map(|index| {
+    ///             let sample = (index as f32 * 440. * std::f32::consts::TAU / 44_100.).sin();
+    ///             [sample, sample]
+    ///         })
+    ///         .collect();
+    ///     let tone = AudioSource::from_frames(44_100, frames).unwrap();
+    ///     audio.play(audio_sources.add(tone));
+    /// }
+    /// ```
+    pub fn from_frames(sample_rate: u32, frames: Vec<[f32; 2]>) -> Result<Self, FromFramesError> {
+        Self::from_kira_frames(
+            sample_rate,
+            frames
+                .into_iter()
+                .map(|[left, right]| Frame::new(left, right))
+                .collect(),
+        )
+    }
+
+    /// A source playing the given mono samples on both sides
+    ///
+    /// See [`from_frames`](Self::from_frames).
+    pub fn from_mono_frames(sample_rate: u32, samples: Vec<f32>) -> Result<Self, FromFramesError> {
+        Self::from_kira_frames(
+            sample_rate,
+            samples.into_iter().map(Frame::from_mono).collect(),
+        )
+    }
+
+    /// The duration of the sound at its original playback rate
+    ///
+    /// Use it to schedule what comes after the sound before playing it. For a
+    /// [`slice`](Self::slice), this is the duration of the sliced part.
+    pub fn duration(&self) -> Duration {
+        let region = self.sound.settings.playback_region;
+        let end = match region.end {
+            EndPosition::EndOfAudio => self.sound.duration().as_secs_f64(),
+            EndPosition::Custom(end) => self.sound.seconds(end),
+        };
+
+        Duration::from_secs_f64((end - self.sound.seconds(region.start)).max(0.))
+    }
+
+    /// A part of the sound from `start` to `end` in seconds, played like a sound of its own
+    ///
+    /// The slice shares the samples with this source, so packing many short sounds into one
+    /// file costs no extra memory. Add it to `Assets<AudioSource>` to play it. Looping the
+    /// slice loops only the sliced part. Positions are still counted from the start of the
+    /// whole sound, for example in [`PlayAudioCommand::start_from`](crate::PlayAudioCommand::start_from)
+    /// and [`AudioInstance::position`](crate::AudioInstance::position).
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// fn play_coin(
+    ///     sheet: Res<SoundSheet>,
+    ///     mut audio_sources: ResMut<Assets<AudioSource>>,
+    ///     audio: Res<Audio>,
+    /// ) {
+    ///     if let Some(sheet) = audio_sources.get(&sheet.0) {
+    ///         let coin = sheet.slice(3.2, 3.65).unwrap();
+    ///         audio.play(audio_sources.add(coin));
+    ///     }
+    /// }
+    /// # #[derive(Resource)]
+    /// # struct SoundSheet(Handle<AudioSource>);
+    /// ```
+    pub fn slice(&self, start: f64, end: f64) -> Result<AudioSource, SliceError> {
+        // Also catches NaN
+        if start.partial_cmp(&end) != Some(std::cmp::Ordering::Less) {
+            return Err(SliceError::Inverted { start, end });
+        }
+        let length = self.sound.duration().as_secs_f64();
+        if start < 0. || end > length {
+            return Err(SliceError::OutOfRange { start, end, length });
+        }
+        let region = Region {
+            start: PlaybackPosition::Seconds(start),
+            end: EndPosition::Custom(PlaybackPosition::Seconds(end)),
+        };
+        let mut sound = self.sound.clone();
+        sound.settings.playback_region = region;
+        sound.settings.loop_region = sound.settings.loop_region.map(|_| region);
+
+        Ok(AudioSource {
+            sound,
+            loop_region: Some(region),
+            gain: self.gain,
+        })
+    }
+
+    /// The number of frames per second
+    pub fn sample_rate(&self) -> u32 {
+        self.sound.sample_rate
+    }
+
+    /// The number of channels, 1 for mono and 2 for stereo
+    ///
+    /// Kira plays every sound in stereo, so decoded mono files have the same sample on both
+    /// sides. Sounds are counted as mono if all of their frames are like that.
+    pub fn channels(&self) -> u16 {
+        if self
+            .sound
+            .frames
+            .iter()
+            .all(|frame| frame.left == frame.right)
+        {
+            1
+        } else {
+            2
+        }
+    }
+
+    fn from_kira_frames(sample_rate: u32, frames: Vec<Frame>) -> Result<Self, FromFramesError> {
+        if sample_rate == 0 {
+            return Err(FromFramesError::ZeroSampleRate);
+        }
+        if frames.is_empty() {
+            return Err(FromFramesError::NoFrames);
+        }
+
+        Ok(AudioSource {
+            sound: StaticSoundData {
+                sample_rate,
+                frames: Arc::from(frames),
+                settings: StaticSoundSettings::default(),
+            },
+            loop_region: None,
+            gain: 1.,
+        })
+    }
+
+    /// Mix both stereo channels into a single mono signal played on both sides
+    ///
+    /// Use this for stereo recordings played on an [`AudioEmitter`](crate::AudioEmitter), whose own
+    /// stereo image would otherwise fight the spacial panning. The samples are converted once,
+    /// so playing the source afterwards costs nothing extra. With the `settings_loader` feature,
+    /// sources can also be downmixed while loading by setting `mono: true` in their settings file.
+    pub fn downmix_to_mono(&mut self) {
+        self.sound.frames = self
+            .sound
+            .frames
+            .iter()
+            .map(|frame| Frame::from_mono((frame.left + frame.right) * 0.5))
+            .collect();
+    }
+
+    /// One source that plays the given sources back to back
+    ///
+    /// The result has the sample rate of the first part, the other parts are resampled to it.
+    /// The settings of the parts, like their loop regions, are not used. Their gains are applied
+    /// to the samples.
+    pub(crate) fn sequence(parts: &[&AudioSource]) -> AudioSource {
+        let sample_rate = parts.first().map_or(44_100, |part| part.sound.sample_rate);
+        let mut frames = vec![];
+        for part in parts {
+            let start = frames.len();
+            let gain = part.gain as f32;
+            let part = &part.sound;
+            if part.sample_rate == sample_rate {
+                frames.extend_from_slice(&part.frames);
+            } else {
+                frames.extend(resample(&part.frames, part.sample_rate, sample_rate));
+            }
+            if gain != 1. {
+                frames[start..].iter_mut().for_each(|frame| *frame *= gain);
+            }
+        }
+
+        AudioSource {
+            sound: StaticSoundData {
+                sample_rate,
+                frames: Arc::from(frames),
+                settings: StaticSoundSettings::default(),
+            },
+            loop_region: None,
+            gain: 1.,
+        }
+    }
+}
+
+/// The frames at another sample rate, interpolated linearly
+fn resample(frames: &[Frame], from: u32, to: u32) -> impl Iterator<Item = Frame> + '_ {
+    let step = from as f64 / to as f64;
+    let length = (frames.len() as f64 / step).round() as usize;
+    (0..length).map(move |index| {
+        let position = index as f64 * step;
+        let previous = position.floor() as usize;
+        let next = (previous + 1).min(frames.len() - 1);
+        let fraction = (position - previous as f64) as f32;
+        frames[previous] * (1. - fraction) + frames[next] * fraction
+    })
+}
+
+/// The settings of a sound and its length, to work out positions in it
+pub(crate) trait SoundLayout {
+    fn settings(&self) -> &StaticSoundSettings;
+    fn settings_mut(&mut self) -> &mut StaticSoundSettings;
+    fn length(&self) -> Duration;
+    fn seconds(&self, position: PlaybackPosition) -> f64;
+    /// The loop region of the source, used when the sound is looped without one
+    fn source_loop_region(&self) -> Option<Region> {
+        None
+    }
+}
+
+impl SoundLayout for StaticSoundData {
+    fn settings(&self) -> &StaticSoundSettings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut StaticSoundSettings {
+        &mut self.settings
+    }
+
+    fn length(&self) -> Duration {
+        self.duration()
+    }
+
+    fn seconds(&self, position: PlaybackPosition) -> f64 {
+        match position {
+            PlaybackPosition::Seconds(seconds) => seconds,
+            PlaybackPosition::Samples(samples) => samples as f64 / self.sample_rate as f64,
+        }
+    }
+}
+
+/// A sound about to be played
+///
+/// Streamed sounds take the settings that apply to them once they play.
+pub(crate) struct PlayedSound {
+    pub(crate) settings: StaticSoundSettings,
+    loop_region: Option<Region>,
+    gain: f64,
+    data: PlayedData,
+}
+
+enum PlayedData {
+    Static {
+        sample_rate: u32,
+        frames: Arc<[Frame]>,
+    },
+    Streamed(StreamedAudioSource),
+}
+
+impl From<&AudioSource> for PlayedSound {
+    fn from(source: &AudioSource) -> Self {
+        PlayedSound {
+            settings: source.sound.settings,
+            loop_region: source.loop_region,
+            gain: source.gain,
+            data: PlayedData::Static {
+                sample_rate: source.sound.sample_rate,
+                frames: source.sound.frames.clone(),
+            },
+        }
+    }
+}
+
+impl From<&StreamedAudioSource> for PlayedSound {
+    fn from(source: &StreamedAudioSource) -> Self {
+        PlayedSound {
+            settings: StaticSoundSettings::default(),
+            loop_region: None,
+            gain: 1.,
+            data: PlayedData::Streamed(source.clone()),
+        }
+    }
+}
+
+impl PlayedSound {
+    pub(crate) fn is_streamed(&self) -> bool {
+        matches!(self.data, PlayedData::Streamed(_))
+    }
+
+    pub(crate) fn gain(&self) -> f64 {
+        self.gain
+    }
+
+    pub(crate) fn duration(&self) -> Duration {
+        match &self.data {
+            PlayedData::Static {
+                sample_rate,
+                frames,
+            } => Duration::from_secs_f64(frames.len() as f64 / *sample_rate as f64),
+            PlayedData::Streamed(source) => source.duration,
+        }
+    }
+
+    /// Hand the sound to kira
+    pub(crate) fn play<B: Backend>(
+        self,
+        manager: &mut AudioManager<B>,
+    ) -> Result<SoundHandle, AudioErrorKind> {
+        let settings = self.settings;
+        match self.data {
+            PlayedData::Static {
+                sample_rate,
+                frames,
+            } => manager
+                .play(StaticSoundData {
+                    sample_rate,
+                    frames,
+                    settings,
+                })
+                .map(SoundHandle::Static)
+                .map_err(AudioErrorKind::from),
+            PlayedData::Streamed(source) => {
+                let mut streaming_settings = StreamingSoundSettings::default();
+                streaming_settings.start_time = settings.start_time;
+                streaming_settings.playback_region = settings.playback_region;
+                streaming_settings.loop_region = settings.loop_region;
+                streaming_settings.volume = settings.volume;
+                streaming_settings.playback_rate = settings.playback_rate;
+                streaming_settings.panning = settings.panning;
+                streaming_settings.output_destination = settings.output_destination;
+                streaming_settings.fade_in_tween = settings.fade_in_tween;
+                let data = source
+                    .data(streaming_settings)
+                    .map_err(AudioErrorKind::Decode)?;
+                manager
+                    .play(data)
+                    .map(SoundHandle::Streamed)
+                    .map_err(AudioErrorKind::from)
+            }
+        }
+    }
+}
+
+impl SoundLayout for PlayedSound {
+    fn settings(&self) -> &StaticSoundSettings {
+        &self.settings
+    }
+
+    fn settings_mut(&mut self) -> &mut StaticSoundSettings {
+        &mut self.settings
+    }
+
+    fn length(&self) -> Duration {
+        self.duration()
+    }
+
+    fn seconds(&self, position: PlaybackPosition) -> f64 {
+        match (position, &self.data) {
+            (PlaybackPosition::Seconds(seconds), _) => seconds,
+            (PlaybackPosition::Samples(samples), PlayedData::Static { sample_rate, .. }) => {
+                samples as f64 / *sample_rate as f64
+            }
+            // The plugin only gives streamed sounds positions in seconds
+            (PlaybackPosition::Samples(_), PlayedData::Streamed(_)) => 0.,
+        }
+    }
+
+    fn source_loop_region(&self) -> Option<Region> {
+        self.loop_region
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::audio::PartialSoundSettings;
+
+    #[test]
+    fn downmix_averages_both_channels() {
+        let mut source = AudioSource {
+            sound: StaticSoundData {
+                sample_rate: 44_100,
+                frames: Arc::from(vec![Frame::new(1., 0.), Frame::new(-0.5, 0.5)]),
+                settings: StaticSoundSettings::default(),
+            },
+            loop_region: None,
+            gain: 1.,
+        };
+        source.downmix_to_mono();
+
+        assert_eq!(
+            source.sound.frames.as_ref(),
+            &[Frame::new(0.5, 0.5), Frame::new(0., 0.)]
+        );
+    }
+
+    #[test]
+    fn normalization_is_limited_by_the_peak() {
+        let target = TargetLoudness {
+            rms: -20.,
+            peak_ceiling: 0.,
+        };
+        // A constant signal at -40 dB needs 20 dB of gain
+        let quiet = vec![Frame::from_mono(0.01); 10_000];
+        assert!((target.gain(&quiet) - 10.).abs() < 1e-6);
+
+        // One loud peak in a quiet sound would clip at the full gain
+        let mut peaked = quiet.clone();
+        peaked[0] = Frame::from_mono(0.5);
+        assert!((target.gain(&peaked) - 2.).abs() < 1e-6);
+
+        assert_eq!(target.gain(&[Frame::ZERO; 10]), 1.);
+    }
+
+    #[test]
+    fn sources_from_frames_keep_their_samples() {
+        let stereo = AudioSource::from_frames(48_000, vec![[1., -1.], [0.5, 0.]]).unwrap();
+        assert_eq!(stereo.sample_rate(), 48_000);
+        assert_eq!(stereo.channels(), 2);
+        assert_eq!(
+            stereo.sound.frames.as_ref(),
+            &[Frame::new(1., -1.), Frame::new(0.5, 0.)]
+        );
+        let mono = AudioSource::from_mono_frames(8_000, vec![0.25; 8_000]).unwrap();
+        assert_eq!(mono.sound.frames[0], Frame::new(0.25, 0.25));
+        assert_eq!(mono.duration(), Duration::from_secs(1));
+        assert_eq!(mono.channels(), 1);
+
+        assert_eq!(
+            AudioSource::from_frames(44_100, vec![]).err(),
+            Some(FromFramesError::NoFrames)
+        );
+        assert_eq!(
+            AudioSource::from_mono_frames(0, vec![0.]).err(),
+            Some(FromFramesError::ZeroSampleRate)
+        );
+    }
+
+    #[test]
+    fn loader_settings_become_sound_settings() {
+        let settings = AudioLoaderSettings {
+            loop_region: Some((1., 2.5)),
+            volume: 0.5,
+            playback_rate: 2.,
+            normalize: None,
+        }
+        .sound_settings();
+        assert_eq!(
+            settings.loop_region,
+            Some(Region {
+                start: PlaybackPosition::Seconds(1.),
+                end: EndPosition::Custom(PlaybackPosition::Seconds(2.5)),
+            })
+        );
+        assert_eq!(settings.volume, Volume::Amplitude(0.5).into());
+        assert_eq!(settings.playback_rate, PlaybackRate::Factor(2.).into());
+
+        let defaults = AudioLoaderSettings::default().sound_settings();
+        assert_eq!(defaults.loop_region, None);
+        assert_eq!(defaults.volume, Volume::Amplitude(1.).into());
+    }
+
+    #[test]
+    fn looped_sounds_use_the_loop_region_of_their_source() {
+        let mut source = AudioSource::from_mono_frames(10, vec![0.; 100]).unwrap();
+        let region = Region {
+            start: PlaybackPosition::Samples(10),
+            end: EndPosition::EndOfAudio,
+        };
+        source.loop_region = Some(region);
+
+        let mut sound = PlayedSound::from(&source);
+        PartialSoundSettings {
+            loop_start: Some(0.),
+            source_loop: true,
+            ..Default::default()
+        }
+        .apply(&mut sound);
+        assert_eq!(sound.settings.loop_region, Some(region));
+
+        let mut sound = PlayedSound::from(&source);
+        PartialSoundSettings {
+            loop_start: Some(2.),
+            ..Default::default()
+        }
+        .apply(&mut sound);
+        assert_eq!(
+            sound.settings.loop_region.map(|region| region.start),
+            Some(PlaybackPosition::Seconds(2.))
+        );
+    }
+
+    #[test]
+    fn slices_share_the_samples_and_loop_alone() {
+        let sheet = AudioSource::from_mono_frames(100, vec![0.5; 500]).unwrap();
+        let slice = sheet.slice(3.2, 3.65).unwrap();
+        assert!(Arc::ptr_eq(&sheet.sound.frames, &slice.sound.frames));
+        assert!((slice.duration().as_secs_f64() - 0.45).abs() < 1e-9);
+
+        let mut sound = PlayedSound::from(&slice);
+        // Like `PlayAudioCommand::looped`
+        PartialSoundSettings {
+            loop_start: Some(0.),
+            source_loop: true,
+            ..Default::default()
+        }
+        .apply(&mut sound);
+        let region = Region {
+            start: PlaybackPosition::Seconds(3.2),
+            end: EndPosition::Custom(PlaybackPosition::Seconds(3.65)),
+        };
+        assert_eq!(sound.settings.playback_region, region);
+        assert_eq!(sound.settings.loop_region, Some(region));
+
+        assert_eq!(
+            sheet.slice(2., 1.).err(),
+            Some(SliceError::Inverted { start: 2., end: 1. })
+        );
+        assert!(matches!(
+            sheet.slice(4., 6.),
+            Err(SliceError::OutOfRange { length, .. }) if length == 5.
+        ));
+    }
+
+    #[test]
+    fn sequences_are_resampled_to_the_first_part() {
+        let source = |sample_rate, frames: Vec<f32>| AudioSource {
+            sound: StaticSoundData {
+                sample_rate,
+                frames: frames.into_iter().map(Frame::from_mono).collect(),
+                settings: StaticSoundSettings::default(),
+            },
+            loop_region: None,
+            gain: 1.,
+        };
+        let sequence = AudioSource::sequence(&[
+            &source(2, vec![1., 2.]),
+            &source(1, vec![0., 1.]),
+            &source(2, vec![3.]),
+        ]);
+
+        assert_eq!(sequence.sound.sample_rate, 2);
+        assert_eq!(
+            sequence.sound.frames.as_ref(),
+            &[1., 2., 0., 0.5, 1., 1., 3.].map(Frame::from_mono)
+        );
+    }
 }
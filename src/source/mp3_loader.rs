@@ -1,16 +1,27 @@
 use anyhow::Result;
 use bevy::asset::io::Reader;
 use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::ecs::world::{FromWorld, World};
 use bevy::utils::BoxedFuture;
-use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
 use kira::sound::FromFileError;
-use std::io::Cursor;
 use thiserror::Error;
 
-use crate::source::AudioSource;
+use crate::source::decode::DecodeProgress;
+use crate::source::{AudioLoaderSettings, AudioSource};
 
-#[derive(Default)]
-pub struct Mp3Loader;
+pub struct Mp3Loader {
+    progress: DecodeProgress,
+}
+
+impl FromWorld for Mp3Loader {
+    fn from_world(world: &mut World) -> Self {
+        Mp3Loader {
+            progress: world
+                .get_resource_or_insert_with(DecodeProgress::default)
+                .clone(),
+        }
+    }
+}
 
 /// Possible errors that can be produced by [`Mp3Loader`]
 #[non_exhaustive]
@@ -26,23 +37,22 @@ pub enum Mp3LoaderError {
 
 impl AssetLoader for Mp3Loader {
     type Asset = AudioSource;
-    type Settings = ();
+    type Settings = AudioLoaderSettings;
     type Error = Mp3LoaderError;
 
     fn load<'a>(
         &'a self,
         reader: &'a mut Reader,
-        _settings: &'a (),
-        _load_context: &'a mut LoadContext,
+        settings: &'a AudioLoaderSettings,
+        load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
         Box::pin(async move {
             let mut sound_bytes = vec![];
             reader.read_to_end(&mut sound_bytes).await?;
-            let sound = StaticSoundData::from_cursor(
-                Cursor::new(sound_bytes),
-                StaticSoundSettings::default(),
-            )?;
-            Ok(AudioSource { sound })
+            Ok(self
+                .progress
+                .source(load_context.asset_path(), sound_bytes, settings)
+                .await?)
         })
     }
 
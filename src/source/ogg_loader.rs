@@ -1,13 +1,14 @@
 use anyhow::Result;
 use bevy::asset::io::Reader;
 use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::ecs::world::{FromWorld, World};
+use bevy::log::warn;
 use bevy::utils::BoxedFuture;
-use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
-use kira::sound::FromFileError;
-use std::io::Cursor;
+use kira::sound::{EndPosition, FromFileError, PlaybackPosition, Region};
 use thiserror::Error;
 
-use crate::source::AudioSource;
+use crate::source::decode::DecodeProgress;
+use crate::source::{AudioLoaderSettings, AudioSource};
 
 /// Possible errors that can be produced by [`OggLoader`]
 #[non_exhaustive]
@@ -21,28 +22,49 @@ pub enum OggLoaderError {
     FileError(#[from] FromFileError),
 }
 
-#[derive(Default)]
-pub struct OggLoader;
+pub struct OggLoader {
+    progress: DecodeProgress,
+}
+
+impl FromWorld for OggLoader {
+    fn from_world(world: &mut World) -> Self {
+        OggLoader {
+            progress: world
+                .get_resource_or_insert_with(DecodeProgress::default)
+                .clone(),
+        }
+    }
+}
 
 impl AssetLoader for OggLoader {
     type Asset = AudioSource;
-    type Settings = ();
+    type Settings = AudioLoaderSettings;
     type Error = OggLoaderError;
 
     fn load<'a>(
         &'a self,
         reader: &'a mut Reader,
-        _settings: &'a (),
-        _load_context: &'a mut LoadContext,
+        settings: &'a AudioLoaderSettings,
+        load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
         Box::pin(async move {
             let mut sound_bytes = vec![];
             reader.read_to_end(&mut sound_bytes).await?;
-            let sound = StaticSoundData::from_cursor(
-                Cursor::new(sound_bytes),
-                StaticSoundSettings::default(),
-            )?;
-            Ok(AudioSource { sound })
+            let comments = vorbis_comments(&sound_bytes);
+            let mut source = self
+                .progress
+                .source(load_context.asset_path(), sound_bytes, settings)
+                .await?;
+            source.loop_region = tagged_loop_region(&comments, source.sound.frames.len() as u64)
+                .unwrap_or_else(|error| {
+                    warn!(
+                        "Ignoring the loop tags of {:?}: {}",
+                        load_context.path(),
+                        error
+                    );
+                    None
+                });
+            Ok(source)
         })
     }
 
@@ -50,3 +72,212 @@ impl AssetLoader for OggLoader {
         &["ogg", "oga", "spx"]
     }
 }
+
+/// The comments in the header of an ogg vorbis file as upper case keys and their values
+///
+/// Only the first logical stream is read. Files that are no valid ogg vorbis have no comments.
+fn vorbis_comments(bytes: &[u8]) -> Vec<(String, String)> {
+    let mut packets = vec![];
+    let mut packet = vec![];
+    let mut serial = None;
+    let mut rest = bytes;
+    // The comments are the second packet of the stream
+    while packets.len() < 2 {
+        let Some(header) = rest.get(..27) else {
+            return vec![];
+        };
+        if &header[..4] != b"OggS" {
+            return vec![];
+        }
+        let page_serial = &header[14..18];
+        let segment_count = header[26] as usize;
+        let Some(segments) = rest.get(27..27 + segment_count) else {
+            return vec![];
+        };
+        let mut data = &rest[27 + segment_count..];
+        rest = &data[segments
+            .iter()
+            .map(|&length| length as usize)
+            .sum::<usize>()
+            .min(data.len())..];
+        if *serial.get_or_insert(page_serial) != page_serial {
+            continue;
+        }
+        for &length in segments {
+            let Some(segment) = data.get(..length as usize) else {
+                return vec![];
+            };
+            packet.extend_from_slice(segment);
+            data = &data[length as usize..];
+            // Packets end with a segment shorter than 255 bytes
+            if length < 255 {
+                packets.push(std::mem::take(&mut packet));
+            }
+        }
+    }
+
+    parse_comment_packet(&packets[1]).unwrap_or_default()
+}
+
+fn parse_comment_packet(packet: &[u8]) -> Option<Vec<(String, String)>> {
+    let mut rest = packet.strip_prefix(b"\x03vorbis")?;
+    let read_u32 = |rest: &mut &[u8]| {
+        let value = u32::from_le_bytes(rest.get(..4)?.try_into().ok()?);
+        *rest = &rest[4..];
+        Some(value as usize)
+    };
+    let vendor_length = read_u32(&mut rest)?;
+    rest = rest.get(vendor_length..)?;
+    let count = read_u32(&mut rest)?;
+    let mut comments = vec![];
+    for _ in 0..count {
+        let length = read_u32(&mut rest)?;
+        let comment = String::from_utf8_lossy(rest.get(..length)?);
+        rest = &rest[length..];
+        if let Some((key, value)) = comment.split_once('=') {
+            comments.push((key.to_uppercase(), value.trim().to_string()));
+        }
+    }
+
+    Some(comments)
+}
+
+/// The loop region of `LOOPSTART` and `LOOPLENGTH` comments, counted in samples
+///
+/// Without `LOOPLENGTH`, the loop lasts until the end of the sound.
+fn tagged_loop_region(
+    comments: &[(String, String)],
+    frames: u64,
+) -> Result<Option<Region>, String> {
+    let tag = |name: &str| {
+        comments
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| format!("{} is not a sample count: {:?}", name, value))
+            })
+            .transpose()
+    };
+    let Some(start) = tag("LOOPSTART")? else {
+        return Ok(None);
+    };
+    if start >= frames {
+        return Err(format!(
+            "LOOPSTART {} is not before the end of the sound at {}",
+            start, frames
+        ));
+    }
+    let end = match tag("LOOPLENGTH")? {
+        None => EndPosition::EndOfAudio,
+        Some(0) => return Err("LOOPLENGTH is zero".to_string()),
+        Some(length) if start.saturating_add(length) > frames => {
+            return Err(format!(
+                "the loop ends at {}, after the end of the sound at {}",
+                start + length,
+                frames
+            ))
+        }
+        Some(length) => EndPosition::Custom(PlaybackPosition::Samples((start + length) as i64)),
+    };
+
+    Ok(Some(Region {
+        start: PlaybackPosition::Samples(start as i64),
+        end,
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn page(serial: u8, packets: &[&[u8]]) -> Vec<u8> {
+        let mut segments = vec![];
+        let mut data = vec![];
+        for packet in packets {
+            segments.extend(std::iter::repeat(255).take(packet.len() / 255));
+            segments.push((packet.len() % 255) as u8);
+            data.extend_from_slice(packet);
+        }
+        let mut page = b"OggS".to_vec();
+        page.extend_from_slice(&[0; 10]);
+        page.extend_from_slice(&[serial, 0, 0, 0]);
+        page.extend_from_slice(&[0; 8]);
+        page.push(segments.len() as u8);
+        page.extend(segments);
+        page.extend(data);
+
+        page
+    }
+
+    fn comment_packet(comments: &[&str]) -> Vec<u8> {
+        let mut packet = b"\x03vorbis".to_vec();
+        packet.extend_from_slice(&4u32.to_le_bytes());
+        packet.extend_from_slice(b"test");
+        packet.extend_from_slice(&(comments.len() as u32).to_le_bytes());
+        for comment in comments {
+            packet.extend_from_slice(&(comment.len() as u32).to_le_bytes());
+            packet.extend_from_slice(comment.as_bytes());
+        }
+
+        packet
+    }
+
+    #[test]
+    fn comments_are_read_across_pages_and_streams() {
+        let long_title = format!("TITLE={}", "a".repeat(300));
+        let mut bytes = page(1, &[b"\x01vorbis"]);
+        bytes.extend(page(2, &[&comment_packet(&["LOOPSTART=1"])]));
+        bytes.extend(page(
+            1,
+            &[&comment_packet(&[
+                &long_title,
+                "loopstart=44100",
+                "LOOPLENGTH=100",
+            ])],
+        ));
+
+        let comments = vorbis_comments(&bytes);
+        assert_eq!(comments.len(), 3);
+        assert_eq!(comments[1], ("LOOPSTART".to_string(), "44100".to_string()));
+        assert!(vorbis_comments(b"RIFF").is_empty());
+    }
+
+    #[test]
+    fn loop_tags_outside_of_the_sound_are_errors() {
+        let comments = |tags: &[(&str, &str)]| {
+            tags.iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            tagged_loop_region(&comments(&[("LOOPSTART", "10"), ("LOOPLENGTH", "20")]), 30),
+            Ok(Some(Region {
+                start: PlaybackPosition::Samples(10),
+                end: EndPosition::Custom(PlaybackPosition::Samples(30)),
+            }))
+        );
+        assert_eq!(
+            tagged_loop_region(&comments(&[("LOOPSTART", "10")]), 30),
+            Ok(Some(Region {
+                start: PlaybackPosition::Samples(10),
+                end: EndPosition::EndOfAudio,
+            }))
+        );
+        assert_eq!(tagged_loop_region(&comments(&[]), 30), Ok(None));
+        for tags in [
+            &[("LOOPSTART", "30")][..],
+            &[("LOOPSTART", "-1")],
+            &[("LOOPSTART", "10"), ("LOOPLENGTH", "21")],
+            &[("LOOPSTART", "10"), ("LOOPLENGTH", "0")],
+        ] {
+            assert!(
+                tagged_loop_region(&comments(tags), 30).is_err(),
+                "{:?}",
+                tags
+            );
+        }
+    }
+}
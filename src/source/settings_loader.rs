@@ -68,6 +68,13 @@ struct SoundSettings {
     /// The [`u64`] value is the duration of the tween in milliseconds.
     #[serde(default)]
     pub fade_in_tween: Option<u64>,
+
+    /// Mix stereo sounds down to mono
+    ///
+    /// Useful for stereo recordings played on an [`AudioEmitter`](crate::AudioEmitter).
+    /// See [`AudioSource::downmix_to_mono`].
+    #[serde(default)]
+    pub mono: bool,
 }
 
 fn default_full_file() -> Region {
@@ -141,10 +148,19 @@ impl AssetLoader for SettingsLoader {
                 .read_asset_bytes(sound_settings.file.clone())
                 .await?;
 
+            let mono = sound_settings.mono;
             let sound =
                 StaticSoundData::from_cursor(Cursor::new(sound_bytes), sound_settings.into())?;
-
-            Ok(AudioSource { sound })
+            let mut source = AudioSource {
+                sound,
+                loop_region: None,
+                gain: 1.,
+            };
+            if mono {
+                source.downmix_to_mono();
+            }
+
+            Ok(source)
         })
     }
 
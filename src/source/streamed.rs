@@ -0,0 +1,65 @@
+//! Sources that are decoded while they play, for long music tracks
+
+use bevy::asset::Asset;
+use bevy::reflect::TypePath;
+use kira::sound::streaming::StreamingSoundData;
+use kira::sound::streaming::StreamingSoundSettings;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A source of audio that is decoded while it plays
+///
+/// An [`AudioSource`](crate::AudioSource) decodes the whole file while loading, which takes long
+/// and a lot of memory for music. A streamed source only keeps the encoded file in memory and
+/// decodes it on the fly. Files are loaded as streamed sources if their name has a `.stream`
+/// in front of the extension, like `music.stream.ogg`. Play them with
+/// [`AudioControl::play_streamed`](crate::AudioControl::play_streamed).
+///
+/// Streamed sounds can't be played in reverse or with a negative playback rate. Kira does not
+/// support that for them, so the plugin reports an error instead.
+#[derive(Clone, Asset, TypePath)]
+pub struct StreamedAudioSource {
+    #[cfg_attr(
+        not(any(feature = "flac", feature = "mp3", feature = "ogg", feature = "wav")),
+        allow(dead_code)
+    )]
+    pub(crate) bytes: Arc<[u8]>,
+    pub(crate) duration: Duration,
+}
+
+impl StreamedAudioSource {
+    /// The duration of the sound
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+/// The error of decoding a streamed sound
+#[cfg(any(feature = "flac", feature = "mp3", feature = "ogg", feature = "wav"))]
+pub(crate) type StreamError = kira::sound::FromFileError;
+/// The error of decoding a streamed sound
+///
+/// Without any audio format feature, there is no decoder and nothing can be streamed.
+#[cfg(not(any(feature = "flac", feature = "mp3", feature = "ogg", feature = "wav")))]
+pub(crate) type StreamError = std::convert::Infallible;
+
+impl StreamedAudioSource {
+    /// Start decoding the sound with the given settings
+    #[cfg(any(feature = "flac", feature = "mp3", feature = "ogg", feature = "wav"))]
+    pub(crate) fn data(
+        &self,
+        settings: StreamingSoundSettings,
+    ) -> Result<StreamingSoundData<StreamError>, String> {
+        StreamingSoundData::from_cursor(std::io::Cursor::new(self.bytes.clone()), settings)
+            .map_err(|error| format!("{:?}", error))
+    }
+
+    /// Start decoding the sound with the given settings
+    #[cfg(not(any(feature = "flac", feature = "mp3", feature = "ogg", feature = "wav")))]
+    pub(crate) fn data(
+        &self,
+        _settings: StreamingSoundSettings,
+    ) -> Result<StreamingSoundData<StreamError>, String> {
+        Err("no audio format feature is enabled to decode streamed sounds".to_string())
+    }
+}
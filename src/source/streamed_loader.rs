@@ -0,0 +1,72 @@
+use anyhow::Result;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::utils::BoxedFuture;
+use kira::sound::streaming::{StreamingSoundData, StreamingSoundSettings};
+use kira::sound::FromFileError;
+use std::io::Cursor;
+use std::sync::Arc;
+use thiserror::Error;
+
+use crate::source::streamed::StreamedAudioSource;
+
+/// Possible errors that can be produced by [`StreamedLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum StreamedLoaderError {
+    /// An [IO Error](std::io::Error)
+    #[error("Could not read the file: {0}")]
+    Io(#[from] std::io::Error),
+    /// An Error loading sound from a file. See [`FromFileError`]
+    #[error("Error while loading a sound: {0}")]
+    FileError(#[from] FromFileError),
+}
+
+/// Loads [`StreamedAudioSource`]s from files with a `.stream` in front of their extension
+#[derive(Default)]
+pub struct StreamedLoader;
+
+impl AssetLoader for StreamedLoader {
+    type Asset = StreamedAudioSource;
+    type Settings = ();
+    type Error = StreamedLoaderError;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut sound_bytes = vec![];
+            reader.read_to_end(&mut sound_bytes).await?;
+            let bytes: Arc<[u8]> = Arc::from(sound_bytes);
+            // Only reads the header, but fails early for files that can't be decoded
+            let sound = StreamingSoundData::from_cursor(
+                Cursor::new(bytes.clone()),
+                StreamingSoundSettings::default(),
+            )?;
+            Ok(StreamedAudioSource {
+                duration: sound.duration(),
+                bytes,
+            })
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[
+            #[cfg(feature = "flac")]
+            "stream.flac",
+            #[cfg(feature = "mp3")]
+            "stream.mp3",
+            #[cfg(feature = "ogg")]
+            "stream.ogg",
+            #[cfg(feature = "ogg")]
+            "stream.oga",
+            #[cfg(feature = "ogg")]
+            "stream.spx",
+            #[cfg(feature = "wav")]
+            "stream.wav",
+        ]
+    }
+}
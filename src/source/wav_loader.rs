@@ -1,16 +1,27 @@
 use anyhow::Result;
 use bevy::asset::io::Reader;
 use bevy::asset::{AssetLoader, AsyncReadExt, LoadContext};
+use bevy::ecs::world::{FromWorld, World};
 use bevy::utils::BoxedFuture;
-use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
 use kira::sound::FromFileError;
-use std::io::Cursor;
 use thiserror::Error;
 
-use crate::source::AudioSource;
+use crate::source::decode::DecodeProgress;
+use crate::source::{AudioLoaderSettings, AudioSource};
 
-#[derive(Default)]
-pub struct WavLoader;
+pub struct WavLoader {
+    progress: DecodeProgress,
+}
+
+impl FromWorld for WavLoader {
+    fn from_world(world: &mut World) -> Self {
+        WavLoader {
+            progress: world
+                .get_resource_or_insert_with(DecodeProgress::default)
+                .clone(),
+        }
+    }
+}
 
 /// Possible errors that can be produced by [`WavLoader`]
 #[non_exhaustive]
@@ -26,23 +37,22 @@ pub enum WavLoaderError {
 
 impl AssetLoader for WavLoader {
     type Asset = AudioSource;
-    type Settings = ();
+    type Settings = AudioLoaderSettings;
     type Error = WavLoaderError;
 
     fn load<'a>(
         &'a self,
         reader: &'a mut Reader,
-        _settings: &'a (),
-        _load_context: &'a mut LoadContext,
+        settings: &'a AudioLoaderSettings,
+        load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
         Box::pin(async move {
             let mut sound_bytes = vec![];
             reader.read_to_end(&mut sound_bytes).await?;
-            let sound = StaticSoundData::from_cursor(
-                Cursor::new(sound_bytes),
-                StaticSoundSettings::default(),
-            )?;
-            Ok(AudioSource { sound })
+            Ok(self
+                .progress
+                .source(load_context.asset_path(), sound_bytes, settings)
+                .await?)
         })
     }
     fn extensions(&self) -> &[&str] {
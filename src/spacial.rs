@@ -1,13 +1,29 @@
-use crate::{AudioInstance, AudioTween};
-use bevy::asset::{Assets, Handle};
+use crate::audio_output::AudioOutput;
+use crate::spacial::zone::{in_reverb_zone, ReverbZone, ZoneExtent};
+use crate::{
+    AudioControl, AudioInstance, AudioSource, AudioTween, Channel, PlayAudioCommand, PlaybackState,
+};
+use bevy::asset::{AssetId, Assets, Handle};
 use bevy::ecs::component::Component;
-use bevy::prelude::{GlobalTransform, Query, Res, ResMut, Resource, With};
-use bevy::transform::components::Transform;
+use bevy::log::warn;
+use bevy::prelude::{
+    Changed, Entity, GlobalTransform, Has, Local, NonSend, Query, RemovedComponents, Res, ResMut,
+    Resource, Vec3, With,
+};
+use bevy::prelude::{ReflectComponent, ReflectResource};
+use bevy::reflect::Reflect;
+use bevy::time::Time;
+use bevy::utils::{HashMap, HashSet};
+use std::time::Duration;
 
-enum SoundPath {
-    Direct,
-    Ambient,
-}
+#[cfg(feature = "debug")]
+pub(crate) mod debug;
+pub(crate) mod despawn;
+pub(crate) mod interaural_delay;
+#[cfg(feature = "propagation")]
+pub(crate) mod propagation;
+pub(crate) mod scene;
+pub(crate) mod zone;
 
 #[doc(alias = "mix")]
 #[inline]
@@ -15,11 +31,224 @@ pub fn lerp(lhs: f32, rhs: f32, s: f32) -> f32 {
     lhs + ((rhs - lhs) * s)
 }
 
+/// Cutoff frequency that leaves audible frequencies untouched
+pub(crate) const UNFILTERED_CUTOFF: f32 = 20_000.;
+
+/// Low-pass cutoff at the given fraction of the maximum distance
+///
+/// The cutoff moves exponentially from [`UNFILTERED_CUTOFF`] at `0` to `cutoff_at_max_distance` at `1`.
+pub(crate) fn muffle(relative_distance: f32, cutoff_at_max_distance: f32) -> f32 {
+    let relative_distance = if relative_distance.is_nan() {
+        1.
+    } else {
+        relative_distance.clamp(0., 1.)
+    };
+
+    UNFILTERED_CUTOFF * (cutoff_at_max_distance / UNFILTERED_CUTOFF).powf(relative_distance)
+}
+
+/// Speed of sound in air in meters per second
+pub(crate) const SPEED_OF_SOUND: f32 = 343.;
+
+/// Fraction of the maximum distance over which emitters fade out before the hard cutoff
+pub(crate) const EDGE_FADE: f32 = 0.1;
+
+/// Volume factor that smoothly falls from `1` to `0` at the end of the maximum distance
+pub(crate) fn edge_fade(distance: f32, max_distance: f32) -> f32 {
+    if distance >= max_distance || distance.is_nan() {
+        return 0.;
+    }
+    let fade_start = max_distance * (1. - EDGE_FADE);
+    let t = ((max_distance - distance) / (max_distance - fade_start)).clamp(0., 1.);
+
+    t * t * (3. - 2. * t)
+}
+
+/// How positions of emitters and receivers are interpreted
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpacialMode {
+    /// Full 3D spacial audio using the orientation of emitters and receivers
+    #[default]
+    ThreeD,
+    /// Spacial audio on the XY plane, e.g. for 2D games with a `Camera2dBundle` as receiver
+    ///
+    /// The Z coordinate and the orientation of emitters and receivers are ignored.
+    /// Panning follows the signed X offset of the emitter relative to the receiver.
+    TwoD {
+        /// X offset at which an emitter is panned fully to one side
+        half_width: f32,
+    },
+}
+
+impl SpacialMode {
+    /// The position used for spacial audio computations
+    pub fn project(&self, translation: Vec3) -> Vec3 {
+        match self {
+            SpacialMode::ThreeD => translation,
+            SpacialMode::TwoD { .. } => translation.truncate().extend(0.),
+        }
+    }
+}
+
+/// What happens to the instances of emitters beyond their maximum distance
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CullMode {
+    /// Keep playing at zero volume
+    #[default]
+    Mute,
+    /// Pause the instances and resume them when the emitter is back in range
+    ///
+    /// Only instances that were paused by spacial audio are resumed.
+    /// Instances paused by the user stay paused.
+    Pause,
+}
+
+/// Where spacial audio is computed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SpacialBackend {
+    /// Compute volume, panning and filtering in systems and send them to every instance (default)
+    ///
+    /// Supports all settings of [`SpacialAudio`], [`AudioEmitter`] and [`AudioReceiver`].
+    #[default]
+    Manual,
+    /// Use a kira spatial scene that attenuates and pans emitters in the mixer
+    ///
+    /// The [`PrimaryReceiver`] (or any receiver if there is none) becomes the kira listener and each
+    /// [`AudioEmitter`] a kira emitter. Only their positions are sent to the audio thread, and only
+    /// when they move. Distances are taken from [`SpacialAudio::max_distance_of`] and
+    /// [`SpacialAudio::min_distance`] when the kira emitter is created, and attenuation is linear
+    /// between them. Attenuation models, cones, rear attenuation, occlusion, filtering,
+    /// ambient sends and doppler are not supported.
+    ///
+    /// Only instances that start playing on an emitter while this backend is selected are
+    /// routed through the kira scene. They stay in the scene when switching back to
+    /// [`Manual`](Self::Manual) or disabling spacial audio.
+    Kira,
+}
+
+/// How the direction of an emitter is turned into panning
+///
+/// Kira applies square root gains to the panning value of a sound.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PanningLaw {
+    /// Maps the sideways direction linearly to the panning value (original behavior)
+    #[default]
+    Linear,
+    /// Sine/cosine pan law, resulting in left and right gains of `cos(θ)` and `sin(θ)`
+    /// with `θ` going from `0` (hard left) to `π/2` (hard right)
+    ConstantPower,
+    /// No panning, only the volume is changed
+    ///
+    /// Useful for mono speaker setups.
+    Off,
+}
+
+impl PanningLaw {
+    /// Panning value for an emitter in the given sideways direction
+    ///
+    /// `side` is `-1` if the emitter is directly to the left and `1` if it is directly to the right
+    /// of the receiver.
+    pub fn pan(&self, side: f32) -> f32 {
+        let side = side.clamp(-1., 1.);
+        match self {
+            PanningLaw::Linear => (side + 1.) / 2.,
+            PanningLaw::ConstantPower => (std::f32::consts::FRAC_PI_4 * (side + 1.)).sin().powi(2),
+            PanningLaw::Off => 0.5,
+        }
+    }
+}
+
+/// Describes how the volume of an emitter falls off with distance
+///
+/// All models except [`AttenuationModel::Linear`] are relative to the [`range`](AudioEmitter::range)
+/// of the emitter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AttenuationModel {
+    /// The original falloff of `16 * range / distance`
+    ///
+    /// This is the default to keep existing projects sounding the same.
+    #[default]
+    Inverse,
+    /// The volume drops linearly from `1` at distance `0` to `0` at `max_distance`
+    Linear {
+        /// Distance at which the emitter can no longer be heard
+        max_distance: f32,
+    },
+    /// `1 / (1 + rolloff * (distance / range)²)`
+    InverseSquare {
+        /// How fast the volume falls off
+        rolloff: f32,
+    },
+    /// `e^(-rolloff * distance / range)`
+    Exponential {
+        /// How fast the volume falls off
+        rolloff: f32,
+    },
+    /// The distance has no influence on the volume
+    None,
+}
+
+impl AttenuationModel {
+    /// Volume factor for an emitter with the given range that is `distance` away from the receiver
+    pub fn attenuate(&self, distance: f32, range: f32) -> f32 {
+        let relative_distance = distance / range.max(f32::EPSILON);
+        match *self {
+            AttenuationModel::Inverse => 16. * range / distance,
+            AttenuationModel::Linear { max_distance } => {
+                (1. - distance / max_distance.max(f32::EPSILON)).clamp(0., 1.)
+            }
+            AttenuationModel::InverseSquare { rolloff } => {
+                1. / (1. + rolloff * relative_distance.powi(2))
+            }
+            AttenuationModel::Exponential { rolloff } => (-rolloff * relative_distance).exp(),
+            AttenuationModel::None => 1.,
+        }
+    }
+}
+
+/// Directional cone of an emitter
+///
+/// Angles are in radians and measured between the forward direction of the emitter and the
+/// direction to the receiver.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct EmitterCone {
+    /// Inside of this angle, the emitter plays at full volume
+    pub inner_angle: f32,
+    /// Outside of this angle, the volume is multiplied with [`outer_gain`](Self::outer_gain)
+    pub outer_angle: f32,
+    /// Volume factor outside of the outer cone
+    pub outer_gain: f32,
+}
+
+impl EmitterCone {
+    /// Volume factor for a receiver at the given angle
+    ///
+    /// Between the inner and outer angles the volume is smoothly interpolated.
+    pub fn gain(&self, angle: f32) -> f32 {
+        if angle <= self.inner_angle {
+            return 1.;
+        }
+        if angle >= self.outer_angle {
+            return self.outer_gain;
+        }
+        let t = (angle - self.inner_angle) / (self.outer_angle - self.inner_angle);
+        let smooth = t * t * (3. - 2. * t);
+
+        lerp(1., self.outer_gain, smooth)
+    }
+}
+
 /// Component for audio emitters
 ///
 /// Add [`Handle<AudioInstance>`]s to control their pan and volume based on emitter
 /// and receiver positions.
-#[derive(Component, Default)]
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct AudioEmitter {
     /// Direct attenuation
     /// Sounds facing away, and facing away from sounds will dampen
@@ -31,99 +260,2877 @@ pub struct AudioEmitter {
     /// the range is the distance at which it sounds balanced.
     pub range: f32,
 
+    /// Attenuation model of this emitter
+    ///
+    /// Overrides [`SpacialAudio::attenuation`] if set.
+    pub attenuation: Option<AttenuationModel>,
+
+    /// Directional cone of the emitter
+    ///
+    /// Replaces [`self_occlusion`](Self::self_occlusion) if set.
+    pub cone: Option<EmitterCone>,
+
+    /// Low-pass cutoff frequency in Hz at [`SpacialAudio::max_distance`]
+    ///
+    /// Overrides [`SpacialAudio::cutoff_at_max_distance`] if set.
+    pub cutoff_at_max_distance: Option<f32>,
+
+    /// Distance below which the volume stops increasing and the sound is centered
+    ///
+    /// Overrides [`SpacialAudio::min_distance`] if set.
+    pub min_distance: Option<f32>,
+
+    /// Distance at and beyond which this emitter is silent
+    ///
+    /// Overrides [`SpacialAudio::max_distance`] if set.
+    pub max_distance: Option<f32>,
+
+    /// Let the instances of this emitter finish naturally when it is removed or despawned
+    ///
+    /// By default, all instances are stopped with [`SpacialAudio::despawn_stop_tween`].
+    pub keep_playing_on_despawn: bool,
+
+    /// Volume of the signal sent to [`SpacialAudio::reverb`]
+    ///
+    /// `0.0` (default) disables the send. Only instances played
+    /// [`with_ambient_send`](crate::PlayAudioCommand::with_ambient_send) are affected;
+    /// [`AudioEmitter::play`] does that automatically if the send is enabled.
+    pub ambient_send: f32,
+
     /// Audio instances that are played by this emitter
     ///
     /// The same instance should only be on one emitter.
+    /// Instances are not reflected, since they only exist at runtime.
+    #[reflect(ignore)]
     pub instances: Vec<Handle<AudioInstance>>,
 }
 
 impl AudioEmitter {
-    // pub fn play(&mut self, instance: AudioInstance) {
-    //     let mut ambient = instance.handle
-    //     ambient.
-    //     self.instances.push()
-    // }
+    /// Play a sound on the given channel and register the new instance with this emitter
+    ///
+    /// The instance starts silent and gets its volume and panning from the next spacial
+    /// audio update, so there is no full volume blip before the emitter is positioned.
+    /// A volume set on the returned command becomes the [base volume](AudioInstance::base_volume)
+    /// of the instance.
+    /// The returned command can be used to further configure the sound, e.g. to loop it.
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use bevy_kira_audio::prelude::*;
+    /// fn my_system(mut commands: Commands, asset_server: Res<AssetServer>, audio: Res<Audio>) {
+    ///     let mut emitter = AudioEmitter::default();
+    ///     emitter.play(audio.as_ref(), asset_server.load("sounds/loop.ogg")).looped();
+    ///     commands.spawn((TransformBundle::default(), emitter));
+    /// }
+    /// ```
+    pub fn play<'a>(
+        &mut self,
+        audio: &'a impl AudioControl,
+        source: Handle<AudioSource>,
+    ) -> PlayAudioCommand<'a> {
+        let mut command = audio.play(source);
+        command.settings.start_silent = true;
+        command.settings.ambient_send = self.ambient_send > 0.;
+        self.instances.push(command.handle());
+
+        command
+    }
 }
 
 /// Component for the audio receiver
 ///
 /// Most likely you will want to add this component to your player or you camera.
-/// The entity needs a [`Transform`] and [`GlobalTransform`]. The view direction of the [`GlobalTransform`]
-/// will
-#[derive(Component)]
+/// The entity needs a [`Transform`](bevy::prelude::Transform) and [`GlobalTransform`].
+/// The view direction of the [`GlobalTransform`] will decide which sounds are in front of the receiver.
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
 pub struct AudioReceiver {
-    /// Direct attenuation
-    /// Sounds facing away, and facing away from sounds will dampen
-    /// the 'direct' component of a sound
-    pub self_occlusion: f32,
+    /// Head shadow for sounds behind the receiver
+    ///
+    /// The direct volume is multiplied by `1 - rear_attenuation * rear`, where `rear` goes from
+    /// `0` for sounds straight ahead over `0.5` for sounds to the side to `1` for sounds straight
+    /// behind the receiver (`rear = (1 - cos(angle)) / 2` with the angle between
+    /// [`GlobalTransform::forward`] and the direction to the emitter). `0.0` disables it and
+    /// `1.0` silences sounds straight behind the receiver.
+    ///
+    /// This is independent of the directivity of emitters.
+    pub rear_attenuation: f32,
+    /// Low pass filter cutoff frequency in Hz for sounds straight behind the receiver
+    ///
+    /// Follows the same `rear` curve as [`rear_attenuation`](Self::rear_attenuation), moving
+    /// exponentially from unfiltered in front to this cutoff behind. Only instances played
+    /// [`with_low_pass_filter`](crate::PlayAudioCommand::with_low_pass_filter) are affected.
+    /// `None` by default.
+    pub rear_filter_cutoff: Option<f32>,
+    /// Distance between the ears in meters for an interaural time delay
+    ///
+    /// Sounds from the side reach the far ear up to `ear_distance / 343` seconds later, which
+    /// makes directions easier to hear with headphones. `0.0` (default) only uses panning.
+    /// Only instances played
+    /// [`with_interaural_delay`](crate::PlayAudioCommand::with_interaural_delay) are affected.
+    /// Realistic values are around `0.2`, while delays longer than 2 ms are cut.
+    pub ear_distance: f32,
+}
+
+impl AudioReceiver {
+    /// How far a direction points behind the receiver
+    ///
+    /// `0` straight ahead, `1` straight behind.
+    fn rear(receiver_transform: &GlobalTransform, direction: Vec3) -> f32 {
+        (1. - receiver_transform.forward().dot(direction)) * 0.5
+    }
+}
+
+/// How much an emitter is blocked by obstacles between it and the receiver
+///
+/// `0.0` means no occlusion and `1.0` silences the emitter. Spacial audio does not compute this
+/// itself; write it from your own system, e.g. based on physics raycasts. If
+/// [`SpacialAudio::cutoff_at_full_occlusion`] is set, occluded emitters are also muffled.
+///
+/// With [bevy_rapier](https://github.com/dimforge/bevy_rapier) this could look like:
+/// ```ignore
+/// fn occlude_emitters(
+///     rapier_context: Res<RapierContext>,
+///     receiver: Query<&GlobalTransform, With<AudioReceiver>>,
+///     mut emitters: Query<(Entity, &GlobalTransform, &mut OcclusionFactor), With<AudioEmitter>>,
+/// ) {
+///     let receiver = receiver.single().translation();
+///     for (entity, emitter, mut occlusion) in emitters.iter_mut() {
+///         let path = emitter.translation() - receiver;
+///         let hit = rapier_context.cast_ray(
+///             receiver,
+///             path.normalize_or_zero(),
+///             path.length(),
+///             true,
+///             QueryFilter::default().exclude_collider(entity),
+///         );
+///         occlusion.0 = if hit.is_some() { 0.8 } else { 0. };
+///     }
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct OcclusionFactor(pub f32);
+
+/// Velocity of an emitter or receiver for the Doppler effect
+///
+/// Without this component, the velocity is estimated from the change in translation between frames.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct SpacialVelocity(pub Vec3);
+
+/// Settings for the Doppler effect
+///
+/// See [`SpacialAudio::doppler`]
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Doppler {
+    /// Speed of sound in units per second
+    pub speed_of_sound: f32,
+    /// Scales the relative velocities; `0` disables the effect
+    pub intensity: f32,
+    /// Lower bound of the resulting playback rate
+    pub min_playback_rate: f64,
+    /// Upper bound of the resulting playback rate
+    ///
+    /// Together with [`min_playback_rate`](Self::min_playback_rate) this prevents extreme rates
+    /// when an entity is teleported.
+    pub max_playback_rate: f64,
+}
+
+impl Default for Doppler {
+    fn default() -> Self {
+        Doppler {
+            speed_of_sound: SPEED_OF_SOUND,
+            intensity: 1.,
+            min_playback_rate: 0.5,
+            max_playback_rate: 2.,
+        }
+    }
+}
+
+impl Doppler {
+    /// Playback rate for an emitter at the end of `sound_path`
+    ///
+    /// `sound_path` points from the receiver to the emitter.
+    pub fn playback_rate(
+        &self,
+        sound_path: Vec3,
+        receiver_velocity: Vec3,
+        emitter_velocity: Vec3,
+    ) -> f64 {
+        let direction = sound_path.normalize_or_zero();
+        let receiver_speed = receiver_velocity.dot(direction) * self.intensity;
+        let emitter_speed = emitter_velocity.dot(direction) * self.intensity;
+        let rate = (self.speed_of_sound + receiver_speed)
+            / (self.speed_of_sound + emitter_speed).max(f32::EPSILON);
+
+        if rate.is_finite() {
+            (rate as f64).clamp(self.min_playback_rate, self.max_playback_rate)
+        } else {
+            self.max_playback_rate
+        }
+    }
+}
+
+/// Volume and panning computed for an emitter
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct SpacialOutput {
+    /// Volume as amplitude for all instances of the emitter
+    pub volume: f32,
+    /// Panning from `0.0` (hard left) to `1.0` (hard right)
+    pub panning: f32,
+    /// Cutoff frequency in Hz for instances that were played with a low-pass filter
+    ///
+    /// See [`PlayAudioCommand::with_low_pass_filter`](crate::PlayAudioCommand::with_low_pass_filter)
+    pub low_pass_cutoff: Option<f32>,
+    /// Volume as amplitude of the signal sent to the spacial reverb
+    ///
+    /// Only affects instances played
+    /// [`with_ambient_send`](crate::PlayAudioCommand::with_ambient_send).
+    pub ambient: f32,
+    /// Delay of the left ear in seconds, negative values delay the right ear
+    ///
+    /// Only affects instances played
+    /// [`with_interaural_delay`](crate::PlayAudioCommand::with_interaural_delay).
+    /// See [`AudioReceiver::ear_distance`].
+    pub interaural_delay: f32,
+}
+
+impl Default for SpacialOutput {
+    fn default() -> Self {
+        SpacialOutput {
+            volume: 1.,
+            panning: 0.5,
+            low_pass_cutoff: None,
+            ambient: 0.,
+            interaural_delay: 0.,
+        }
+    }
+}
+
+impl SpacialOutput {
+    /// Blend outputs weighted by the inverse of their distance
+    ///
+    /// Returns `None` if there are no outputs.
+    pub fn blend(outputs: impl IntoIterator<Item = (SpacialOutput, f32)>) -> Option<SpacialOutput> {
+        let mut total_weight = 0.;
+        let mut volume = 0.;
+        let mut panning = 0.;
+        let mut ambient = 0.;
+        let mut interaural_delay = 0.;
+        let mut cutoff_weight = 0.;
+        let mut cutoff = 0.;
+        for (output, distance) in outputs {
+            let weight = 1. / distance.max(f32::EPSILON);
+            total_weight += weight;
+            volume += output.volume * weight;
+            panning += output.panning * weight;
+            ambient += output.ambient * weight;
+            interaural_delay += output.interaural_delay * weight;
+            if let Some(low_pass_cutoff) = output.low_pass_cutoff {
+                cutoff_weight += weight;
+                cutoff += low_pass_cutoff * weight;
+            }
+        }
+        if total_weight <= 0. {
+            return None;
+        }
+
+        Some(SpacialOutput {
+            volume: volume / total_weight,
+            panning: panning / total_weight,
+            low_pass_cutoff: (cutoff_weight > 0.).then(|| cutoff / cutoff_weight),
+            ambient: ambient / total_weight,
+            interaural_delay: interaural_delay / total_weight,
+        })
+    }
+}
+
+/// Custom attenuation function
+///
+/// The arguments are the [`GlobalTransform`] of the receiver, the [`GlobalTransform`] of the emitter
+/// and the emitter itself.
+pub type SpacialAttenuationFn =
+    dyn Fn(&GlobalTransform, &GlobalTransform, &AudioEmitter) -> SpacialOutput + Send + Sync;
+
+/// Marker for the receiver that should be used if there are multiple [`AudioReceiver`]s
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component)]
+pub struct PrimaryReceiver;
+
+/// How to pick the receiver for an emitter if there are multiple [`AudioReceiver`]s
+///
+/// Receivers marked with [`PrimaryReceiver`] are always preferred.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReceiverSelection {
+    /// Use the receiver closest to each emitter
+    #[default]
+    Nearest,
+    /// Only use receivers marked with [`PrimaryReceiver`]
+    ///
+    /// Spacial audio will not be applied if there is no primary receiver.
+    PrimaryOnly,
+    /// Compute volume and panning for every receiver and blend them weighted by distance
+    ///
+    /// Closer receivers have more influence. This is useful for local multiplayer with one receiver
+    /// per player. Effects that need a single receiver, like [`Doppler`], use the nearest one.
+    Blend,
+}
+
+impl ReceiverSelection {
+    /// Index of the receiver to use for an emitter at the given translation
+    ///
+    /// The receivers are given as translation and whether they are a [`PrimaryReceiver`].
+    pub(crate) fn select(
+        &self,
+        receivers: impl Iterator<Item = (Vec3, bool)> + Clone,
+        emitter_translation: Vec3,
+    ) -> Option<usize> {
+        let has_primary = receivers.clone().any(|(_, primary)| primary);
+        if !has_primary && *self == ReceiverSelection::PrimaryOnly {
+            return None;
+        }
+        receivers
+            .enumerate()
+            .filter(|(_, (_, primary))| !has_primary || *primary)
+            .map(|(index, (translation, _))| {
+                (index, translation.distance_squared(emitter_translation))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(index, _)| index)
+    }
 }
 
 /// Configuration resource for spacial audio
 ///
 /// If this resource is not added to the ECS, spacial audio is not applied.
-#[derive(Resource)]
+///
+/// With the `serde` feature, the settings can be (de)serialized. Missing fields keep their
+/// default. The [`custom_attenuation`](Self::custom_attenuation) and the
+/// [`channels`](Self::channels) are not saved and are always `None` after loading.
+#[derive(Resource, Reflect)]
+#[reflect(Resource)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
 pub struct SpacialAudio {
-    /// The volume will change from `1` at distance `0` to `0` at distance `max_distance`
+    /// Spacial audio is only applied if this is `true` (default)
+    ///
+    /// When disabled, all spacial instances are reset to full volume and centered panning once.
+    pub enabled: bool,
+    /// Distance at and beyond which emitters are silent
+    ///
+    /// The volume fades out smoothly over the last 10% of this distance. Emitters out of range
+    /// are only muted and their panning is not updated.
+    /// Emitters can override this with [`AudioEmitter::max_distance`].
+    /// Not used while a [`custom_attenuation`](Self::custom_attenuation) is set.
     pub max_distance: f32,
+    /// Attenuation model for all emitters that do not define their own
+    pub attenuation: AttenuationModel,
+    /// Replaces the built-in volume and panning computation if set
+    ///
+    /// See [`SpacialAudio::set_custom_attenuation`]
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub custom_attenuation: Option<Box<SpacialAttenuationFn>>,
+    /// Change the playback rate of emitters based on their velocity relative to the receiver
+    ///
+    /// Disabled by default. The playback rate of spacial instances will be overwritten while enabled.
+    pub doppler: Option<Doppler>,
+    /// Which receiver to use if there are multiple [`AudioReceiver`]s
+    pub receiver_selection: ReceiverSelection,
+    /// Low-pass cutoff frequency in Hz for emitters at [`max_distance`](Self::max_distance)
+    ///
+    /// Closer emitters are filtered less. Only instances played
+    /// [`with_low_pass_filter`](crate::PlayAudioCommand::with_low_pass_filter) are affected.
+    /// `None` by default, which disables filtering.
+    pub cutoff_at_max_distance: Option<f32>,
+    /// Attenuation of sounds above or below the receiver
+    ///
+    /// At `1.0` sounds directly above or below the receiver are silent, while `0.0` (default)
+    /// ignores the elevation.
+    pub vertical_attenuation: f32,
+    /// Low-pass cutoff frequency in Hz for fully occluded emitters (see [`OcclusionFactor`])
+    ///
+    /// Only instances played
+    /// [`with_low_pass_filter`](crate::PlayAudioCommand::with_low_pass_filter) are affected.
+    /// `None` by default, which only lowers the volume of occluded emitters.
+    pub cutoff_at_full_occlusion: Option<f32>,
+    /// How the direction of emitters is turned into panning
+    pub panning_law: PanningLaw,
+    /// Tween for all changes spacial audio makes to instances
+    ///
+    /// The default is [`AudioTween::default`].
+    #[reflect(ignore)]
+    pub update_tween: AudioTween,
+    /// Tween for volume changes; falls back to [`update_tween`](Self::update_tween)
+    #[reflect(ignore)]
+    pub volume_tween: Option<AudioTween>,
+    /// Tween for panning changes; falls back to [`update_tween`](Self::update_tween)
+    #[reflect(ignore)]
+    pub panning_tween: Option<AudioTween>,
+    /// Distance below which the volume stops increasing and the sound is centered
+    ///
+    /// Emitters can override this with [`AudioEmitter::min_distance`].
+    pub min_distance: f32,
+    /// Upper limit for the volume of spacial instances (as amplitude)
+    ///
+    /// The default of `16` is the volume of the original falloff at the range of an emitter.
+    pub max_volume: f32,
+    /// What happens to instances of emitters that are out of range
+    pub cull_mode: CullMode,
+    /// Tween used to resume instances paused by [`CullMode::Pause`]
+    #[reflect(ignore)]
+    pub cull_resume_tween: AudioTween,
+    /// Tween used to stop the instances of emitters that are removed or despawned
+    #[reflect(ignore)]
+    pub despawn_stop_tween: AudioTween,
+    /// Changes of volume and panning smaller than this are not sent to instances
+    ///
+    /// Defaults to `0.001`. Static scenes do not send any commands to the audio thread.
+    pub update_epsilon: f32,
+    /// Only update spacial audio at this interval instead of every frame
+    ///
+    /// `None` by default. Consider a [`update_tween`](Self::update_tween) about as long as the
+    /// interval to keep changes smooth.
+    pub update_interval: Option<Duration>,
+    /// Only apply spacial audio to this many of the loudest emitters
+    ///
+    /// All other emitters are handled like emitters out of range (see [`cull_mode`](Self::cull_mode)).
+    /// Emitters that were active in the last frame are slightly preferred, so emitters with
+    /// similar volumes do not keep switching. `None` (default) updates all emitters.
+    pub max_active_emitters: Option<usize>,
+    /// Whether spacial audio works in 3D (default) or on the XY plane
+    pub mode: SpacialMode,
+    /// Reverb that emitters send their ambient signal to (see [`AudioEmitter::ambient_send`])
+    ///
+    /// `None` by default. Only instances that start playing while this is set get an ambient send.
+    /// Once there are [`ReverbZone`]s, the reverb follows the zone the receiver is in and these
+    /// settings are only used outside of all zones.
+    pub reverb: Option<SpacialReverb>,
+    /// Only control instances played on these channels
+    ///
+    /// `None` (default) controls instances of all channels. Instances on other channels are not
+    /// touched by spacial audio, even if they are registered on an [`AudioEmitter`].
+    /// ```
+    /// # use bevy::utils::HashSet;
+    /// # use bevy_kira_audio::prelude::*;
+    /// # use bevy_kira_audio::Channel;
+    /// struct WorldSounds;
+    ///
+    /// let spacial_audio = SpacialAudio {
+    ///     channels: Some(HashSet::from_iter([Channel::typed::<WorldSounds>()])),
+    ///     ..Default::default()
+    /// };
+    /// ```
+    #[reflect(ignore)]
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub channels: Option<HashSet<Channel>>,
+    /// Compute spacial audio in systems (default) or let kira do it in the mixer
+    pub backend: SpacialBackend,
+}
+
+/// Settings of the reverb used for the ambient signal of emitters
+#[derive(Clone, Debug, PartialEq, Reflect)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct SpacialReverb {
+    /// How much the room reverberates, from `0.0` to `1.0`
+    ///
+    /// Default is `0.9`.
+    pub feedback: f64,
+    /// How quickly high frequencies disappear from the reverberation, from `0.0` to `1.0`
+    ///
+    /// Default is `0.1`.
+    pub damping: f64,
+    /// Stereo width of the reverberation, from `0.0` (mono) to `1.0`
+    ///
+    /// Default is `1.0`.
+    pub stereo_width: f64,
+}
+
+impl Default for SpacialReverb {
+    fn default() -> Self {
+        SpacialReverb {
+            feedback: 0.9,
+            damping: 0.1,
+            stereo_width: 1.,
+        }
+    }
+}
+
+impl Default for SpacialAudio {
+    fn default() -> Self {
+        SpacialAudio {
+            enabled: true,
+            max_distance: 25.,
+            attenuation: AttenuationModel::default(),
+            custom_attenuation: None,
+            doppler: None,
+            receiver_selection: ReceiverSelection::default(),
+            cutoff_at_max_distance: None,
+            vertical_attenuation: 0.,
+            cutoff_at_full_occlusion: None,
+            panning_law: PanningLaw::default(),
+            update_tween: AudioTween::default(),
+            volume_tween: None,
+            panning_tween: None,
+            min_distance: 0.,
+            max_volume: 16.,
+            cull_mode: CullMode::default(),
+            cull_resume_tween: AudioTween::linear(Duration::from_millis(200)),
+            despawn_stop_tween: AudioTween::linear(Duration::from_millis(200)),
+            update_epsilon: 0.001,
+            update_interval: None,
+            max_active_emitters: None,
+            mode: SpacialMode::default(),
+            reverb: None,
+            channels: None,
+            backend: SpacialBackend::default(),
+        }
+    }
 }
 
 impl SpacialAudio {
-    pub(crate) fn update(
+    /// Use the given function to compute volume and panning of all emitters
+    ///
+    /// The function replaces the whole built-in computation, including the fade out towards
+    /// [`max_distance`](Self::max_distance) and the culling of emitters beyond it. That way it
+    /// can define its own idea of distance, like for sounds behind walls or under water.
+    ///
+    /// ```
+    /// # use bevy_kira_audio::prelude::*;
+    /// let mut spacial_audio = SpacialAudio::default();
+    /// spacial_audio.set_custom_attenuation(|receiver, emitter_transform, _emitter| {
+    ///     let distance = receiver.translation().distance(emitter_transform.translation());
+    ///     SpacialOutput {
+    ///         volume: if distance < 10. { 1. } else { 0. },
+    ///         ..Default::default()
+    ///     }
+    /// });
+    /// ```
+    pub fn set_custom_attenuation(
+        &mut self,
+        attenuation: impl Fn(&GlobalTransform, &GlobalTransform, &AudioEmitter) -> SpacialOutput
+            + Send
+            + Sync
+            + 'static,
+    ) {
+        self.custom_attenuation = Some(Box::new(attenuation));
+    }
+
+    /// Remove a custom attenuation function and fall back to the built-in computation
+    pub fn clear_custom_attenuation(&mut self) {
+        self.custom_attenuation = None;
+    }
+
+    /// Whether the instance was played on one of the [`channels`](Self::channels)
+    pub(crate) fn controls(&self, instance: &AudioInstance) -> bool {
+        !instance.in_kira_scene && self.includes(&instance.channel)
+    }
+
+    pub(crate) fn includes(&self, channel: &Channel) -> bool {
+        self.channels
+            .as_ref()
+            .map_or(true, |channels| channels.contains(channel))
+    }
+
+    /// Give instances on other channels, that were played silent for spacial audio, their volume
+    ///
+    /// Returns the number of commands sent to the instance.
+    fn release(&self, instance: &mut AudioInstance) -> usize {
+        if !instance.started_silent {
+            return 0;
+        }
+        instance.started_silent = false;
+        instance.set_spacial_volume(1., 0., self.effective_volume_tween());
+
+        1
+    }
+
+    pub(crate) fn effective_volume_tween(&self) -> AudioTween {
+        self.volume_tween
+            .clone()
+            .unwrap_or_else(|| self.update_tween.clone())
+    }
+
+    pub(crate) fn effective_panning_tween(&self) -> AudioTween {
+        self.panning_tween
+            .clone()
+            .unwrap_or_else(|| self.update_tween.clone())
+    }
+
+    /// The distance at which the given emitter becomes silent
+    pub fn max_distance_of(&self, emitter: &AudioEmitter) -> f32 {
+        emitter.max_distance.unwrap_or(self.max_distance)
+    }
+
+    /// Distance between receiver and emitter respecting the [`SpacialMode`]
+    pub(crate) fn distance(
+        &self,
+        receiver_transform: &GlobalTransform,
+        emitter_transform: &GlobalTransform,
+    ) -> f32 {
+        self.mode
+            .project(receiver_transform.translation())
+            .distance(self.mode.project(emitter_transform.translation()))
+    }
+
+    pub(crate) fn output(
         &self,
         receiver_transform: &GlobalTransform,
         receiver: &AudioReceiver,
-        emitters: &Query<(&GlobalTransform, &AudioEmitter), With<AudioEmitter>>,
-        audio_instances: &mut Assets<AudioInstance>,
-    ) {
-        for (emitter_transform, emitter) in emitters {
-            let sound_path = emitter_transform.translation() - receiver_transform.translation();
-            let volume =  4. * emitter.range / sound_path.length();
+        emitter_transform: &GlobalTransform,
+        emitter: &AudioEmitter,
+    ) -> SpacialOutput {
+        if let Some(custom_attenuation) = &self.custom_attenuation {
+            return custom_attenuation(receiver_transform, emitter_transform, emitter);
+        }
+        let max_distance = self.max_distance_of(emitter);
+        let mut output = self.attenuated_output(
+            receiver_transform,
+            receiver,
+            emitter_transform,
+            emitter,
+            max_distance,
+        );
+        let fade = edge_fade(
+            self.distance(receiver_transform, emitter_transform),
+            max_distance,
+        );
+        output.volume *= fade;
+        output.ambient *= fade;
+
+        output
+    }
+
+    fn attenuated_output(
+        &self,
+        receiver_transform: &GlobalTransform,
+        receiver: &AudioReceiver,
+        emitter_transform: &GlobalTransform,
+        emitter: &AudioEmitter,
+        max_distance: f32,
+    ) -> SpacialOutput {
+        let sound_path = self.mode.project(emitter_transform.translation())
+            - self.mode.project(receiver_transform.translation());
+        let distance = sound_path.length();
+        let min_distance = emitter.min_distance.unwrap_or(self.min_distance);
+        let attenuation = emitter.attenuation.unwrap_or(self.attenuation);
+
+        // Within the minimum distance the volume is held and the sound is centered.
+        let (volume, panning, rear_cutoff, side) =
+            if distance <= min_distance || distance <= f32::EPSILON {
+                (
+                    attenuation.attenuate(min_distance, emitter.range),
+                    0.5,
+                    None,
+                    0.,
+                )
+            } else if let SpacialMode::TwoD { half_width } = self.mode {
+                let side = (sound_path.x / half_width.max(f32::EPSILON)).clamp(-1., 1.);
+
+                (
+                    attenuation.attenuate(distance, emitter.range),
+                    self.panning_law.pan(side),
+                    None,
+                    side,
+                )
+            } else {
+                let direction = sound_path / distance;
+                let emitter_directivity = match emitter.cone {
+                    Some(cone) => cone.gain(emitter_transform.forward().angle_between(-direction)),
+                    None => lerp(
+                        1.,
+                        emitter_transform.back().dot(direction) * 0.5 + 0.5,
+                        emitter.self_occlusion,
+                    ),
+                };
+                let rear = AudioReceiver::rear(receiver_transform, direction);
+                let elevation = receiver_transform.up().dot(direction).abs();
+                let direct_volume = attenuation.attenuate(distance, emitter.range)
+                    * emitter_directivity
+                    * (1. - receiver.rear_attenuation * rear)
+                    * (1. - self.vertical_attenuation * elevation).clamp(0., 1.);
+
+                // Only the horizontal part of the sound path moves the panning away from the center.
+                // Sounds straight above or below the receiver are centered.
+                let side = receiver_transform.right().dot(direction);
+                let rear_cutoff = receiver
+                    .rear_filter_cutoff
+                    .map(|cutoff| muffle(rear, cutoff));
+
+                (direct_volume, self.panning_law.pan(side), rear_cutoff, side)
+            };
+        let volume = if volume.is_nan() {
+            0.
+        } else {
+            volume.clamp(0., self.max_volume)
+        };
+
+        let distance_cutoff = emitter
+            .cutoff_at_max_distance
+            .or(self.cutoff_at_max_distance)
+            .map(|cutoff| muffle(distance / max_distance, cutoff));
+        let low_pass_cutoff = match (distance_cutoff, rear_cutoff) {
+            (Some(distance_cutoff), Some(rear_cutoff)) => Some(distance_cutoff.min(rear_cutoff)),
+            (distance_cutoff, rear_cutoff) => distance_cutoff.or(rear_cutoff),
+        };
 
-            let direct_volume = 4. * volume * lerp(1., emitter_transform.back().dot(sound_path.normalize_or_zero()) * 0.5 + 0.5, emitter.self_occlusion) *
-                                                lerp(1., receiver_transform.forward().dot(sound_path.normalize_or_zero()) * 0.5 + 0.5, receiver.self_occlusion);
+        SpacialOutput {
+            volume,
+            panning,
+            low_pass_cutoff,
+            // The reverberated signal does not fall off with distance like the direct one,
+            // so distant emitters sound more reverberant.
+            ambient: emitter.ambient_send.max(0.),
+            // The sound reaches the ear on its side first
+            interaural_delay: receiver.ear_distance.max(0.) * side / SPEED_OF_SOUND,
+        }
+    }
 
-            let ambient_volume = volume / sound_path.length();
-            // (1. - sound_path.length() / self.max_distance)
-            //     .clamp(0., 1.)
-            //     .powi(2);
+    /// Apply an [`OcclusionFactor`] to the output for an emitter
+    pub(crate) fn occlude(&self, output: SpacialOutput, occlusion: f32) -> SpacialOutput {
+        let occlusion = if occlusion.is_nan() {
+            0.
+        } else {
+            occlusion.clamp(0., 1.)
+        };
+        let occlusion_cutoff = self
+            .cutoff_at_full_occlusion
+            .map(|cutoff| muffle(occlusion, cutoff));
 
-            let right_ear_angle = receiver_transform.right().angle_between(sound_path);
-            let panning = (right_ear_angle.cos() + 1.) / 2.;
+        SpacialOutput {
+            volume: output.volume * (1. - occlusion),
+            low_pass_cutoff: match (output.low_pass_cutoff, occlusion_cutoff) {
+                (Some(distance_cutoff), Some(occlusion_cutoff)) => {
+                    Some(distance_cutoff.min(occlusion_cutoff))
+                }
+                (distance_cutoff, occlusion_cutoff) => distance_cutoff.or(occlusion_cutoff),
+            },
+            ..output
+        }
+    }
 
-            for instance in emitter.instances.iter() {
-                if let Some(instance) = audio_instances.get_mut(instance) {
-                    instance.set_volume(direct_volume as f64, AudioTween::default());
-                    instance.set_panning(panning as f64, AudioTween::default());
+    /// Mute or pause all instances of an emitter that is out of range without touching their panning
+    ///
+    /// Returns the number of commands sent to instances.
+    fn cull(
+        &self,
+        emitter: &AudioEmitter,
+        audio_instances: &mut Assets<AudioInstance>,
+        state: &mut SpacialAudioState,
+    ) -> usize {
+        let mut commands = 0;
+        for handle in emitter.instances.iter() {
+            let Some(instance) = audio_instances.get_mut(handle) else {
+                continue;
+            };
+            if !self.controls(instance) {
+                commands += self.release(instance);
+                continue;
+            }
+            match self.cull_mode {
+                CullMode::Mute => {
+                    let muted = state
+                        .applied
+                        .get(&handle.id())
+                        .map_or(false, |applied| applied.volume == 0.);
+                    if !muted {
+                        instance.set_volume(0., self.effective_volume_tween());
+                        commands += 1;
+                        // NaN forces the next update to send all other values again
+                        state.applied.insert(
+                            handle.id(),
+                            SpacialOutput {
+                                volume: 0.,
+                                panning: f32::NAN,
+                                low_pass_cutoff: Some(f32::NAN),
+                                ambient: f32::NAN,
+                                interaural_delay: f32::NAN,
+                            },
+                        );
+                    }
+                }
+                CullMode::Pause => {
+                    if matches!(instance.state(), PlaybackState::Playing { .. }) {
+                        instance.pause(self.effective_volume_tween());
+                        instance.culled = true;
+                        commands += 1;
+                        state.culled.insert(handle.id());
+                        state.applied.remove(&handle.id());
+                    }
                 }
             }
         }
+
+        commands
     }
-}
 
-pub(crate) fn run_spacial_audio(
-    spacial_audio: Res<SpacialAudio>,
-    receiver: Query<(&GlobalTransform, &AudioReceiver), With<AudioReceiver>>,
-    emitters: Query<(&GlobalTransform, &AudioEmitter), With<AudioEmitter>>,
-    mut audio_instances: ResMut<Assets<AudioInstance>>,
-) {
-    if let Ok((receiver_transform, receiver)) = receiver.get_single() {
-        spacial_audio.update(&receiver_transform, &receiver, &emitters, &mut audio_instances);
+    /// Evaluate an emitter against the receivers
+    pub(crate) fn emitter_output(
+        &self,
+        receivers: &[(&GlobalTransform, &AudioReceiver, bool)],
+        emitter_transform: &GlobalTransform,
+        emitter: &AudioEmitter,
+        occlusion: Option<&OcclusionFactor>,
+    ) -> EmitterOutput {
+        let max_distance = self.max_distance_of(emitter);
+        // A custom attenuation decides on its own when emitters are out of range
+        let in_range = |receiver_transform: &GlobalTransform| {
+            self.custom_attenuation.is_some()
+                || self.distance(receiver_transform, emitter_transform) < max_distance
+        };
+        let output = if self.receiver_selection == ReceiverSelection::Blend {
+            if !receivers
+                .iter()
+                .any(|(transform, _, _)| in_range(transform))
+            {
+                return if receivers.is_empty() {
+                    EmitterOutput::NoReceiver
+                } else {
+                    EmitterOutput::OutOfRange
+                };
+            }
+            SpacialOutput::blend(receivers.iter().map(|(receiver_transform, receiver, _)| {
+                (
+                    self.output(receiver_transform, receiver, emitter_transform, emitter),
+                    self.distance(receiver_transform, emitter_transform),
+                )
+            }))
+        } else {
+            let Some(receiver_index) = self.select_receiver(receivers, emitter_transform) else {
+                return EmitterOutput::NoReceiver;
+            };
+            let (receiver_transform, receiver, _) = receivers[receiver_index];
+            if !in_range(receiver_transform) {
+                return EmitterOutput::OutOfRange;
+            }
+            Some(self.output(receiver_transform, receiver, emitter_transform, emitter))
+        };
+        let Some(mut output) = output else {
+            return EmitterOutput::NoReceiver;
+        };
+        if let Some(OcclusionFactor(occlusion)) = occlusion {
+            output = self.occlude(output, *occlusion);
+        }
+
+        EmitterOutput::Audible(output)
     }
-}
 
-pub(crate) fn cleanup_stopped_spacial_instances(
-    mut emitters: Query<&mut AudioEmitter>,
-    instances: ResMut<Assets<AudioInstance>>,
-) {
-    for mut emitter in emitters.iter_mut() {
-        let handles = &mut emitter.instances;
+    /// Index of the receiver used for the given emitter according to the [`ReceiverSelection`]
+    pub(crate) fn select_receiver(
+        &self,
+        receivers: &[(&GlobalTransform, &AudioReceiver, bool)],
+        emitter_transform: &GlobalTransform,
+    ) -> Option<usize> {
+        self.receiver_selection.select(
+            receivers.iter().map(|(transform, _, primary)| {
+                (self.mode.project(transform.translation()), *primary)
+            }),
+            self.mode.project(emitter_transform.translation()),
+        )
+    }
 
-        handles.retain(|handle| {
-            if let Some(instance) = instances.get(handle) {
-                instance.handle.state() != kira::sound::PlaybackState::Stopped
-            } else {
-                true
+    /// Apply spacial audio to the instances of all emitters
+    ///
+    /// Returns the number of commands sent to instances.
+    pub(crate) fn update(
+        &self,
+        receivers: &[(&GlobalTransform, &AudioReceiver, bool)],
+        emitters: &Query<SpacialEmitter, With<AudioEmitter>>,
+        audio_instances: &mut Assets<AudioInstance>,
+        state: &mut SpacialAudioState,
+    ) -> usize {
+        let mut commands = 0;
+        let mut audible = vec![];
+        for (entity, emitter_transform, emitter, occlusion) in emitters {
+            #[cfg(feature = "propagation")]
+            let propagated = state
+                .sound_paths
+                .get(&entity)
+                .map(|path| path.apply(emitter_transform, occlusion));
+            #[cfg(feature = "propagation")]
+            let (emitter_transform, occlusion) = match &propagated {
+                Some((transform, occlusion)) => (transform, Some(occlusion)),
+                None => (emitter_transform, occlusion),
+            };
+            match self.emitter_output(receivers, emitter_transform, emitter, occlusion) {
+                EmitterOutput::NoReceiver => (),
+                EmitterOutput::OutOfRange => {
+                    commands += self.cull(emitter, audio_instances, state);
+                }
+                EmitterOutput::Audible(mut output) => {
+                    if !state.reverb_zones.is_empty()
+                        && !in_reverb_zone(&state.reverb_zones, emitter_transform.translation())
+                    {
+                        output.ambient = 0.;
+                    }
+                    audible.push((entity, emitter, output));
+                }
             }
-        });
+        }
+        if let Some(max_active_emitters) = self.max_active_emitters {
+            state.active_emitters = select_active_emitters(
+                audible
+                    .iter()
+                    .map(|(entity, _, output)| (*entity, output.volume)),
+                &state.active_emitters,
+                max_active_emitters,
+            );
+        }
+
+        for (entity, emitter, output) in audible {
+            if self.max_active_emitters.is_some() && !state.active_emitters.contains(&entity) {
+                commands += self.cull(emitter, audio_instances, state);
+                continue;
+            }
+            for handle in emitter.instances.iter() {
+                if let Some(instance) = audio_instances.get_mut(handle) {
+                    commands += self.apply(handle.id(), instance, &output, state);
+                }
+            }
+        }
+
+        commands
+    }
+
+    /// Send the parts of the output that changed since the last update to the instance
+    ///
+    /// Returns the number of commands sent to the instance.
+    fn apply(
+        &self,
+        id: AssetId<AudioInstance>,
+        instance: &mut AudioInstance,
+        output: &SpacialOutput,
+        state: &mut SpacialAudioState,
+    ) -> usize {
+        if !self.controls(instance) {
+            return self.release(instance);
+        }
+        let mut commands = 0;
+        // Instances that were paused by hand since they were culled stay paused
+        if state.culled.remove(&id) && std::mem::take(&mut instance.culled) {
+            instance.resume(self.cull_resume_tween.clone());
+            commands += 1;
+        }
+        let changed = |previous: f32, current: f32| {
+            let delta = (current - previous).abs();
+            delta.is_nan() || delta > self.update_epsilon
+        };
+        let previous = state.applied.get(&id).copied();
+        let volume_changed = previous.map_or(true, |previous| {
+            changed(previous.volume, output.volume) || changed(previous.ambient, output.ambient)
+        });
+        if volume_changed {
+            instance.started_silent = false;
+            instance.set_spacial_volume(
+                output.volume as f64,
+                output.ambient as f64,
+                self.effective_volume_tween(),
+            );
+            commands += 1;
+        }
+        if previous.map_or(true, |previous| changed(previous.panning, output.panning)) {
+            instance.set_panning(output.panning as f64, self.effective_panning_tween());
+            commands += 1;
+        }
+        if instance.has_interaural_delay()
+            && previous.map_or(true, |previous| {
+                // Delays are tiny, so they are compared in samples at 48 kHz instead of seconds
+                changed(
+                    previous.interaural_delay * 48_000.,
+                    output.interaural_delay * 48_000.,
+                )
+            })
+        {
+            instance.set_interaural_delay(output.interaural_delay);
+            commands += 1;
+        }
+        if let Some(cutoff) = output.low_pass_cutoff {
+            let cutoff_changed = previous
+                .and_then(|previous| previous.low_pass_cutoff)
+                .map_or(true, |previous| {
+                    // Cutoffs are compared relative to the frequency
+                    changed(previous / cutoff, 1.)
+                });
+            if cutoff_changed && instance.has_filter() {
+                instance.set_filter_cutoff(cutoff as f64, self.update_tween.clone());
+                commands += 1;
+            }
+        }
+        state.applied.insert(id, *output);
+
+        commands
+    }
+}
+
+/// Score bonus for emitters that were active in the last frame, so they don't flap in and out
+/// of the active set when their volume is close to the boundary
+pub(crate) const ACTIVE_EMITTER_HYSTERESIS: f32 = 1.2;
+
+/// The loudest `max_active_emitters` emitters, preferring previously active ones
+pub(crate) fn select_active_emitters(
+    candidates: impl IntoIterator<Item = (Entity, f32)>,
+    previously_active: &HashSet<Entity>,
+    max_active_emitters: usize,
+) -> HashSet<Entity> {
+    let mut candidates: Vec<_> = candidates
+        .into_iter()
+        .map(|(entity, volume)| {
+            let volume = if volume.is_nan() { 0. } else { volume };
+            if previously_active.contains(&entity) {
+                (entity, volume * ACTIVE_EMITTER_HYSTERESIS)
+            } else {
+                (entity, volume)
+            }
+        })
+        .collect();
+    candidates.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    candidates
+        .into_iter()
+        .take(max_active_emitters)
+        .map(|(entity, _)| entity)
+        .collect()
+}
+
+/// Result of evaluating an emitter against the receivers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum EmitterOutput {
+    /// There is no receiver to compute an output for
+    NoReceiver,
+    /// The emitter is beyond its maximum distance
+    OutOfRange,
+    /// The emitter can be heard with the given output
+    Audible(SpacialOutput),
+}
+
+/// State of spacial audio between frames
+#[derive(Resource, Default)]
+pub(crate) struct SpacialAudioState {
+    /// Instances that were paused by [`CullMode::Pause`]
+    pub(crate) culled: HashSet<AssetId<AudioInstance>>,
+    /// Emitters selected by [`SpacialAudio::max_active_emitters`] in the last update
+    pub(crate) active_emitters: HashSet<Entity>,
+    /// The last output sent to each instance
+    pub(crate) applied: HashMap<AssetId<AudioInstance>, SpacialOutput>,
+    /// Time since the last update for [`SpacialAudio::update_interval`]
+    pub(crate) since_last_update: Duration,
+    /// Number of commands sent to instances in the last update
+    pub(crate) commands: usize,
+    /// The [`ReverbZone`]s of the last update, emitters outside of them send no ambient signal
+    pub(crate) reverb_zones: Vec<(ZoneExtent, GlobalTransform)>,
+    /// The ways of emitters around occluders, see [`AudioOccluder`](crate::AudioOccluder)
+    #[cfg(feature = "propagation")]
+    pub(crate) sound_paths: HashMap<Entity, propagation::SoundPath>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn run_spacial_audio(
+    spacial_audio: Res<SpacialAudio>,
+    mut state: ResMut<SpacialAudioState>,
+    time: Res<Time>,
+    mut warned_about_multiple_receivers: Local<bool>,
+    receivers: Query<(&GlobalTransform, &AudioReceiver, Has<PrimaryReceiver>)>,
+    emitters: Query<SpacialEmitter, With<AudioEmitter>>,
+    reverb_zones: Query<(&ReverbZone, &GlobalTransform)>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    if let Some(update_interval) = spacial_audio.update_interval {
+        state.since_last_update += time.delta();
+        if state.since_last_update < update_interval {
+            state.commands = 0;
+            return;
+        }
+        state.since_last_update = Duration::ZERO;
+    }
+    let receivers: Vec<_> = receivers.iter().collect();
+    if !*warned_about_multiple_receivers
+        && spacial_audio.receiver_selection == ReceiverSelection::Nearest
+        && receivers.len() > 1
+        && !receivers.iter().any(|(_, _, primary)| *primary)
+    {
+        warn!("There are multiple audio receivers. Spacial audio will use the receiver closest to each emitter. Add `PrimaryReceiver` to one of them to pick it explicitly.");
+        *warned_about_multiple_receivers = true;
+    }
+    let state = state.as_mut();
+    state.culled.retain(|id| audio_instances.contains(*id));
+    state.applied.retain(|id, _| audio_instances.contains(*id));
+    state.reverb_zones = reverb_zones
+        .iter()
+        .map(|(zone, transform)| (zone.extent, *transform))
+        .collect();
+    state.commands = spacial_audio.update(&receivers, &emitters, &mut audio_instances, state);
+}
+
+pub(crate) fn spacial_audio_enabled(spacial_audio: Option<Res<SpacialAudio>>) -> bool {
+    spacial_audio.map_or(false, |spacial_audio| spacial_audio.enabled)
+}
+
+pub(crate) fn reset_disabled_spacial_audio(
+    spacial_audio: Res<SpacialAudio>,
+    mut was_enabled: Local<Option<bool>>,
+    mut state: ResMut<SpacialAudioState>,
+    emitters: Query<&AudioEmitter>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    let enabled = spacial_audio.enabled;
+    if was_enabled.replace(enabled) == Some(true) && !enabled {
+        state.applied.clear();
+        for emitter in emitters.iter() {
+            for handle in emitter.instances.iter() {
+                if let Some(instance) = audio_instances.get_mut(handle) {
+                    if !spacial_audio.controls(instance) {
+                        spacial_audio.release(instance);
+                        continue;
+                    }
+                    instance.started_silent = false;
+                    if state.culled.remove(&handle.id()) && std::mem::take(&mut instance.culled) {
+                        instance.resume(spacial_audio.cull_resume_tween.clone());
+                    }
+                    instance.set_spacial_volume(1., 0., spacial_audio.effective_volume_tween());
+                    instance.set_panning(0.5, spacial_audio.effective_panning_tween());
+                    if instance.doppler != 1. {
+                        instance.set_doppler(1., spacial_audio.update_tween.clone());
+                    }
+                    instance.set_interaural_delay(0.);
+                    if instance.has_filter() {
+                        instance.set_filter_cutoff(
+                            UNFILTERED_CUTOFF as f64,
+                            spacial_audio.update_tween.clone(),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) type SpacialEmitter<'a> = (
+    Entity,
+    &'a GlobalTransform,
+    &'a AudioEmitter,
+    Option<&'a OcclusionFactor>,
+);
+
+type DopplerReceiver<'a> = (
+    Entity,
+    &'a GlobalTransform,
+    Option<&'a SpacialVelocity>,
+    Has<PrimaryReceiver>,
+);
+
+pub(crate) fn run_doppler_effect(
+    spacial_audio: Res<SpacialAudio>,
+    time: Res<Time>,
+    mut previous_translations: Local<HashMap<Entity, Vec3>>,
+    receivers: Query<DopplerReceiver, With<AudioReceiver>>,
+    emitters: Query<(
+        Entity,
+        &GlobalTransform,
+        &AudioEmitter,
+        Option<&SpacialVelocity>,
+    )>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    let Some(doppler) = spacial_audio.doppler else {
+        if !previous_translations.is_empty() {
+            previous_translations.clear();
+            for emitter in emitters.iter().map(|(_, _, emitter, _)| emitter) {
+                for instance in emitter.instances.iter() {
+                    if let Some(instance) = audio_instances.get_mut(instance) {
+                        if instance.doppler != 1. {
+                            instance.set_doppler(1., spacial_audio.update_tween.clone());
+                        }
+                    }
+                }
+            }
+        }
+        return;
+    };
+    let delta = time.delta_seconds();
+    let velocity = |entity: Entity, translation: Vec3, velocity: Option<&SpacialVelocity>| {
+        if let Some(velocity) = velocity {
+            return spacial_audio.mode.project(velocity.0);
+        }
+        match previous_translations.get(&entity) {
+            Some(previous) if delta > 0. => (translation - *previous) / delta,
+            _ => Vec3::ZERO,
+        }
+    };
+    let mut translations = HashMap::default();
+
+    let receivers: Vec<_> = receivers
+        .iter()
+        .map(|(entity, transform, receiver_velocity, primary)| {
+            let translation = spacial_audio.mode.project(transform.translation());
+            translations.insert(entity, translation);
+            (
+                translation,
+                velocity(entity, translation, receiver_velocity),
+                primary,
+            )
+        })
+        .collect();
+
+    for (entity, emitter_transform, emitter, emitter_velocity) in emitters.iter() {
+        let emitter_translation = spacial_audio.mode.project(emitter_transform.translation());
+        translations.insert(entity, emitter_translation);
+        let Some(receiver_index) = spacial_audio.receiver_selection.select(
+            receivers
+                .iter()
+                .map(|(translation, _, primary)| (*translation, *primary)),
+            emitter_translation,
+        ) else {
+            continue;
+        };
+        let (receiver_translation, receiver_velocity, _) = receivers[receiver_index];
+        let playback_rate = doppler.playback_rate(
+            emitter_translation - receiver_translation,
+            receiver_velocity,
+            velocity(entity, emitter_translation, emitter_velocity),
+        );
+
+        for instance in emitter.instances.iter() {
+            if let Some(instance) = audio_instances.get_mut(instance) {
+                if !spacial_audio.controls(instance) {
+                    continue;
+                }
+                instance.set_doppler(playback_rate, spacial_audio.update_tween.clone());
+            }
+        }
+    }
+
+    *previous_translations = translations;
+}
+
+/// Number of frames an instance handle may point to a missing asset before it is dropped
+///
+/// Frames in which the play command of the instance still waits for its audio source don't
+/// count, so only handles of sounds that failed to play are dropped.
+pub(crate) const MISSING_INSTANCE_GRACE_FRAMES: u32 = 3;
+
+/// Tracks which instance assets existed and for how long others have been missing
+#[derive(Default)]
+pub(crate) struct InstanceAvailability {
+    seen: HashSet<AssetId<AudioInstance>>,
+    missing_frames: HashMap<AssetId<AudioInstance>, u32>,
+}
+
+pub(crate) fn cleanup_stopped_spacial_instances(
+    mut availability: Local<InstanceAvailability>,
+    mut emitters: Query<&mut AudioEmitter>,
+    instances: Res<Assets<AudioInstance>>,
+    audio_output: Option<NonSend<AudioOutput>>,
+) {
+    let mut seen = HashSet::default();
+    let mut missing_frames = HashMap::default();
+    for mut emitter in emitters.iter_mut() {
+        // Only emitters that lose handles are changed, so the others keep their change ticks
+        let keep: Vec<bool> = emitter
+            .instances
+            .iter()
+            .map(|handle| {
+                let id = handle.id();
+                if let Some(instance) = instances.get(handle) {
+                    seen.insert(id);
+                    return instance.handle.state() != kira::sound::PlaybackState::Stopped;
+                }
+                // The asset was removed after it existed
+                if availability.seen.contains(&id) {
+                    return false;
+                }
+                if audio_output
+                    .as_ref()
+                    .map_or(false, |audio_output| audio_output.is_play_pending(id))
+                {
+                    return true;
+                }
+                let frames = availability.missing_frames.get(&id).copied().unwrap_or(0) + 1;
+                if frames > MISSING_INSTANCE_GRACE_FRAMES {
+                    return false;
+                }
+                missing_frames.insert(id, frames);
+                true
+            })
+            .collect();
+        if keep.contains(&false) {
+            let mut keep = keep.into_iter();
+            emitter.instances.retain(|_| keep.next().unwrap_or(true));
+        }
+    }
+    *availability = InstanceAvailability {
+        seen,
+        missing_frames,
+    };
+}
+
+/// Instances that are on more than one emitter together with the first two emitters they are on
+#[cfg(debug_assertions)]
+pub(crate) fn shared_instances<'a>(
+    emitters: impl IntoIterator<Item = (Entity, &'a AudioEmitter)>,
+) -> Vec<(AssetId<AudioInstance>, Entity, Entity)> {
+    let mut owners = HashMap::<AssetId<AudioInstance>, Entity>::default();
+    let mut shared = vec![];
+    for (entity, emitter) in emitters {
+        for handle in emitter.instances.iter() {
+            let owner = *owners.entry(handle.id()).or_insert(entity);
+            if owner != entity {
+                shared.push((handle.id(), owner, entity));
+            }
+        }
+    }
+
+    shared
+}
+
+/// Warn once per instance that is on multiple emitters, since their outputs would fight each other
+#[cfg(debug_assertions)]
+pub(crate) fn warn_about_shared_instances(
+    mut warned: Local<HashSet<AssetId<AudioInstance>>>,
+    emitters: Query<(Entity, &AudioEmitter)>,
+) {
+    for (instance, first, second) in shared_instances(emitters.iter()) {
+        if warned.insert(instance) {
+            warn!("The audio instance {instance:?} is on the emitters {first:?} and {second:?}. An instance should only be on one emitter, otherwise its volume and panning flicker between the emitters.");
+        }
+    }
+}
+
+pub(crate) fn stop_removed_emitter_instances(
+    spacial_audio: Res<SpacialAudio>,
+    mut tracked: Local<HashMap<Entity, Vec<Handle<AudioInstance>>>>,
+    emitters: Query<(Entity, &AudioEmitter), Changed<AudioEmitter>>,
+    mut removed: RemovedComponents<AudioEmitter>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    for entity in removed.read() {
+        let Some(handles) = tracked.remove(&entity) else {
+            continue;
+        };
+        for handle in handles.iter() {
+            if let Some(instance) = audio_instances.get_mut(handle) {
+                instance.stop(spacial_audio.despawn_stop_tween.clone());
+            }
+        }
+    }
+    for (entity, emitter) in emitters.iter() {
+        if emitter.keep_playing_on_despawn {
+            tracked.remove(&entity);
+        } else {
+            tracked.insert(entity, emitter.instances.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::audio::AudioCommand;
+    use crate::instance::{SoundHandle, SoundTiming};
+    use crate::modulator::Modulations;
+    use crate::{Audio, MainTrack};
+    use bevy::prelude::{Schedule, Transform, World};
+    use kira::dsp::Frame;
+    use kira::manager::backend::mock::MockBackend;
+    use kira::manager::{AudioManager, AudioManagerSettings};
+    use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+    use kira::Volume;
+    use std::sync::Arc;
+
+    const RANGE: f32 = 4.;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn spacial_audio_settings_round_trip() {
+        let mut spacial_audio = SpacialAudio {
+            max_distance: 40.,
+            attenuation: AttenuationModel::InverseSquare { rolloff: 2. },
+            doppler: Some(Doppler::default()),
+            receiver_selection: ReceiverSelection::Blend,
+            panning_law: PanningLaw::ConstantPower,
+            update_tween: AudioTween::linear(Duration::from_millis(50)),
+            cull_mode: CullMode::Pause,
+            update_interval: Some(Duration::from_millis(100)),
+            mode: SpacialMode::TwoD { half_width: 8. },
+            reverb: Some(SpacialReverb::default()),
+            channels: Some(HashSet::from_iter([Channel::typed::<MainTrack>()])),
+            backend: SpacialBackend::Kira,
+            ..Default::default()
+        };
+        spacial_audio.set_custom_attenuation(|_, _, _| SpacialOutput::default());
+        let loaded: SpacialAudio = ron::from_str(&ron::to_string(&spacial_audio).unwrap()).unwrap();
+        assert_eq!(loaded.max_distance, 40.);
+        assert_eq!(loaded.attenuation, spacial_audio.attenuation);
+        assert_eq!(loaded.doppler, spacial_audio.doppler);
+        assert_eq!(loaded.receiver_selection, ReceiverSelection::Blend);
+        assert_eq!(loaded.panning_law, PanningLaw::ConstantPower);
+        assert_eq!(loaded.update_tween, spacial_audio.update_tween);
+        assert_eq!(loaded.cull_mode, CullMode::Pause);
+        assert_eq!(loaded.update_interval, spacial_audio.update_interval);
+        assert_eq!(loaded.mode, spacial_audio.mode);
+        assert_eq!(loaded.reverb, spacial_audio.reverb);
+        assert_eq!(loaded.backend, SpacialBackend::Kira);
+        assert!(loaded.channels.is_none());
+        assert!(loaded.custom_attenuation.is_none());
+
+        let old: SpacialAudio = ron::from_str("(max_distance: 10.)").unwrap();
+        assert_eq!(old.max_distance, 10.);
+        assert_eq!(old.max_volume, SpacialAudio::default().max_volume);
+        assert_eq!(
+            old.cull_resume_tween,
+            SpacialAudio::default().cull_resume_tween
+        );
+    }
+
+    #[test]
+    fn inverse_keeps_original_falloff() {
+        let model = AttenuationModel::Inverse;
+
+        assert!(model.attenuate(0., RANGE).is_infinite());
+        assert_eq!(model.attenuate(RANGE, RANGE), 16.);
+        assert!(model.attenuate(1000. * RANGE, RANGE) < 0.02);
+    }
+
+    #[test]
+    fn linear_reaches_zero_at_max_distance() {
+        let model = AttenuationModel::Linear { max_distance: 8. };
+
+        assert_eq!(model.attenuate(0., RANGE), 1.);
+        assert_eq!(model.attenuate(RANGE, RANGE), 0.5);
+        assert_eq!(model.attenuate(10. * RANGE, RANGE), 0.);
+    }
+
+    #[test]
+    fn inverse_square_falls_off_relative_to_range() {
+        let model = AttenuationModel::InverseSquare { rolloff: 1. };
+
+        assert_eq!(model.attenuate(0., RANGE), 1.);
+        assert_eq!(model.attenuate(RANGE, RANGE), 0.5);
+        assert!(model.attenuate(100. * RANGE, RANGE) < 0.001);
+    }
+
+    #[test]
+    fn exponential_falls_off_relative_to_range() {
+        let model = AttenuationModel::Exponential { rolloff: 2. };
+
+        assert_eq!(model.attenuate(0., RANGE), 1.);
+        assert!((model.attenuate(RANGE, RANGE) - (-2_f32).exp()).abs() < f32::EPSILON);
+        assert!(model.attenuate(100. * RANGE, RANGE) < 0.001);
+    }
+
+    #[test]
+    fn none_ignores_distance() {
+        let model = AttenuationModel::None;
+
+        assert_eq!(model.attenuate(0., RANGE), 1.);
+        assert_eq!(model.attenuate(RANGE, RANGE), 1.);
+        assert_eq!(model.attenuate(100. * RANGE, RANGE), 1.);
+    }
+
+    #[test]
+    fn zero_range_does_not_produce_nan() {
+        for model in [
+            AttenuationModel::InverseSquare { rolloff: 1. },
+            AttenuationModel::Exponential { rolloff: 1. },
+        ] {
+            assert!(!model.attenuate(0., 0.).is_nan());
+            assert!(!model.attenuate(5., 0.).is_nan());
+        }
+    }
+
+    #[test]
+    fn custom_attenuation_replaces_built_in_computation() {
+        let mut spacial_audio = SpacialAudio::default();
+        let receiver_transform = GlobalTransform::default();
+        let emitter_transform = GlobalTransform::from(Transform::from_xyz(3., 0., 0.));
+        let emitter = AudioEmitter {
+            range: 1.,
+            ..Default::default()
+        };
+        let receiver = AudioReceiver::default();
+
+        let built_in =
+            spacial_audio.output(&receiver_transform, &receiver, &emitter_transform, &emitter);
+        spacial_audio.set_custom_attenuation(|_, _, _| SpacialOutput {
+            volume: 0.25,
+            panning: 0.1,
+            ..Default::default()
+        });
+        assert_eq!(
+            spacial_audio.output(&receiver_transform, &receiver, &emitter_transform, &emitter),
+            SpacialOutput {
+                volume: 0.25,
+                panning: 0.1,
+                ..Default::default()
+            }
+        );
+
+        spacial_audio.clear_custom_attenuation();
+        assert_eq!(
+            spacial_audio.output(&receiver_transform, &receiver, &emitter_transform, &emitter),
+            built_in
+        );
+    }
+
+    #[test]
+    fn doppler_raises_pitch_of_approaching_emitter() {
+        let doppler = Doppler::default();
+        let sound_path = Vec3::new(10., 0., 0.);
+
+        assert_eq!(
+            doppler.playback_rate(sound_path, Vec3::ZERO, Vec3::ZERO),
+            1.
+        );
+        assert!(doppler.playback_rate(sound_path, Vec3::ZERO, Vec3::new(-30., 0., 0.)) > 1.);
+        assert!(doppler.playback_rate(sound_path, Vec3::ZERO, Vec3::new(30., 0., 0.)) < 1.);
+        assert!(doppler.playback_rate(sound_path, Vec3::new(30., 0., 0.), Vec3::ZERO) > 1.);
+    }
+
+    #[test]
+    fn doppler_clamps_playback_rate() {
+        let doppler = Doppler::default();
+        let sound_path = Vec3::new(10., 0., 0.);
+
+        assert_eq!(
+            doppler.playback_rate(sound_path, Vec3::ZERO, Vec3::new(-1e6, 0., 0.)),
+            doppler.max_playback_rate
+        );
+        assert_eq!(
+            doppler.playback_rate(sound_path, Vec3::ZERO, Vec3::new(1e6, 0., 0.)),
+            doppler.min_playback_rate
+        );
+    }
+
+    #[test]
+    fn selects_nearest_receiver() {
+        let receivers = [
+            (Vec3::new(10., 0., 0.), false),
+            (Vec3::new(-1., 0., 0.), false),
+        ];
+
+        assert_eq!(
+            ReceiverSelection::Nearest.select(receivers.into_iter(), Vec3::ZERO),
+            Some(1)
+        );
+        assert_eq!(
+            ReceiverSelection::PrimaryOnly.select(receivers.into_iter(), Vec3::ZERO),
+            None
+        );
+    }
+
+    #[test]
+    fn prefers_primary_receiver() {
+        let receivers = [
+            (Vec3::new(10., 0., 0.), true),
+            (Vec3::new(-1., 0., 0.), false),
+        ];
+
+        assert_eq!(
+            ReceiverSelection::Nearest.select(receivers.into_iter(), Vec3::ZERO),
+            Some(0)
+        );
+        assert_eq!(
+            ReceiverSelection::PrimaryOnly.select(receivers.into_iter(), Vec3::ZERO),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn blends_outputs_weighted_by_distance() {
+        let near = SpacialOutput {
+            volume: 1.,
+            panning: 0.,
+            low_pass_cutoff: Some(1000.),
+            ambient: 0.,
+            interaural_delay: 0.,
+        };
+        let far = SpacialOutput {
+            volume: 0.,
+            panning: 1.,
+            low_pass_cutoff: None,
+            ambient: 0.,
+            interaural_delay: 0.,
+        };
+
+        let blended = SpacialOutput::blend([(near, 1.), (far, 3.)]).unwrap();
+        assert!((blended.volume - 0.75).abs() < 1e-6);
+        assert!((blended.panning - 0.25).abs() < 1e-6);
+        assert_eq!(blended.low_pass_cutoff, Some(1000.));
+
+        let equal = SpacialOutput::blend([(near, 2.), (far, 2.)]).unwrap();
+        assert!((equal.volume - 0.5).abs() < 1e-6);
+        assert!(SpacialOutput::blend([]).is_none());
+    }
+
+    #[test]
+    fn cone_interpolates_between_inner_and_outer_angle() {
+        let cone = EmitterCone {
+            inner_angle: 0.5,
+            outer_angle: 1.5,
+            outer_gain: 0.2,
+        };
+
+        assert_eq!(cone.gain(0.), 1.);
+        assert_eq!(cone.gain(0.5), 1.);
+        assert!((cone.gain(1.) - 0.6).abs() < 1e-6);
+        assert_eq!(cone.gain(1.5), 0.2);
+        assert_eq!(cone.gain(std::f32::consts::PI), 0.2);
+    }
+
+    #[test]
+    fn cone_is_louder_in_front_of_emitter() {
+        let spacial_audio = SpacialAudio::default();
+        let receiver = AudioReceiver::default();
+        let emitter = AudioEmitter {
+            range: 1.,
+            cone: Some(EmitterCone {
+                inner_angle: 0.5,
+                outer_angle: 1.5,
+                outer_gain: 0.2,
+            }),
+            ..Default::default()
+        };
+        // the emitter looks along -Z
+        let emitter_transform = GlobalTransform::default();
+        let in_front = GlobalTransform::from(Transform::from_xyz(0., 0., -2.));
+        let behind = GlobalTransform::from(Transform::from_xyz(0., 0., 2.));
+
+        let front_volume = spacial_audio
+            .output(&in_front, &receiver, &emitter_transform, &emitter)
+            .volume;
+        let back_volume = spacial_audio
+            .output(&behind, &receiver, &emitter_transform, &emitter)
+            .volume;
+        assert!((back_volume - front_volume * 0.2).abs() < 1e-5);
+    }
+
+    #[test]
+    fn rear_attenuation_dampens_sounds_behind_the_receiver() {
+        let spacial_audio = SpacialAudio {
+            attenuation: AttenuationModel::None,
+            ..Default::default()
+        };
+        let receiver = AudioReceiver {
+            rear_attenuation: 0.5,
+            ..Default::default()
+        };
+        // Facing away from the receiver must not matter
+        let emitter = AudioEmitter::default();
+        let volume_at = |x: f32, z: f32| {
+            spacial_audio
+                .output(
+                    &GlobalTransform::default(),
+                    &receiver,
+                    &GlobalTransform::from(Transform::from_xyz(x, 0., z)),
+                    &emitter,
+                )
+                .volume
+        };
+
+        assert!((volume_at(0., -2.) - 1.).abs() < 1e-5);
+        assert!((volume_at(2., 0.) - 0.75).abs() < 1e-5);
+        assert!((volume_at(0., 2.) - 0.5).abs() < 1e-5);
+    }
+
+    #[test]
+    fn ear_distance_delays_the_far_ear() {
+        let spacial_audio = SpacialAudio::default();
+        let delay_at = |ear_distance: f32, x: f32, z: f32| {
+            spacial_audio
+                .output(
+                    &GlobalTransform::default(),
+                    &AudioReceiver {
+                        ear_distance,
+                        ..Default::default()
+                    },
+                    &GlobalTransform::from(Transform::from_xyz(x, 0., z)),
+                    &AudioEmitter::default(),
+                )
+                .interaural_delay
+        };
+
+        assert!((delay_at(0.2, 2., 0.) - 0.2 / SPEED_OF_SOUND).abs() < 1e-7);
+        assert!((delay_at(0.2, -2., 0.) + 0.2 / SPEED_OF_SOUND).abs() < 1e-7);
+        assert!(delay_at(0.2, 0., -2.).abs() < 1e-7);
+        assert_eq!(delay_at(0., 2., 0.), 0.);
+    }
+
+    #[test]
+    fn rear_filter_cutoff_muffles_sounds_behind_the_receiver() {
+        let spacial_audio = SpacialAudio::default();
+        let receiver = AudioReceiver {
+            rear_filter_cutoff: Some(1_000.),
+            ..Default::default()
+        };
+        let cutoff_at = |z: f32| {
+            spacial_audio
+                .output(
+                    &GlobalTransform::default(),
+                    &receiver,
+                    &GlobalTransform::from(Transform::from_xyz(0., 0., z)),
+                    &AudioEmitter::default(),
+                )
+                .low_pass_cutoff
+                .unwrap()
+        };
+
+        assert!((cutoff_at(-2.) - UNFILTERED_CUTOFF).abs() < 1e-1);
+        assert!((cutoff_at(2.) - 1_000.).abs() < 1e-1);
+
+        let with_distance_cutoff = SpacialAudio {
+            cutoff_at_max_distance: Some(500.),
+            ..Default::default()
+        }
+        .output(
+            &GlobalTransform::default(),
+            &receiver,
+            &GlobalTransform::from(Transform::from_xyz(0., 0., 24.)),
+            &AudioEmitter::default(),
+        );
+        assert!(with_distance_cutoff.low_pass_cutoff.unwrap() < 1_000.);
+    }
+
+    #[test]
+    fn muffles_with_distance() {
+        assert_eq!(muffle(0., 500.), UNFILTERED_CUTOFF);
+        assert!((muffle(1., 500.) - 500.).abs() < 1e-2);
+        assert!((muffle(4., 500.) - 500.).abs() < 1e-2);
+        let halfway = muffle(0.5, 500.);
+        assert!(halfway < UNFILTERED_CUTOFF && halfway > 500.);
+    }
+
+    #[test]
+    fn no_filtering_by_default() {
+        let spacial_audio = SpacialAudio::default();
+        let output = spacial_audio.output(
+            &GlobalTransform::default(),
+            &AudioReceiver::default(),
+            &GlobalTransform::from(Transform::from_xyz(20., 0., 0.)),
+            &AudioEmitter {
+                range: 1.,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(output.low_pass_cutoff, None);
+    }
+
+    #[test]
+    fn attenuates_sounds_above_receiver() {
+        let mut spacial_audio = SpacialAudio {
+            attenuation: AttenuationModel::None,
+            ..Default::default()
+        };
+        let receiver = AudioReceiver::default();
+        let emitter = AudioEmitter::default();
+        let above = GlobalTransform::from(Transform::from_xyz(0.01, 10., 0.));
+        let beside = GlobalTransform::from(Transform::from_xyz(10., 0., 0.));
+
+        let output = spacial_audio.output(&GlobalTransform::default(), &receiver, &above, &emitter);
+        assert!((output.volume - 1.).abs() < 1e-5);
+        assert!((output.panning - 0.5).abs() < 1e-3);
+
+        spacial_audio.vertical_attenuation = 0.5;
+        let output = spacial_audio.output(&GlobalTransform::default(), &receiver, &above, &emitter);
+        assert!((output.volume - 0.5).abs() < 1e-3);
+        let output =
+            spacial_audio.output(&GlobalTransform::default(), &receiver, &beside, &emitter);
+        assert_eq!(output.volume, 1.);
+        assert_eq!(output.panning, 1.);
+    }
+
+    fn panning_at(law: PanningLaw, translation: Vec3) -> f32 {
+        SpacialAudio {
+            panning_law: law,
+            ..Default::default()
+        }
+        .output(
+            &GlobalTransform::default(),
+            &AudioReceiver::default(),
+            &GlobalTransform::from(Transform::from_translation(translation)),
+            &AudioEmitter::default(),
+        )
+        .panning
+    }
+
+    #[test]
+    fn panning_laws_at_boundary_angles() {
+        let left = Vec3::new(-5., 0., 0.);
+        let right = Vec3::new(5., 0., 0.);
+        let front = Vec3::new(0., 0., -5.);
+        let behind = Vec3::new(0., 0., 5.);
+
+        for law in [PanningLaw::Linear, PanningLaw::ConstantPower] {
+            assert!(panning_at(law, left).abs() < 1e-6);
+            assert!((panning_at(law, right) - 1.).abs() < 1e-6);
+            assert!((panning_at(law, front) - 0.5).abs() < 1e-6);
+            assert!((panning_at(law, behind) - 0.5).abs() < 1e-6);
+        }
+        for translation in [left, right, front, behind] {
+            assert_eq!(panning_at(PanningLaw::Off, translation), 0.5);
+        }
+    }
+
+    #[test]
+    fn constant_power_law_results_in_sine_cosine_gains() {
+        let panning = PanningLaw::ConstantPower.pan(0.5);
+        let theta = std::f32::consts::FRAC_PI_4 * 1.5;
+
+        // kira applies square root gains to the panning value
+        assert!(((1. - panning).sqrt() - theta.cos()).abs() < 1e-6);
+        assert!((panning.sqrt() - theta.sin()).abs() < 1e-6);
+        assert!(panning > PanningLaw::Linear.pan(0.5));
+    }
+
+    #[test]
+    fn tweens_fall_back_to_update_tween() {
+        let mut spacial_audio = SpacialAudio::default();
+        let default_tween = kira::tween::Tween::from(AudioTween::default());
+        assert_eq!(
+            kira::tween::Tween::from(spacial_audio.effective_volume_tween()),
+            default_tween
+        );
+        assert_eq!(
+            kira::tween::Tween::from(spacial_audio.effective_panning_tween()),
+            default_tween
+        );
+
+        spacial_audio.update_tween = AudioTween::linear(std::time::Duration::from_millis(50));
+        spacial_audio.panning_tween = Some(AudioTween::linear(std::time::Duration::from_millis(5)));
+        assert_eq!(
+            kira::tween::Tween::from(spacial_audio.effective_volume_tween()).duration,
+            std::time::Duration::from_millis(50)
+        );
+        assert_eq!(
+            kira::tween::Tween::from(spacial_audio.effective_panning_tween()).duration,
+            std::time::Duration::from_millis(5)
+        );
+    }
+
+    #[test]
+    fn emitter_at_receiver_position_produces_sane_output() {
+        let receiver_transform = GlobalTransform::from(Transform::from_xyz(1., 2., 3.));
+        let emitters = [
+            AudioEmitter {
+                range: 1.,
+                ..Default::default()
+            },
+            AudioEmitter {
+                range: 1.,
+                self_occlusion: 1.,
+                cone: Some(EmitterCone {
+                    inner_angle: 0.5,
+                    outer_angle: 1.,
+                    outer_gain: 0.,
+                }),
+                ..Default::default()
+            },
+            AudioEmitter::default(),
+        ];
+        let receiver = AudioReceiver {
+            rear_attenuation: 1.,
+            ..Default::default()
+        };
+        for attenuation in [
+            AttenuationModel::Inverse,
+            AttenuationModel::Linear { max_distance: 10. },
+            AttenuationModel::InverseSquare { rolloff: 1. },
+            AttenuationModel::Exponential { rolloff: 1. },
+            AttenuationModel::None,
+        ] {
+            for min_distance in [0., 0.5] {
+                let spacial_audio = SpacialAudio {
+                    attenuation,
+                    min_distance,
+                    vertical_attenuation: 1.,
+                    cutoff_at_max_distance: Some(500.),
+                    ..Default::default()
+                };
+                for emitter in emitters.iter() {
+                    let output = spacial_audio.output(
+                        &receiver_transform,
+                        &receiver,
+                        &receiver_transform,
+                        emitter,
+                    );
+                    assert!(output.volume.is_finite() && output.volume <= 1e3);
+                    assert_eq!(output.panning, 0.5);
+                    assert!(output.low_pass_cutoff.unwrap().is_finite());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn volume_is_held_within_min_distance() {
+        let spacial_audio = SpacialAudio {
+            attenuation: AttenuationModel::Linear { max_distance: 10. },
+            min_distance: 2.,
+            ..Default::default()
+        };
+        let emitter = AudioEmitter::default();
+        let output_at = |x: f32| {
+            spacial_audio.output(
+                &GlobalTransform::default(),
+                &AudioReceiver::default(),
+                &GlobalTransform::from(Transform::from_xyz(x, 0., 0.)),
+                &emitter,
+            )
+        };
+
+        assert!((output_at(0.).volume - 0.8).abs() < 1e-6);
+        assert!((output_at(1.).volume - 0.8).abs() < 1e-6);
+        assert_eq!(output_at(1.).panning, 0.5);
+        assert!((output_at(5.).volume - 0.5).abs() < 1e-6);
+        assert_eq!(output_at(5.).panning, 1.);
+    }
+
+    #[test]
+    fn volume_is_clamped_to_ceiling() {
+        let spacial_audio = SpacialAudio {
+            max_volume: 2.,
+            ..Default::default()
+        };
+        let output = spacial_audio.output(
+            &GlobalTransform::default(),
+            &AudioReceiver::default(),
+            &GlobalTransform::from(Transform::from_xyz(0.1, 0., 0.)),
+            &AudioEmitter {
+                range: 10.,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(output.volume, 2.);
+    }
+
+    #[test]
+    fn emitters_are_silent_beyond_max_distance() {
+        let spacial_audio = SpacialAudio {
+            attenuation: AttenuationModel::None,
+            max_distance: 10.,
+            ..Default::default()
+        };
+        let output_at = |x: f32, emitter: &AudioEmitter| {
+            spacial_audio
+                .output(
+                    &GlobalTransform::default(),
+                    &AudioReceiver::default(),
+                    &GlobalTransform::from(Transform::from_xyz(x, 0., 0.)),
+                    emitter,
+                )
+                .volume
+        };
+        let emitter = AudioEmitter::default();
+
+        assert_eq!(output_at(5., &emitter), 1.);
+        assert_eq!(output_at(10., &emitter), 0.);
+        assert_eq!(output_at(30., &emitter), 0.);
+        let near_edge = output_at(9.5, &emitter);
+        assert!(near_edge > 0. && near_edge < 1.);
+
+        let short_range = AudioEmitter {
+            max_distance: Some(4.),
+            ..Default::default()
+        };
+        assert_eq!(output_at(3., &short_range), 1.);
+        assert_eq!(output_at(5., &short_range), 0.);
+    }
+
+    #[test]
+    fn custom_attenuation_is_not_cut_at_max_distance() {
+        let mut spacial_audio = SpacialAudio {
+            max_distance: 10.,
+            ..Default::default()
+        };
+        spacial_audio.set_custom_attenuation(|_, _, _| SpacialOutput::default());
+        let receiver_transform = GlobalTransform::default();
+        let receiver = AudioReceiver::default();
+        let emitter_transform = GlobalTransform::from(Transform::from_xyz(20., 0., 0.));
+        let emitter = AudioEmitter::default();
+
+        for distance in [9.5, 20.] {
+            let emitter_transform = GlobalTransform::from(Transform::from_xyz(distance, 0., 0.));
+            let output =
+                spacial_audio.output(&receiver_transform, &receiver, &emitter_transform, &emitter);
+            assert_eq!(output, SpacialOutput::default());
+        }
+        assert_eq!(
+            spacial_audio.emitter_output(
+                &[(&receiver_transform, &receiver, false)],
+                &emitter_transform,
+                &emitter,
+                None,
+            ),
+            EmitterOutput::Audible(SpacialOutput::default())
+        );
+    }
+
+    #[test]
+    fn edge_fade_is_monotonic() {
+        let mut previous = edge_fade(0., 10.);
+        assert_eq!(previous, 1.);
+        for step in 1..=100 {
+            let fade = edge_fade(step as f32 * 0.1, 10.);
+            assert!(fade <= previous);
+            previous = fade;
+        }
+        assert_eq!(previous, 0.);
+    }
+
+    #[test]
+    fn playing_on_an_emitter_registers_a_silent_instance() {
+        let audio = Audio::default();
+        let mut emitter = AudioEmitter::default();
+        let handle = emitter
+            .play(&audio, Handle::default())
+            .looped()
+            .with_volume(0.5)
+            .handle();
+
+        assert_eq!(emitter.instances, vec![handle]);
+        let commands = audio.commands.read();
+        match commands.front() {
+            Some(AudioCommand::Play(settings)) => {
+                assert!(settings.settings.start_silent);
+                assert_eq!(settings.settings.volume, Some(Volume::Amplitude(0.5)));
+                assert_eq!(settings.settings.loop_start, Some(0.));
+            }
+            _ => panic!("Expected a play command"),
+        }
+    }
+
+    /// A playing instance of the main track as the plugin would create it
+    fn instance(handle: SoundHandle) -> AudioInstance {
+        AudioInstance {
+            handle,
+            track: None,
+            base_volume: 1.,
+            spacial_volume: 1.,
+            volume: Volume::Amplitude(1.),
+            muted: false,
+            duck: 1.,
+            gain: 1.,
+            channel: Channel::typed::<MainTrack>(),
+            started_silent: false,
+            in_kira_scene: false,
+            on_output_device: false,
+            scheduled_resume: None,
+            stop_requested: false,
+            playback_rate: 1.,
+            panning: 0.5,
+            modulations: Modulations::default(),
+            time_scale: 1.,
+            doppler: 1.,
+            culled: false,
+            timing: SoundTiming::default(),
+            fading_until: None,
+            resume_tween: None,
+            seek_target: None,
+        }
+    }
+
+    fn cleanup_schedule() -> (World, Schedule) {
+        let mut world = World::new();
+        world.init_resource::<Assets<AudioInstance>>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(cleanup_stopped_spacial_instances);
+
+        (world, schedule)
+    }
+
+    #[test]
+    fn handles_of_removed_instances_are_dropped() {
+        let (mut world, mut schedule) = cleanup_schedule();
+        let mut manager =
+            AudioManager::new(AudioManagerSettings::<MockBackend>::default()).unwrap();
+        let sound = manager
+            .play(StaticSoundData {
+                sample_rate: 44_100,
+                frames: Arc::from(vec![Frame::ZERO; 100]),
+                settings: StaticSoundSettings::default(),
+            })
+            .unwrap();
+        let handle = world
+            .resource_mut::<Assets<AudioInstance>>()
+            .add(instance(SoundHandle::Static(sound)));
+        let emitter = world
+            .spawn(AudioEmitter {
+                instances: vec![handle.clone()],
+                ..Default::default()
+            })
+            .id();
+
+        schedule.run(&mut world);
+        assert_eq!(
+            world.get::<AudioEmitter>(emitter).unwrap().instances.len(),
+            1
+        );
+
+        world
+            .resource_mut::<Assets<AudioInstance>>()
+            .remove(&handle);
+        schedule.run(&mut world);
+        assert!(world
+            .get::<AudioEmitter>(emitter)
+            .unwrap()
+            .instances
+            .is_empty());
+    }
+
+    fn spacial_schedule() -> (World, Schedule, Entity) {
+        let mut world = World::new();
+        world.insert_resource(SpacialAudio::default());
+        world.init_resource::<SpacialAudioState>();
+        world.init_resource::<Time>();
+        world.init_resource::<Assets<AudioInstance>>();
+        let mut manager =
+            AudioManager::new(AudioManagerSettings::<MockBackend>::default()).unwrap();
+        let sound = manager
+            .play(StaticSoundData {
+                sample_rate: 44_100,
+                frames: Arc::from(vec![Frame::ZERO; 100]),
+                settings: StaticSoundSettings::default(),
+            })
+            .unwrap();
+        let handle = world
+            .resource_mut::<Assets<AudioInstance>>()
+            .add(instance(SoundHandle::Static(sound)));
+        world.spawn((GlobalTransform::default(), AudioReceiver::default()));
+        let emitter = world
+            .spawn((
+                GlobalTransform::from_translation(Vec3::new(3., 0., 0.)),
+                AudioEmitter {
+                    instances: vec![handle],
+                    ..Default::default()
+                },
+            ))
+            .id();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(run_spacial_audio);
+
+        (world, schedule, emitter)
+    }
+
+    #[test]
+    fn emitters_outside_of_reverb_zones_stay_dry() {
+        let (mut world, mut schedule, emitter) = spacial_schedule();
+        world.get_mut::<AudioEmitter>(emitter).unwrap().ambient_send = 0.5;
+        let handle = world.get::<AudioEmitter>(emitter).unwrap().instances[0].id();
+        let ambient =
+            |world: &World| world.resource::<SpacialAudioState>().applied[&handle].ambient;
+        schedule.run(&mut world);
+        assert_eq!(ambient(&world), 0.5);
+
+        let zone = world
+            .spawn((
+                GlobalTransform::default(),
+                ReverbZone::new(ZoneExtent::Sphere { radius: 1. }, SpacialReverb::default()),
+            ))
+            .id();
+        schedule.run(&mut world);
+        assert_eq!(ambient(&world), 0.);
+
+        *world.get_mut::<GlobalTransform>(zone).unwrap() =
+            GlobalTransform::from_translation(Vec3::new(3., 0., 0.));
+        schedule.run(&mut world);
+        assert_eq!(ambient(&world), 0.5);
+    }
+
+    #[cfg(feature = "propagation")]
+    #[test]
+    fn occluded_emitters_come_through_portals() {
+        use crate::spacial::propagation::{propagate_sound, AudioOccluder, AudioPortal};
+        use bevy::prelude::IntoSystemConfigs;
+
+        let (mut world, mut schedule, emitter) = spacial_schedule();
+        schedule.add_systems(propagate_sound.before(run_spacial_audio));
+        world.get_mut::<AudioEmitter>(emitter).unwrap().attenuation =
+            Some(AttenuationModel::Linear { max_distance: 10. });
+        let handle = world.get::<AudioEmitter>(emitter).unwrap().instances[0].id();
+        let output = |world: &World| world.resource::<SpacialAudioState>().applied[&handle];
+        schedule.run(&mut world);
+        let unoccluded = output(&world);
+
+        world.spawn((
+            GlobalTransform::from_translation(Vec3::new(1.5, 0., 0.)),
+            AudioOccluder::cuboid(Vec3::new(0.1, 2., 2.)),
+        ));
+        schedule.run(&mut world);
+        assert_eq!(output(&world).volume, 0.);
+
+        world.spawn((
+            GlobalTransform::from_translation(Vec3::new(1.5, 1.5, 0.)),
+            AudioPortal::default(),
+        ));
+        schedule.run(&mut world);
+        let through_portal = output(&world);
+        assert!(through_portal.volume > 0.);
+        assert!(through_portal.volume < unoccluded.volume);
+    }
+
+    #[test]
+    fn static_scenes_do_not_send_commands() {
+        let (mut world, mut schedule, emitter) = spacial_schedule();
+
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpacialAudioState>().commands, 2);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpacialAudioState>().commands, 0);
+
+        *world.get_mut::<GlobalTransform>(emitter).unwrap() =
+            GlobalTransform::from_translation(Vec3::new(0., 0., 3.));
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpacialAudioState>().commands, 1);
+    }
+
+    #[test]
+    fn instances_on_other_channels_are_not_touched() {
+        struct WorldSounds;
+        let (mut world, mut schedule, emitter) = spacial_schedule();
+        world.resource_mut::<SpacialAudio>().channels =
+            Some(HashSet::from_iter([Channel::typed::<WorldSounds>()]));
+
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpacialAudioState>().commands, 0);
+
+        // Instances played on an emitter only get their volume back
+        let handle = world.get::<AudioEmitter>(emitter).unwrap().instances[0].clone();
+        world
+            .resource_mut::<Assets<AudioInstance>>()
+            .get_mut(&handle)
+            .unwrap()
+            .started_silent = true;
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpacialAudioState>().commands, 1);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpacialAudioState>().commands, 0);
+
+        world.resource_mut::<SpacialAudio>().channels =
+            Some(HashSet::from_iter([Channel::typed::<MainTrack>()]));
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpacialAudioState>().commands, 2);
+    }
+
+    #[test]
+    fn muted_emitters_are_only_muted_once() {
+        let (mut world, mut schedule, emitter) = spacial_schedule();
+        *world.get_mut::<GlobalTransform>(emitter).unwrap() =
+            GlobalTransform::from_translation(Vec3::new(100., 0., 0.));
+
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpacialAudioState>().commands, 1);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpacialAudioState>().commands, 0);
+
+        *world.get_mut::<GlobalTransform>(emitter).unwrap() =
+            GlobalTransform::from_translation(Vec3::new(3., 0., 0.));
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpacialAudioState>().commands, 2);
+    }
+
+    #[test]
+    fn updates_wait_for_the_update_interval() {
+        let (mut world, mut schedule, _) = spacial_schedule();
+        world.resource_mut::<SpacialAudio>().update_interval = Some(Duration::from_millis(100));
+
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_millis(60));
+        world.insert_resource(time);
+        schedule.run(&mut world);
+        assert!(world.resource::<SpacialAudioState>().applied.is_empty());
+
+        time.advance_by(Duration::from_millis(60));
+        world.insert_resource(time);
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<SpacialAudioState>().commands, 2);
+    }
+
+    #[test]
+    fn handles_of_instances_that_never_appear_are_dropped_after_grace_period() {
+        let (mut world, mut schedule) = cleanup_schedule();
+        let emitter = world
+            .spawn(AudioEmitter {
+                instances: vec![Handle::default()],
+                ..Default::default()
+            })
+            .id();
+
+        for _ in 0..MISSING_INSTANCE_GRACE_FRAMES {
+            schedule.run(&mut world);
+        }
+        assert_eq!(
+            world.get::<AudioEmitter>(emitter).unwrap().instances.len(),
+            1
+        );
+
+        schedule.run(&mut world);
+        assert!(world
+            .get::<AudioEmitter>(emitter)
+            .unwrap()
+            .instances
+            .is_empty());
+    }
+
+    #[derive(Resource, Default)]
+    struct ChangedEmitters(usize);
+
+    fn despawn_schedule() -> (World, Schedule, Entity) {
+        use bevy::prelude::IntoSystemConfigs;
+
+        let (mut world, _, emitter) = spacial_schedule();
+        world.init_resource::<ChangedEmitters>();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                cleanup_stopped_spacial_instances,
+                |emitters: Query<(), Changed<AudioEmitter>>,
+                 mut changed: ResMut<ChangedEmitters>| {
+                    changed.0 = emitters.iter().count();
+                },
+                stop_removed_emitter_instances,
+            )
+                .chain(),
+        );
+
+        (world, schedule, emitter)
+    }
+
+    fn stop_requested(world: &World, handle: &Handle<AudioInstance>) -> bool {
+        world
+            .resource::<Assets<AudioInstance>>()
+            .get(handle)
+            .unwrap()
+            .stop_requested
+    }
+
+    #[test]
+    fn despawned_emitters_stop_their_instances() {
+        let (mut world, mut schedule, emitter) = despawn_schedule();
+        let handle = world.get::<AudioEmitter>(emitter).unwrap().instances[0].clone();
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<ChangedEmitters>().0, 1);
+        // Checking the handles of emitters doesn't change them
+        schedule.run(&mut world);
+        assert_eq!(world.resource::<ChangedEmitters>().0, 0);
+        assert!(!stop_requested(&world, &handle));
+
+        world.despawn(emitter);
+        schedule.run(&mut world);
+        assert!(stop_requested(&world, &handle));
+    }
+
+    #[test]
+    fn instances_keep_playing_on_despawn_if_asked_to() {
+        let (mut world, mut schedule, emitter) = despawn_schedule();
+        world
+            .get_mut::<AudioEmitter>(emitter)
+            .unwrap()
+            .keep_playing_on_despawn = true;
+        let handle = world.get::<AudioEmitter>(emitter).unwrap().instances[0].clone();
+        schedule.run(&mut world);
+
+        world.despawn(emitter);
+        schedule.run(&mut world);
+        assert!(!stop_requested(&world, &handle));
+    }
+
+    /// Render the mock backend long enough for the sounds to follow their commands
+    fn render(manager: &mut AudioManager<MockBackend>) {
+        let backend = manager.backend_mut();
+        // The sounds pick up their commands in the first batch and publish their state in the next
+        for _ in 0..2 {
+            backend.on_start_processing();
+            for _ in 0..50 {
+                backend.process();
+            }
+        }
+    }
+
+    /// An emitter in range of the receiver with instances of sounds playing on the manager
+    fn rendered_world(
+        count: usize,
+    ) -> (
+        World,
+        AudioManager<MockBackend>,
+        Vec<Handle<AudioInstance>>,
+        Entity,
+    ) {
+        use kira::manager::backend::mock::MockBackendSettings;
+
+        let mut manager = AudioManager::new(AudioManagerSettings::<MockBackend> {
+            backend_settings: MockBackendSettings { sample_rate: 1_000 },
+            ..Default::default()
+        })
+        .unwrap();
+        let mut world = World::new();
+        world.insert_resource(SpacialAudio {
+            cull_mode: CullMode::Pause,
+            ..Default::default()
+        });
+        world.init_resource::<SpacialAudioState>();
+        world.init_resource::<Time>();
+        world.init_resource::<Assets<AudioInstance>>();
+        let instances: Vec<Handle<AudioInstance>> = (0..count)
+            .map(|_| {
+                let sound = manager
+                    .play(StaticSoundData {
+                        sample_rate: 1_000,
+                        frames: Arc::from(vec![Frame::ZERO; 10_000]),
+                        settings: StaticSoundSettings::default(),
+                    })
+                    .unwrap();
+                world
+                    .resource_mut::<Assets<AudioInstance>>()
+                    .add(instance(SoundHandle::Static(sound)))
+            })
+            .collect();
+        world.spawn((GlobalTransform::default(), AudioReceiver::default()));
+        let emitter = world
+            .spawn((
+                GlobalTransform::from_translation(Vec3::new(3., 0., 0.)),
+                AudioEmitter {
+                    instances: instances.clone(),
+                    ..Default::default()
+                },
+            ))
+            .id();
+
+        (world, manager, instances, emitter)
+    }
+
+    fn move_emitter(world: &mut World, emitter: Entity, x: f32) {
+        *world.get_mut::<GlobalTransform>(emitter).unwrap() =
+            GlobalTransform::from_translation(Vec3::new(x, 0., 0.));
+    }
+
+    fn instance_of<'a>(world: &'a World, handle: &Handle<AudioInstance>) -> &'a AudioInstance {
+        world
+            .resource::<Assets<AudioInstance>>()
+            .get(handle)
+            .unwrap()
+    }
+
+    #[test]
+    fn only_culled_instances_resume_in_range() {
+        let (mut world, mut manager, instances, emitter) = rendered_world(2);
+        let mut schedule = Schedule::default();
+        schedule.add_systems(run_spacial_audio);
+        let paused = |world: &World, index: usize| {
+            matches!(
+                instance_of(world, &instances[index]).state(),
+                PlaybackState::Paused { .. }
+            )
+        };
+
+        move_emitter(&mut world, emitter, 100.);
+        schedule.run(&mut world);
+        render(&mut manager);
+        assert!(paused(&world, 0));
+        assert!(paused(&world, 1));
+
+        // Paused by hand while culled
+        world
+            .resource_mut::<Assets<AudioInstance>>()
+            .get_mut(&instances[1])
+            .unwrap()
+            .pause(AudioTween::default());
+        move_emitter(&mut world, emitter, 3.);
+        schedule.run(&mut world);
+        render(&mut manager);
+        assert!(matches!(
+            instance_of(&world, &instances[0]).state(),
+            PlaybackState::Playing { .. }
+        ));
+        assert!(paused(&world, 1));
+    }
+
+    #[test]
+    fn disabling_spacial_audio_resets_instances_once() {
+        use bevy::prelude::IntoSystemConfigs;
+
+        let (mut world, mut manager, instances, emitter) = rendered_world(2);
+        let mut schedule = Schedule::default();
+        schedule.add_systems(
+            (
+                reset_disabled_spacial_audio,
+                run_spacial_audio.run_if(spacial_audio_enabled),
+            )
+                .chain(),
+        );
+        schedule.run(&mut world);
+        let spacial = instance_of(&world, &instances[0]);
+        assert!(spacial.spacial_volume < 1.);
+        assert!(spacial.panning > 0.5);
+        // Only the second instance plays on out of range
+        world.get_mut::<AudioEmitter>(emitter).unwrap().instances = vec![instances[1].clone()];
+        move_emitter(&mut world, emitter, 100.);
+        schedule.run(&mut world);
+        render(&mut manager);
+        assert!(matches!(
+            instance_of(&world, &instances[1]).state(),
+            PlaybackState::Paused { .. }
+        ));
+        world.get_mut::<AudioEmitter>(emitter).unwrap().instances = instances.clone();
+
+        world.resource_mut::<SpacialAudio>().enabled = false;
+        schedule.run(&mut world);
+        render(&mut manager);
+        for handle in instances.iter() {
+            let instance = instance_of(&world, handle);
+            assert_eq!(instance.spacial_volume, 1.);
+            assert_eq!(instance.panning, 0.5);
+        }
+        assert!(matches!(
+            instance_of(&world, &instances[1]).state(),
+            PlaybackState::Playing { .. }
+        ));
+
+        // Later changes of the instances are left alone
+        let mut audio_instances = world.resource_mut::<Assets<AudioInstance>>();
+        let instance = audio_instances.get_mut(&instances[0]).unwrap();
+        instance.set_panning(0.2, AudioTween::default());
+        instance.set_spacial_volume(0.3, 0., AudioTween::default());
+        schedule.run(&mut world);
+        let instance = instance_of(&world, &instances[0]);
+        assert_eq!(instance.panning, 0.2);
+        assert_eq!(instance.spacial_volume, 0.3);
+    }
+
+    #[test]
+    fn doppler_keeps_the_playback_rate_of_instances() {
+        let (mut world, mut schedule, emitter) = spacial_schedule();
+        schedule.add_systems(run_doppler_effect);
+        world.resource_mut::<SpacialAudio>().doppler = Some(Doppler::default());
+        world
+            .entity_mut(emitter)
+            .insert(SpacialVelocity(Vec3::new(-10., 0., 0.)));
+        let handle = world.get::<AudioEmitter>(emitter).unwrap().instances[0].clone();
+        world
+            .resource_mut::<Assets<AudioInstance>>()
+            .get_mut(&handle)
+            .unwrap()
+            .set_playback_rate(1.5, AudioTween::default());
+
+        schedule.run(&mut world);
+        let instance = world
+            .resource::<Assets<AudioInstance>>()
+            .get(&handle)
+            .unwrap();
+        assert_eq!(instance.playback_rate, 1.5);
+        assert!(instance.doppler > 1.);
+        assert_eq!(instance.scaled_playback_rate(), 1.5 * instance.doppler);
+
+        // Without the Doppler effect, only its factor is reset
+        world.resource_mut::<SpacialAudio>().doppler = None;
+        schedule.run(&mut world);
+        let instance = world
+            .resource::<Assets<AudioInstance>>()
+            .get(&handle)
+            .unwrap();
+        assert_eq!(instance.doppler, 1.);
+        assert_eq!(instance.playback_rate, 1.5);
+    }
+
+    #[test]
+    fn handles_are_kept_while_their_source_is_loading() {
+        use crate::{AudioPlugin, AudioSource};
+        use bevy::asset::AssetPlugin;
+        use bevy::prelude::{App, MinimalPlugins};
+        use bevy::utils::Uuid;
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            AudioPlugin::default(),
+        ))
+        .insert_resource(SpacialAudio::default());
+        let source = AssetId::<AudioSource>::Uuid {
+            uuid: Uuid::from_u128(1),
+        };
+        let mut emitter = AudioEmitter::default();
+        let instance = emitter
+            .play(app.world.resource::<Audio>(), Handle::Weak(source))
+            .handle();
+        let emitter = app.world.spawn((GlobalTransform::default(), emitter)).id();
+
+        for _ in 0..MISSING_INSTANCE_GRACE_FRAMES + 10 {
+            app.update();
+        }
+        app.world.resource_mut::<Assets<AudioSource>>().insert(
+            source,
+            AudioSource::from_frames(1_000, vec![[0.; 2]; 1_000]).unwrap(),
+        );
+        app.update();
+
+        assert!(app
+            .world
+            .resource::<Assets<AudioInstance>>()
+            .contains(&instance));
+        assert_eq!(
+            app.world.get::<AudioEmitter>(emitter).unwrap().instances,
+            vec![instance]
+        );
+    }
+
+    #[test]
+    fn spacial_systems_run_in_the_configured_schedule() {
+        use crate::AudioPlugin;
+        use bevy::app::{PostUpdate, Update};
+        use bevy::asset::AssetPlugin;
+        use bevy::ecs::schedule::{InternedScheduleLabel, ScheduleLabel};
+        use bevy::prelude::{App, MinimalPlugins};
+
+        let has_spacial_audio = |app: &App, schedule: InternedScheduleLabel| {
+            app.get_schedule(schedule).map_or(false, |schedule| {
+                schedule
+                    .graph()
+                    .systems()
+                    .any(|(_, system, _)| system.name().ends_with("run_spacial_audio"))
+            })
+        };
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            AudioPlugin::default(),
+        ));
+        assert!(has_spacial_audio(&app, PostUpdate.intern()));
+        assert!(!has_spacial_audio(&app, Update.intern()));
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            AudioPlugin::with_spacial_schedule(Update),
+        ));
+        assert!(has_spacial_audio(&app, Update.intern()));
+        assert!(!has_spacial_audio(&app, PostUpdate.intern()));
+    }
+
+    #[test]
+    fn two_d_mode_pans_by_x_offset_and_ignores_depth_and_orientation() {
+        let spacial_audio = SpacialAudio {
+            mode: SpacialMode::TwoD { half_width: 10. },
+            attenuation: AttenuationModel::None,
+            ..Default::default()
+        };
+        // A 2D camera sits far away on the Z axis
+        let receiver_transform = GlobalTransform::from(Transform::from_xyz(0., 0., 999.9));
+        let receiver = AudioReceiver {
+            rear_attenuation: 1.,
+            ..Default::default()
+        };
+        let emitter = AudioEmitter {
+            self_occlusion: 1.,
+            ..Default::default()
+        };
+        let output_at = |x: f32| {
+            spacial_audio.output(
+                &receiver_transform,
+                &receiver,
+                &GlobalTransform::from(Transform::from_xyz(x, 0., 0.)),
+                &emitter,
+            )
+        };
+
+        assert_eq!(output_at(5.).volume, 1.);
+        assert_eq!(output_at(5.).panning, 0.75);
+        assert_eq!(output_at(-5.).panning, 0.25);
+        assert_eq!(output_at(20.).panning, 1.);
+        assert_eq!(output_at(-20.).panning, 0.);
+    }
+
+    #[test]
+    fn two_d_mode_measures_distance_on_the_plane() {
+        let spacial_audio = SpacialAudio {
+            mode: SpacialMode::TwoD { half_width: 10. },
+            attenuation: AttenuationModel::Linear { max_distance: 10. },
+            ..Default::default()
+        };
+        let output = spacial_audio.output(
+            &GlobalTransform::from(Transform::from_xyz(0., 0., 999.9)),
+            &AudioReceiver::default(),
+            &GlobalTransform::from(Transform::from_xyz(0., 5., 0.)),
+            &AudioEmitter::default(),
+        );
+
+        assert!((output.volume - 0.5).abs() < 1e-6);
+        assert_eq!(output.panning, 0.5);
+    }
+
+    #[test]
+    fn occlusion_lowers_volume_and_muffles() {
+        let spacial_audio = SpacialAudio {
+            cutoff_at_full_occlusion: Some(500.),
+            ..Default::default()
+        };
+        let output = SpacialOutput {
+            volume: 0.8,
+            panning: 0.3,
+            low_pass_cutoff: None,
+            ambient: 0.,
+            interaural_delay: 0.,
+        };
+
+        let unoccluded = spacial_audio.occlude(output, 0.);
+        assert_eq!(unoccluded.volume, 0.8);
+        assert_eq!(unoccluded.low_pass_cutoff, Some(UNFILTERED_CUTOFF));
+
+        let occluded = spacial_audio.occlude(output, 0.5);
+        assert!((occluded.volume - 0.4).abs() < 1e-6);
+        assert_eq!(occluded.panning, 0.3);
+        let cutoff = occluded.low_pass_cutoff.unwrap();
+        assert!(cutoff < UNFILTERED_CUTOFF && cutoff > 500.);
+
+        let fully_occluded = spacial_audio.occlude(output, 1.);
+        assert_eq!(fully_occluded.volume, 0.);
+        assert!((fully_occluded.low_pass_cutoff.unwrap() - 500.).abs() < 1e-2);
+    }
+
+    #[test]
+    fn occlusion_keeps_the_stronger_distance_muffling() {
+        let spacial_audio = SpacialAudio {
+            cutoff_at_full_occlusion: Some(5_000.),
+            ..Default::default()
+        };
+        let output = SpacialOutput {
+            volume: 1.,
+            panning: 0.5,
+            low_pass_cutoff: Some(1_000.),
+            ambient: 0.,
+            interaural_delay: 0.,
+        };
+
+        assert_eq!(
+            spacial_audio.occlude(output, 0.5).low_pass_cutoff,
+            Some(1_000.)
+        );
+        assert_eq!(
+            SpacialAudio::default().occlude(output, 0.5).low_pass_cutoff,
+            Some(1_000.)
+        );
+    }
+
+    #[test]
+    fn ambient_send_does_not_fall_off_until_max_distance() {
+        let spacial_audio = SpacialAudio {
+            max_distance: 10.,
+            ..Default::default()
+        };
+        let emitter = AudioEmitter {
+            range: 1.,
+            ambient_send: 0.5,
+            ..Default::default()
+        };
+        let output_at = |x: f32| {
+            spacial_audio.output(
+                &GlobalTransform::default(),
+                &AudioReceiver::default(),
+                &GlobalTransform::from(Transform::from_xyz(x, 0., 0.)),
+                &emitter,
+            )
+        };
+
+        let near = output_at(2.);
+        let far = output_at(8.);
+        assert_eq!(near.ambient, 0.5);
+        assert_eq!(far.ambient, 0.5);
+        assert!(far.ambient / far.volume > near.ambient / near.volume);
+        assert_eq!(output_at(10.).ambient, 0.);
+    }
+
+    #[test]
+    fn no_ambient_send_by_default() {
+        let output = SpacialAudio::default().output(
+            &GlobalTransform::default(),
+            &AudioReceiver::default(),
+            &GlobalTransform::from(Transform::from_xyz(3., 0., 0.)),
+            &AudioEmitter::default(),
+        );
+
+        assert_eq!(output.ambient, 0.);
+    }
+
+    #[test]
+    fn emitters_can_be_edited_through_reflection() {
+        use bevy::reflect::GetPath;
+
+        let mut emitter = AudioEmitter {
+            instances: vec![Handle::default()],
+            ..Default::default()
+        };
+        *emitter.path_mut::<f32>("range").unwrap() = 3.;
+        *emitter.path_mut::<f32>("self_occlusion").unwrap() = 0.5;
+
+        assert_eq!(emitter.range, 3.);
+        assert_eq!(emitter.self_occlusion, 0.5);
+        assert!(emitter
+            .path::<Vec<Handle<AudioInstance>>>("instances")
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn finds_instances_on_multiple_emitters() {
+        let mut world = World::new();
+        let first = world.spawn_empty().id();
+        let second = world.spawn_empty().id();
+        let shared = Handle::<AudioInstance>::Weak(AssetId::Uuid {
+            uuid: bevy::utils::Uuid::from_u128(1),
+        });
+        let first_emitter = AudioEmitter {
+            instances: vec![shared.clone(), Handle::default()],
+            ..Default::default()
+        };
+        let second_emitter = AudioEmitter {
+            instances: vec![shared.clone()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            shared_instances([(first, &first_emitter), (second, &second_emitter)]),
+            vec![(shared.id(), first, second)]
+        );
+        assert!(shared_instances([(first, &first_emitter)]).is_empty());
+    }
+
+    #[test]
+    fn only_the_loudest_emitters_are_active() {
+        let mut world = World::new();
+        let [quiet, medium, loud] = [(); 3].map(|_| world.spawn_empty().id());
+        let candidates = [(quiet, 0.1), (medium, 0.5), (loud, 0.9)];
+
+        let active = select_active_emitters(candidates, &HashSet::default(), 2);
+        assert_eq!(active, HashSet::from_iter([medium, loud]));
+        assert!(select_active_emitters(candidates, &HashSet::default(), 0).is_empty());
+    }
+
+    #[test]
+    fn active_emitters_stay_active_near_the_boundary() {
+        let mut world = World::new();
+        let [previous, challenger] = [(); 2].map(|_| world.spawn_empty().id());
+        let previously_active = HashSet::from_iter([previous]);
+
+        let active =
+            select_active_emitters([(previous, 0.5), (challenger, 0.55)], &previously_active, 1);
+        assert_eq!(active, previously_active);
+
+        let active =
+            select_active_emitters([(previous, 0.5), (challenger, 0.7)], &previously_active, 1);
+        assert_eq!(active, HashSet::from_iter([challenger]));
     }
 }
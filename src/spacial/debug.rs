@@ -0,0 +1,173 @@
+use crate::spacial::{
+    AudioEmitter, AudioReceiver, EmitterOutput, PrimaryReceiver, ReceiverSelection, SpacialAudio,
+    SpacialAudioState, SpacialEmitter, SpacialOutput,
+};
+use bevy::gizmos::gizmos::Gizmos;
+use bevy::prelude::{
+    Color, Entity, GlobalTransform, Has, Quat, Query, Res, ResMut, Resource, With,
+};
+use bevy::utils::HashMap;
+
+/// Last values computed by spacial audio for an emitter
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpacialEmitterDebug {
+    /// The receiver the emitter was evaluated for
+    ///
+    /// With [`ReceiverSelection::Blend`] this is the closest receiver.
+    pub receiver: Option<Entity>,
+    /// Distance to [`receiver`](Self::receiver)
+    pub distance: Option<f32>,
+    /// The applied output or `None` if there is no receiver or the emitter is out of range
+    pub output: Option<SpacialOutput>,
+    /// Number of instances on the emitter
+    pub instances: usize,
+}
+
+/// Debug information about spacial audio, updated every frame
+///
+/// Only available with the `debug` feature.
+#[derive(Resource, Debug)]
+pub struct SpacialAudioDebug {
+    /// Last computed values per emitter entity
+    pub emitters: HashMap<Entity, SpacialEmitterDebug>,
+    /// Number of commands sent to audio instances in the last update
+    ///
+    /// Changes below [`SpacialAudio::update_epsilon`] are not sent, so this is `0` for static scenes.
+    pub instance_commands: usize,
+    /// Draw gizmos for emitter ranges and lines to their receivers (default `true`)
+    ///
+    /// Requires bevy's `GizmoPlugin`, which is part of the `DefaultPlugins`.
+    pub draw_gizmos: bool,
+}
+
+impl Default for SpacialAudioDebug {
+    fn default() -> Self {
+        SpacialAudioDebug {
+            emitters: HashMap::default(),
+            instance_commands: 0,
+            draw_gizmos: true,
+        }
+    }
+}
+
+pub(crate) fn record_spacial_audio_debug(
+    spacial_audio: Res<SpacialAudio>,
+    state: Res<SpacialAudioState>,
+    mut debug: ResMut<SpacialAudioDebug>,
+    receivers: Query<(
+        Entity,
+        &GlobalTransform,
+        &AudioReceiver,
+        Has<PrimaryReceiver>,
+    )>,
+    emitters: Query<SpacialEmitter, With<AudioEmitter>>,
+) {
+    let (receiver_entities, receivers): (Vec<_>, Vec<_>) = receivers
+        .iter()
+        .map(|(entity, transform, receiver, primary)| (entity, (transform, receiver, primary)))
+        .unzip();
+    debug.instance_commands = state.commands;
+    debug.emitters.clear();
+    for (entity, emitter_transform, emitter, occlusion) in emitters.iter() {
+        let receiver_index = if spacial_audio.receiver_selection == ReceiverSelection::Blend {
+            ReceiverSelection::Nearest.select(
+                receivers.iter().map(|(transform, _, primary)| {
+                    (
+                        spacial_audio.mode.project(transform.translation()),
+                        *primary,
+                    )
+                }),
+                spacial_audio.mode.project(emitter_transform.translation()),
+            )
+        } else {
+            spacial_audio.select_receiver(&receivers, emitter_transform)
+        };
+        let output =
+            match spacial_audio.emitter_output(&receivers, emitter_transform, emitter, occlusion) {
+                EmitterOutput::Audible(output) => Some(output),
+                EmitterOutput::NoReceiver | EmitterOutput::OutOfRange => None,
+            };
+        debug.emitters.insert(
+            entity,
+            SpacialEmitterDebug {
+                receiver: receiver_index.map(|index| receiver_entities[index]),
+                distance: receiver_index
+                    .map(|index| spacial_audio.distance(receivers[index].0, emitter_transform)),
+                output,
+                instances: emitter.instances.len(),
+            },
+        );
+    }
+}
+
+pub(crate) fn draw_spacial_audio_gizmos(
+    debug: Res<SpacialAudioDebug>,
+    mut gizmos: Gizmos,
+    receivers: Query<&GlobalTransform, With<AudioReceiver>>,
+    emitters: Query<(Entity, &GlobalTransform, &AudioEmitter)>,
+) {
+    if !debug.draw_gizmos {
+        return;
+    }
+    for (entity, emitter_transform, emitter) in emitters.iter() {
+        let translation = emitter_transform.translation();
+        gizmos.sphere(translation, Quat::IDENTITY, emitter.range, Color::YELLOW);
+        let Some(emitter_debug) = debug.emitters.get(&entity) else {
+            continue;
+        };
+        let Some(receiver_transform) = emitter_debug
+            .receiver
+            .and_then(|receiver| receivers.get(receiver).ok())
+        else {
+            continue;
+        };
+        let color = if emitter_debug.output.is_some() {
+            Color::GREEN
+        } else {
+            Color::RED
+        };
+        gizmos.line(translation, receiver_transform.translation(), color);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bevy::prelude::{Schedule, Transform, World};
+
+    #[test]
+    fn records_emitter_values() {
+        let mut world = World::new();
+        world.insert_resource(SpacialAudio::default());
+        world.init_resource::<SpacialAudioState>();
+        world.init_resource::<SpacialAudioDebug>();
+        let receiver = world
+            .spawn((GlobalTransform::default(), AudioReceiver::default()))
+            .id();
+        let near = world
+            .spawn((
+                GlobalTransform::from(Transform::from_xyz(3., 0., 0.)),
+                AudioEmitter::default(),
+            ))
+            .id();
+        let far = world
+            .spawn((
+                GlobalTransform::from(Transform::from_xyz(100., 0., 0.)),
+                AudioEmitter::default(),
+            ))
+            .id();
+        let mut schedule = Schedule::default();
+        schedule.add_systems(record_spacial_audio_debug);
+        schedule.run(&mut world);
+
+        let debug = world.resource::<SpacialAudioDebug>();
+        let near = debug.emitters[&near];
+        assert_eq!(near.receiver, Some(receiver));
+        assert_eq!(near.distance, Some(3.));
+        assert!(near.output.is_some());
+        assert_eq!(near.instances, 0);
+        let far = debug.emitters[&far];
+        assert_eq!(far.distance, Some(100.));
+        assert!(far.output.is_none());
+    }
+}
@@ -0,0 +1,123 @@
+//! Entities that live as long as the sounds of their emitter
+
+use crate::spacial::AudioEmitter;
+use bevy::ecs::component::Component;
+use bevy::hierarchy::DespawnRecursiveExt;
+use bevy::prelude::{Commands, Entity, Local, Query, ReflectComponent, RemovedComponents};
+use bevy::reflect::Reflect;
+use bevy::utils::HashSet;
+
+/// Despawn the entity once all sounds of its [`AudioEmitter`] completed
+///
+/// The emitter drops the handles of stopped instances in the
+/// [`InstanceCleanup`](crate::AudioSystemSet::InstanceCleanup) set, and the entity is despawned
+/// together with its children right after, in the same frame. Sounds that are still loading keep
+/// the entity alive. The entity is only despawned once its emitter had an instance, so the sound
+/// can also be played after spawning the entity.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn impact(mut commands: Commands, asset_server: Res<AssetServer>, audio: Res<Audio>) {
+///     let mut emitter = AudioEmitter::default();
+///     emitter.play(audio.as_ref(), asset_server.load("sounds/impact.ogg"));
+///     commands.spawn((
+///         TransformBundle::default(),
+///         emitter,
+///         DespawnOnAudioComplete::default(),
+///     ));
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct DespawnOnAudioComplete {
+    /// Only remove the [`AudioEmitter`] and this component instead of despawning the entity
+    pub remove_components: bool,
+}
+
+pub(crate) fn despawn_on_audio_complete(
+    mut commands: Commands,
+    mut played: Local<HashSet<Entity>>,
+    emitters: Query<(Entity, &AudioEmitter, &DespawnOnAudioComplete)>,
+    mut removed: RemovedComponents<DespawnOnAudioComplete>,
+) {
+    for entity in removed.read() {
+        played.remove(&entity);
+    }
+    for (entity, emitter, despawn) in emitters.iter() {
+        if !emitter.instances.is_empty() {
+            played.insert(entity);
+            continue;
+        }
+        if !played.remove(&entity) {
+            continue;
+        }
+        if despawn.remove_components {
+            commands
+                .entity(entity)
+                .remove::<(AudioEmitter, DespawnOnAudioComplete)>();
+        } else {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Audio, AudioInstance, AudioPlugin, AudioSource};
+    use bevy::app::App;
+    use bevy::asset::{AssetPlugin, Assets};
+    use bevy::prelude::MinimalPlugins;
+    use kira::dsp::Frame;
+    use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+    use std::sync::Arc;
+
+    #[test]
+    fn entities_are_despawned_once_their_sounds_completed() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            AudioPlugin::default(),
+        ));
+        let source = app
+            .world
+            .resource_mut::<Assets<AudioSource>>()
+            .add(AudioSource {
+                sound: StaticSoundData {
+                    sample_rate: 1_000,
+                    frames: Arc::from(vec![Frame::ZERO; 1_000]),
+                    settings: StaticSoundSettings::default(),
+                },
+                loop_region: None,
+                gain: 1.,
+            });
+        let spawn = |app: &mut App, despawn: DespawnOnAudioComplete| {
+            let mut emitter = AudioEmitter::default();
+            let instance = emitter
+                .play(app.world.resource::<Audio>(), source.clone())
+                .handle();
+            (app.world.spawn((emitter, despawn)).id(), instance)
+        };
+        let (despawned, first) = spawn(&mut app, DespawnOnAudioComplete::default());
+        let (kept, second) = spawn(
+            &mut app,
+            DespawnOnAudioComplete {
+                remove_components: true,
+            },
+        );
+        app.update();
+        app.update();
+        assert!(app.world.get_entity(despawned).is_some());
+        assert!(app.world.get::<AudioEmitter>(kept).is_some());
+
+        let mut instances = app.world.resource_mut::<Assets<AudioInstance>>();
+        instances.remove(&first);
+        instances.remove(&second);
+        app.update();
+        assert!(app.world.get_entity(despawned).is_none());
+        assert!(app.world.get_entity(kept).is_some());
+        assert!(app.world.get::<AudioEmitter>(kept).is_none());
+        assert!(app.world.get::<DespawnOnAudioComplete>(kept).is_none());
+    }
+}
@@ -0,0 +1,168 @@
+use kira::clock::clock_info::ClockInfoProvider;
+use kira::dsp::Frame;
+use kira::modulator::value_provider::ModulatorValueProvider;
+use kira::track::effect::{Effect, EffectBuilder};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+/// Longest supported delay between the ears in seconds
+///
+/// Enough for an ear distance of about 0.68 meters.
+pub(crate) const MAX_INTERAURAL_DELAY: f32 = 0.002;
+
+/// Time in seconds for the applied delay to follow most of a change
+///
+/// Jumping to a new delay would click, so the delay glides over a few milliseconds.
+const SMOOTHING_TIME: f32 = 0.005;
+
+/// Delays one stereo channel relative to the other
+///
+/// A positive delay holds back the left channel, a negative delay the right channel.
+pub(crate) struct InterauralDelayBuilder {
+    delay: Arc<AtomicU32>,
+}
+
+impl InterauralDelayBuilder {
+    pub(crate) fn new() -> Self {
+        InterauralDelayBuilder {
+            delay: Arc::new(AtomicU32::new(0f32.to_bits())),
+        }
+    }
+}
+
+impl EffectBuilder for InterauralDelayBuilder {
+    type Handle = InterauralDelayHandle;
+
+    fn build(self) -> (Box<dyn Effect>, Self::Handle) {
+        (
+            Box::new(InterauralDelay {
+                target: self.delay.clone(),
+                delay: 0.,
+                smoothing: 0.,
+                sample_rate: 0.,
+                buffer: vec![],
+                write_index: 0,
+            }),
+            InterauralDelayHandle { delay: self.delay },
+        )
+    }
+}
+
+/// Controls an interaural delay effect from the main thread
+///
+/// The delay is shared through an atomic, so setting it does not use the command queue of kira.
+pub(crate) struct InterauralDelayHandle {
+    delay: Arc<AtomicU32>,
+}
+
+impl InterauralDelayHandle {
+    /// Set the delay in seconds, see [`InterauralDelayBuilder`]
+    pub(crate) fn set_delay(&self, delay: f32) {
+        let delay = if delay.is_nan() {
+            0.
+        } else {
+            delay.clamp(-MAX_INTERAURAL_DELAY, MAX_INTERAURAL_DELAY)
+        };
+        self.delay.store(delay.to_bits(), Ordering::Relaxed);
+    }
+}
+
+struct InterauralDelay {
+    target: Arc<AtomicU32>,
+    delay: f32,
+    /// Fraction of the remaining change applied per sample
+    smoothing: f32,
+    sample_rate: f32,
+    buffer: Vec<Frame>,
+    write_index: usize,
+}
+
+impl InterauralDelay {
+    fn allocate(&mut self, sample_rate: u32) {
+        self.sample_rate = sample_rate as f32;
+        self.smoothing = 1. - (-1. / (SMOOTHING_TIME * self.sample_rate)).exp();
+        let length = (MAX_INTERAURAL_DELAY * self.sample_rate).ceil() as usize + 2;
+        self.buffer = vec![Frame::ZERO; length];
+        self.write_index = 0;
+    }
+
+    /// The input from `delay` seconds ago, interpolated between samples
+    fn read(&self, delay: f32) -> Frame {
+        let length = self.buffer.len();
+        let samples = (delay * self.sample_rate).clamp(0., (length - 2) as f32);
+        let whole = samples.floor();
+        let fraction = samples - whole;
+        let newer = (self.write_index + length - whole as usize) % length;
+        let older = (newer + length - 1) % length;
+
+        self.buffer[newer] * (1. - fraction) + self.buffer[older] * fraction
+    }
+}
+
+impl Effect for InterauralDelay {
+    fn init(&mut self, sample_rate: u32) {
+        self.allocate(sample_rate);
+    }
+
+    fn on_change_sample_rate(&mut self, sample_rate: u32) {
+        self.allocate(sample_rate);
+    }
+
+    fn process(
+        &mut self,
+        input: Frame,
+        _dt: f64,
+        _clock_info_provider: &ClockInfoProvider,
+        _modulator_value_provider: &ModulatorValueProvider,
+    ) -> Frame {
+        if self.buffer.is_empty() {
+            return input;
+        }
+        let target = f32::from_bits(self.target.load(Ordering::Relaxed));
+        self.delay += (target - self.delay) * self.smoothing;
+        self.buffer[self.write_index] = input;
+        let output = Frame {
+            left: self.read(self.delay.max(0.)).left,
+            right: self.read((-self.delay).max(0.)).right,
+        };
+        self.write_index = (self.write_index + 1) % self.buffer.len();
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kira::clock::clock_info::MockClockInfoProviderBuilder;
+    use kira::modulator::value_provider::MockModulatorValueProviderBuilder;
+
+    #[test]
+    fn delays_the_far_ear() {
+        let builder = InterauralDelayBuilder::new();
+        let (mut effect, handle) = builder.build();
+        effect.init(1_000);
+        handle.set_delay(MAX_INTERAURAL_DELAY);
+        let clock_info_provider = MockClockInfoProviderBuilder::new(0).build();
+        let modulator_value_provider = MockModulatorValueProviderBuilder::new(0).build();
+        let mut outputs = vec![];
+        for sample in 0..2_000 {
+            let input = if sample == 1_000 {
+                Frame::from_mono(1.)
+            } else {
+                Frame::ZERO
+            };
+            outputs.push(effect.process(
+                input,
+                0.001,
+                &clock_info_provider,
+                &modulator_value_provider,
+            ));
+        }
+
+        // The delay had time to settle. At 1000 Hz, the left ear hears the impulse 2 samples later.
+        assert_eq!(outputs[1_000].right, 1.);
+        assert!(outputs[1_000].left.abs() < 0.05);
+        assert!((outputs[1_002].left - 1.).abs() < 0.05);
+    }
+}
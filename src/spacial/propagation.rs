@@ -0,0 +1,360 @@
+//! Geometry that blocks sounds on their way to the receiver and openings they can take instead
+
+use crate::spacial::zone::{zone_receiver, ZoneExtent, ZoneReceivers};
+use crate::spacial::{AudioEmitter, OcclusionFactor, SpacialAudioState};
+use bevy::ecs::component::Component;
+use bevy::prelude::{Entity, GlobalTransform, Query, ReflectComponent, ResMut, Vec3, With};
+use bevy::reflect::Reflect;
+
+/// An obstacle that absorbs sounds passing through it, like a wall
+///
+/// Spacial audio checks the straight path from every emitter to the receiver against all
+/// occluders. Each occluder on the path keeps only `1 - absorption` of the sound, and the
+/// remaining occlusion lowers the volume and muffles the emitter like an [`OcclusionFactor`]
+/// does. If the path is fully blocked, the sound takes the shortest way through an
+/// [`AudioPortal`] instead and seems to come out of the opening.
+///
+/// The receiver is the [`PrimaryReceiver`](crate::PrimaryReceiver), or any
+/// [`AudioReceiver`](crate::AudioReceiver) if none is marked. Only available with the
+/// `propagation` feature, since the paths are checked every frame.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn spawn_room(mut commands: Commands) {
+///     // A wall with a door in it
+///     commands.spawn((
+///         TransformBundle::from_transform(Transform::from_xyz(5., 0., 0.)),
+///         AudioOccluder::cuboid(Vec3::new(0.2, 3., 10.)),
+///     ));
+///     commands.spawn((
+///         TransformBundle::from_transform(Transform::from_xyz(5., 0., 2.)),
+///         AudioPortal::default(),
+///     ));
+/// }
+/// ```
+#[derive(Component, Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct AudioOccluder {
+    /// The shape of the occluder in the local space of its entity
+    pub extent: ZoneExtent,
+    /// The share of the sound that the occluder absorbs, from `0.0` to `1.0` (default)
+    pub absorption: f32,
+}
+
+/// A solid box with a size of one along each axis, to be scaled by the transform
+impl Default for AudioOccluder {
+    fn default() -> Self {
+        AudioOccluder::cuboid(Vec3::splat(0.5))
+    }
+}
+
+impl AudioOccluder {
+    /// A box shaped occluder with half of the given size along each axis
+    pub fn cuboid(half_size: Vec3) -> Self {
+        AudioOccluder {
+            extent: ZoneExtent::Box { half_size },
+            absorption: 1.,
+        }
+    }
+
+    /// A spherical occluder with the given radius
+    pub fn sphere(radius: f32) -> Self {
+        AudioOccluder {
+            extent: ZoneExtent::Sphere { radius },
+            absorption: 1.,
+        }
+    }
+
+    /// Set the share of the sound that the occluder absorbs
+    pub fn with_absorption(mut self, absorption: f32) -> Self {
+        self.absorption = absorption;
+        self
+    }
+}
+
+/// An opening that sounds can take around [`AudioOccluder`]s, like a door
+///
+/// The opening is at the translation of the entity. Occluders that contain it are cut open there,
+/// so a portal can sit inside of a wall.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct AudioPortal {
+    /// The share of the sound that the opening absorbs, from `0.0` (default) to `1.0`
+    ///
+    /// Useful for half closed doors or curtains.
+    pub absorption: f32,
+}
+
+/// The way of the sound of an emitter to the receiver, if occluders are in the way
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct SoundPath {
+    /// How much of the sound is absorbed on the path
+    pub(crate) occlusion: f32,
+    /// Where the sound seems to come from, if it takes a way through a portal
+    pub(crate) apparent_translation: Option<Vec3>,
+}
+
+impl SoundPath {
+    /// The transform and occlusion spacial audio uses for the emitter
+    pub(crate) fn apply(
+        &self,
+        emitter_transform: &GlobalTransform,
+        occlusion: Option<&OcclusionFactor>,
+    ) -> (GlobalTransform, OcclusionFactor) {
+        let occlusion = occlusion.map_or(0., |OcclusionFactor(occlusion)| *occlusion);
+        let mut transform = emitter_transform.compute_transform();
+        if let Some(translation) = self.apparent_translation {
+            transform.translation = translation;
+        }
+
+        (
+            GlobalTransform::from(transform),
+            OcclusionFactor(1. - (1. - occlusion) * (1. - self.occlusion)),
+        )
+    }
+}
+
+/// Whether the segment passes through the extent
+pub(crate) fn intersects(
+    extent: &ZoneExtent,
+    transform: &GlobalTransform,
+    start: Vec3,
+    end: Vec3,
+) -> bool {
+    let inverse = transform.affine().inverse();
+    let start = inverse.transform_point3(start);
+    let end = inverse.transform_point3(end);
+    let segment = end - start;
+    match *extent {
+        ZoneExtent::Sphere { radius } => {
+            let length_squared = segment.length_squared();
+            let along = if length_squared > 0. {
+                (-start.dot(segment) / length_squared).clamp(0., 1.)
+            } else {
+                0.
+            };
+            (start + segment * along).length_squared() <= radius * radius
+        }
+        ZoneExtent::Box { half_size } => {
+            // Clip the segment against the slabs of all three axes
+            let (mut enter, mut exit) = (0f32, 1f32);
+            for axis in 0..3 {
+                let (from, delta, half) = (start[axis], segment[axis], half_size[axis]);
+                if delta.abs() <= f32::EPSILON {
+                    if from.abs() > half {
+                        return false;
+                    }
+                    continue;
+                }
+                let (near, far) = ((-half - from) / delta, (half - from) / delta);
+                enter = enter.max(near.min(far));
+                exit = exit.min(near.max(far));
+                if enter > exit {
+                    return false;
+                }
+            }
+            true
+        }
+    }
+}
+
+/// The share of the sound that makes it along the segment
+///
+/// Occluders that contain the opening are ignored.
+fn transmission(
+    occluders: &[(AudioOccluder, GlobalTransform)],
+    start: Vec3,
+    end: Vec3,
+    opening: Option<Vec3>,
+) -> f32 {
+    occluders
+        .iter()
+        .filter(|(occluder, transform)| {
+            opening.map_or(true, |opening| {
+                !occluder.extent.contains(transform, opening)
+            }) && intersects(&occluder.extent, transform, start, end)
+        })
+        .map(|(occluder, _)| 1. - occluder.absorption.clamp(0., 1.))
+        .product()
+}
+
+/// How the sound of an emitter reaches the receiver, `None` if nothing is in the way
+pub(crate) fn sound_path(
+    emitter: Vec3,
+    receiver: Vec3,
+    occluders: &[(AudioOccluder, GlobalTransform)],
+    portals: &[(AudioPortal, Vec3)],
+) -> Option<SoundPath> {
+    let direct = transmission(occluders, emitter, receiver, None);
+    if direct >= 1. {
+        return None;
+    }
+    if direct > 0. {
+        return Some(SoundPath {
+            occlusion: 1. - direct,
+            apparent_translation: None,
+        });
+    }
+    let shortest = portals
+        .iter()
+        .filter_map(|(portal, opening)| {
+            let transmitted = transmission(occluders, emitter, *opening, Some(*opening))
+                * transmission(occluders, *opening, receiver, Some(*opening))
+                * (1. - portal.absorption.clamp(0., 1.));
+            let length = emitter.distance(*opening) + opening.distance(receiver);
+            (transmitted > 0.).then_some((*opening, transmitted, length))
+        })
+        .min_by(|(_, _, a), (_, _, b)| a.total_cmp(b));
+    let Some((opening, transmitted, length)) = shortest else {
+        return Some(SoundPath {
+            occlusion: 1.,
+            apparent_translation: None,
+        });
+    };
+
+    // The sound comes from the direction of the opening, but travelled the whole way
+    Some(SoundPath {
+        occlusion: 1. - transmitted,
+        apparent_translation: Some(receiver + (opening - receiver).normalize_or_zero() * length),
+    })
+}
+
+pub(crate) fn propagate_sound(
+    occluders: Query<(&AudioOccluder, &GlobalTransform)>,
+    portals: Query<(&AudioPortal, &GlobalTransform)>,
+    receivers: ZoneReceivers,
+    emitters: Query<(Entity, &GlobalTransform), With<AudioEmitter>>,
+    mut state: ResMut<SpacialAudioState>,
+) {
+    if occluders.is_empty() {
+        if !state.sound_paths.is_empty() {
+            state.sound_paths.clear();
+        }
+        return;
+    }
+    let Some(receiver) = zone_receiver(&receivers) else {
+        state.sound_paths.clear();
+        return;
+    };
+    let occluders: Vec<_> = occluders
+        .iter()
+        .map(|(occluder, transform)| (*occluder, *transform))
+        .collect();
+    let portals: Vec<_> = portals
+        .iter()
+        .map(|(portal, transform)| (*portal, transform.translation()))
+        .collect();
+    state.sound_paths = emitters
+        .iter()
+        .filter_map(|(entity, transform)| {
+            sound_path(transform.translation(), receiver, &occluders, &portals)
+                .map(|path| (entity, path))
+        })
+        .collect();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn wall() -> (AudioOccluder, GlobalTransform) {
+        (
+            AudioOccluder::cuboid(Vec3::new(0.5, 5., 5.)),
+            GlobalTransform::from_translation(Vec3::new(5., 0., 0.)),
+        )
+    }
+
+    #[test]
+    fn segments_are_checked_against_shapes() {
+        let (occluder, transform) = wall();
+        assert!(intersects(
+            &occluder.extent,
+            &transform,
+            Vec3::ZERO,
+            Vec3::X * 10.
+        ));
+        assert!(!intersects(
+            &occluder.extent,
+            &transform,
+            Vec3::ZERO,
+            Vec3::X * 4.
+        ));
+        assert!(!intersects(
+            &occluder.extent,
+            &transform,
+            Vec3::new(0., 6., 0.),
+            Vec3::new(10., 6., 0.)
+        ));
+        let sphere = ZoneExtent::Sphere { radius: 1. };
+        let at_origin = GlobalTransform::default();
+        assert!(intersects(
+            &sphere,
+            &at_origin,
+            Vec3::new(-2., 0.5, 0.),
+            Vec3::new(2., 0.5, 0.)
+        ));
+        assert!(!intersects(
+            &sphere,
+            &at_origin,
+            Vec3::new(-2., 1.5, 0.),
+            Vec3::new(2., 1.5, 0.)
+        ));
+        assert!(!intersects(
+            &sphere,
+            &at_origin,
+            Vec3::new(2., 0., 0.),
+            Vec3::new(4., 0., 0.)
+        ));
+    }
+
+    #[test]
+    fn absorbing_occluders_multiply() {
+        let (occluder, transform) = wall();
+        let thin = occluder.with_absorption(0.5);
+        let occluders = [
+            (thin, transform),
+            (
+                thin,
+                GlobalTransform::from_translation(Vec3::new(7., 0., 0.)),
+            ),
+        ];
+        let path = sound_path(Vec3::X * 10., Vec3::ZERO, &occluders, &[]).unwrap();
+        assert_eq!(path.occlusion, 0.75);
+        assert_eq!(path.apparent_translation, None);
+        assert_eq!(
+            sound_path(Vec3::new(10., 6., 0.), Vec3::Y * 6., &occluders, &[]),
+            None
+        );
+    }
+
+    #[test]
+    fn blocked_sounds_come_through_the_nearest_portal() {
+        let occluders = [wall()];
+        let emitter = Vec3::X * 10.;
+        let blocked = sound_path(emitter, Vec3::ZERO, &occluders, &[]).unwrap();
+        assert_eq!(blocked.occlusion, 1.);
+
+        let near_door = Vec3::new(5., 0., 3.);
+        let far_door = Vec3::new(5., 0., -4.);
+        let portals = [
+            (AudioPortal::default(), far_door),
+            (AudioPortal { absorption: 0.2 }, near_door),
+        ];
+        let path = sound_path(emitter, Vec3::ZERO, &occluders, &portals).unwrap();
+        assert!((path.occlusion - 0.2).abs() < 1e-6);
+        let apparent = path.apparent_translation.unwrap();
+        // From the direction of the door, as far away as the way through it
+        assert!(apparent
+            .normalize()
+            .abs_diff_eq(near_door.normalize(), 1e-6));
+        let length = emitter.distance(near_door) + near_door.length();
+        assert!((apparent.length() - length).abs() < 1e-4);
+
+        let (transform, occlusion) = path.apply(
+            &GlobalTransform::from_translation(emitter),
+            Some(&OcclusionFactor(0.5)),
+        );
+        assert_eq!(transform.translation(), apparent);
+        assert!((occlusion.0 - 0.6).abs() < 1e-6);
+    }
+}
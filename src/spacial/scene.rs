@@ -0,0 +1,175 @@
+//! Emitters that name their sound by path, so they can be saved in scenes
+
+use crate::spacial::{AudioEmitter, SpacialAudio};
+use crate::{Audio, AudioInstance, AudioTween};
+use bevy::asset::{AssetServer, Assets, Handle};
+use bevy::ecs::component::Component;
+use bevy::prelude::{
+    Changed, Commands, Entity, Local, Query, ReflectComponent, ReflectDefault, RemovedComponents,
+    Res, ResMut,
+};
+use bevy::reflect::Reflect;
+use bevy::utils::HashMap;
+
+/// A sound that the [`AudioEmitter`] of the entity plays, named by the path of its file
+///
+/// Handles can't be saved in scenes, so this component refers to the sound by its asset path.
+/// Once it is added, for example by spawning a scene, the plugin loads the sound, plays it on the
+/// [`Audio`] channel and adds the instance to the [`AudioEmitter`] of the entity. Entities without
+/// an emitter get a default one. Changing the component starts the sound again, and removing it
+/// or despawning the entity stops the sound with [`SpacialAudio::despawn_stop_tween`].
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn spawn_waterfall(mut commands: Commands) {
+///     commands.spawn((
+///         TransformBundle::default(),
+///         SpacialAudioSource {
+///             path: "sounds/waterfall.ogg".to_owned(),
+///             looped: true,
+///             ..Default::default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default)]
+pub struct SpacialAudioSource {
+    /// The asset path of the sound
+    pub path: String,
+    /// Loop the whole sound
+    pub looped: bool,
+    /// The base volume of the sound as amplitude (default `1.0`)
+    pub volume: f64,
+}
+
+impl Default for SpacialAudioSource {
+    fn default() -> Self {
+        SpacialAudioSource {
+            path: String::new(),
+            looped: false,
+            volume: 1.,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn play_spacial_audio_sources(
+    mut commands: Commands,
+    asset_server: Option<Res<AssetServer>>,
+    audio: Res<Audio>,
+    spacial_audio: Option<Res<SpacialAudio>>,
+    mut started: Local<HashMap<Entity, Handle<AudioInstance>>>,
+    mut sources: Query<
+        (Entity, &SpacialAudioSource, Option<&mut AudioEmitter>),
+        Changed<SpacialAudioSource>,
+    >,
+    mut removed: RemovedComponents<SpacialAudioSource>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    let stop_tween = spacial_audio.map_or_else(AudioTween::default, |spacial_audio| {
+        spacial_audio.despawn_stop_tween.clone()
+    });
+    let mut stop = |handle: Handle<AudioInstance>| {
+        if let Some(instance) = audio_instances.get_mut(&handle) {
+            instance.stop(stop_tween.clone());
+        }
+    };
+    for entity in removed.read() {
+        if let Some(handle) = started.remove(&entity) {
+            stop(handle);
+        }
+    }
+    let Some(asset_server) = asset_server else {
+        return;
+    };
+    for (entity, source, emitter) in sources.iter_mut() {
+        let mut inserted = None;
+        let emitter = match emitter {
+            Some(emitter) => emitter.into_inner(),
+            None => inserted.insert(AudioEmitter::default()),
+        };
+        if let Some(previous) = started.remove(&entity) {
+            emitter.instances.retain(|handle| *handle != previous);
+            stop(previous);
+        }
+        let mut command = emitter.play(audio.as_ref(), asset_server.load(&source.path));
+        command.with_volume(source.volume);
+        if source.looped {
+            command.looped();
+        }
+        started.insert(entity, command.handle());
+        if let Some(emitter) = inserted {
+            commands.entity(entity).insert(emitter);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AudioPlugin, AudioSource};
+    use bevy::app::App;
+    use bevy::asset::AssetPlugin;
+    use bevy::prelude::MinimalPlugins;
+    use kira::dsp::Frame;
+    use kira::sound::static_sound::{StaticSoundData, StaticSoundSettings};
+    use std::sync::Arc;
+
+    #[test]
+    fn sources_play_on_their_emitter_until_despawned() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            AudioPlugin::default(),
+        ));
+        let entity = app
+            .world
+            .spawn(SpacialAudioSource {
+                path: "sounds/ambience.test".to_owned(),
+                looped: true,
+                volume: 0.5,
+            })
+            .id();
+        app.update();
+
+        let instances = &app.world.get::<AudioEmitter>(entity).unwrap().instances;
+        assert_eq!(instances.len(), 1);
+        let instance = instances[0].clone();
+        let source = app
+            .world
+            .resource::<AssetServer>()
+            .load::<AudioSource>("sounds/ambience.test");
+        app.world.resource_mut::<Assets<AudioSource>>().insert(
+            &source,
+            AudioSource {
+                sound: StaticSoundData {
+                    sample_rate: 1_000,
+                    frames: Arc::from(vec![Frame::ZERO; 1_000]),
+                    settings: StaticSoundSettings::default(),
+                },
+                loop_region: None,
+                gain: 1.,
+            },
+        );
+        app.update();
+        let audio_instance = app
+            .world
+            .resource::<Assets<AudioInstance>>()
+            .get(&instance)
+            .unwrap();
+        assert_eq!(audio_instance.base_volume(), 0.5);
+        assert!(!audio_instance.stop_requested);
+
+        app.world.despawn(entity);
+        app.update();
+        assert!(
+            app.world
+                .resource::<Assets<AudioInstance>>()
+                .get(&instance)
+                .unwrap()
+                .stop_requested
+        );
+    }
+}
@@ -0,0 +1,596 @@
+//! Regions that change the mix of channels while the receiver is inside
+
+use crate::audio_output::AudioOutput;
+use crate::spacial::{AudioReceiver, PrimaryReceiver, SpacialReverb};
+use crate::{AudioInstance, AudioTween, Channel};
+use bevy::asset::Assets;
+use bevy::ecs::component::Component;
+use bevy::prelude::{Entity, GlobalTransform, Has, Local, NonSendMut, Query, ResMut, Vec3, With};
+use bevy::reflect::Reflect;
+use bevy::utils::HashMap;
+use std::time::Duration;
+
+/// The region of an [`AudioZone`] in the local space of its entity
+///
+/// The region moves, rotates and scales with the [`GlobalTransform`] of the entity.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub enum ZoneExtent {
+    /// A sphere around the entity
+    Sphere {
+        /// The radius of the sphere
+        radius: f32,
+    },
+    /// A box centered on the entity
+    Box {
+        /// Half of the size of the box along each axis
+        half_size: Vec3,
+    },
+}
+
+impl ZoneExtent {
+    /// Whether the given point in world space is inside the region
+    pub fn contains(&self, transform: &GlobalTransform, point: Vec3) -> bool {
+        let local = transform.affine().inverse().transform_point3(point);
+        match *self {
+            ZoneExtent::Sphere { radius } => local.length_squared() <= radius * radius,
+            ZoneExtent::Box { half_size } => local.abs().cmple(half_size).all(),
+        }
+    }
+}
+
+/// How an [`AudioZone`] changes the mix of one channel
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChannelAdjustment {
+    /// The adjusted channel
+    pub channel: Channel,
+    /// Factor of the volume of all sounds in the channel
+    pub volume: f64,
+    /// Cutoff frequency in Hz for the filters of sounds in the channel
+    ///
+    /// Only sounds played with their own filter are affected, see
+    /// [`PlayAudioCommand::with_filter`](crate::PlayAudioCommand::with_filter).
+    pub filter_cutoff: Option<f64>,
+}
+
+impl ChannelAdjustment {
+    /// An adjustment of the given channel that does not change anything yet
+    pub fn new(channel: Channel) -> Self {
+        ChannelAdjustment {
+            channel,
+            volume: 1.,
+            filter_cutoff: None,
+        }
+    }
+
+    /// Scale the volume of the channel
+    pub fn with_volume(mut self, volume: f64) -> Self {
+        self.volume = volume;
+        self
+    }
+
+    /// Move the filters of sounds in the channel to the given cutoff frequency in Hz
+    pub fn with_filter_cutoff(mut self, cutoff: f64) -> Self {
+        self.filter_cutoff = Some(cutoff);
+        self
+    }
+}
+
+/// Change the mix of channels while the receiver is inside the region of the entity
+///
+/// The receiver is the [`PrimaryReceiver`], or any [`AudioReceiver`] if none is marked. When it
+/// enters the zone, the adjustments fade in with [`fade_in`](Self::fade_in). When it leaves, or
+/// the zone is removed, they are reverted with [`fade_out`](Self::fade_out).
+///
+/// If the receiver is inside several zones that adjust the same channel, their volume factors are
+/// multiplied and the lowest filter cutoff wins. A zone inside a larger one can so darken the mix
+/// further without knowing about the outer zone.
+///
+/// Filter cutoffs set with [`AudioInstance::set_filter_cutoff`] while the receiver is inside a
+/// zone replace the cutoff of the zone until the mix of the channel changes again, and are
+/// restored when the receiver leaves. Spacial audio muffling sets the cutoff of its emitters
+/// every frame, so zone cutoffs only apply to sounds that are not muffled by distance.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// # use bevy_kira_audio::Channel;
+/// #[derive(Resource)]
+/// struct Music;
+///
+/// fn spawn_cave(mut commands: Commands) {
+///     commands.spawn((
+///         TransformBundle::from_transform(Transform::from_xyz(20., 0., 0.)),
+///         AudioZone::sphere(10.).with(
+///             ChannelAdjustment::new(Channel::typed::<Music>())
+///                 .with_volume(0.5)
+///                 .with_filter_cutoff(800.),
+///         ),
+///     ));
+/// }
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct AudioZone {
+    /// The region of the zone
+    pub extent: ZoneExtent,
+    /// The changes to the mix while the receiver is inside
+    pub adjustments: Vec<ChannelAdjustment>,
+    /// Tween to the adjusted mix when the receiver enters
+    pub fade_in: AudioTween,
+    /// Tween back when the receiver leaves
+    pub fade_out: AudioTween,
+}
+
+impl AudioZone {
+    /// A zone with the given region and no adjustments
+    pub fn new(extent: ZoneExtent) -> Self {
+        AudioZone {
+            extent,
+            adjustments: vec![],
+            fade_in: AudioTween::linear(Duration::from_millis(500)),
+            fade_out: AudioTween::linear(Duration::from_millis(500)),
+        }
+    }
+
+    /// A spherical zone with the given radius
+    pub fn sphere(radius: f32) -> Self {
+        Self::new(ZoneExtent::Sphere { radius })
+    }
+
+    /// A box shaped zone with half of the given size along each axis
+    pub fn cuboid(half_size: Vec3) -> Self {
+        Self::new(ZoneExtent::Box { half_size })
+    }
+
+    /// Add an adjustment of a channel
+    pub fn with(mut self, adjustment: ChannelAdjustment) -> Self {
+        self.adjustments.push(adjustment);
+        self
+    }
+
+    /// Set the tweens used when the receiver enters and leaves
+    pub fn with_fades(mut self, fade_in: AudioTween, fade_out: AudioTween) -> Self {
+        self.fade_in = fade_in;
+        self.fade_out = fade_out;
+        self
+    }
+}
+
+/// Give emitters the acoustics of the space the receiver is in
+///
+/// Once there is any reverb zone, the [`SpacialAudio::reverb`](crate::SpacialAudio::reverb) track
+/// always exists and its settings follow the zone the receiver is in. If the receiver is inside
+/// several zones, the one it entered last wins, so walking from a hall into a small room nested in
+/// it switches to the room and back. When switching zones, the reverb moves to the settings of
+/// the entered zone with its [`fade`](Self::fade), or back with the fade of the zone that was
+/// left. Outside of all zones, the reverb uses [`SpacialAudio::reverb`](crate::SpacialAudio::reverb)
+/// or the default settings.
+///
+/// Only emitters inside a reverb zone send their
+/// [`ambient_send`](crate::AudioEmitter::ambient_send) to the reverb, all others stay dry. Sounds
+/// have to start playing with the send enabled, see [`AudioEmitter::play`](crate::AudioEmitter::play).
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// # use bevy_kira_audio::SpacialReverb;
+/// fn spawn_cathedral(mut commands: Commands) {
+///     commands.spawn((
+///         TransformBundle::default(),
+///         ReverbZone::new(
+///             ZoneExtent::Box {
+///                 half_size: Vec3::new(20., 15., 40.),
+///             },
+///             SpacialReverb {
+///                 feedback: 0.95,
+///                 ..default()
+///             },
+///         ),
+///     ));
+/// }
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct ReverbZone {
+    /// The region of the zone
+    pub extent: ZoneExtent,
+    /// The reverb heard while the receiver is inside
+    pub reverb: SpacialReverb,
+    /// Tween of the reverb settings when the receiver enters or leaves
+    pub fade: AudioTween,
+}
+
+impl ReverbZone {
+    /// A zone with the given region and reverb
+    pub fn new(extent: ZoneExtent, reverb: SpacialReverb) -> Self {
+        ReverbZone {
+            extent,
+            reverb,
+            fade: AudioTween::linear(Duration::from_secs(1)),
+        }
+    }
+
+    /// Set the tween of the reverb settings when the receiver enters or leaves
+    pub fn with_fade(mut self, fade: AudioTween) -> Self {
+        self.fade = fade;
+        self
+    }
+}
+
+/// The reverb zones the receiver is in, in the order it entered them
+#[derive(Default)]
+pub(crate) struct EnteredReverbZones {
+    /// The zones with their fade, so zones that were despawned can still fade out
+    entered: Vec<(Entity, AudioTween)>,
+}
+
+impl EnteredReverbZones {
+    /// Update with the zones the receiver is in now
+    ///
+    /// Returns the fade to use if the zone that is heard changed.
+    pub(crate) fn update(&mut self, inside: &[(Entity, &ReverbZone)]) -> Option<AudioTween> {
+        let previous = self.current();
+        let mut left = None;
+        self.entered.retain(|(entity, fade)| {
+            let stays = inside.iter().any(|(inside, _)| inside == entity);
+            if !stays && Some(*entity) == previous {
+                left = Some(fade.clone());
+            }
+            stays
+        });
+        let mut entered = None;
+        for (entity, zone) in inside {
+            if !self.entered.iter().any(|(known, _)| known == entity) {
+                self.entered.push((*entity, zone.fade.clone()));
+                entered = Some(zone.fade.clone());
+            }
+        }
+        if self.current() == previous {
+            return None;
+        }
+        // Newly entered zones are heard right away, so falling back to an outer zone or leaving all
+        // zones fades like the zone that was left
+        Some(entered.or(left).unwrap_or_default())
+    }
+
+    /// The zone whose reverb is heard
+    pub(crate) fn current(&self) -> Option<Entity> {
+        self.entered.last().map(|(entity, _)| *entity)
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entered.clear();
+    }
+}
+
+/// Whether the point is inside one of the given reverb zones
+pub(crate) fn in_reverb_zone(zones: &[(ZoneExtent, GlobalTransform)], point: Vec3) -> bool {
+    zones
+        .iter()
+        .any(|(extent, transform)| extent.contains(transform, point))
+}
+
+/// The receivers that zones are checked against
+pub(crate) type ZoneReceivers<'w, 's> =
+    Query<'w, 's, (&'static GlobalTransform, Has<PrimaryReceiver>), With<AudioReceiver>>;
+
+/// The position of the [`PrimaryReceiver`], or any receiver if none is marked
+pub(crate) fn zone_receiver(receivers: &ZoneReceivers) -> Option<Vec3> {
+    receivers
+        .iter()
+        .find(|(_, primary)| *primary)
+        .or_else(|| receivers.iter().next())
+        .map(|(transform, _)| transform.translation())
+}
+
+/// The combined adjustments of all zones a channel is in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct ZoneMix {
+    pub(crate) volume: f64,
+    pub(crate) filter_cutoff: Option<f64>,
+}
+
+impl Default for ZoneMix {
+    fn default() -> Self {
+        ZoneMix {
+            volume: 1.,
+            filter_cutoff: None,
+        }
+    }
+}
+
+/// Combine the adjustments of overlapping zones per channel
+///
+/// Volume factors multiply and the lowest cutoff wins.
+pub(crate) fn combine_zones<'a>(
+    zones: impl IntoIterator<Item = &'a AudioZone>,
+) -> HashMap<Channel, ZoneMix> {
+    let mut mixes: HashMap<Channel, ZoneMix> = HashMap::default();
+    for adjustment in zones.into_iter().flat_map(|zone| zone.adjustments.iter()) {
+        let mix = mixes.entry(adjustment.channel.clone()).or_default();
+        mix.volume *= adjustment.volume;
+        mix.filter_cutoff = match (mix.filter_cutoff, adjustment.filter_cutoff) {
+            (Some(current), Some(cutoff)) => Some(current.min(cutoff)),
+            (current, cutoff) => current.or(cutoff),
+        };
+    }
+
+    mixes
+}
+
+pub(crate) fn update_audio_zones(
+    zones: Query<(Entity, &AudioZone, &GlobalTransform)>,
+    receivers: ZoneReceivers,
+    mut inside: Local<HashMap<Entity, AudioZone>>,
+    mut applied: Local<HashMap<Channel, ZoneMix>>,
+    mut audio_output: NonSendMut<AudioOutput>,
+    mut audio_instances: ResMut<Assets<AudioInstance>>,
+) {
+    let current: HashMap<Entity, &AudioZone> = zone_receiver(&receivers)
+        .map(|receiver| {
+            zones
+                .iter()
+                .filter(|(_, zone, transform)| zone.extent.contains(transform, receiver))
+                .map(|(entity, zone, _)| (entity, zone))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mixes = combine_zones(current.values().copied());
+    let mut channels: Vec<Channel> = applied.keys().cloned().collect();
+    channels.extend(
+        mixes
+            .keys()
+            .filter(|channel| !applied.contains_key(*channel))
+            .cloned(),
+    );
+    for channel in channels {
+        let mix = mixes.get(&channel).copied().unwrap_or_default();
+        if applied.get(&channel).copied().unwrap_or_default() == mix {
+            continue;
+        }
+        let adjusts = |zone: &AudioZone| {
+            zone.adjustments
+                .iter()
+                .any(|adjustment| adjustment.channel == channel)
+        };
+        // Fade like the zones that were entered, otherwise like the ones that were left
+        let tween = current
+            .iter()
+            .find(|(entity, zone)| !inside.contains_key(*entity) && adjusts(zone))
+            .map(|(_, zone)| zone.fade_in.clone())
+            .or_else(|| {
+                inside
+                    .iter()
+                    .find(|(entity, zone)| !current.contains_key(*entity) && adjusts(zone))
+                    .map(|(_, zone)| zone.fade_out.clone())
+            })
+            .or_else(|| {
+                current
+                    .values()
+                    .find(|zone| adjusts(zone))
+                    .map(|zone| zone.fade_in.clone())
+            })
+            .unwrap_or_default();
+        audio_output.set_zone_mix(&channel, mix, tween, &mut audio_instances);
+        if mix == ZoneMix::default() {
+            applied.remove(&channel);
+        } else {
+            applied.insert(channel, mix);
+        }
+    }
+    *inside = current
+        .into_iter()
+        .map(|(entity, zone)| (entity, zone.clone()))
+        .collect();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{AudioControl, AudioPlugin, AudioSource, DynamicAudioChannels, SpacialAudioSet};
+    use bevy::app::{App, PostUpdate, Update};
+    use bevy::asset::AssetPlugin;
+    use bevy::prelude::{
+        IntoSystemConfigs, IntoSystemSetConfigs, MinimalPlugins, Quat, Res, Resource, Transform,
+        TransformBundle, TransformPlugin,
+    };
+    use bevy::transform::TransformSystem;
+
+    #[test]
+    fn extents_follow_the_transform_of_their_entity() {
+        let transform = GlobalTransform::from(
+            Transform::from_xyz(10., 0., 0.)
+                .with_rotation(Quat::from_rotation_z(std::f32::consts::FRAC_PI_2))
+                .with_scale(Vec3::splat(2.)),
+        );
+        let sphere = ZoneExtent::Sphere { radius: 1. };
+        assert!(sphere.contains(&transform, Vec3::new(11.9, 0., 0.)));
+        assert!(!sphere.contains(&transform, Vec3::new(12.1, 0., 0.)));
+        let cuboid = ZoneExtent::Box {
+            half_size: Vec3::new(2., 1., 1.),
+        };
+        // The long side of the box points along the y axis after the rotation
+        assert!(cuboid.contains(&transform, Vec3::new(10., 3.9, 0.)));
+        assert!(!cuboid.contains(&transform, Vec3::new(12.5, 0., 0.)));
+    }
+
+    #[test]
+    fn overlapping_zones_multiply_volumes_and_keep_the_lowest_cutoff() {
+        let music = Channel::dynamic("music");
+        let effects = Channel::dynamic("effects");
+        let outer = AudioZone::sphere(10.)
+            .with(
+                ChannelAdjustment::new(music.clone())
+                    .with_volume(0.5)
+                    .with_filter_cutoff(2_000.),
+            )
+            .with(ChannelAdjustment::new(effects.clone()).with_filter_cutoff(5_000.));
+        let inner = AudioZone::sphere(2.).with(
+            ChannelAdjustment::new(music.clone())
+                .with_volume(0.5)
+                .with_filter_cutoff(500.),
+        );
+
+        let mixes = combine_zones([&outer, &inner]);
+        assert_eq!(
+            mixes[&music],
+            ZoneMix {
+                volume: 0.25,
+                filter_cutoff: Some(500.)
+            }
+        );
+        assert_eq!(
+            mixes[&effects],
+            ZoneMix {
+                volume: 1.,
+                filter_cutoff: Some(5_000.)
+            }
+        );
+        assert!(combine_zones([]).is_empty());
+    }
+
+    #[test]
+    fn the_reverb_zone_entered_last_is_heard() {
+        let hall = ReverbZone::new(ZoneExtent::Sphere { radius: 50. }, SpacialReverb::default())
+            .with_fade(AudioTween::linear(Duration::from_secs(2)));
+        let room = ReverbZone::new(ZoneExtent::Sphere { radius: 5. }, SpacialReverb::default())
+            .with_fade(AudioTween::linear(Duration::from_millis(300)));
+        let (hall_entity, room_entity) = (Entity::from_raw(1), Entity::from_raw(2));
+        let mut entered = EnteredReverbZones::default();
+        assert_eq!(entered.update(&[]), None);
+
+        assert_eq!(
+            entered.update(&[(hall_entity, &hall)]),
+            Some(hall.fade.clone())
+        );
+        assert_eq!(entered.current(), Some(hall_entity));
+        assert_eq!(entered.update(&[(hall_entity, &hall)]), None);
+        // The order of the queried zones does not matter
+        assert_eq!(
+            entered.update(&[(room_entity, &room), (hall_entity, &hall)]),
+            Some(room.fade.clone())
+        );
+        assert_eq!(entered.current(), Some(room_entity));
+
+        // Falling back to the hall fades like the room that was left
+        assert_eq!(
+            entered.update(&[(hall_entity, &hall)]),
+            Some(room.fade.clone())
+        );
+        assert_eq!(entered.current(), Some(hall_entity));
+        assert_eq!(entered.update(&[]), Some(hall.fade.clone()));
+        assert_eq!(entered.current(), None);
+    }
+
+    #[test]
+    fn adjustments_apply_inside_and_are_reverted_on_exit() {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            AudioPlugin::default(),
+        ))
+        .add_plugins(TransformPlugin)
+        .configure_sets(
+            PostUpdate,
+            SpacialAudioSet::Update.after(TransformSystem::TransformPropagate),
+        );
+        let source = AudioSource::from_frames(1_000, vec![[0.; 2]; 10_000]).unwrap();
+        let source = app.world.resource_mut::<Assets<AudioSource>>().add(source);
+        let instance = app
+            .world
+            .resource_mut::<DynamicAudioChannels>()
+            .create_channel("music")
+            .play(source)
+            .looped()
+            .handle();
+        let receiver = app
+            .world
+            .spawn((TransformBundle::default(), AudioReceiver::default()))
+            .id();
+        let quiet = |volume| {
+            ChannelAdjustment::new(Channel::dynamic("music"))
+                .with_volume(volume)
+                .with_filter_cutoff(1_000.)
+        };
+        app.world.spawn((
+            TransformBundle::default(),
+            AudioZone::sphere(10.).with(quiet(0.5)),
+        ));
+        app.world.spawn((
+            TransformBundle::from_transform(Transform::from_xyz(8., 0., 0.)),
+            AudioZone::sphere(1.).with(quiet(0.5)),
+        ));
+        let duck = |app: &App| {
+            app.world
+                .resource::<Assets<AudioInstance>>()
+                .get(&instance)
+                .unwrap()
+                .duck
+        };
+        app.update();
+        assert_eq!(duck(&app), 0.5);
+
+        app.world
+            .entity_mut(receiver)
+            .insert(TransformBundle::from_transform(Transform::from_xyz(
+                8., 0., 0.,
+            )));
+        app.update();
+        assert_eq!(duck(&app), 0.25);
+
+        app.world
+            .entity_mut(receiver)
+            .insert(TransformBundle::from_transform(Transform::from_xyz(
+                20., 0., 0.,
+            )));
+        app.update();
+        assert_eq!(duck(&app), 1.);
+    }
+
+    #[test]
+    fn zones_apply_in_the_frame_of_a_moved_spacial_schedule() {
+        #[derive(Resource, Default)]
+        struct SeenDuck(Vec<f64>);
+
+        fn after_spacial_audio(
+            audio_instances: Res<Assets<AudioInstance>>,
+            mut seen: ResMut<SeenDuck>,
+        ) {
+            seen.0
+                .extend(audio_instances.iter().map(|(_, instance)| instance.duck));
+        }
+
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            AudioPlugin::with_spacial_schedule(Update),
+        ))
+        .init_resource::<SeenDuck>()
+        .add_systems(Update, after_spacial_audio.after(SpacialAudioSet::Update));
+        let source = AudioSource::from_frames(1_000, vec![[0.; 2]; 10_000]).unwrap();
+        let source = app.world.resource_mut::<Assets<AudioSource>>().add(source);
+        app.world
+            .resource_mut::<DynamicAudioChannels>()
+            .create_channel("music")
+            .play(source)
+            .looped();
+        let receiver = app
+            .world
+            .spawn((
+                GlobalTransform::from_xyz(20., 0., 0.),
+                AudioReceiver::default(),
+            ))
+            .id();
+        app.world.spawn((
+            GlobalTransform::from_xyz(8., 0., 0.),
+            AudioZone::sphere(1.)
+                .with(ChannelAdjustment::new(Channel::dynamic("music")).with_volume(0.5)),
+        ));
+        app.update();
+        assert!(app.world.resource::<SeenDuck>().0.is_empty());
+
+        *app.world.get_mut::<GlobalTransform>(receiver).unwrap() =
+            GlobalTransform::from_xyz(8., 0., 0.);
+        app.update();
+        // Systems after the spacial audio of the schedule see the zone of this frame
+        assert_eq!(app.world.resource::<SeenDuck>().0, vec![0.5]);
+    }
+}
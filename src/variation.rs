@@ -0,0 +1,104 @@
+//! Random variation of sounds, so repeated sounds don't play identically
+
+use bevy::ecs::system::Resource;
+use parking_lot::Mutex;
+use std::sync::Arc;
+
+/// The random numbers for [`PlayAudioCommand::pitch_variation`](crate::PlayAudioCommand::pitch_variation)
+/// and [`PlayAudioCommand::volume_variation`](crate::PlayAudioCommand::volume_variation)
+///
+/// Seed it for the same variations in every run, for example in tests or replays.
+/// Sounds take their random offsets in the order the plugin plays them.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// fn start_replay(audio_rng: Res<AudioRng>) {
+///     audio_rng.seed(42);
+/// }
+/// ```
+#[derive(Resource, Clone)]
+pub struct AudioRng {
+    rng: Arc<Mutex<fastrand::Rng>>,
+}
+
+impl Default for AudioRng {
+    fn default() -> Self {
+        AudioRng {
+            rng: Arc::new(Mutex::new(fastrand::Rng::new())),
+        }
+    }
+}
+
+impl AudioRng {
+    /// A generator with the given seed
+    ///
+    /// Insert it before adding the [`AudioPlugin`](crate::AudioPlugin) to seed the variations
+    /// from the start.
+    pub fn seeded(seed: u64) -> Self {
+        AudioRng {
+            rng: Arc::new(Mutex::new(fastrand::Rng::with_seed(seed))),
+        }
+    }
+
+    /// Restart the generator with the given seed
+    pub fn seed(&self, seed: u64) {
+        self.rng.lock().seed(seed);
+    }
+
+    /// A uniformly distributed random number between `0` and `1`
+    pub(crate) fn fraction(&self) -> f64 {
+        self.rng.lock().f64()
+    }
+
+    /// A uniformly distributed random offset between `-max` and `max`
+    pub(crate) fn offset(&self, max: f64) -> f64 {
+        (self.rng.lock().f64() * 2. - 1.) * max
+    }
+}
+
+/// Random offsets of a sound, sampled when it starts to play
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Variation {
+    /// Factor of the playback rate
+    pub(crate) playback_rate: f64,
+    /// Factor of the volume as amplitude
+    pub(crate) volume: f64,
+}
+
+impl Variation {
+    pub(crate) fn sample(semitones: Option<f64>, decibels: Option<f64>, rng: &AudioRng) -> Self {
+        Variation {
+            playback_rate: semitones
+                .map_or(1., |semitones| 2f64.powf(rng.offset(semitones.abs()) / 12.)),
+            volume: decibels.map_or(1., |decibels| 10f64.powf(rng.offset(decibels.abs()) / 20.)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn seeded_variations_repeat() {
+        let rng = AudioRng::seeded(7);
+        let first: Vec<_> = (0..5)
+            .map(|_| Variation::sample(Some(2.), Some(3.), &rng).playback_rate)
+            .collect();
+        rng.seed(7);
+        let second: Vec<_> = (0..5)
+            .map(|_| Variation::sample(Some(2.), Some(3.), &rng).playback_rate)
+            .collect();
+        assert_eq!(first, second);
+        assert!(first.windows(2).any(|rates| rates[0] != rates[1]));
+        // Two semitones in either direction
+        assert!(first.iter().all(|rate| (0.8908..=1.1225).contains(rate)));
+    }
+
+    #[test]
+    fn no_variation_without_a_range() {
+        let variation = Variation::sample(None, None, &AudioRng::default());
+        assert_eq!(variation.playback_rate, 1.);
+        assert_eq!(variation.volume, 1.);
+    }
+}
@@ -0,0 +1,115 @@
+//! Volumes keep their unit on the way to kira, so tweens interpolate in it
+//!
+//! Kira interpolates a tween in the unit of its target volume. A fade to a
+//! [`Volume::Decibels`] value sounds even, while a fade to a [`Volume::Amplitude`]
+//! value seems to happen mostly at its end.
+
+use crate::audio::AudioTween;
+use bevy::ecs::system::Resource;
+use kira::Volume;
+
+/// The volume of all audio, on top of the volumes of channels
+///
+/// It scales every channel, typed and dynamic, on every output device, including sounds that
+/// start later. Fade it for smooth transitions, like an accessibility toggle that turns all
+/// audio off.
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy_kira_audio::prelude::*;
+/// # use std::time::Duration;
+/// fn toggle_audio(keys: Res<Input<KeyCode>>, mut master: ResMut<MasterVolume>) {
+///     if keys.just_pressed(KeyCode::M) {
+///         let volume = if master.volume().as_amplitude() > 0. { 0. } else { 1. };
+///         master.set(volume, AudioTween::linear(Duration::from_millis(500)));
+///     }
+/// }
+/// ```
+#[derive(Resource, Clone)]
+pub struct MasterVolume {
+    volume: Volume,
+    tween: AudioTween,
+}
+
+impl Default for MasterVolume {
+    fn default() -> Self {
+        MasterVolume {
+            volume: Volume::Amplitude(1.),
+            tween: AudioTween::default(),
+        }
+    }
+}
+
+impl MasterVolume {
+    /// The volume all audio plays at, or fades to
+    pub fn volume(&self) -> Volume {
+        self.volume
+    }
+
+    /// Fade all audio to the given volume
+    pub fn set(&mut self, volume: impl Into<Volume>, tween: AudioTween) {
+        self.volume = volume.into();
+        self.tween = tween;
+    }
+
+    pub(crate) fn tween(&self) -> &AudioTween {
+        &self.tween
+    }
+}
+
+/// Scale a volume by an amplitude factor without changing its unit
+pub(crate) fn scale_volume(volume: Volume, factor: f64) -> Volume {
+    if factor == 1. {
+        return finite_volume(volume);
+    }
+    match volume {
+        Volume::Amplitude(amplitude) => Volume::Amplitude(amplitude * factor),
+        Volume::Decibels(_) if factor <= 0. => Volume::Decibels(Volume::MIN_DECIBELS),
+        Volume::Decibels(decibels) => finite_volume(Volume::Decibels(
+            decibels + Volume::Amplitude(factor).as_decibels(),
+        )),
+    }
+}
+
+/// Replace decibels below the silent minimum of kira (like `-inf`) with that minimum
+///
+/// Interpolating from or to `-inf` decibels would not give a usable volume.
+pub(crate) fn finite_volume(volume: Volume) -> Volume {
+    match volume {
+        Volume::Decibels(decibels) if decibels.is_nan() || decibels < Volume::MIN_DECIBELS => {
+            Volume::Decibels(Volume::MIN_DECIBELS)
+        }
+        volume => volume,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use kira::tween::Tweenable;
+
+    #[test]
+    fn scaling_keeps_the_unit_of_a_volume() {
+        assert_eq!(
+            scale_volume(Volume::Amplitude(0.8), 0.5),
+            Volume::Amplitude(0.4)
+        );
+        let Volume::Decibels(decibels) = scale_volume(Volume::Decibels(-6.), 0.5) else {
+            panic!("expected a volume in decibels");
+        };
+        assert!((decibels + 12.0206).abs() < 1e-3);
+        assert_eq!(
+            scale_volume(Volume::Decibels(-6.), 0.),
+            Volume::Decibels(Volume::MIN_DECIBELS)
+        );
+    }
+
+    #[test]
+    fn minus_infinity_decibels_is_silence() {
+        let silence = finite_volume(Volume::Decibels(f64::NEG_INFINITY));
+        assert_eq!(silence.as_amplitude(), 0.);
+        let halfway = Tweenable::interpolate(silence, Volume::Decibels(0.), 0.5);
+        assert_eq!(halfway, Volume::Decibels(Volume::MIN_DECIBELS / 2.));
+        let start = Tweenable::interpolate(Volume::Decibels(0.), silence, 0.);
+        assert_eq!(start.as_amplitude(), 1.);
+    }
+}